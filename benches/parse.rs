@@ -2,8 +2,8 @@
 
 extern crate test;
 
-use mt940::parse_mt940;
 use mt940::sanitizers::sanitize;
+use mt940::{parse_mt940, parse_mt940_borrowed};
 use test::Bencher;
 
 static LONGER_STATEMENT: &str =
@@ -24,6 +24,12 @@ fn bench_longer_statement_presanitize(b: &mut Bencher) {
     b.iter(|| parse_mt940(&sanitized).unwrap());
 }
 
+#[bench]
+fn bench_longer_statement_presanitize_borrowed(b: &mut Bencher) {
+    let sanitized = sanitize(&LONGER_STATEMENT);
+    b.iter(|| parse_mt940_borrowed(&sanitized).unwrap());
+}
+
 #[bench]
 fn bench_long_statement(b: &mut Bencher) {
     b.iter(|| parse_mt940(&LONG_STATEMENT).unwrap());
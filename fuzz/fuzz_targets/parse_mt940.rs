@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mt940::parse_mt940;
+
+fuzz_target!(|data: &str| {
+    let _ = parse_mt940(data);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mt940::{parse_mt940, ArbitraryStatement};
+
+fuzz_target!(|statement: ArbitraryStatement| {
+    assert_eq!(parse_mt940(&statement.text).unwrap(), statement.messages);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mt940::parse_mt940;
+use mt940::sanitizers::sanitize;
+
+fuzz_target!(|data: &str| {
+    let _ = parse_mt940(&sanitize(data));
+});
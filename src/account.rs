@@ -0,0 +1,182 @@
+//! Decomposition of the account identification in tag `:25:`.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::Message;
+
+/// The account identification of a [`Message`] broken down into its parts.
+///
+/// # Example
+/// ```
+/// use mt940::AccountId;
+///
+/// assert_eq!(
+///     AccountId::from("DABADKKK/111111-11111111"),
+///     AccountId::BicAndAccount {
+///         bic: "DABADKKK".to_string(),
+///         account: "111111-11111111".to_string(),
+///     }
+/// );
+/// assert_eq!(
+///     AccountId::from("NL91ABNA0417164300"),
+///     AccountId::Iban("NL91ABNA0417164300".to_string())
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AccountId {
+    /// An IBAN with a valid checksum, without any spaces.
+    Iban(String),
+    /// A BIC followed by an account number, separated by `/`.
+    BicAndAccount { bic: String, account: String },
+    /// A national bank code, e.g. a German BLZ, followed by an account number, separated by `/`.
+    BankCodeAndAccount { bank_code: String, account: String },
+    /// Anything else.
+    Raw(String),
+}
+
+impl AccountId {
+    /// The IBAN if this is one.
+    pub fn iban(&self) -> Option<&str> {
+        match self {
+            AccountId::Iban(iban) => Some(iban),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for AccountId {
+    fn from(account_id: &str) -> AccountId {
+        let without_spaces = account_id.replace(' ', "");
+        if is_valid_iban(&without_spaces) {
+            return AccountId::Iban(without_spaces);
+        }
+
+        if let Some((prefix, account)) = account_id.split_once('/') {
+            if !account.is_empty() {
+                if is_bic(prefix) {
+                    return AccountId::BicAndAccount {
+                        bic: prefix.to_string(),
+                        account: account.to_string(),
+                    };
+                }
+                if !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_digit()) {
+                    return AccountId::BankCodeAndAccount {
+                        bank_code: prefix.to_string(),
+                        account: account.to_string(),
+                    };
+                }
+            }
+        }
+
+        AccountId::Raw(account_id.to_string())
+    }
+}
+
+impl Message {
+    /// The account identification of tag `:25:` broken down into its parts.
+    pub fn parsed_account_id(&self) -> AccountId {
+        AccountId::from(self.account_id.as_str())
+    }
+}
+
+/// Check whether `iban` is a well-formed IBAN with a valid checksum.
+///
+/// The IBAN must not contain spaces.
+///
+/// # Example
+/// ```
+/// use mt940::is_valid_iban;
+///
+/// assert!(is_valid_iban("DE89370400440532013000"));
+/// assert!(!is_valid_iban("DE88370400440532013000"));
+/// ```
+pub fn is_valid_iban(iban: &str) -> bool {
+    let chars = iban.chars().collect::<Vec<_>>();
+    let well_formed = (15..=34).contains(&chars.len())
+        && matches!(chars.as_slice(), [a, b, c, d, ..]
+            if a.is_ascii_uppercase() && b.is_ascii_uppercase() && c.is_ascii_digit() && d.is_ascii_digit())
+        && chars
+            .iter()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase());
+    if !well_formed {
+        return false;
+    }
+
+    // Move the country code and check digits to the end, replace letters by numbers and check the
+    // remainder digit by digit so that we don't overflow.
+    let remainder = chars
+        .iter()
+        .cycle()
+        .skip(4)
+        .take(chars.len())
+        .filter_map(|c| c.to_digit(36))
+        .fold(0, |remainder, digit| {
+            if digit < 10 {
+                (remainder * 10 + digit) % 97
+            } else {
+                (remainder * 100 + digit) % 97
+            }
+        });
+    remainder == 1
+}
+
+/// Check whether `s` looks like a BIC, i.e. 8 or 11 characters starting with 6 letters.
+pub(crate) fn is_bic(s: &str) -> bool {
+    let chars = s.chars().collect::<Vec<_>>();
+    (chars.len() == 8 || chars.len() == 11)
+        && chars.iter().take(6).all(|c| c.is_ascii_uppercase())
+        && chars
+            .iter()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest(
+        input,
+        expected,
+        case("NL91ABNA0417164300", AccountId::Iban("NL91ABNA0417164300".to_string())),
+        case("GB82 WEST 1234 5698 7654 32", AccountId::Iban("GB82WEST12345698765432".to_string())),
+        case("NL92ABNA0417164300", AccountId::Raw("NL92ABNA0417164300".to_string())),
+        case(
+            "BPHKPLPK/320000546101",
+            AccountId::BicAndAccount {
+                bic: "BPHKPLPK".to_string(),
+                account: "320000546101".to_string(),
+            }
+        ),
+        case(
+            "20752041/0291593375",
+            AccountId::BankCodeAndAccount {
+                bank_code: "20752041".to_string(),
+                account: "0291593375".to_string(),
+            }
+        ),
+        case("0067-00763/93984487", AccountId::Raw("0067-00763/93984487".to_string())),
+        case("1291.99.348EUR", AccountId::Raw("1291.99.348EUR".to_string())),
+        case("12345678/", AccountId::Raw("12345678/".to_string()))
+    )]
+    fn account_id_from_str(input: &str, expected: AccountId) {
+        assert_eq!(AccountId::from(input), expected);
+    }
+
+    #[rstest(
+        iban,
+        valid,
+        case("DE89370400440532013000", true),
+        case("GB82WEST12345698765432", true),
+        case("NO9386011117947", true),
+        case("MT84MALT011000012345MTLCAST001S", true),
+        case("DE89370400440532013001", false),
+        case("de89370400440532013000", false),
+        case("DE89", false)
+    )]
+    fn iban_checksum(iban: &str, valid: bool) {
+        assert_eq!(is_valid_iban(iban), valid);
+    }
+}
@@ -0,0 +1,513 @@
+//! Replacement of personal data in [`Message`]s with pseudonyms.
+
+use crate::account::is_valid_iban;
+use crate::utils::fnv1a;
+use crate::{InformationFormat, Message, StructuredInformation};
+
+/// Placeholder used in reference fields if there is no reference.
+const NONREF: &str = "NONREF";
+
+/// Subfields of structured German tag `:86:` which identify the counterparty.
+const COUNTERPARTY_SUBFIELDS: [&str; 4] = ["30", "31", "32", "33"];
+
+/// Options for [`anonymize`].
+///
+/// ```
+/// use mt940::AnonymizeOptions;
+///
+/// let options = AnonymizeOptions::new()
+///     .salt("my secret")
+///     .redact_information(true);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AnonymizeOptions {
+    pub(crate) salt: String,
+    pub(crate) redact_information: bool,
+}
+
+impl AnonymizeOptions {
+    /// Create new [`AnonymizeOptions`] without a salt which only replace known personal data in
+    /// tag `:86:`.
+    pub fn new() -> AnonymizeOptions {
+        AnonymizeOptions::default()
+    }
+
+    /// Mixed into every pseudonym.
+    ///
+    /// Without a salt, anyone who can guess an original value can check the guess by anonymizing
+    /// it as well.
+    pub fn salt(mut self, salt: &str) -> AnonymizeOptions {
+        self.salt = salt.to_string();
+        self
+    }
+
+    /// Scramble all of tag `:86:` instead of only the parts known to contain personal data.
+    ///
+    /// This is necessary for banks whose tag `:86:` is free text.
+    pub fn redact_information(mut self, redact_information: bool) -> AnonymizeOptions {
+        self.redact_information = redact_information;
+        self
+    }
+}
+
+/// Replace account ids, references and counterparty details of `message` with pseudonyms.
+///
+/// Amounts, dates, balances and the structure of every value are kept. Pseudonyms are
+/// deterministic so the same account or reference gets the same pseudonym in every message which
+/// keeps the relations between messages intact. Digits are replaced by digits and letters by
+/// letters of the same case so the result is still valid MT940. IBANs are replaced by other IBANs
+/// with a valid checksum. Proprietary fields, including the strings in
+/// [`Message::custom_fields`], are scrambled entirely.
+///
+/// In tag `:86:`, the counterparty subfields `?30` to `?33` of German statements, `/NAME/` and
+/// `/CNTP/` fields of Dutch statements and all IBANs are replaced, as are name and address of the
+/// counterparty of statement lines parsed with [`InformationFormat::Nordic`]. Use
+/// [`AnonymizeOptions::redact_information`] for anything else.
+/// [`StatementLine::structured_information`] and [`StatementLine::sepa_purpose`] are broken down
+/// from the anonymized text again.
+///
+/// [`StatementLine::structured_information`]: crate::StatementLine::structured_information
+/// [`StatementLine::sepa_purpose`]: crate::StatementLine::sepa_purpose
+///
+/// # Example
+/// ```
+/// use mt940::{anonymize, parse_mt940, AnonymizeOptions};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DE89370400440532013000\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:166?00GUTSCHRIFT?20Invoice 42?32Jane Doe\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let mut message = parse_mt940(input).unwrap().remove(0);
+/// anonymize(&mut message, &AnonymizeOptions::new());
+/// assert_ne!(message.account_id, "DE89370400440532013000");
+/// assert!(mt940::is_valid_iban(&message.account_id));
+/// let info = message.statement_lines[0].information_to_account_owner.as_ref().unwrap();
+/// assert!(info.starts_with("166?00GUTSCHRIFT?20Invoice 42?32"));
+/// assert!(!info.contains("Jane Doe"));
+/// assert_eq!(message.statement_lines[0].amount.to_string(), "583.92");
+/// ```
+pub fn anonymize(message: &mut Message, options: &AnonymizeOptions) {
+    let salt = options.salt.as_str();
+    message.transaction_ref_no = pseudonymize_reference(&message.transaction_ref_no, salt);
+    if let Some(ref mut related) = message.ref_to_related_msg {
+        *related = pseudonymize_reference(related, salt);
+    }
+    message.account_id = pseudonymize_account(&message.account_id, salt);
+    // The original text contains everything we are about to replace.
+    message.raw = None;
+    if let Some(ref mut info) = message.information_to_account_owner {
+        *info = anonymize_information(info, options);
+    }
+    anonymize_lines(
+        &mut message.information_lines,
+        message.information_to_account_owner.as_deref(),
+        salt,
+    );
+    for statement_line in &mut message.statement_lines {
+        statement_line.customer_ref = pseudonymize_reference(&statement_line.customer_ref, salt);
+        if let Some(ref mut bank_ref) = statement_line.bank_ref {
+            *bank_ref = pseudonymize_reference(bank_ref, salt);
+        }
+        if let Some(ref mut details) = statement_line.supplementary_details {
+            *details = scramble(details, salt);
+        }
+        let format = statement_line
+            .structured_information
+            .as_ref()
+            .map(StructuredInformation::format);
+        if let Some(ref mut info) = statement_line.information_to_account_owner {
+            *info = anonymize_information(info, options);
+            if format == Some(InformationFormat::Nordic) && !options.redact_information {
+                *info = anonymize_nordic_counterparty(info, salt);
+            }
+        }
+        // The structured information is taken from the anonymized text again.
+        statement_line.structured_information = format.and_then(|format| {
+            let info = statement_line.information_to_account_owner.as_deref()?;
+            StructuredInformation::parse(format, info)
+        });
+        if statement_line.sepa_purpose.is_some() {
+            statement_line.sepa_purpose = statement_line.parse_sepa_purpose();
+        }
+        anonymize_lines(
+            &mut statement_line.information_lines,
+            statement_line.information_to_account_owner.as_deref(),
+            salt,
+        );
+        statement_line.raw = None;
+    }
+    for field in &mut message.non_swift_fields {
+        field.value = scramble(&field.value, salt);
+        field.raw = None;
+    }
+    for value in message.custom_fields.values_mut() {
+        scramble_json(value, salt);
+    }
+}
+
+/// Scramble every string in `value`, wherever it is nested.
+fn scramble_json(value: &mut serde_json::Value, salt: &str) {
+    match value {
+        serde_json::Value::String(s) => *s = scramble(s, salt),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                scramble_json(value, salt);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                scramble_json(value, salt);
+            }
+        }
+        serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => (),
+    }
+}
+
+fn pseudonymize_reference(reference: &str, salt: &str) -> String {
+    if reference == NONREF {
+        reference.to_string()
+    } else {
+        scramble(reference, salt)
+    }
+}
+
+fn pseudonymize_account(account: &str, salt: &str) -> String {
+    if is_valid_iban(account) {
+        pseudonymize_iban(account, salt)
+    } else {
+        scramble(account, salt)
+    }
+}
+
+/// Replace the account part of an IBAN and recalculate its check digits.
+fn pseudonymize_iban(iban: &str, salt: &str) -> String {
+    let country_code = iban.get(..2).unwrap_or_default();
+    let bban = scramble(iban.get(4..).unwrap_or_default(), salt);
+    let remainder = format!("{}{}00", bban, country_code)
+        .chars()
+        .filter_map(|c| c.to_digit(36))
+        .fold(0, |remainder, digit| {
+            if digit < 10 {
+                (remainder * 10 + digit) % 97
+            } else {
+                (remainder * 100 + digit) % 97
+            }
+        });
+    format!("{}{:02}{}", country_code, 98 - remainder, bban)
+}
+
+fn anonymize_information(info: &str, options: &AnonymizeOptions) -> String {
+    let salt = options.salt.as_str();
+    if options.redact_information {
+        return scramble(info, salt);
+    }
+
+    let info = anonymize_subfields(info, salt);
+    let info = anonymize_slash_fields(&info, salt);
+    anonymize_ibans(&info, salt)
+}
+
+/// Replace the lines of tag `:86:` by the corresponding parts of the already anonymized `info`.
+///
+/// Anonymizing keeps the length of every value, so the lines are cut from `info` one after
+/// another, skipping the line break between them unless the lines were joined. Lines which
+/// can't be found in `info` are scrambled on their own.
+fn anonymize_lines(lines: &mut [String], info: Option<&str>, salt: &str) {
+    let mut rest = info.unwrap_or_default().chars().peekable();
+    for line in lines {
+        let len = line.chars().count();
+        let anonymized: String = rest.by_ref().take(len).collect();
+        rest.next_if_eq(&'\n');
+        *line = if anonymized.chars().count() == len {
+            anonymized
+        } else {
+            scramble(line, salt)
+        };
+    }
+}
+
+/// Scramble name and address of the counterparty, which follow the first line in the Nordic
+/// format.
+fn anonymize_nordic_counterparty(info: &str, salt: &str) -> String {
+    let mut seen_first = false;
+    info.split('\n')
+        .map(|line| {
+            if seen_first {
+                scramble(line, salt)
+            } else {
+                seen_first = !line.trim().is_empty();
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Scramble the counterparty subfields of structured German tag `:86:`.
+fn anonymize_subfields(info: &str, salt: &str) -> String {
+    let mut subfields = info.split('?');
+    let mut out = subfields.next().unwrap_or_default().to_string();
+    for subfield in subfields {
+        out.push('?');
+        match (subfield.get(..2), subfield.get(2..)) {
+            (Some(number), Some(value)) if COUNTERPARTY_SUBFIELDS.contains(&number) => {
+                out.push_str(number);
+                out.push_str(&scramble(value, salt));
+            }
+            _ => out.push_str(subfield),
+        }
+    }
+    out
+}
+
+/// Scramble the `/NAME/` and `/CNTP/` fields of Dutch tag `:86:`.
+fn anonymize_slash_fields(info: &str, salt: &str) -> String {
+    let parts = info.split('/').collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(parts.len());
+    let mut scramble_next = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if scramble_next > 0 {
+            scramble_next -= 1;
+            // The second part of `/CNTP/` is a BIC which isn't personal.
+            let is_cntp_bic = i >= 2 && parts.get(i - 2) == Some(&"CNTP");
+            out.push(if is_cntp_bic {
+                part.to_string()
+            } else {
+                scramble(part, salt)
+            });
+            continue;
+        }
+        match *part {
+            "NAME" => scramble_next = 1,
+            // Account, BIC, name and city
+            "CNTP" => scramble_next = 4,
+            _ => (),
+        }
+        out.push(part.to_string());
+    }
+    out.join("/")
+}
+
+/// Replace every IBAN by a pseudonym.
+fn anonymize_ibans(info: &str, salt: &str) -> String {
+    let mut out = String::with_capacity(info.len());
+    let mut token = String::new();
+    for c in info.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_uppercase() || c.is_ascii_digit() {
+            token.push(c);
+            continue;
+        }
+        if is_valid_iban(&token) {
+            out.push_str(&pseudonymize_iban(&token, salt));
+        } else {
+            out.push_str(&token);
+        }
+        token.clear();
+        if c != '\0' {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Deterministically replace every digit by a digit and every letter by a letter of the same
+/// case. All other characters are kept.
+fn scramble(value: &str, salt: &str) -> String {
+    let mut state = fnv1a(salt.bytes().chain([0]).chain(value.bytes()));
+    let mut next = |modulus: u64| {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % modulus) as u8
+    };
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_digit() {
+                char::from(b'0' + next(10))
+            } else if c.is_ascii_uppercase() {
+                char::from(b'A' + next(26))
+            } else if c.is_ascii_lowercase() {
+                char::from(b'a' + next(26))
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::{assert_eq, assert_ne};
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{parse_mt940, parse_mt940_with_config, serialize_mt940, ParserConfig};
+
+    #[test]
+    fn scramble_keeps_structure() {
+        let scrambled = scramble("Ab-12 c", "");
+        assert_eq!(scrambled.len(), 7);
+        let classes = |s: &str| {
+            s.chars()
+                .map(|c| {
+                    (
+                        c.is_ascii_digit(),
+                        c.is_ascii_uppercase(),
+                        c.is_ascii_lowercase(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(classes(&scrambled), classes("Ab-12 c"));
+        assert_eq!(scrambled.get(2..3), Some("-"));
+        assert_eq!(scramble("Ab-12 c", ""), scrambled);
+        assert_ne!(scramble("Ab-12 c", "salt"), scrambled);
+    }
+
+    #[test]
+    fn pseudonymized_iban_is_valid() {
+        let iban = pseudonymize_iban("NL91ABNA0417164300", "");
+        assert!(iban.starts_with("NL"));
+        assert_ne!(iban, "NL91ABNA0417164300");
+        assert!(is_valid_iban(&iban));
+    }
+
+    #[test]
+    fn anonymize_dutch_information() {
+        let info = "/CNTP/NL91ABNA0417164300/ABNANL2A/Jane Doe/Amsterdam/\
+                    /REMI/Invoice 42/NAME/John/";
+        let anonymized = anonymize_information(info, &AnonymizeOptions::new());
+        let parts = anonymized.split('/').collect::<Vec<_>>();
+        assert_eq!(parts.get(3), Some(&"ABNANL2A"));
+        assert_eq!(parts.get(8), Some(&"Invoice 42"));
+        assert!(!anonymized.contains("NL91ABNA0417164300"));
+        assert!(!anonymized.contains("Jane Doe"));
+        assert!(!anonymized.contains("Amsterdam"));
+        assert!(!anonymized.contains("John"));
+    }
+
+    #[test]
+    fn anonymize_information_lines() {
+        let input = "\
+            :20:ref\r\n\
+            :25:DABADKKK/111111-11111111\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925C2,NTRFcustomer//bank\r\n\
+            :86:/NAME/JANE SECRETPERSON\r\n\
+            /IBAN/NL91ABNA0417164300\r\n\
+            :62F:C090925EUR3,\r\n\
+            :86:/NAME/JOHN SECRETPERSON\r\n";
+        let config = ParserConfig::new().keep_information_lines(true);
+        let mut message = parse_mt940_with_config(input, &config).unwrap().remove(0);
+        anonymize(&mut message, &AnonymizeOptions::new());
+
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert!(!serialized.contains("SECRETPERSON"));
+        assert!(!serialized.contains("NL91ABNA0417164300"));
+        let line = message.statement_lines.first().unwrap();
+        assert_eq!(
+            line.information_lines.join("\n"),
+            line.information_to_account_owner.clone().unwrap()
+        );
+        assert_eq!(
+            message.information_lines.join("\n"),
+            message.information_to_account_owner.clone().unwrap()
+        );
+    }
+
+    #[rstest(
+        format,
+        info,
+        case(
+            InformationFormat::Dutch,
+            "/TRTP/SEPA OVERBOEKING/IBAN/NL91ABNA0417164300/NAME/JANE SECRETPERSON/REMI/Invoice 42"
+        ),
+        case(
+            InformationFormat::Nordic,
+            "Invoice 42\nJANE SECRETPERSON\nNL91ABNA0417164300"
+        )
+    )]
+    fn anonymize_structured_information(format: InformationFormat, info: &str) {
+        let input = format!(
+            ":20:ref\r\n\
+            :25:DABADKKK/111111-11111111\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925C2,NTRFcustomer//bank\r\n\
+            :86:{}\r\n\
+            :62F:C090925EUR3,\r\n",
+            info.replace('\n', "\r\n")
+        );
+        let config = ParserConfig::new().information_format(format);
+        let mut message = parse_mt940_with_config(&input, &config).unwrap().remove(0);
+        anonymize(&mut message, &AnonymizeOptions::new());
+
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert!(!serialized.contains("SECRETPERSON"));
+        assert!(!serialized.contains("NL91ABNA0417164300"));
+        assert!(serialized.contains("Invoice 42"));
+        let line = message.statement_lines.first().unwrap();
+        let info = line.information_to_account_owner.as_deref().unwrap();
+        assert_eq!(
+            line.structured_information,
+            StructuredInformation::parse(format, info)
+        );
+    }
+
+    #[test]
+    fn anonymize_custom_fields() {
+        let input = "\
+            :20:ref\r\n\
+            :25:DABADKKK/111111-11111111\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :99:Jane Secretperson\r\n\
+            :62F:C090924EUR1,\r\n";
+        let config = ParserConfig::new().tag_handler("99", |field, custom_fields| {
+            custom_fields.insert(
+                "owner".to_string(),
+                serde_json::json!({ "name": field.value, "accounts": [field.value, 1] }),
+            );
+            Ok(())
+        });
+        let mut message = parse_mt940_with_config(input, &config).unwrap().remove(0);
+        anonymize(&mut message, &AnonymizeOptions::new());
+
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert!(!serialized.contains("Secretperson"));
+        assert_eq!(message.custom_fields["owner"]["accounts"][1], 1);
+    }
+
+    #[test]
+    fn anonymized_message_is_still_valid() {
+        let input = "\
+            :20:ref\r\n\
+            :21:NONREF\r\n\
+            :25:DABADKKK/111111-11111111\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925C2,NTRFcustomer//bank\r\n\
+            :86:Secret\r\n\
+            :62F:C090925EUR3,\r\n";
+        let mut messages = parse_mt940(input).unwrap();
+        let options = AnonymizeOptions::new().redact_information(true);
+        for message in &mut messages {
+            anonymize(message, &options);
+        }
+        let message = messages.first().unwrap();
+        assert_eq!(message.ref_to_related_msg.as_deref(), Some("NONREF"));
+        assert_ne!(message.account_id, "DABADKKK/111111-11111111");
+        assert_eq!(message.closing_balance.amount, 3.into());
+        let line = message.statement_lines.first().unwrap();
+        assert_ne!(line.information_to_account_owner.as_deref(), Some("Secret"));
+        assert_eq!(parse_mt940(&serialize_mt940(&messages)).unwrap(), messages);
+    }
+}
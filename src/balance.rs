@@ -0,0 +1,288 @@
+//! Uniform handling of all the balances of a message.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{AvailableBalance, Balance, DebitOrCredit, Message, Tag};
+
+/// Which of the balances of a [`Message`] a [`BalanceInfo`] is.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum BalanceKind {
+    /// Tag `:60F:` or `:60M:`
+    Opening,
+    /// Tag `:62F:` or `:62M:`
+    Closing,
+    /// Tag `:64:`
+    ClosingAvailable,
+    /// Tag `:65:`
+    ForwardAvailable,
+}
+
+impl BalanceKind {
+    /// The tag a balance of this kind is written with.
+    ///
+    /// Available balances are always final.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::{BalanceKind, BalanceStatus, Tag};
+    ///
+    /// assert_eq!(
+    ///     BalanceKind::Closing.tag(BalanceStatus::Intermediate),
+    ///     Tag::IntermediateClosingBalance
+    /// );
+    /// assert_eq!(
+    ///     BalanceKind::from_tag(&Tag::IntermediateClosingBalance),
+    ///     Some((BalanceKind::Closing, BalanceStatus::Intermediate))
+    /// );
+    /// ```
+    pub fn tag(self, status: BalanceStatus) -> Tag {
+        match (self, status) {
+            (BalanceKind::Opening, BalanceStatus::Final) => Tag::OpeningBalance,
+            (BalanceKind::Opening, BalanceStatus::Intermediate) => Tag::IntermediateOpeningBalance,
+            (BalanceKind::Closing, BalanceStatus::Final) => Tag::ClosingBalance,
+            (BalanceKind::Closing, BalanceStatus::Intermediate) => Tag::IntermediateClosingBalance,
+            (BalanceKind::ClosingAvailable, _) => Tag::ClosingAvailableBalance,
+            (BalanceKind::ForwardAvailable, _) => Tag::ForwardAvailableBalance,
+        }
+    }
+
+    /// The kind and status of a balance written with `tag`, if it is a balance tag.
+    pub fn from_tag(tag: &Tag) -> Option<(BalanceKind, BalanceStatus)> {
+        let kind_and_status = match tag {
+            Tag::OpeningBalance => (BalanceKind::Opening, BalanceStatus::Final),
+            Tag::IntermediateOpeningBalance => (BalanceKind::Opening, BalanceStatus::Intermediate),
+            Tag::ClosingBalance => (BalanceKind::Closing, BalanceStatus::Final),
+            Tag::IntermediateClosingBalance => (BalanceKind::Closing, BalanceStatus::Intermediate),
+            Tag::ClosingAvailableBalance => (BalanceKind::ClosingAvailable, BalanceStatus::Final),
+            Tag::ForwardAvailableBalance => (BalanceKind::ForwardAvailable, BalanceStatus::Final),
+            _ => return None,
+        };
+        Some(kind_and_status)
+    }
+}
+
+/// Whether a balance is final or continued in another statement.
+///
+/// This is the `F` or `M` at the end of the tags `:60F:`, `:60M:`, `:62F:` and `:62M:`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum BalanceStatus {
+    /// `F`
+    #[default]
+    Final,
+    /// `M`, the balance is continued from or in another statement.
+    Intermediate,
+}
+
+impl BalanceStatus {
+    pub fn is_intermediate(self) -> bool {
+        self == BalanceStatus::Intermediate
+    }
+}
+
+impl From<bool> for BalanceStatus {
+    /// The status of a balance for which [`Balance::is_intermediate`] is `is_intermediate`.
+    fn from(is_intermediate: bool) -> BalanceStatus {
+        if is_intermediate {
+            BalanceStatus::Intermediate
+        } else {
+            BalanceStatus::Final
+        }
+    }
+}
+
+impl Balance {
+    /// [`Balance::is_intermediate`] as a [`BalanceStatus`].
+    pub fn status(&self) -> BalanceStatus {
+        BalanceStatus::from(self.is_intermediate)
+    }
+}
+
+/// A [`Balance`] or [`AvailableBalance`] along with which balance of its [`Message`] it is.
+///
+/// This allows handling all balances the same way, e.g. in reports.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, BalanceKind};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n\
+///     :64:C090930EUR54484,04\r\n\
+///     :65:C091001EUR54484,04\r\n";
+///
+/// let message = &parse_mt940(input).unwrap()[0];
+/// let kinds = message
+///     .balances()
+///     .iter()
+///     .map(|balance| balance.kind)
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     kinds,
+///     [
+///         BalanceKind::Opening,
+///         BalanceKind::Closing,
+///         BalanceKind::ClosingAvailable,
+///         BalanceKind::ForwardAvailable,
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BalanceInfo {
+    pub kind: BalanceKind,
+    /// Always `false` for available balances.
+    pub is_intermediate: bool,
+    pub debit_credit_indicator: DebitOrCredit,
+    pub date: NaiveDate,
+    pub iso_currency_code: String,
+    pub amount: Decimal,
+}
+
+impl BalanceInfo {
+    /// Describe `balance` as the `kind` balance of its message.
+    pub fn from_balance(kind: BalanceKind, balance: &Balance) -> BalanceInfo {
+        BalanceInfo {
+            kind,
+            is_intermediate: balance.is_intermediate,
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code.clone(),
+            amount: balance.amount,
+        }
+    }
+
+    /// Describe `balance` as the `kind` balance of its message.
+    pub fn from_available_balance(kind: BalanceKind, balance: &AvailableBalance) -> BalanceInfo {
+        BalanceInfo {
+            kind,
+            is_intermediate: false,
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code.clone(),
+            amount: balance.amount,
+        }
+    }
+
+    /// The amount of this balance which is negative in case of a debit balance.
+    pub fn signed_amount(&self) -> Decimal {
+        self.debit_credit_indicator.apply_sign(self.amount)
+    }
+
+    /// [`BalanceInfo::is_intermediate`] as a [`BalanceStatus`].
+    pub fn status(&self) -> BalanceStatus {
+        BalanceStatus::from(self.is_intermediate)
+    }
+
+    /// The tag this balance was or would be written with, e.g. `:62M:`.
+    pub fn tag(&self) -> Tag {
+        self.kind.tag(self.status())
+    }
+}
+
+impl From<BalanceInfo> for Balance {
+    fn from(balance: BalanceInfo) -> Balance {
+        Balance {
+            is_intermediate: balance.is_intermediate,
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code,
+            amount: balance.amount,
+        }
+    }
+}
+
+impl From<BalanceInfo> for AvailableBalance {
+    fn from(balance: BalanceInfo) -> AvailableBalance {
+        AvailableBalance {
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code,
+            amount: balance.amount,
+        }
+    }
+}
+
+impl From<Balance> for AvailableBalance {
+    fn from(balance: Balance) -> AvailableBalance {
+        AvailableBalance {
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code,
+            amount: balance.amount,
+        }
+    }
+}
+
+impl Message {
+    /// All balances of this message in the order of their tags.
+    pub fn balances(&self) -> Vec<BalanceInfo> {
+        let mut balances = vec![
+            BalanceInfo::from_balance(BalanceKind::Opening, &self.opening_balance),
+            BalanceInfo::from_balance(BalanceKind::Closing, &self.closing_balance),
+        ];
+        balances.extend(self.closing_available_balance.iter().map(|balance| {
+            BalanceInfo::from_available_balance(BalanceKind::ClosingAvailable, balance)
+        }));
+        balances.extend(self.forward_available_balances.iter().map(|balance| {
+            BalanceInfo::from_available_balance(BalanceKind::ForwardAvailable, balance)
+        }));
+        balances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn round_trip() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60M:C090924EUR1,\r\n\
+            :62F:D090924EUR2,\r\n\
+            :64:C090924EUR3,\r\n\
+            :65:C090925EUR4,\r\n\
+            :65:C090926EUR5,\r\n";
+        let message = &parse_mt940(input).unwrap()[0];
+        let balances = message.balances();
+
+        let amounts = balances
+            .iter()
+            .map(BalanceInfo::signed_amount)
+            .collect::<Vec<_>>();
+        assert_eq!(amounts, [1, -2, 3, 4, 5].map(Decimal::from).to_vec());
+        let tags = balances.iter().map(BalanceInfo::tag).collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            ["60M", "62F", "64", "65", "65"].map(Tag::from).to_vec()
+        );
+        for balance in &balances {
+            let (kind, status) = BalanceKind::from_tag(&balance.tag()).unwrap();
+            assert_eq!((kind, status), (balance.kind, balance.status()));
+        }
+
+        assert_eq!(Balance::from(balances[0].clone()), message.opening_balance);
+        assert_eq!(Balance::from(balances[1].clone()), message.closing_balance);
+        assert_eq!(
+            Some(AvailableBalance::from(balances[2].clone())),
+            message.closing_available_balance
+        );
+        assert_eq!(
+            AvailableBalance::from(balances[4].clone()),
+            message.forward_available_balances[1]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use clap::Parser;
+use mt940::{parse_mt940, serialize_mt940, ParsedStatements};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Convert json to mt940 statement.
+///
+/// The input has to be in the format produced by sta2json, with or
+/// without --versioned. The resulting statement is parsed again to
+/// make sure it's valid.
+#[derive(Parser)]
+#[clap(name = "json2sta", author, about, version)]
+pub struct Args {
+    /// Input file in JSON format.
+    #[clap()]
+    pub json: PathBuf,
+
+    /// Output mt940 statement.
+    #[clap()]
+    pub output: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let input = fs::read_to_string(args.json)?;
+    let parsed: ParsedStatements = serde_json::from_str(&input)?;
+
+    let statement = serialize_mt940(&parsed.messages);
+
+    // Values edited in the JSON might not be representable in MT940.
+    parse_mt940(&statement)?;
+
+    if let Some(output) = args.output {
+        // Write to a file.
+        fs::write(output, statement)?;
+    } else {
+        // Write to stdout instead.
+        io::stdout().write_all(statement.as_bytes())?;
+    };
+
+    Ok(())
+}
@@ -0,0 +1,363 @@
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use mt940::export::json::{self, JsonOptions};
+use mt940::sanitizers::sanitize;
+use mt940::{
+    anonymize, diagnose_mt940, diff_messages, lint, parse_mt940, parse_mt940_with_config,
+    serialize_mt940_with_line_ending, summarize, summarize_by_period, AnonymizeOptions, LineEnding,
+    Message, ParserConfig, Period, Severity,
+};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Work with mt940 statements.
+#[derive(Parser)]
+#[clap(name = "mt940", author, about, version)]
+pub struct Args {
+    /// Enable strict parsing. When this is on, input won't be sanitized.
+    #[clap(short, long, global = true)]
+    pub strict: bool,
+
+    /// Print the JSON Schema of the JSON output and exit.
+    #[cfg(feature = "schemars")]
+    #[clap(long, exclusive = true)]
+    pub schema: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convert a statement into another format.
+    Convert {
+        /// Output format.
+        #[clap(long, visible_alias = "format", value_enum, default_value_t = Format::Json)]
+        to: Format,
+
+        /// How to write amounts in JSON.
+        #[clap(long, value_enum, default_value_t = AmountFormat::String)]
+        amount_format: AmountFormat,
+
+        /// Number of decimal places of amounts in JSON if the amount format is fixed.
+        #[clap(long, default_value_t = 2)]
+        amount_scale: u32,
+
+        /// Format of dates in JSON, e.g. "%d.%m.%Y". Defaults to ISO 8601.
+        #[clap(long, value_parser = parse_date_format)]
+        date_format: Option<String>,
+
+        /// Input mt940 statement.
+        statement: PathBuf,
+
+        /// Output file. Defaults to stdout.
+        output: Option<PathBuf>,
+    },
+
+    /// Report every syntax error and every message whose balances do not add up.
+    Validate {
+        /// Input mt940 statement.
+        statement: PathBuf,
+    },
+
+    /// Check a statement against the spec and report every finding with its severity.
+    ///
+    /// The statement is never sanitized. Exits with 1 if there are errors.
+    Lint {
+        /// Input mt940 statement.
+        statement: PathBuf,
+    },
+
+    /// Print the totals of a statement per currency.
+    Summarize {
+        /// Print the totals and closing balance of every account per period instead.
+        #[clap(long, value_enum)]
+        period: Option<SummaryPeriod>,
+
+        /// Input mt940 statement.
+        statement: PathBuf,
+    },
+
+    /// Show which statement lines and balances changed between two deliveries of a statement.
+    ///
+    /// Exits with 1 if there are differences.
+    Diff {
+        /// Original mt940 statement.
+        old: PathBuf,
+
+        /// Corrected mt940 statement.
+        new: PathBuf,
+    },
+
+    /// Replace account ids, references and counterparties with pseudonyms.
+    ///
+    /// Amounts, dates and balances are kept as they are.
+    Anonymize {
+        /// Mixed into every pseudonym.
+        #[clap(long, default_value = "")]
+        salt: String,
+
+        /// Scramble all of tag 86 instead of only known counterparty fields.
+        #[clap(long)]
+        redact_information: bool,
+
+        /// Line ending of the output.
+        #[clap(long, value_enum, default_value_t = OutputLineEnding::Crlf)]
+        line_ending: OutputLineEnding,
+
+        /// Input mt940 statement.
+        statement: PathBuf,
+
+        /// Output mt940 statement. Defaults to stdout.
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    #[cfg(feature = "csv")]
+    Csv,
+    #[cfg(feature = "ofx")]
+    Ofx,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    Table,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SummaryPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl From<SummaryPeriod> for Period {
+    fn from(period: SummaryPeriod) -> Period {
+        match period {
+            SummaryPeriod::Day => Period::Day,
+            SummaryPeriod::Week => Period::Week,
+            SummaryPeriod::Month => Period::Month,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputLineEnding {
+    Crlf,
+    Lf,
+}
+
+impl From<OutputLineEnding> for LineEnding {
+    fn from(line_ending: OutputLineEnding) -> LineEnding {
+        match line_ending {
+            OutputLineEnding::Crlf => LineEnding::CrLf,
+            OutputLineEnding::Lf => LineEnding::Lf,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum AmountFormat {
+    /// Strings in their shortest form, e.g. "100".
+    String,
+    /// Strings with a fixed number of decimal places, e.g. "100.00".
+    Fixed,
+    /// Numbers.
+    Number,
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    #[cfg(feature = "schemars")]
+    if args.schema {
+        println!("{}", serde_json::to_string_pretty(&mt940::json_schema())?);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let Some(command) = args.command else {
+        Args::command()
+            .error(ErrorKind::MissingSubcommand, "a subcommand is required")
+            .exit();
+    };
+    match command {
+        Command::Convert {
+            to,
+            amount_format,
+            amount_scale,
+            date_format,
+            statement,
+            output,
+        } => {
+            let messages = read_statement(&statement, args.strict)?;
+            let converted = match to {
+                Format::Json => {
+                    let mut options =
+                        JsonOptions::new()
+                            .pretty(true)
+                            .amount_format(match amount_format {
+                                AmountFormat::String => json::AmountFormat::String,
+                                AmountFormat::Fixed => json::AmountFormat::Fixed(amount_scale),
+                                AmountFormat::Number => json::AmountFormat::Number,
+                            });
+                    if let Some(ref date_format) = date_format {
+                        options = options.date_format(date_format);
+                    }
+                    json::to_json(&messages, &options)?
+                }
+                #[cfg(feature = "csv")]
+                Format::Csv => mt940::export::csv::to_csv(&messages)?,
+                #[cfg(feature = "ofx")]
+                Format::Ofx => mt940::export::ofx::to_ofx(&messages),
+                #[cfg(feature = "yaml")]
+                Format::Yaml => mt940::report::to_yaml(&messages)?,
+                Format::Table => mt940::report::to_table(&messages),
+            };
+            write_output(output, &converted)?;
+        }
+        Command::Validate { statement } => {
+            let input = read_input(&statement, args.strict)?;
+            let diagnostics = diagnose_mt940(&input);
+            for diagnostic in &diagnostics {
+                println!("{}: {}", statement.display(), diagnostic);
+            }
+            if !diagnostics.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+            println!("{} messages are valid", parse_mt940(&input)?.len());
+        }
+        Command::Lint { statement } => {
+            let input = fs::read_to_string(&statement)?;
+            let findings = lint(&input);
+            for finding in &findings {
+                println!("{}: {}", statement.display(), finding);
+            }
+            if findings
+                .iter()
+                .any(|finding| finding.severity == Severity::Error)
+            {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Summarize { period, statement } => {
+            let messages = read_statement(&statement, args.strict)?;
+            match period {
+                Some(period) => print_period_summary(&messages, period.into()),
+                None => print_summary(&messages),
+            }
+        }
+        Command::Diff { old, new } => {
+            let old = read_statement(&old, args.strict)?;
+            let new = read_statement(&new, args.strict)?;
+            let diff = diff_messages(&old, &new);
+            print!("{}", diff);
+            if !diff.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Anonymize {
+            salt,
+            redact_information,
+            line_ending,
+            statement,
+            output,
+        } => {
+            let mut messages = read_statement(&statement, args.strict)?;
+            let options = AnonymizeOptions::new()
+                .salt(&salt)
+                .redact_information(redact_information);
+            for message in &mut messages {
+                anonymize(message, &options);
+            }
+            write_output(
+                output,
+                &serialize_mt940_with_line_ending(&messages, line_ending.into()),
+            )?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reject date formats which can't format dates before reading any statement.
+fn parse_date_format(date_format: &str) -> Result<String, String> {
+    let options = JsonOptions::new().date_format(date_format);
+    json::to_json(&[], &options).map_err(|err| err.to_string())?;
+    Ok(date_format.to_string())
+}
+
+fn read_input(path: &PathBuf, strict: bool) -> io::Result<String> {
+    let input = fs::read_to_string(path)?;
+
+    // Do some sanitizing if not running in strict mode.
+    if strict {
+        Ok(input)
+    } else {
+        Ok(sanitize(&input))
+    }
+}
+
+fn read_statement(
+    path: &PathBuf,
+    strict: bool,
+) -> Result<Vec<Message>, Box<dyn std::error::Error>> {
+    let config = ParserConfig::new().source_label(&path.display().to_string());
+    Ok(parse_mt940_with_config(
+        &read_input(path, strict)?,
+        &config,
+    )?)
+}
+
+fn write_output(output: Option<PathBuf>, content: &str) -> io::Result<()> {
+    if let Some(output) = output {
+        // Write to a file.
+        fs::write(output, content)
+    } else {
+        // Write to stdout instead.
+        io::stdout().write_all(content.as_bytes())
+    }
+}
+
+fn print_summary(messages: &[Message]) {
+    let summary = summarize(messages);
+    println!(
+        "{:<8} {:>10} {:>16} {:>16} {:>16}",
+        "Currency", "Lines", "Credits", "Debits", "Net"
+    );
+    for (currency, currency_summary) in &summary.currencies {
+        let totals = &currency_summary.totals;
+        println!(
+            "{:<8} {:>10} {:>16} {:>16} {:>16}",
+            currency,
+            totals.count(),
+            totals.credits,
+            totals.debits,
+            totals.net()
+        );
+    }
+}
+
+fn print_period_summary(messages: &[Message], period: Period) {
+    println!(
+        "{:<34} {:<8} {:<10} {:>10} {:>16} {:>16} {:>16}",
+        "Account", "Currency", "Period", "Lines", "Credits", "Debits", "Closing balance"
+    );
+    for (account, summaries) in summarize_by_period(messages, period) {
+        for summary in summaries {
+            let totals = &summary.totals;
+            println!(
+                "{:<34} {:<8} {:<10} {:>10} {:>16} {:>16} {:>16}",
+                account.account_id,
+                account.iso_currency_code,
+                summary.start,
+                totals.count(),
+                totals.credits,
+                totals.debits,
+                summary.closing_balance
+            );
+        }
+    }
+}
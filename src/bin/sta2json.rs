@@ -1,9 +1,20 @@
 use clap::Parser;
-use mt940::parse_mt940;
-use mt940::sanitizers::sanitize;
+use mt940::sanitizers::sanitize_with_report;
+use mt940::{parse_mt940, parse_mt940_iter, ParseError, ParsedStatements, Span};
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Exit code if reading the input or writing the output fails.
+const EXIT_IO_ERROR: u8 = 3;
+
+/// Exit code if the input isn't syntactically valid MT940.
+const EXIT_PARSE_ERROR: u8 = 4;
+
+/// Exit code if the input is valid MT940 syntax but its content isn't, for instance because tags
+/// are in the wrong order or a date doesn't exist.
+const EXIT_VALIDATION_ERROR: u8 = 5;
 
 /// Convert mt940 statement to json.
 ///
@@ -11,6 +22,9 @@ use std::path::PathBuf;
 /// try to fit all found characters into the allowable SWIFT charset
 /// before attempting conversion. You can turn off this behavior by
 /// enabling strict mode.
+///
+/// Exits with 3 on I/O errors, with 4 if the input isn't valid mt940
+/// syntax and with 5 if its content is invalid.
 #[derive(Parser)]
 #[clap(name = "sta2json", author, about, version)]
 pub struct Args {
@@ -18,7 +32,17 @@ pub struct Args {
     #[clap(short, long)]
     pub strict: bool,
 
-    /// Input mt940 statement.
+    /// Write one message per line (JSON Lines) instead of a single array.
+    #[clap(long)]
+    pub jsonl: bool,
+
+    /// Wrap the messages in an object along with the version of the format.
+    ///
+    /// Use this to store the output long-term. json2sta reads both.
+    #[clap(long, conflicts_with = "jsonl")]
+    pub versioned: bool,
+
+    /// Input mt940 statement. Use "-" to read from stdin.
     #[clap()]
     pub statement: PathBuf,
 
@@ -27,27 +51,132 @@ pub struct Args {
     pub output: Option<PathBuf>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// Everything that can go wrong in here.
+enum Error {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Error {
+        Error::Io(err.into())
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        Error::Parse(err)
+    }
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
 
-    let mut input = fs::read_to_string(args.statement)?;
+    let input = match read_input(&args.statement) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {}: {}", args.statement.display(), err);
+            return ExitCode::from(EXIT_IO_ERROR);
+        }
+    };
 
     // Do some sanitizing if not running in strict mode.
-    if !args.strict {
-        input = sanitize(&input);
-    }
+    let (sanitized, report) = if args.strict {
+        (None, None)
+    } else {
+        let (sanitized, report) = sanitize_with_report(&input);
+        (Some(sanitized), Some(report))
+    };
 
-    let parsed = parse_mt940(&input).unwrap_or_else(|e| panic!("{}", e));
+    match convert(sanitized.as_deref().unwrap_or(&input), &args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::Io(err)) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(EXIT_IO_ERROR)
+        }
+        Err(Error::Parse(err)) => {
+            // Point at the line of the input rather than the one of the sanitized input.
+            let err = match report {
+                Some(report) => err.map_span(|span| Span {
+                    line: report.original_line(span.line),
+                    ..span
+                }),
+                None => err,
+            };
+            report_parse_error(&err, &input, &args.statement);
+            match err {
+                ParseError::PestParseError(..) => ExitCode::from(EXIT_PARSE_ERROR),
+                ParseError::IoError { .. } => ExitCode::from(EXIT_IO_ERROR),
+                _ => ExitCode::from(EXIT_VALIDATION_ERROR),
+            }
+        }
+    }
+}
 
-    let json = serde_json::to_string_pretty(&parsed)?;
+fn read_input(statement: &Path) -> io::Result<String> {
+    if statement.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(statement)
+    }
+}
 
-    if let Some(output) = args.output {
+fn convert(input: &str, args: &Args) -> Result<(), Error> {
+    let mut out: Box<dyn Write> = if let Some(ref output) = args.output {
         // Write to a file.
-        fs::write(output, json)?;
+        Box::new(io::BufWriter::new(fs::File::create(output)?))
     } else {
         // Write to stdout instead.
-        io::stdout().write_all(json.as_bytes())?;
+        Box::new(io::BufWriter::new(io::stdout().lock()))
     };
 
+    if args.jsonl {
+        // Write messages as soon as they are parsed so that huge statements don't have to be held
+        // in memory as a whole.
+        for message in parse_mt940_iter(input) {
+            serde_json::to_writer(&mut out, &message?)?;
+            out.write_all(b"\n")?;
+        }
+    } else {
+        let parsed = parse_mt940(input)?;
+        if args.versioned {
+            serde_json::to_writer_pretty(&mut out, &ParsedStatements::new(parsed))?;
+        } else {
+            serde_json::to_writer_pretty(&mut out, &parsed)?;
+        }
+    }
+    out.flush()?;
+
     Ok(())
 }
+
+/// Print `err` along with the line of `input` it most likely refers to.
+fn report_parse_error(err: &ParseError, input: &str, statement: &Path) {
+    eprintln!("error: {}", err);
+
+    let Some((line_no, line)) = locate_error(err, input) else {
+        return;
+    };
+    let gutter = " ".repeat(line_no.to_string().len());
+    eprintln!("{}--> {}:{}", gutter, statement.display(), line_no);
+    eprintln!("{} |", gutter);
+    eprintln!("{} | {}", line_no, line);
+    eprintln!("{} |", gutter);
+}
+
+/// Find the 1-based number and content of the line `err` refers to.
+fn locate_error<'a>(err: &ParseError, input: &'a str) -> Option<(usize, &'a str)> {
+    let line_no = err.span()?.line;
+    input
+        .lines()
+        .nth(line_no.checked_sub(1)?)
+        .map(|line| (line_no, line))
+}
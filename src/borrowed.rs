@@ -0,0 +1,634 @@
+//! Zero-copy parsing into types which borrow their text from the input.
+//!
+//! The owned [`Message`] is built from a [`MessageRef`] as well, so this is where the state
+//! machine of MT940 lives.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use log::debug;
+use pest::iterators::Pair;
+use rust_decimal::Decimal;
+use serde_derive::Serialize;
+
+use crate::config::NonSwiftTags;
+use crate::errors::{ErrorLocation, RequiredTagNotFoundError, UnexpectedTagError};
+use crate::tag_parsers::{
+    parse_20_tag, parse_21_tag, parse_25_tag, parse_25p_tag, parse_28_tag, parse_60_tag,
+    parse_61_tag, parse_62_tag, parse_64_tag, parse_65_tag, parse_86_tag,
+};
+use crate::{
+    allowed_successors, parse_message_refs, relaxed_successors, with_current_span,
+    AvailableBalance, Balance, DebitOrCredit, EntryType, ExtDebitOrCredit, Field, Message,
+    MessageSource, ParseError, ParserConfig, Rule, SepaPurpose, Span, StatementLine,
+    StructuredInformation, Tag, TransactionTypeIdentificationCode, FIRST_TAGS,
+};
+
+/// A field which borrows its tag and value.
+///
+/// Unlike [`Field::value`], line breaks in the value are kept as they are in the input.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FieldRef<'a> {
+    pub(crate) tag: &'a str,
+    pub(crate) value: &'a str,
+    pub(crate) span: Option<Span>,
+    pub(crate) raw: Option<&'a str>,
+}
+
+impl<'a> FieldRef<'a> {
+    /// Build a [`FieldRef`] from a `field` rule.
+    pub(crate) fn from_pair(
+        pair: Pair<'a, Rule>,
+    ) -> Result<FieldRef<'a>, Box<pest::error::Error<Rule>>> {
+        let span = pair.as_span();
+        let mut inner = pair.into_inner();
+        let (Some(tag), Some(value)) = (inner.next(), inner.next()) else {
+            return Err(Box::new(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: "Field without tag or value".to_string(),
+                },
+                span,
+            )));
+        };
+        Ok(FieldRef {
+            tag: tag.into_inner().as_str(),
+            value: value.as_str().trim(),
+            span: Some(Span::from_pest(span)),
+            raw: None,
+        })
+    }
+}
+
+impl<'a> From<&'a Field> for FieldRef<'a> {
+    fn from(field: &'a Field) -> FieldRef<'a> {
+        FieldRef {
+            tag: field.tag.as_str(),
+            value: &field.value,
+            span: field.span,
+            raw: field.raw.as_deref(),
+        }
+    }
+}
+
+impl From<FieldRef<'_>> for Field {
+    fn from(field: FieldRef<'_>) -> Field {
+        Field {
+            tag: Tag::from(field.tag),
+            value: normalize_newlines(field.value).into_owned(),
+            span: field.span,
+            raw: field.raw.map(str::to_string),
+        }
+    }
+}
+
+/// Like [`Message`] but borrowing references and texts from the input.
+///
+/// Use [`parse_mt940_borrowed`] to get these and [`Message::from`] to turn them into owned
+/// messages. See [`Message`] for the meaning of the fields.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct MessageRef<'a> {
+    pub transaction_ref_no: &'a str,
+    pub ref_to_related_msg: Option<&'a str>,
+    pub account_id: &'a str,
+    pub account_identifier_bic: Option<&'a str>,
+    pub statement_no: &'a str,
+    pub sequence_no: Option<&'a str>,
+    pub opening_balance: Balance,
+    pub statement_lines: Vec<StatementLineRef<'a>>,
+    pub closing_balance: Balance,
+    pub closing_available_balance: Option<AvailableBalance>,
+    pub forward_available_balances: Vec<AvailableBalance>,
+
+    /// Only owned if it spans multiple lines or `:86:` tags.
+    pub information_to_account_owner: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub non_swift_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+
+    /// Only owned if it is made up of fields which aren't next to each other in the input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<MessageSource>,
+}
+
+/// Like [`StatementLine`] but borrowing references and texts from the input.
+///
+/// See [`StatementLine`] for the meaning of the fields.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct StatementLineRef<'a> {
+    pub value_date: NaiveDate,
+    pub entry_date: Option<NaiveDate>,
+    pub ext_debit_credit_indicator: ExtDebitOrCredit,
+    pub funds_code: Option<&'a str>,
+    pub amount: Decimal,
+    pub entry_type: EntryType,
+    pub transaction_type_ident_code: TransactionTypeIdentificationCode,
+    pub customer_ref: &'a str,
+    pub bank_ref: Option<&'a str>,
+    pub supplementary_details: Option<&'a str>,
+
+    /// Only owned if it spans multiple lines or `:86:` tags.
+    pub information_to_account_owner: Option<Cow<'a, str>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_information: Option<StructuredInformation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sepa_purpose: Option<SepaPurpose>,
+
+    /// Only owned if it is made up of fields which aren't next to each other in the input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+}
+
+impl StatementLineRef<'_> {
+    /// The amount of this statement line which is negative for debits, see
+    /// [`StatementLine::signed_amount`].
+    pub fn signed_amount(&self) -> Decimal {
+        self.ext_debit_credit_indicator.apply_sign(self.amount)
+    }
+}
+
+impl From<StatementLineRef<'_>> for StatementLine {
+    fn from(statement_line: StatementLineRef<'_>) -> StatementLine {
+        StatementLine {
+            value_date: statement_line.value_date,
+            entry_date: statement_line.entry_date,
+            ext_debit_credit_indicator: statement_line.ext_debit_credit_indicator,
+            funds_code: statement_line.funds_code.map(str::to_string),
+            amount: statement_line.amount,
+            entry_type: statement_line.entry_type,
+            transaction_type_ident_code: statement_line.transaction_type_ident_code,
+            customer_ref: statement_line.customer_ref.to_string(),
+            bank_ref: statement_line.bank_ref.map(str::to_string),
+            supplementary_details: statement_line.supplementary_details.map(str::to_string),
+            information_to_account_owner: statement_line
+                .information_to_account_owner
+                .map(Cow::into_owned),
+            information_lines: to_strings(statement_line.information_lines),
+            structured_information: statement_line.structured_information,
+            sepa_purpose: statement_line.sepa_purpose,
+            raw: statement_line.raw.map(Cow::into_owned),
+            index: statement_line.index,
+        }
+    }
+}
+
+impl From<MessageRef<'_>> for Message {
+    fn from(message: MessageRef<'_>) -> Message {
+        Message {
+            transaction_ref_no: message.transaction_ref_no.to_string(),
+            ref_to_related_msg: message.ref_to_related_msg.map(str::to_string),
+            account_id: message.account_id.to_string(),
+            account_identifier_bic: message.account_identifier_bic.map(str::to_string),
+            statement_no: message.statement_no.to_string(),
+            sequence_no: message.sequence_no.map(str::to_string),
+            opening_balance: message.opening_balance,
+            statement_lines: message
+                .statement_lines
+                .into_iter()
+                .map(StatementLine::from)
+                .collect(),
+            closing_balance: message.closing_balance,
+            closing_available_balance: message.closing_available_balance,
+            forward_available_balances: message.forward_available_balances,
+            information_to_account_owner: message.information_to_account_owner.map(Cow::into_owned),
+            information_lines: to_strings(message.information_lines),
+            non_swift_fields: message.non_swift_fields,
+            custom_fields: message.custom_fields,
+            raw: message.raw.map(Cow::into_owned),
+            source: message.source,
+        }
+    }
+}
+
+fn to_strings(lines: Vec<&str>) -> Vec<String> {
+    lines.into_iter().map(str::to_string).collect()
+}
+
+/// Parse and validate a MT940 statement without copying its texts.
+///
+/// This is the same as [`parse_mt940`] except that references and other texts of the resulting
+/// [`MessageRef`]s point into `statement`. This saves an allocation per field which adds up when
+/// processing lots of statements.
+///
+/// [`parse_mt940`]: crate::parse_mt940
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, parse_mt940_borrowed, Message};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:11100304030101391234\r\n\
+///     :62F:C090930EUR53126,94\r\n";
+///
+/// let messages = parse_mt940_borrowed(input).unwrap();
+/// assert_eq!(messages[0].statement_lines[0].customer_ref, "1110030403010139");
+///
+/// let owned = messages.into_iter().map(Message::from).collect::<Vec<_>>();
+/// assert_eq!(owned, parse_mt940(input).unwrap());
+/// ```
+pub fn parse_mt940_borrowed(statement: &str) -> Result<Vec<MessageRef<'_>>, ParseError> {
+    parse_mt940_borrowed_with_config(statement, &ParserConfig::default())
+}
+
+/// Like [`parse_mt940_borrowed`] but with a custom [`ParserConfig`].
+///
+/// The messages can only borrow from `statement` as it is, so
+/// [`ParserConfig::allow_non_swift_chars`] and [`ParserConfig::strip_stuff_between_messages`]
+/// don't apply. Sanitize the statement beforehand instead, e.g. with [`sanitize`].
+///
+/// [`sanitize`]: crate::sanitizers::sanitize
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940_borrowed_with_config, ParserConfig};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:11100304030101391234\r\n";
+///
+/// let config = ParserConfig::new().allow_missing_closing_balance(true).keep_raw(true);
+/// let messages = parse_mt940_borrowed_with_config(input, &config).unwrap();
+/// assert_eq!(messages[0].closing_balance.amount.to_string(), "53900.12");
+/// assert_eq!(messages[0].raw.as_deref(), Some(input));
+/// ```
+pub fn parse_mt940_borrowed_with_config<'a>(
+    statement: &'a str,
+    config: &ParserConfig,
+) -> Result<Vec<MessageRef<'a>>, ParseError> {
+    parse_message_refs(statement, config, |span| span, |message| message)
+}
+
+impl<'a> MessageRef<'a> {
+    /// Build a message from its fields according to `config`.
+    ///
+    /// If `errors` is given, any error in a tag which the message can do without (`:21:`, `:61:`,
+    /// `:86:`, `:64:` and `:65:`) is pushed to it and the tag is skipped. A skipped `:61:` takes
+    /// its `:86:` tags along.
+    pub(crate) fn from_fields_with_config(
+        fields: &[FieldRef<'a>],
+        config: &ParserConfig,
+        errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<MessageRef<'a>, ParseError> {
+        let message_span = fields.first().and_then(|field| field.span);
+        with_current_span(message_span, |current_span| {
+            MessageRef::from_fields_tracking_span(fields, config, errors, current_span)
+        })
+    }
+
+    /// Implementation of [`MessageRef::from_fields_with_config`] which keeps `current_span`
+    /// pointed at the field being parsed.
+    fn from_fields_tracking_span(
+        fields: &[FieldRef<'a>],
+        config: &ParserConfig,
+        mut errors: Option<&mut Vec<ParseError>>,
+        current_span: &mut Option<Span>,
+    ) -> Result<MessageRef<'a>, ParseError> {
+        let message_span = *current_span;
+        // Only a few tags may follow after each specific tag.
+        let mut current_acceptable_tags = FIRST_TAGS;
+
+        let mut transaction_ref_no = None;
+        let mut ref_to_related_msg = None;
+        let mut account_id = None;
+        let mut account_identifier_bic = None;
+        let mut statement_no = None;
+        let mut sequence_no = None;
+        let mut opening_balance = None;
+        let mut statement_lines: Vec<StatementLineRef> = vec![];
+        let mut closing_balance = None;
+        let mut closing_available_balance = None;
+        let mut forward_available_balances = vec![];
+        let mut information_to_account_owner = None;
+        let mut information_lines = vec![];
+        let mut non_swift_fields = vec![];
+        let mut custom_fields = BTreeMap::new();
+
+        let mut last_tag: Option<Tag> = None;
+        let mut skipped_statement_line = false;
+        let mut raw = None;
+
+        for &field in fields {
+            debug!("Now parsing tag: {}", field.tag);
+            *current_span = field.span;
+            append_raw(&mut raw, field);
+
+            // We reject unknown tags unless configured otherwise. They don't take part in the
+            // state machine at all.
+            let Some(tag) = Tag::known(field.tag).filter(Tag::is_mt940) else {
+                if let Some(handler) = config.tag_handlers.get(field.tag) {
+                    handler(&Field::from(field), &mut custom_fields)?;
+                    continue;
+                }
+                if field.tag == "NS" {
+                    match config.non_swift_tags {
+                        NonSwiftTags::Reject => {
+                            return Err(ParseError::UnknownTagError(
+                                field.tag.to_string(),
+                                ErrorLocation::default(),
+                            ));
+                        }
+                        NonSwiftTags::Ignore => (),
+                        NonSwiftTags::Collect => non_swift_fields.push(Field::from(field)),
+                        NonSwiftTags::AsInformation => {
+                            let line = normalize_newlines(field.value);
+                            match (&last_tag, statement_lines.last_mut()) {
+                                (Some(Tag::StatementLine | Tag::InformationToAccountOwner), _)
+                                    if skipped_statement_line => {}
+                                (
+                                    Some(Tag::StatementLine | Tag::InformationToAccountOwner),
+                                    Some(sl),
+                                ) => {
+                                    append_raw(&mut sl.raw, field);
+                                    append_line(&mut sl.information_to_account_owner, line)
+                                }
+                                _ => append_line(&mut information_to_account_owner, line),
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if config.ignore_unknown_tags {
+                    debug!("Ignoring unknown tag: {}", field.tag);
+                    continue;
+                }
+                return Err(ParseError::UnknownTagError(
+                    field.tag.to_string(),
+                    ErrorLocation::default(),
+                ));
+            };
+
+            // We reject unexpected tags.
+            if !current_acceptable_tags.contains(&tag) {
+                return Err(UnexpectedTagError::new(
+                    tag,
+                    last_tag,
+                    current_acceptable_tags.to_vec(),
+                )
+                .into());
+            }
+
+            match tag {
+                Tag::TransactionRef => {
+                    transaction_ref_no = Some(parse_20_tag(field)?);
+                }
+                Tag::RelatedRef => {
+                    ref_to_related_msg = recover(parse_21_tag(field), field.span, &mut errors)?;
+                }
+                Tag::AccountId => {
+                    account_id = Some(parse_25_tag(field)?);
+                }
+                Tag::AccountIdWithBic => {
+                    let (account, identifier_code) = parse_25p_tag(field)?;
+                    account_id = Some(account);
+                    account_identifier_bic = Some(identifier_code);
+                }
+                Tag::StatementNoDeprecated | Tag::StatementNo => {
+                    let (number, sequence) = parse_28_tag(field)?;
+                    statement_no = Some(number);
+                    sequence_no = sequence;
+                }
+                Tag::IntermediateOpeningBalance | Tag::OpeningBalance => {
+                    opening_balance = Some(parse_60_tag(field, config.year_pivot)?);
+                }
+                Tag::StatementLine => {
+                    match recover(
+                        parse_61_tag(field, config.year_pivot),
+                        field.span,
+                        &mut errors,
+                    )? {
+                        Some(mut statement_line) => {
+                            statement_line.raw = field.raw.map(Cow::Borrowed);
+                            statement_lines.push(statement_line);
+                            skipped_statement_line = false;
+                        }
+                        None => skipped_statement_line = true,
+                    }
+                }
+                Tag::InformationToAccountOwner => {
+                    let Some(info) = recover(parse_86_tag(field), field.span, &mut errors)? else {
+                        last_tag = Some(tag);
+                        current_acceptable_tags = successors(config, field.tag);
+                        continue;
+                    };
+                    let lines = match config.keep_information_lines {
+                        true => info.lines().collect(),
+                        false => vec![],
+                    };
+                    let mut info = normalize_newlines(info);
+                    if config.join_information_lines {
+                        info = Cow::Owned(info.replace('\n', ""));
+                    }
+                    // If the last tag was either :61: or :86: then this tag belongs to that
+                    // previous tag and we'll attach the information to the previous tag. After
+                    // the closing balance, it always belongs to the message.
+                    match &last_tag {
+                        Some(Tag::InformationToAccountOwner) if closing_balance.is_some() => {
+                            append_information(&mut information_to_account_owner, info);
+                            information_lines.extend(lines);
+                        }
+                        Some(Tag::StatementLine | Tag::InformationToAccountOwner)
+                            if skipped_statement_line => {}
+                        Some(Tag::StatementLine | Tag::InformationToAccountOwner) => {
+                            if let Some(sl) = statement_lines.last_mut() {
+                                append_raw(&mut sl.raw, field);
+                                append_information(&mut sl.information_to_account_owner, info);
+                                sl.information_lines.extend(lines);
+                            }
+                        }
+                        Some(
+                            Tag::IntermediateClosingBalance
+                            | Tag::ClosingBalance
+                            | Tag::ClosingAvailableBalance
+                            | Tag::ForwardAvailableBalance,
+                        ) => {
+                            append_information(&mut information_to_account_owner, info);
+                            information_lines.extend(lines);
+                        }
+                        _ => (),
+                    }
+                }
+                Tag::IntermediateClosingBalance | Tag::ClosingBalance => {
+                    closing_balance = Some(parse_62_tag(field, config.year_pivot)?);
+                }
+                Tag::ClosingAvailableBalance => {
+                    closing_available_balance = recover(
+                        parse_64_tag(field, config.year_pivot),
+                        field.span,
+                        &mut errors,
+                    )?;
+                }
+                Tag::ForwardAvailableBalance => {
+                    if let Some(balance) = recover(
+                        parse_65_tag(field, config.year_pivot),
+                        field.span,
+                        &mut errors,
+                    )? {
+                        forward_available_balances.push(balance);
+                    }
+                }
+                _ => (),
+            }
+
+            current_acceptable_tags = successors(config, field.tag);
+            last_tag = Some(tag);
+        }
+        *current_span = message_span;
+
+        for statement_line in &mut statement_lines {
+            let info = statement_line.information_to_account_owner.as_deref();
+            if let Some(format) = config.information_format {
+                statement_line.structured_information =
+                    info.and_then(|info| StructuredInformation::parse(format, info));
+            }
+            if config.sepa_purpose {
+                statement_line.sepa_purpose = info.and_then(SepaPurpose::from_information);
+            }
+        }
+
+        let opening_balance = opening_balance.ok_or_else(|| RequiredTagNotFoundError::new("60"))?;
+        let closing_balance = match closing_balance {
+            Some(closing_balance) => closing_balance,
+            None if config.allow_missing_closing_balance => {
+                derive_closing_balance(&opening_balance, &statement_lines)
+            }
+            None => return Err(RequiredTagNotFoundError::new("62").into()),
+        };
+
+        let message = MessageRef {
+            transaction_ref_no: transaction_ref_no
+                .ok_or_else(|| RequiredTagNotFoundError::new("20"))?,
+            ref_to_related_msg,
+            account_id: account_id.ok_or_else(|| RequiredTagNotFoundError::new("25"))?,
+            account_identifier_bic,
+            statement_no: statement_no.ok_or_else(|| RequiredTagNotFoundError::new("28C"))?,
+            sequence_no,
+            opening_balance,
+            statement_lines,
+            closing_balance,
+            closing_available_balance,
+            forward_available_balances,
+            information_to_account_owner,
+            information_lines,
+            non_swift_fields,
+            custom_fields,
+            raw,
+            source: None,
+        };
+        #[cfg(feature = "iso4217")]
+        if config.validate_currencies {
+            crate::currency::validate_currencies(&message)?;
+        }
+        if config.strict_field_lengths {
+            if let Some(err) = message.validate_lengths().into_iter().next() {
+                return Err(err.into());
+            }
+        }
+
+        Ok(message)
+    }
+}
+
+/// The tags which may follow `tag` according to `config`.
+fn successors(config: &ParserConfig, tag: &str) -> &'static [Tag] {
+    if config.relaxed_tag_order {
+        relaxed_successors(tag)
+    } else {
+        allowed_successors(tag)
+    }
+}
+
+/// Calculate the closing balance of a message which doesn't state it.
+fn derive_closing_balance(
+    opening_balance: &Balance,
+    statement_lines: &[StatementLineRef<'_>],
+) -> Balance {
+    let amount = statement_lines
+        .iter()
+        .map(StatementLineRef::signed_amount)
+        .fold(opening_balance.signed_amount(), |sum, amount| sum + amount);
+    Balance {
+        is_intermediate: false,
+        debit_credit_indicator: if amount < Decimal::ZERO {
+            DebitOrCredit::Debit
+        } else {
+            DebitOrCredit::Credit
+        },
+        date: statement_lines
+            .last()
+            .map_or(opening_balance.date, |sl| sl.value_date),
+        iso_currency_code: opening_balance.iso_currency_code.clone(),
+        amount: amount.abs(),
+    }
+}
+
+/// Either pass on `result` or, if `errors` is given, record the error in it and carry on.
+fn recover<T>(
+    result: Result<T, ParseError>,
+    span: Option<Span>,
+    errors: &mut Option<&mut Vec<ParseError>>,
+) -> Result<Option<T>, ParseError> {
+    match (result, errors) {
+        (Ok(value), _) => Ok(Some(value)),
+        (Err(err), Some(errors)) => {
+            errors.push(err.at(span));
+            Ok(None)
+        }
+        (Err(err), None) => Err(err),
+    }
+}
+
+/// Add the raw text of `field` to `raw`, if it was kept.
+fn append_raw<'a>(raw: &mut Option<Cow<'a, str>>, field: FieldRef<'a>) {
+    if let Some(field_raw) = field.raw {
+        append_information(raw, Cow::Borrowed(field_raw));
+    }
+}
+
+/// Add `info` to `target`, only allocating if there is something already.
+fn append_information<'a>(target: &mut Option<Cow<'a, str>>, info: Cow<'a, str>) {
+    match target {
+        Some(existing) => existing.to_mut().push_str(&info),
+        None => *target = Some(info),
+    }
+}
+
+/// Like [`append_information`] but starts a new line for `line`.
+fn append_line<'a>(target: &mut Option<Cow<'a, str>>, line: Cow<'a, str>) {
+    match target {
+        Some(existing) => {
+            let existing = existing.to_mut();
+            existing.push('\n');
+            existing.push_str(&line);
+        }
+        None => *target = Some(line),
+    }
+}
+
+/// Replace `\r\n` with `\n`, only allocating if there is any.
+fn normalize_newlines(value: &str) -> Cow<'_, str> {
+    if value.contains('\r') {
+        Cow::Owned(value.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
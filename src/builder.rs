@@ -0,0 +1,882 @@
+//! Builders to construct valid [`Message`]s, [`StatementLine`]s and whole statements by hand.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::account::is_bic;
+use crate::errors::BuildError;
+use crate::sanitizers::is_swift_char;
+use crate::writer::{format_amount, serialize_mt940, statement_line_fields};
+use crate::{
+    AvailableBalance, Balance, DebitOrCredit, EntryType, ExtDebitOrCredit, Message, StatementLine,
+    TransactionTypeIdentificationCode,
+};
+
+/// Maximum length of an amount including its decimal separator.
+pub(crate) const AMOUNT_MAX_LENGTH: usize = 15;
+
+/// Builder for a [`Message`] which checks that it could be written as MT940.
+///
+/// The closing balance is calculated from the opening balance and the statement lines unless it
+/// is given explicitly in which case it has to match them.
+///
+/// # Example
+/// ```
+/// use chrono::NaiveDate;
+/// use mt940::{
+///     Balance, DebitOrCredit, ExtDebitOrCredit, MessageBuilder, StatementLineBuilder,
+///     TransactionTypeIdentificationCode,
+/// };
+/// use rust_decimal::Decimal;
+///
+/// let date = NaiveDate::from_ymd_opt(2009, 9, 25).unwrap();
+/// let message = MessageBuilder::new()
+///     .transaction_ref_no("3996-11-11111111")
+///     .account_id("DABADKKK/111111-11111111")
+///     .statement_no("00001")
+///     .opening_balance(Balance {
+///         is_intermediate: false,
+///         debit_credit_indicator: DebitOrCredit::Credit,
+///         date,
+///         iso_currency_code: "EUR".to_string(),
+///         amount: Decimal::new(5448404, 2),
+///     })
+///     .statement_line(
+///         StatementLineBuilder::new()
+///             .value_date(date)
+///             .ext_debit_credit_indicator(ExtDebitOrCredit::Debit)
+///             .amount(Decimal::new(58392, 2))
+///             .transaction_type_ident_code(TransactionTypeIdentificationCode::MSC)
+///             .build()
+///             .unwrap(),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(message.closing_balance.amount, Decimal::new(5390012, 2));
+/// ```
+#[derive(Debug, Default)]
+pub struct MessageBuilder {
+    transaction_ref_no: Option<String>,
+    ref_to_related_msg: Option<String>,
+    account_id: Option<String>,
+    account_identifier_bic: Option<String>,
+    statement_no: Option<String>,
+    sequence_no: Option<String>,
+    opening_balance: Option<Balance>,
+    statement_lines: Vec<StatementLine>,
+    closing_balance: Option<Balance>,
+    closing_available_balance: Option<AvailableBalance>,
+    forward_available_balances: Vec<AvailableBalance>,
+    information_to_account_owner: Option<String>,
+}
+
+impl MessageBuilder {
+    /// Create an empty [`MessageBuilder`].
+    pub fn new() -> MessageBuilder {
+        MessageBuilder::default()
+    }
+
+    /// Set the transaction reference number of tag `:20:`. Required.
+    pub fn transaction_ref_no(mut self, transaction_ref_no: &str) -> MessageBuilder {
+        self.transaction_ref_no = Some(transaction_ref_no.to_string());
+        self
+    }
+
+    /// Set the reference to a related message of tag `:21:`.
+    pub fn ref_to_related_msg(mut self, ref_to_related_msg: &str) -> MessageBuilder {
+        self.ref_to_related_msg = Some(ref_to_related_msg.to_string());
+        self
+    }
+
+    /// Set the account identification of tag `:25:`. Required.
+    pub fn account_id(mut self, account_id: &str) -> MessageBuilder {
+        self.account_id = Some(account_id.to_string());
+        self
+    }
+
+    /// Set the BIC of the account which makes the message use tag `:25P:`.
+    pub fn account_identifier_bic(mut self, bic: &str) -> MessageBuilder {
+        self.account_identifier_bic = Some(bic.to_string());
+        self
+    }
+
+    /// Set the statement number of tag `:28C:`. Required.
+    pub fn statement_no(mut self, statement_no: &str) -> MessageBuilder {
+        self.statement_no = Some(statement_no.to_string());
+        self
+    }
+
+    /// Set the sequence number of tag `:28C:`.
+    pub fn sequence_no(mut self, sequence_no: &str) -> MessageBuilder {
+        self.sequence_no = Some(sequence_no.to_string());
+        self
+    }
+
+    /// Set the opening balance. Required.
+    pub fn opening_balance(mut self, balance: Balance) -> MessageBuilder {
+        self.opening_balance = Some(balance);
+        self
+    }
+
+    /// Append a statement line.
+    pub fn statement_line(mut self, statement_line: StatementLine) -> MessageBuilder {
+        self.statement_lines.push(statement_line);
+        self
+    }
+
+    /// Append several statement lines.
+    pub fn statement_lines(
+        mut self,
+        statement_lines: impl IntoIterator<Item = StatementLine>,
+    ) -> MessageBuilder {
+        self.statement_lines.extend(statement_lines);
+        self
+    }
+
+    /// Set the closing balance instead of calculating it.
+    pub fn closing_balance(mut self, balance: Balance) -> MessageBuilder {
+        self.closing_balance = Some(balance);
+        self
+    }
+
+    /// Set the closing available balance of tag `:64:`.
+    pub fn closing_available_balance(mut self, balance: AvailableBalance) -> MessageBuilder {
+        self.closing_available_balance = Some(balance);
+        self
+    }
+
+    /// Append a forward available balance of tag `:65:`.
+    pub fn forward_available_balance(mut self, balance: AvailableBalance) -> MessageBuilder {
+        self.forward_available_balances.push(balance);
+        self
+    }
+
+    /// Set the information of tag `:86:` for the whole message.
+    pub fn information_to_account_owner(mut self, information: &str) -> MessageBuilder {
+        self.information_to_account_owner = Some(information.to_string());
+        self
+    }
+
+    /// Check all fields and build the [`Message`].
+    ///
+    /// A calculated closing balance is dated at the latest value date of the statement lines or
+    /// the date of the opening balance if there are none.
+    pub fn build(self) -> Result<Message, BuildError> {
+        let transaction_ref_no = required(self.transaction_ref_no, "transaction_ref_no")?;
+        check_text(&transaction_ref_no, "transaction_ref_no", 16)?;
+        if let Some(ref related) = self.ref_to_related_msg {
+            check_text(related, "ref_to_related_msg", 16)?;
+        }
+        let account_id = required(self.account_id, "account_id")?;
+        check_text(&account_id, "account_id", 35)?;
+        if let Some(ref bic) = self.account_identifier_bic {
+            check(is_bic(bic), "account_identifier_bic", bic)?;
+        }
+        let statement_no = required(self.statement_no, "statement_no")?;
+        check_digits(&statement_no, "statement_no", 1)?;
+        if let Some(ref sequence_no) = self.sequence_no {
+            check_digits(sequence_no, "sequence_no", 0)?;
+        }
+        if let Some(ref information) = self.information_to_account_owner {
+            check_information(information)?;
+        }
+
+        let opening_balance = required(self.opening_balance, "opening_balance")?;
+        let currency = opening_balance.iso_currency_code.clone();
+        check(
+            currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()),
+            "iso_currency_code",
+            &currency,
+        )?;
+        check_amount(&opening_balance.amount, "opening_balance")?;
+
+        let expected = self
+            .statement_lines
+            .iter()
+            .map(StatementLine::signed_amount)
+            .fold(opening_balance.signed_amount(), |sum, amount| sum + amount);
+        let closing_balance = match self.closing_balance {
+            Some(balance) => {
+                check_currency(&currency, &balance.iso_currency_code)?;
+                if balance.signed_amount() != expected {
+                    return Err(BuildError::BalanceMismatch {
+                        expected,
+                        found: balance.signed_amount(),
+                    });
+                }
+                balance
+            }
+            None => Balance {
+                is_intermediate: false,
+                debit_credit_indicator: if expected.is_sign_negative() {
+                    DebitOrCredit::Debit
+                } else {
+                    DebitOrCredit::Credit
+                },
+                date: self
+                    .statement_lines
+                    .iter()
+                    .map(|statement_line| statement_line.value_date)
+                    .max()
+                    .unwrap_or(opening_balance.date),
+                iso_currency_code: currency.clone(),
+                amount: expected.abs(),
+            },
+        };
+        check_amount(&closing_balance.amount, "closing_balance")?;
+        for balance in self
+            .closing_available_balance
+            .iter()
+            .chain(&self.forward_available_balances)
+        {
+            check_currency(&currency, &balance.iso_currency_code)?;
+            check_amount(&balance.amount, "available_balance")?;
+        }
+
+        Ok(Message {
+            transaction_ref_no,
+            ref_to_related_msg: self.ref_to_related_msg,
+            account_id,
+            account_identifier_bic: self.account_identifier_bic,
+            statement_no,
+            sequence_no: self.sequence_no,
+            opening_balance,
+            statement_lines: self.statement_lines,
+            closing_balance,
+            closing_available_balance: self.closing_available_balance,
+            forward_available_balances: self.forward_available_balances,
+            information_to_account_owner: self.information_to_account_owner,
+            information_lines: vec![],
+            non_swift_fields: vec![],
+            custom_fields: BTreeMap::new(),
+            raw: None,
+            source: None,
+        })
+    }
+}
+
+/// Builder for a whole statement which is split into pages of a limited number of lines.
+///
+/// Each page is a [`Message`] built by [`MessageBuilder`]. All pages share the statement number
+/// and are numbered by their sequence number if there is more than one. The closing balance of a
+/// page is carried over to the next one as its opening balance and both are marked as
+/// intermediate.
+///
+/// # Example
+/// ```
+/// use chrono::NaiveDate;
+/// use mt940::{
+///     parse_mt940, Balance, DebitOrCredit, ExtDebitOrCredit, StatementBuilder,
+///     StatementLineBuilder, TransactionTypeIdentificationCode,
+/// };
+/// use rust_decimal::Decimal;
+///
+/// let date = NaiveDate::from_ymd_opt(2009, 9, 25).unwrap();
+/// let statement_lines = (1..=3).map(|i| {
+///     StatementLineBuilder::new()
+///         .value_date(date)
+///         .ext_debit_credit_indicator(ExtDebitOrCredit::Credit)
+///         .amount(Decimal::new(i * 100, 2))
+///         .transaction_type_ident_code(TransactionTypeIdentificationCode::TRF)
+///         .build()
+///         .unwrap()
+/// });
+/// let text = StatementBuilder::new()
+///     .transaction_ref_no("3996-11-11111111")
+///     .account_id("DABADKKK/111111-11111111")
+///     .statement_no("42")
+///     .opening_balance(Balance {
+///         is_intermediate: false,
+///         debit_credit_indicator: DebitOrCredit::Credit,
+///         date,
+///         iso_currency_code: "EUR".to_string(),
+///         amount: Decimal::new(1000, 2),
+///     })
+///     .statement_lines(statement_lines)
+///     .max_lines_per_page(7)
+///     .build_mt940()
+///     .unwrap();
+///
+/// let messages = parse_mt940(&text).unwrap();
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(messages[1].sequence_no.as_deref(), Some("2"));
+/// assert!(messages[1].opening_balance.is_intermediate);
+/// assert_eq!(messages[1].closing_balance.amount, Decimal::new(1600, 2));
+/// ```
+#[derive(Debug, Default)]
+pub struct StatementBuilder {
+    transaction_ref_no: Option<String>,
+    account_id: Option<String>,
+    account_identifier_bic: Option<String>,
+    statement_no: Option<String>,
+    opening_balance: Option<Balance>,
+    statement_lines: Vec<StatementLine>,
+    max_lines_per_page: Option<usize>,
+}
+
+impl StatementBuilder {
+    /// Create an empty [`StatementBuilder`].
+    pub fn new() -> StatementBuilder {
+        StatementBuilder::default()
+    }
+
+    /// Set the transaction reference number of tag `:20:` of every page. Required.
+    pub fn transaction_ref_no(mut self, transaction_ref_no: &str) -> StatementBuilder {
+        self.transaction_ref_no = Some(transaction_ref_no.to_string());
+        self
+    }
+
+    /// Set the account identification of tag `:25:`. Required.
+    pub fn account_id(mut self, account_id: &str) -> StatementBuilder {
+        self.account_id = Some(account_id.to_string());
+        self
+    }
+
+    /// Set the BIC of the account which makes the pages use tag `:25P:`.
+    pub fn account_identifier_bic(mut self, bic: &str) -> StatementBuilder {
+        self.account_identifier_bic = Some(bic.to_string());
+        self
+    }
+
+    /// Set the statement number of tag `:28C:`. Defaults to `1`.
+    pub fn statement_no(mut self, statement_no: &str) -> StatementBuilder {
+        self.statement_no = Some(statement_no.to_string());
+        self
+    }
+
+    /// Set the opening balance of the first page. Required.
+    pub fn opening_balance(mut self, balance: Balance) -> StatementBuilder {
+        self.opening_balance = Some(balance);
+        self
+    }
+
+    /// Append a statement line.
+    pub fn statement_line(mut self, statement_line: StatementLine) -> StatementBuilder {
+        self.statement_lines.push(statement_line);
+        self
+    }
+
+    /// Append several statement lines.
+    pub fn statement_lines(
+        mut self,
+        statement_lines: impl IntoIterator<Item = StatementLine>,
+    ) -> StatementBuilder {
+        self.statement_lines.extend(statement_lines);
+        self
+    }
+
+    /// Start a new page before a page would exceed `max_lines_per_page` lines of MT940 text.
+    ///
+    /// Every line of every tag counts, including the tags at the start and end of each page. By
+    /// default, everything goes onto a single page.
+    pub fn max_lines_per_page(mut self, max_lines_per_page: usize) -> StatementBuilder {
+        self.max_lines_per_page = Some(max_lines_per_page);
+        self
+    }
+
+    /// Check all fields and build one [`Message`] per page.
+    pub fn build(self) -> Result<Vec<Message>, BuildError> {
+        let transaction_ref_no = required(self.transaction_ref_no, "transaction_ref_no")?;
+        let account_id = required(self.account_id, "account_id")?;
+        let statement_no = self.statement_no.unwrap_or_else(|| "1".to_string());
+        let mut opening_balance = required(self.opening_balance, "opening_balance")?;
+
+        let pages = match self.max_lines_per_page {
+            Some(max_lines_per_page) => {
+                // :20:, :25: or the two lines of :25P:, :28C:, :60F: and :62F:
+                let page_lines = 5 + usize::from(self.account_identifier_bic.is_some());
+                paginate(self.statement_lines, max_lines_per_page, page_lines)?
+            }
+            None => vec![self.statement_lines],
+        };
+
+        let page_count = pages.len();
+        let mut messages = Vec::with_capacity(page_count);
+        for (index, statement_lines) in pages.into_iter().enumerate() {
+            let mut builder = MessageBuilder::new()
+                .transaction_ref_no(&transaction_ref_no)
+                .account_id(&account_id)
+                .statement_no(&statement_no)
+                .opening_balance(opening_balance)
+                .statement_lines(statement_lines);
+            if let Some(ref bic) = self.account_identifier_bic {
+                builder = builder.account_identifier_bic(bic);
+            }
+            if page_count > 1 {
+                builder = builder.sequence_no(&(index + 1).to_string());
+            }
+            let mut message = builder.build()?;
+            message.closing_balance.is_intermediate = index + 1 < page_count;
+            opening_balance = carry_over(&message.closing_balance);
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+
+    /// Like [`StatementBuilder::build`] but returns the MT940 text of all pages.
+    pub fn build_mt940(self) -> Result<String, BuildError> {
+        Ok(serialize_mt940(&self.build()?))
+    }
+}
+
+/// Distribute `statement_lines` over pages of at most `max_lines` lines each of which needs
+/// `page_lines` lines for its other tags.
+fn paginate(
+    statement_lines: Vec<StatementLine>,
+    max_lines: usize,
+    page_lines: usize,
+) -> Result<Vec<Vec<StatementLine>>, BuildError> {
+    let budget = max_lines.saturating_sub(page_lines);
+    let mut pages = vec![vec![]];
+    let mut used = 0;
+    for statement_line in statement_lines {
+        let lines = statement_line_fields(&statement_line)
+            .iter()
+            .map(|field| field.value.lines().count())
+            .sum::<usize>();
+        check(
+            lines <= budget,
+            "max_lines_per_page",
+            &max_lines.to_string(),
+        )?;
+        if used + lines > budget {
+            pages.push(vec![]);
+            used = 0;
+        }
+        used += lines;
+        if let Some(page) = pages.last_mut() {
+            page.push(statement_line);
+        }
+    }
+    Ok(pages)
+}
+
+/// The opening balance of the page following the one closing with `balance`.
+fn carry_over(balance: &Balance) -> Balance {
+    Balance {
+        is_intermediate: true,
+        ..balance.clone()
+    }
+}
+
+/// Builder for a [`StatementLine`] which checks that it could be written as MT940.
+///
+/// The customer reference defaults to `NONREF` which SWIFT uses if there is none and the entry
+/// type defaults to [`EntryType::NonSwift`].
+#[derive(Debug, Default)]
+pub struct StatementLineBuilder {
+    value_date: Option<NaiveDate>,
+    entry_date: Option<NaiveDate>,
+    ext_debit_credit_indicator: Option<ExtDebitOrCredit>,
+    funds_code: Option<String>,
+    amount: Option<Decimal>,
+    entry_type: EntryType,
+    transaction_type_ident_code: Option<TransactionTypeIdentificationCode>,
+    customer_ref: Option<String>,
+    bank_ref: Option<String>,
+    supplementary_details: Option<String>,
+    information_to_account_owner: Option<String>,
+}
+
+impl StatementLineBuilder {
+    /// Create an empty [`StatementLineBuilder`].
+    pub fn new() -> StatementLineBuilder {
+        StatementLineBuilder::default()
+    }
+
+    /// Set the value date. Required.
+    pub fn value_date(mut self, value_date: NaiveDate) -> StatementLineBuilder {
+        self.value_date = Some(value_date);
+        self
+    }
+
+    /// Set the entry date. It has to be in the same year as the value date.
+    pub fn entry_date(mut self, entry_date: NaiveDate) -> StatementLineBuilder {
+        self.entry_date = Some(entry_date);
+        self
+    }
+
+    /// Set whether this is a debit, credit or a reversal of one. Required.
+    pub fn ext_debit_credit_indicator(
+        mut self,
+        ext_debit_credit_indicator: ExtDebitOrCredit,
+    ) -> StatementLineBuilder {
+        self.ext_debit_credit_indicator = Some(ext_debit_credit_indicator);
+        self
+    }
+
+    /// Set the third character of the currency code as the funds code.
+    pub fn funds_code(mut self, funds_code: &str) -> StatementLineBuilder {
+        self.funds_code = Some(funds_code.to_string());
+        self
+    }
+
+    /// Set the amount which must not be negative. Required.
+    pub fn amount(mut self, amount: Decimal) -> StatementLineBuilder {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Set the character in front of the transaction type identification code.
+    pub fn entry_type(mut self, entry_type: EntryType) -> StatementLineBuilder {
+        self.entry_type = entry_type;
+        self
+    }
+
+    /// Set the transaction type identification code. Required.
+    pub fn transaction_type_ident_code(
+        mut self,
+        code: TransactionTypeIdentificationCode,
+    ) -> StatementLineBuilder {
+        self.transaction_type_ident_code = Some(code);
+        self
+    }
+
+    /// Set the reference for the account owner.
+    pub fn customer_ref(mut self, customer_ref: &str) -> StatementLineBuilder {
+        self.customer_ref = Some(customer_ref.to_string());
+        self
+    }
+
+    /// Set the reference of the account servicing institution.
+    pub fn bank_ref(mut self, bank_ref: &str) -> StatementLineBuilder {
+        self.bank_ref = Some(bank_ref.to_string());
+        self
+    }
+
+    /// Set the supplementary details.
+    pub fn supplementary_details(mut self, supplementary_details: &str) -> StatementLineBuilder {
+        self.supplementary_details = Some(supplementary_details.to_string());
+        self
+    }
+
+    /// Set the information of tag `:86:`.
+    pub fn information_to_account_owner(mut self, information: &str) -> StatementLineBuilder {
+        self.information_to_account_owner = Some(information.to_string());
+        self
+    }
+
+    /// Check all fields and build the [`StatementLine`].
+    pub fn build(self) -> Result<StatementLine, BuildError> {
+        let value_date = required(self.value_date, "value_date")?;
+        if let Some(entry_date) = self.entry_date {
+            check(
+                entry_date.year() == value_date.year(),
+                "entry_date",
+                &entry_date.to_string(),
+            )?;
+        }
+        let ext_debit_credit_indicator = required(
+            self.ext_debit_credit_indicator,
+            "ext_debit_credit_indicator",
+        )?;
+        if let Some(ref funds_code) = self.funds_code {
+            check(
+                funds_code.len() == 1 && funds_code.chars().all(|c| c.is_ascii_alphabetic()),
+                "funds_code",
+                funds_code,
+            )?;
+        }
+        let amount = required(self.amount, "amount")?;
+        check_amount(&amount, "amount")?;
+        let transaction_type_ident_code = required(
+            self.transaction_type_ident_code,
+            "transaction_type_ident_code",
+        )?;
+        let code = transaction_type_ident_code.code();
+        check(
+            code.len() == 3 && code.chars().all(|c| c.is_ascii_alphanumeric()),
+            "transaction_type_ident_code",
+            code,
+        )?;
+        let customer_ref = self.customer_ref.unwrap_or_else(|| "NONREF".to_string());
+        check_text(&customer_ref, "customer_ref", 16)?;
+        check(!customer_ref.contains("//"), "customer_ref", &customer_ref)?;
+        if let Some(ref bank_ref) = self.bank_ref {
+            check_text(bank_ref, "bank_ref", 16)?;
+        }
+        if let Some(ref supplementary_details) = self.supplementary_details {
+            check_text(supplementary_details, "supplementary_details", 34)?;
+        }
+        if let Some(ref information) = self.information_to_account_owner {
+            check_information(information)?;
+        }
+
+        Ok(StatementLine {
+            value_date,
+            entry_date: self.entry_date,
+            ext_debit_credit_indicator,
+            funds_code: self.funds_code,
+            amount,
+            entry_type: self.entry_type,
+            transaction_type_ident_code,
+            customer_ref,
+            bank_ref: self.bank_ref,
+            supplementary_details: self.supplementary_details,
+            information_to_account_owner: self.information_to_account_owner,
+            information_lines: vec![],
+            structured_information: None,
+            sepa_purpose: None,
+            raw: None,
+            index: None,
+        })
+    }
+}
+
+fn required<T>(value: Option<T>, field: &str) -> Result<T, BuildError> {
+    value.ok_or_else(|| BuildError::MissingField(field.to_string()))
+}
+
+fn check(valid: bool, field: &str, value: &str) -> Result<(), BuildError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(BuildError::InvalidField {
+            field: field.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Check a single line of SWIFT characters of up to `max_length` characters.
+///
+/// Surrounding whitespace is rejected as it would be trimmed when parsing.
+fn check_text(value: &str, field: &str, max_length: usize) -> Result<(), BuildError> {
+    let length = value.chars().count();
+    check(
+        (1..=max_length).contains(&length)
+            && value.trim() == value
+            && value
+                .chars()
+                .all(|c| is_swift_char(c) && c != '\r' && c != '\n'),
+        field,
+        value,
+    )
+}
+
+fn check_digits(value: &str, field: &str, min_length: usize) -> Result<(), BuildError> {
+    check(
+        (min_length..=5).contains(&value.len()) && value.chars().all(|c| c.is_ascii_digit()),
+        field,
+        value,
+    )
+}
+
+/// Check the text of tag `:86:` which may span several lines, none of which may look like a tag.
+fn check_information(information: &str) -> Result<(), BuildError> {
+    check(
+        !information.is_empty()
+            && information.trim() == information
+            && information.chars().all(|c| is_swift_char(c) && c != '\r')
+            && information.lines().all(|line| !line.starts_with(':')),
+        "information_to_account_owner",
+        information,
+    )
+}
+
+fn check_amount(amount: &Decimal, field: &str) -> Result<(), BuildError> {
+    check(
+        !amount.is_sign_negative() && format_amount(amount).len() <= AMOUNT_MAX_LENGTH,
+        field,
+        &amount.to_string(),
+    )
+}
+
+fn check_currency(expected: &str, found: &str) -> Result<(), BuildError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(BuildError::CurrencyMismatch {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940, serialize_mt940};
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2009, 9, 25).unwrap()
+    }
+
+    fn balance(amount: i64) -> Balance {
+        Balance {
+            is_intermediate: false,
+            debit_credit_indicator: DebitOrCredit::Credit,
+            date: date(),
+            iso_currency_code: "EUR".to_string(),
+            amount: Decimal::new(amount, 2),
+        }
+    }
+
+    fn statement_line() -> StatementLineBuilder {
+        StatementLineBuilder::new()
+            .value_date(date())
+            .ext_debit_credit_indicator(ExtDebitOrCredit::Debit)
+            .amount(Decimal::new(150, 2))
+            .transaction_type_ident_code(TransactionTypeIdentificationCode::TRF)
+    }
+
+    fn message() -> MessageBuilder {
+        MessageBuilder::new()
+            .transaction_ref_no("ref")
+            .account_id("account")
+            .statement_no("1")
+            .opening_balance(balance(100))
+    }
+
+    #[test]
+    fn built_message_round_trips() {
+        let message = message()
+            .statement_line(
+                statement_line()
+                    .bank_ref("bank")
+                    .information_to_account_owner("first\nsecond")
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            message.closing_balance.debit_credit_indicator,
+            DebitOrCredit::Debit
+        );
+        assert_eq!(message.closing_balance.amount, Decimal::new(50, 2));
+        let text = serialize_mt940(std::slice::from_ref(&message));
+        assert_eq!(parse_mt940(&text).unwrap(), vec![message]);
+    }
+
+    #[test]
+    fn missing_and_invalid_fields() {
+        assert_eq!(
+            MessageBuilder::new().build().unwrap_err(),
+            BuildError::MissingField("transaction_ref_no".to_string())
+        );
+        assert_eq!(
+            message().statement_no("12a").build().unwrap_err(),
+            BuildError::InvalidField {
+                field: "statement_no".to_string(),
+                value: "12a".to_string()
+            }
+        );
+        assert_eq!(
+            statement_line().customer_ref("a//b").build().unwrap_err(),
+            BuildError::InvalidField {
+                field: "customer_ref".to_string(),
+                value: "a//b".to_string()
+            }
+        );
+        assert_eq!(
+            message()
+                .account_identifier_bic("dabadkkk")
+                .build()
+                .unwrap_err(),
+            BuildError::InvalidField {
+                field: "account_identifier_bic".to_string(),
+                value: "dabadkkk".to_string()
+            }
+        );
+        assert!(statement_line()
+            .information_to_account_owner("info\n:20:tag")
+            .build()
+            .is_err());
+    }
+
+    fn statement() -> StatementBuilder {
+        StatementBuilder::new()
+            .transaction_ref_no("ref")
+            .account_id("account")
+            .opening_balance(balance(100))
+    }
+
+    #[test]
+    fn statement_is_split_into_pages() {
+        let messages = statement()
+            .statement_line(
+                statement_line()
+                    .information_to_account_owner("first\nsecond")
+                    .build()
+                    .unwrap(),
+            )
+            .statement_line(statement_line().build().unwrap())
+            .statement_line(statement_line().build().unwrap())
+            .max_lines_per_page(8)
+            .build()
+            .unwrap();
+
+        let pages = messages
+            .iter()
+            .map(|message| {
+                (
+                    message.sequence_no.as_deref(),
+                    message.statement_lines.len(),
+                    message.opening_balance.is_intermediate,
+                    message.closing_balance.is_intermediate,
+                    message.closing_balance.signed_amount(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pages,
+            vec![
+                (Some("1"), 1, false, true, Decimal::new(-50, 2)),
+                (Some("2"), 2, true, false, Decimal::new(-350, 2)),
+            ]
+        );
+        let text = serialize_mt940(&messages);
+        assert!(text.split(":20:").all(|page| page.lines().count() <= 8));
+        assert_eq!(parse_mt940(&text).unwrap(), messages);
+    }
+
+    #[test]
+    fn statement_without_pages() {
+        let messages = statement()
+            .statement_line(statement_line().build().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].statement_no, "1");
+        assert_eq!(messages[0].sequence_no, None);
+        assert!(!messages[0].closing_balance.is_intermediate);
+    }
+
+    #[test]
+    fn statement_line_exceeds_page() {
+        let err = statement()
+            .statement_line(statement_line().build().unwrap())
+            .max_lines_per_page(5)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BuildError::InvalidField {
+                field: "max_lines_per_page".to_string(),
+                value: "5".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn closing_balance_must_add_up() {
+        let err = message()
+            .statement_line(statement_line().build().unwrap())
+            .closing_balance(balance(100))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BuildError::BalanceMismatch {
+                expected: Decimal::new(-50, 2),
+                found: Decimal::new(100, 2)
+            }
+        );
+    }
+}
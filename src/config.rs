@@ -0,0 +1,525 @@
+//! Configuration of how strictly statements are parsed.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Field, ParseError};
+
+/// A handler for a proprietary tag as registered with [`ParserConfig::tag_handler`].
+///
+/// It receives the field and the [`Message::custom_fields`] of the message being parsed.
+///
+/// [`Message::custom_fields`]: crate::Message::custom_fields
+pub type TagHandler = dyn Fn(&Field, &mut BTreeMap<String, serde_json::Value>) -> Result<(), ParseError>
+    + Send
+    + Sync;
+
+/// Knobs to relax the parser for statements which deviate from the spec.
+///
+/// The default configuration is as strict as [`parse_mt940`]. Use the builder methods to relax it:
+///
+/// ```
+/// use mt940::{parse_mt940_with_config, ParserConfig};
+///
+/// let config = ParserConfig::new()
+///     .ignore_unknown_tags(true)
+///     .allow_missing_closing_balance(true);
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :99:Something proprietary\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n";
+///
+/// let input_parsed = parse_mt940_with_config(input, &config).unwrap();
+/// assert_eq!(input_parsed[0].closing_balance.amount.to_string(), "53900.12");
+/// ```
+///
+/// [`parse_mt940`]: crate::parse_mt940
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ParserConfig {
+    pub(crate) ignore_unknown_tags: bool,
+    pub(crate) allow_missing_closing_balance: bool,
+    pub(crate) allow_non_swift_chars: bool,
+    pub(crate) non_swift_tags: NonSwiftTags,
+    pub(crate) strip_stuff_between_messages: bool,
+    pub(crate) escape_unknown_tags: bool,
+    pub(crate) join_information_lines: bool,
+    pub(crate) year_pivot: Option<u8>,
+    pub(crate) keep_raw: bool,
+    pub(crate) keep_information_lines: bool,
+    pub(crate) information_format: Option<InformationFormat>,
+    pub(crate) sepa_purpose: bool,
+    pub(crate) relaxed_tag_order: bool,
+    pub(crate) strict_field_lengths: bool,
+    pub(crate) duplicate_transaction_ref: DuplicateTransactionRef,
+    pub(crate) track_source: bool,
+    pub(crate) source_label: Option<String>,
+    pub(crate) tag_handlers: TagHandlers,
+    #[cfg(feature = "iso4217")]
+    pub(crate) validate_currencies: bool,
+}
+
+impl ParserConfig {
+    /// Create a new, strict [`ParserConfig`].
+    pub fn new() -> ParserConfig {
+        ParserConfig::default()
+    }
+
+    /// Skip tags which are not part of MT940 instead of failing with
+    /// [`ParseError::UnknownTagError`].
+    ///
+    /// [`ParseError::UnknownTagError`]: crate::ParseError::UnknownTagError
+    pub fn ignore_unknown_tags(mut self, ignore_unknown_tags: bool) -> ParserConfig {
+        self.ignore_unknown_tags = ignore_unknown_tags;
+        self
+    }
+
+    /// Accept messages without a tag `:62F:` or `:62M:`.
+    ///
+    /// The closing balance of such a message is calculated from its opening balance and its
+    /// statement lines.
+    pub fn allow_missing_closing_balance(
+        mut self,
+        allow_missing_closing_balance: bool,
+    ) -> ParserConfig {
+        self.allow_missing_closing_balance = allow_missing_closing_balance;
+        self
+    }
+
+    /// Accept characters which are not in the SWIFT character set.
+    ///
+    /// These are replaced the same way [`to_swift_charset`] does it before parsing.
+    ///
+    /// [`to_swift_charset`]: crate::sanitizers::to_swift_charset
+    pub fn allow_non_swift_chars(mut self, allow_non_swift_chars: bool) -> ParserConfig {
+        self.allow_non_swift_chars = allow_non_swift_chars;
+        self
+    }
+
+    /// Choose what to do with non-SWIFT `:NS:` tags.
+    pub fn non_swift_tags(mut self, non_swift_tags: NonSwiftTags) -> ParserConfig {
+        self.non_swift_tags = non_swift_tags;
+        self
+    }
+
+    /// Remove lines between messages such as `-` separators or repeated headers.
+    ///
+    /// This is done the same way [`strip_stuff_between_messages`] does it before parsing.
+    ///
+    /// [`strip_stuff_between_messages`]: crate::sanitizers::strip_stuff_between_messages
+    pub fn strip_stuff_between_messages(
+        mut self,
+        strip_stuff_between_messages: bool,
+    ) -> ParserConfig {
+        self.strip_stuff_between_messages = strip_stuff_between_messages;
+        self
+    }
+
+    /// Keep lines of tag `:86:` starting with a tag which is not known from starting a new field.
+    ///
+    /// Such lines are taken as they are as part of tag `:86:`. The tags which count as known are
+    /// the same as for [`escape_unknown_tags`] plus those with a [`ParserConfig::tag_handler`].
+    ///
+    /// [`escape_unknown_tags`]: crate::sanitizers::escape_unknown_tags
+    pub fn escape_unknown_tags(mut self, escape_unknown_tags: bool) -> ParserConfig {
+        self.escape_unknown_tags = escape_unknown_tags;
+        self
+    }
+
+    /// Join the lines of tag `:86:` without line breaks.
+    ///
+    /// Many banks simply wrap their information at a fixed width, even in the middle of a word, in
+    /// which case the line breaks carry no meaning.
+    pub fn join_information_lines(mut self, join_information_lines: bool) -> ParserConfig {
+        self.join_information_lines = join_information_lines;
+        self
+    }
+
+    /// Put two-digit years from `year_pivot` upwards into the 1900s instead of the 2000s.
+    ///
+    /// By default, all dates are assumed to be in the 2000s. This is wrong for archived statements
+    /// from the 1990s. With a pivot of `50`, `491231` is 2049-12-31 while `990101` is 1999-01-01.
+    pub fn year_pivot(mut self, year_pivot: u8) -> ParserConfig {
+        self.year_pivot = Some(year_pivot);
+        self
+    }
+
+    /// Keep the original text of every [`Message`], [`StatementLine`] and [`Field`].
+    ///
+    /// This is useful to show the bank's text next to the parsed values or to re-emit a message
+    /// exactly as it was received. If the input is altered before parsing, e.g. by
+    /// [`ParserConfig::allow_non_swift_chars`], this is the altered text.
+    ///
+    /// [`Message`]: crate::Message
+    /// [`StatementLine`]: crate::StatementLine
+    /// [`Field`]: crate::Field
+    pub fn keep_raw(mut self, keep_raw: bool) -> ParserConfig {
+        self.keep_raw = keep_raw;
+        self
+    }
+
+    /// Keep the lines of every tag `:86:` as they were in [`StatementLine::information_lines`] and
+    /// [`Message::information_lines`].
+    ///
+    /// Consecutive `:86:` tags are joined without a separator and
+    /// [`ParserConfig::join_information_lines`] drops line breaks altogether. Formats which
+    /// rely on the original 6x65 layout can be read from these lines instead.
+    ///
+    /// [`Message::information_lines`]: crate::Message::information_lines
+    /// [`StatementLine::information_lines`]: crate::StatementLine::information_lines
+    pub fn keep_information_lines(mut self, keep_information_lines: bool) -> ParserConfig {
+        self.keep_information_lines = keep_information_lines;
+        self
+    }
+
+    /// Break the information to the account owner of every statement line down according to
+    /// `format` and store the result in [`StatementLine::structured_information`].
+    ///
+    /// Formats which rely on line breaks don't work along with
+    /// [`ParserConfig::join_information_lines`].
+    ///
+    /// [`StatementLine::structured_information`]: crate::StatementLine::structured_information
+    pub fn information_format(mut self, format: InformationFormat) -> ParserConfig {
+        self.information_format = Some(format);
+        self
+    }
+
+    /// Extract the SEPA references from the information to the account owner of every statement
+    /// line and store them in [`StatementLine::sepa_purpose`].
+    ///
+    /// [`StatementLine::sepa_purpose`]: crate::StatementLine::sepa_purpose
+    pub fn sepa_purpose(mut self, sepa_purpose: bool) -> ParserConfig {
+        self.sepa_purpose = sepa_purpose;
+        self
+    }
+
+    /// Reject currency codes which are not in ISO 4217 with [`ParseError::UnknownCurrencyError`].
+    ///
+    /// The grammar only requires three letters, so codes like `EUA` or `XXX` pass otherwise. See
+    /// [`is_iso4217_currency`] for which codes are accepted.
+    ///
+    /// [`ParseError::UnknownCurrencyError`]: crate::ParseError::UnknownCurrencyError
+    /// [`is_iso4217_currency`]: crate::is_iso4217_currency
+    #[cfg(feature = "iso4217")]
+    pub fn validate_currencies(mut self, validate_currencies: bool) -> ParserConfig {
+        self.validate_currencies = validate_currencies;
+        self
+    }
+
+    /// Accept tags in some orders which the spec doesn't allow instead of failing with
+    /// [`ParseError::UnexpectedTagError`].
+    ///
+    /// Some exports put `:64:` or `:65:` before `:62F:` or `:21:` after `:25:`. All mandatory tags
+    /// are still required.
+    ///
+    /// [`ParseError::UnexpectedTagError`]: crate::ParseError::UnexpectedTagError
+    pub fn relaxed_tag_order(mut self, relaxed_tag_order: bool) -> ParserConfig {
+        self.relaxed_tag_order = relaxed_tag_order;
+        self
+    }
+
+    /// Fail with [`ParseError::FieldLengthError`] if any component of a message is longer than
+    /// the SWIFT spec allows.
+    ///
+    /// See [`Message::validate_lengths`] for what is checked. Note that information from
+    /// consecutive `:86:` tags is checked as a whole, as is a single line produced by
+    /// [`ParserConfig::join_information_lines`].
+    ///
+    /// [`ParseError::FieldLengthError`]: crate::ParseError::FieldLengthError
+    /// [`Message::validate_lengths`]: crate::Message::validate_lengths
+    pub fn strict_field_lengths(mut self, strict_field_lengths: bool) -> ParserConfig {
+        self.strict_field_lengths = strict_field_lengths;
+        self
+    }
+
+    /// How to handle a tag `:20:` which is repeated before the message it belongs to has ended.
+    ///
+    /// Some providers repeat the transaction reference number in the middle of a message. By
+    /// default such a tag starts a new message, which then lacks its mandatory tags.
+    ///
+    /// ```
+    /// use mt940::{parse_mt940_with_config, DuplicateTransactionRef, ParserConfig};
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :62F:C090924EUR54484,04\r\n";
+    ///
+    /// let config = ParserConfig::new().duplicate_transaction_ref(DuplicateTransactionRef::Merge);
+    /// let input_parsed = parse_mt940_with_config(input, &config).unwrap();
+    /// assert_eq!(input_parsed.len(), 1);
+    /// ```
+    pub fn duplicate_transaction_ref(
+        mut self,
+        duplicate_transaction_ref: DuplicateTransactionRef,
+    ) -> ParserConfig {
+        self.duplicate_transaction_ref = duplicate_transaction_ref;
+        self
+    }
+
+    /// Record which message of the statement every [`Message`] and [`ParseError`] is about in
+    /// [`Message::source`] and [`ParseError::message_source`], and the position of every statement
+    /// line within its message in [`StatementLine::index`].
+    ///
+    /// Errors get the index and starting line of the failing message attached, which otherwise
+    /// only the [`Span`] of some errors tells.
+    ///
+    /// [`Message`]: crate::Message
+    /// [`Message::source`]: crate::Message::source
+    /// [`ParseError::message_source`]: crate::ParseError::message_source
+    /// [`StatementLine::index`]: crate::StatementLine::index
+    /// [`Span`]: crate::Span
+    pub fn track_source(mut self, track_source: bool) -> ParserConfig {
+        self.track_source = track_source;
+        self
+    }
+
+    /// Like [`ParserConfig::track_source`] but also label the source with `label`, e.g. the name
+    /// of the file being parsed.
+    ///
+    /// ```
+    /// use mt940::{parse_mt940_with_config, ParserConfig};
+    ///
+    /// let config = ParserConfig::new().source_label("2009-09-24.sta");
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :62F:C090924EUR54484,04\r\n\
+    ///     :20:3996-11-11111112\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00002/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n";
+    ///
+    /// let err = parse_mt940_with_config(input, &config).unwrap_err();
+    /// let source = err.message_source().unwrap();
+    /// assert_eq!(source.label.as_deref(), Some("2009-09-24.sta"));
+    /// assert_eq!((source.index, source.line), (1, Some(6)));
+    /// ```
+    pub fn source_label(mut self, label: &str) -> ParserConfig {
+        self.track_source = true;
+        self.source_label = Some(label.to_string());
+        self
+    }
+
+    /// Call `handler` for every field with the proprietary tag `tag` instead of treating it as
+    /// unknown.
+    ///
+    /// The handler can store whatever it extracts in [`Message::custom_fields`]. An error returned
+    /// by it fails the message. Handlers for tags which are part of MT940 are never called.
+    ///
+    /// ```
+    /// use mt940::{parse_mt940_with_config, ParserConfig};
+    ///
+    /// let config = ParserConfig::new().tag_handler("99", |field, custom_fields| {
+    ///     custom_fields.insert("branch".to_string(), field.value.clone().into());
+    ///     Ok(())
+    /// });
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :99:Copenhagen\r\n\
+    ///     :62F:C090924EUR54484,04\r\n";
+    ///
+    /// let input_parsed = parse_mt940_with_config(input, &config).unwrap();
+    /// assert_eq!(input_parsed[0].custom_fields["branch"], "Copenhagen");
+    /// ```
+    ///
+    /// [`Message::custom_fields`]: crate::Message::custom_fields
+    pub fn tag_handler(
+        mut self,
+        tag: &str,
+        handler: impl Fn(&Field, &mut BTreeMap<String, serde_json::Value>) -> Result<(), ParseError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> ParserConfig {
+        self.tag_handlers
+            .0
+            .insert(tag.to_string(), Arc::new(handler));
+        self
+    }
+
+    /// Relax the configuration for the known deviations of a [`Dialect`].
+    ///
+    /// This only ever enables options, so presets can be combined with each other as well as with
+    /// the other options.
+    pub fn dialect(self, dialect: Dialect) -> ParserConfig {
+        match dialect {
+            Dialect::Ing => self
+                .allow_non_swift_chars(true)
+                .strip_stuff_between_messages(true)
+                .non_swift_tags(NonSwiftTags::AsInformation)
+                .information_format(InformationFormat::Dutch),
+            Dialect::AbnAmro => self
+                .allow_non_swift_chars(true)
+                .strip_stuff_between_messages(true)
+                .non_swift_tags(NonSwiftTags::AsInformation)
+                .join_information_lines(true)
+                .information_format(InformationFormat::Dutch),
+            Dialect::Rabobank => self
+                .strip_stuff_between_messages(true)
+                .join_information_lines(true)
+                .information_format(InformationFormat::Dutch),
+            Dialect::MBank | Dialect::Sparkasse => self
+                .allow_non_swift_chars(true)
+                .strip_stuff_between_messages(true)
+                .join_information_lines(true),
+            Dialect::DanskeBank => self.information_format(InformationFormat::Nordic),
+        }
+    }
+}
+
+/// The handlers registered with [`ParserConfig::tag_handler`] by tag.
+///
+/// Two configurations are only equal if they share the very same handlers.
+#[derive(Clone, Default)]
+pub(crate) struct TagHandlers(BTreeMap<String, Arc<TagHandler>>);
+
+impl TagHandlers {
+    pub(crate) fn get(&self, tag: &str) -> Option<&TagHandler> {
+        self.0.get(tag).map(|handler| handler.as_ref())
+    }
+}
+
+impl fmt::Debug for TagHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl PartialEq for TagHandlers {
+    fn eq(&self, other: &TagHandlers) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|((tag, handler), (other_tag, other_handler))| {
+                    tag == other_tag && Arc::ptr_eq(handler, other_handler)
+                })
+    }
+}
+
+impl Eq for TagHandlers {}
+
+impl From<Dialect> for ParserConfig {
+    fn from(dialect: Dialect) -> ParserConfig {
+        ParserConfig::new().dialect(dialect)
+    }
+}
+
+/// How to handle the proprietary `:NS:` tags some banks put in between the standard tags.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum NonSwiftTags {
+    /// Fail with [`ParseError::UnknownTagError`] like for any other unknown tag.
+    ///
+    /// [`ParseError::UnknownTagError`]: crate::ParseError::UnknownTagError
+    #[default]
+    Reject,
+    /// Skip them.
+    Ignore,
+    /// Keep them as they are in [`Message::non_swift_fields`].
+    ///
+    /// [`Message::non_swift_fields`]: crate::Message::non_swift_fields
+    Collect,
+    /// Add their contents as a new line to the information to the account owner of the preceding
+    /// statement line or, if there is none, of the [`Message`].
+    ///
+    /// [`Message`]: crate::Message
+    AsInformation,
+}
+
+/// How to handle a tag `:20:` which is repeated within a message, see
+/// [`ParserConfig::duplicate_transaction_ref`].
+///
+/// A tag `:20:` counts as repeated if the message in front of it has no closing balance yet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum DuplicateTransactionRef {
+    /// Start a new message with it like with any other tag `:20:`.
+    #[default]
+    NewMessage,
+    /// Fail with [`ParseError::DuplicateTagError`].
+    ///
+    /// [`ParseError::DuplicateTagError`]: crate::ParseError::DuplicateTagError
+    Reject,
+    /// Skip it and continue the message. The first transaction reference number is kept.
+    Merge,
+}
+
+/// Banks whose statements deviate from the spec in known ways.
+///
+/// Use [`ParserConfig::dialect`] to parse their statements.
+///
+/// ```
+/// use mt940::{parse_mt940_with_config, Dialect, ParserConfig};
+///
+/// let input = "\
+///     ABNANL2A\r\n\
+///     940\r\n\
+///     ABNANL2A\r\n\
+///     :20:ABN AMRO BANK NV\r\n\
+///     :25:123456789\r\n\
+///     :28:23801/1\r\n\
+///     :60F:C190825EUR1111,10\r\n\
+///     :61:1908240824D5,34N426NONREF\r\n\
+///     :86:BEA   NR:YV7XDL   24.08.19/15.33 RESTAURANT IL SENSO MIDD,PAS2\r\n\
+///     63\r\n\
+///     :62F:C190826EUR1105,76\r\n\
+///     -\r\n";
+///
+/// let config = ParserConfig::from(Dialect::AbnAmro);
+/// let input_parsed = parse_mt940_with_config(input, &config).unwrap();
+/// assert_eq!(
+///     input_parsed[0].statement_lines[0].information_to_account_owner.as_deref(),
+///     Some("BEA   NR:YV7XDL   24.08.19/15.33 RESTAURANT IL SENSO MIDD,PAS263"),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Dialect {
+    /// ING uses tabs and other non-SWIFT characters, wraps statements in headers, emits `:NS:`
+    /// tags and lays out `:86:` in the [`InformationFormat::Dutch`] format.
+    Ing,
+    /// ABN AMRO uses non-SWIFT characters, separates messages with headers and `-`, emits `:NS:`
+    /// tags, wraps `:86:` at a fixed width and lays it out in the [`InformationFormat::Dutch`]
+    /// format.
+    AbnAmro,
+    /// Rabobank wraps `:86:` at a fixed width and lays it out in the [`InformationFormat::Dutch`]
+    /// format.
+    Rabobank,
+    /// mBank wraps statements in control characters and `:86:` at a fixed width.
+    MBank,
+    /// Sparkasse uses umlauts, separates messages with `-` and wraps `:86:` at a fixed width.
+    Sparkasse,
+    /// Danske Bank lays out `:86:` in the [`InformationFormat::Nordic`] format.
+    DanskeBank,
+}
+
+/// Structured formats of the information to the account owner in tag `:86:`, see
+/// [`ParserConfig::information_format`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum InformationFormat {
+    /// The format of Nordic banks such as Danske Bank or Handelsbanken, see
+    /// [`NordicInformation`].
+    ///
+    /// [`NordicInformation`]: crate::NordicInformation
+    Nordic,
+    /// The slash-delimited keywords of Dutch banks such as ING, ABN AMRO or Rabobank, see
+    /// [`DutchInformation`].
+    ///
+    /// [`DutchInformation`]: crate::DutchInformation
+    Dutch,
+}
@@ -0,0 +1,236 @@
+//! Checking that consecutive statements of an account connect.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::grouping::{group_by_account, statement_number_gaps, AccountKey, StatementGap};
+use crate::Message;
+
+/// A problem found by [`check_continuity`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum ContinuityFinding {
+    /// Statements are missing in between two statements.
+    Gap {
+        account: AccountKey,
+        gap: StatementGap,
+    },
+
+    /// A statement covers dates already covered by the statement in front of it, or the same page
+    /// of a statement is there twice.
+    Overlap {
+        account: AccountKey,
+        previous_statement_no: String,
+        statement_no: String,
+        sequence_no: Option<String>,
+    },
+
+    /// The opening balance of a statement isn't the closing balance of the statement in front of
+    /// it.
+    BalanceMismatch {
+        account: AccountKey,
+        previous_statement_no: String,
+        statement_no: String,
+        sequence_no: Option<String>,
+        /// Signed closing balance of the previous statement.
+        previous_closing: Decimal,
+        /// Signed opening balance of this statement.
+        opening: Decimal,
+    },
+}
+
+impl fmt::Display for ContinuityFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContinuityFinding::Gap { account, gap } => write!(
+                f,
+                "Statements {} to {} of account '{}' ({}) not found.",
+                gap.missing().start(),
+                gap.missing().end(),
+                account.account_id,
+                account.iso_currency_code
+            ),
+            ContinuityFinding::Overlap {
+                account,
+                previous_statement_no,
+                statement_no,
+                ..
+            } => write!(
+                f,
+                "Statement '{}' of account '{}' ({}) overlaps with statement '{}'.",
+                statement_no, account.account_id, account.iso_currency_code, previous_statement_no
+            ),
+            ContinuityFinding::BalanceMismatch {
+                account,
+                previous_statement_no,
+                statement_no,
+                previous_closing,
+                opening,
+                ..
+            } => write!(
+                f,
+                "Opening balance {} of statement '{}' of account '{}' ({}) doesn't match the closing balance {} of statement '{}'.",
+                opening,
+                statement_no,
+                account.account_id,
+                account.iso_currency_code,
+                previous_closing,
+                previous_statement_no
+            ),
+        }
+    }
+}
+
+/// Check that the statements of every account connect without gaps or overlaps.
+///
+/// Messages are grouped and ordered by [`group_by_account`]. Then for each account:
+///
+/// - Missing statement numbers are reported as [`ContinuityFinding::Gap`], see
+///   [`statement_number_gaps`].
+/// - A page which is there twice or a statement opening before the statement in front of it
+///   closes is reported as [`ContinuityFinding::Overlap`].
+/// - An opening balance which differs from the closing balance in front of it is reported as
+///   [`ContinuityFinding::BalanceMismatch`]. This includes the pages of a statement.
+///
+/// Findings are ordered by account.
+///
+/// # Example
+/// ```
+/// use mt940::{check_continuity, parse_mt940, ContinuityFinding};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR53900,12\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00002/001\r\n\
+///     :60F:C090930EUR53900,12\r\n\
+///     :62F:C091001EUR53837,52\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00004/001\r\n\
+///     :60F:C091002EUR50000,00\r\n\
+///     :62F:C091002EUR50000,00\r\n";
+///
+/// let findings = check_continuity(&parse_mt940(input).unwrap());
+/// assert_eq!(findings.len(), 2);
+/// assert!(matches!(findings[0], ContinuityFinding::Gap { .. }));
+/// assert_eq!(
+///     findings[1].to_string(),
+///     "Opening balance 50000.00 of statement '00004' of account \
+///      'DABADKKK/111111-11111111' (EUR) doesn't match the closing balance 53837.52 of \
+///      statement '00002'."
+/// );
+/// ```
+pub fn check_continuity(messages: &[Message]) -> Vec<ContinuityFinding> {
+    let mut groups = group_by_account(messages).into_iter().collect::<Vec<_>>();
+    groups.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+
+    let mut findings = vec![];
+    for (account, group) in groups {
+        findings.extend(statement_number_gaps(&group).into_iter().map(|gap| {
+            ContinuityFinding::Gap {
+                account: account.clone(),
+                gap,
+            }
+        }));
+
+        for pair in group.windows(2) {
+            let [previous, message] = pair else {
+                continue;
+            };
+            let is_duplicate = previous.statement_no == message.statement_no
+                && previous.sequence_no == message.sequence_no;
+            if is_duplicate || message.opening_balance.date < previous.closing_balance.date {
+                findings.push(ContinuityFinding::Overlap {
+                    account: account.clone(),
+                    previous_statement_no: previous.statement_no.clone(),
+                    statement_no: message.statement_no.clone(),
+                    sequence_no: message.sequence_no.clone(),
+                });
+                if is_duplicate {
+                    continue;
+                }
+            }
+
+            let previous_closing = previous.closing_balance.signed_amount();
+            let opening = message.opening_balance.signed_amount();
+            if previous_closing != opening {
+                findings.push(ContinuityFinding::BalanceMismatch {
+                    account: account.clone(),
+                    previous_statement_no: previous.statement_no.clone(),
+                    statement_no: message.statement_no.clone(),
+                    sequence_no: message.sequence_no.clone(),
+                    previous_closing,
+                    opening,
+                });
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    /// A statement opening on day `opening_day` and closing on day `closing_day` of September
+    /// 2009.
+    fn statement(
+        statement_no: &str,
+        opening_day: u32,
+        opening: &str,
+        closing_day: u32,
+        closing: &str,
+    ) -> String {
+        format!(
+            ":20:a\r\n:25:b\r\n:28C:{}\r\n:60F:C0909{:02}EUR{}\r\n:62F:C0909{:02}EUR{}\r\n",
+            statement_no, opening_day, opening, closing_day, closing
+        )
+    }
+
+    #[test]
+    fn continuity() {
+        let input = [
+            statement("1", 1, "1,", 2, "2,"),
+            statement("2", 2, "2,", 3, "3,"),
+            statement("2", 2, "2,", 3, "3,"),
+            statement("3", 1, "3,", 4, "4,"),
+            statement("4", 4, "5,", 5, "5,"),
+        ]
+        .concat();
+        let messages = parse_mt940(&input).unwrap();
+        let account = messages[0].account_key();
+        let overlap = |previous: &str, statement_no: &str| ContinuityFinding::Overlap {
+            account: account.clone(),
+            previous_statement_no: previous.to_string(),
+            statement_no: statement_no.to_string(),
+            sequence_no: None,
+        };
+        assert_eq!(
+            check_continuity(&messages),
+            [
+                overlap("2", "2"),
+                overlap("2", "3"),
+                ContinuityFinding::BalanceMismatch {
+                    account: account.clone(),
+                    previous_statement_no: "3".to_string(),
+                    statement_no: "4".to_string(),
+                    sequence_no: None,
+                    previous_closing: 4.into(),
+                    opening: 5.into(),
+                },
+            ]
+        );
+
+        assert!(check_continuity(&messages[..2]).is_empty());
+    }
+}
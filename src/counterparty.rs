@@ -0,0 +1,189 @@
+//! Finding out who is on the other side of a transaction.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::account::is_bic;
+use crate::{
+    is_valid_iban, DutchInformation, StatementLine, StructuredInformation,
+    StructuredInformationToAccountOwner,
+};
+
+/// The other party of a [`StatementLine`] as far as it can be told from tag `:86:`.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Counterparty {
+    pub name: Option<String>,
+    /// An IBAN with a valid checksum, without any spaces.
+    pub iban: Option<String>,
+    pub bic: Option<String>,
+}
+
+impl Counterparty {
+    /// Use the parts of `other` which are missing in `self`.
+    fn or(self, other: Counterparty) -> Counterparty {
+        Counterparty {
+            name: self.name.or(other.name),
+            iban: self.iban.or(other.iban),
+            bic: self.bic.or(other.bic),
+        }
+    }
+}
+
+impl From<DutchInformation> for Counterparty {
+    fn from(information: DutchInformation) -> Counterparty {
+        Counterparty {
+            name: information.name,
+            iban: information.iban,
+            bic: information.bic,
+        }
+    }
+}
+
+impl StatementLine {
+    /// The other party of this transaction as far as it can be told from tag `:86:`.
+    ///
+    /// The [`StatementLine::structured_information`] is used if there is any. Otherwise the
+    /// information is checked for Dutch keywords like `/NAME/` and the German subfields `?30` to
+    /// `?33`. Anything still missing is guessed: a word with a valid IBAN checksum is taken as the
+    /// IBAN, a BIC right after it as the BIC and the rest of its line as the name.
+    ///
+    /// Returns `None` if nothing was found.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::parse_mt940;
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+    ///     :86:Invoice 42 DE89370400440532013000 COBADEFFXXX John Doe\r\n\
+    ///     :62F:C090924EUR53900,12\r\n";
+    ///
+    /// let input_parsed = parse_mt940(input).unwrap();
+    /// let counterparty = input_parsed[0].statement_lines[0].counterparty().unwrap();
+    /// assert_eq!(counterparty.iban.as_deref(), Some("DE89370400440532013000"));
+    /// assert_eq!(counterparty.bic.as_deref(), Some("COBADEFFXXX"));
+    /// assert_eq!(counterparty.name.as_deref(), Some("John Doe"));
+    /// ```
+    pub fn counterparty(&self) -> Option<Counterparty> {
+        let info = self.information_to_account_owner.as_deref()?;
+        let structured = match self.structured_information {
+            Some(StructuredInformation::Dutch(ref information)) => information.clone().into(),
+            Some(StructuredInformation::Nordic(ref information)) => Counterparty {
+                name: information.counterparty.clone(),
+                ..Default::default()
+            },
+            None => DutchInformation::from_information(info)
+                .map(Counterparty::from)
+                .unwrap_or_else(|| from_subfields(info)),
+        };
+
+        let counterparty = structured.or(guess(info));
+        if counterparty == Counterparty::default() {
+            None
+        } else {
+            Some(counterparty)
+        }
+    }
+}
+
+/// Get the counterparty from the German `?30` to `?33` subfields.
+fn from_subfields(info: &str) -> Counterparty {
+    let Some(information) = StructuredInformationToAccountOwner::from_information(info) else {
+        return Counterparty::default();
+    };
+    Counterparty {
+        name: information.counterparty_name,
+        iban: information
+            .counterparty_account
+            .filter(|account| is_valid_iban(account)),
+        bic: information.counterparty_bic,
+    }
+}
+
+/// Guess the counterparty from the first word with a valid IBAN checksum.
+fn guess(info: &str) -> Counterparty {
+    for line in info.lines() {
+        let mut words = line.split_whitespace();
+        let Some(iban) = words.find(|word| is_valid_iban(word)) else {
+            continue;
+        };
+        let mut rest = words.collect::<Vec<_>>();
+        let bic = match rest.first() {
+            Some(word) if is_bic(word) => Some(rest.remove(0).to_string()),
+            _ => None,
+        };
+        let name = rest.join(" ");
+        let has_letters = name.chars().any(char::is_alphabetic);
+        return Counterparty {
+            name: has_letters.then_some(name),
+            iban: Some(iban.to_string()),
+            bic,
+        };
+    }
+    Counterparty::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{parse_mt940_with_config, Dialect, ParserConfig};
+
+    fn counterparty(
+        name: Option<&str>,
+        iban: Option<&str>,
+        bic: Option<&str>,
+    ) -> Option<Counterparty> {
+        Some(Counterparty {
+            name: name.map(str::to_string),
+            iban: iban.map(str::to_string),
+            bic: bic.map(str::to_string),
+        })
+    }
+
+    #[rstest(
+        info,
+        config,
+        expected,
+        case(
+            "/TRTP/SEPA OVERBOEKING/IBAN/NL91ABNA0417164300/BIC/ABNANL2A/NAME/J. Doe/REMI/x",
+            ParserConfig::new(),
+            counterparty(Some("J. Doe"), Some("NL91ABNA0417164300"), Some("ABNANL2A")),
+        ),
+        case(
+            "166?00GUTSCHRIFT?20SVWZ+Invoice 42?30COBADEFFXXX?31DE89370400440532013000?32John Doe?33\n?34997",
+            ParserConfig::new(),
+            counterparty(Some("John Doe"), Some("DE89370400440532013000"), Some("COBADEFFXXX")),
+        ),
+        case(
+            "DB faktura 1234567\nTestkunde\nDK5000400440116243",
+            ParserConfig::from(Dialect::DanskeBank),
+            counterparty(Some("Testkunde"), Some("DK5000400440116243"), None),
+        ),
+        case(
+            "Invoice 42\nNL91ABNA0417164300 1234",
+            ParserConfig::new(),
+            counterparty(None, Some("NL91ABNA0417164300"), None),
+        ),
+        case("Invoice 42 NL91ABNA0417164301", ParserConfig::new(), None)
+    )]
+    fn counterparty_from_information(
+        info: &str,
+        config: ParserConfig,
+        expected: Option<Counterparty>,
+    ) {
+        let input = format!(
+            ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:61:0909250925D1,NMSCref\r\n\
+             :86:{}\r\n:62F:C090924EUR0,\r\n",
+            info.replace('\n', "\r\n")
+        );
+        let messages = parse_mt940_with_config(&input, &config).unwrap();
+        assert_eq!(messages[0].statement_lines[0].counterparty(), expected);
+    }
+}
@@ -0,0 +1,111 @@
+//! Validation of currency codes against ISO 4217.
+
+use rust_decimal::Decimal;
+
+use crate::{currency_exponent, ErrorLocation, MessageRef, ParseError};
+
+/// Active ISO 4217 currency codes, sorted for binary search.
+///
+/// `XXX` (no currency) and `XTS` (reserved for testing) are left out as they never denote the
+/// currency of an actual account.
+const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR",
+    "XOF", "XPD", "XPF", "XPT", "XSU", "XUA", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+/// Whether `code` is an active ISO 4217 currency code such as `EUR`.
+///
+/// Codes must be upper case. `XXX` and `XTS` are rejected even though they are part of ISO 4217.
+///
+/// # Example
+/// ```
+/// use mt940::is_iso4217_currency;
+///
+/// assert!(is_iso4217_currency("EUR"));
+/// assert!(!is_iso4217_currency("EUA"));
+/// assert!(!is_iso4217_currency("XXX"));
+/// ```
+pub fn is_iso4217_currency(code: &str) -> bool {
+    ISO_4217_CODES.binary_search(&code).is_ok()
+}
+
+/// The number of decimal places of the ISO 4217 currency `code` if `amount` has more than that.
+///
+/// Currencies which are not in ISO 4217 or don't have minor units are not checked.
+pub(crate) fn exceeded_decimal_places(amount: Decimal, code: &str) -> Option<u32> {
+    if !is_iso4217_currency(code) {
+        return None;
+    }
+    let exponent = currency_exponent(code)?;
+    (amount.scale() > exponent).then_some(exponent)
+}
+
+/// Fail with [`ParseError::UnknownCurrencyError`] if any balance of `message` is not in an ISO
+/// 4217 currency.
+pub(crate) fn validate_currencies(message: &MessageRef) -> Result<(), ParseError> {
+    let currencies = [
+        &message.opening_balance.iso_currency_code,
+        &message.closing_balance.iso_currency_code,
+    ]
+    .into_iter()
+    .chain(
+        message
+            .closing_available_balance
+            .iter()
+            .chain(&message.forward_available_balances)
+            .map(|balance| &balance.iso_currency_code),
+    );
+    for currency in currencies {
+        if !is_iso4217_currency(currency) {
+            return Err(ParseError::UnknownCurrencyError(
+                currency.clone(),
+                ErrorLocation::default(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_mt940_with_config, ParserConfig};
+
+    #[test]
+    fn codes_are_sorted_and_unique() {
+        assert!(ISO_4217_CODES
+            .windows(2)
+            .all(|pair| pair.first() < pair.last()));
+    }
+
+    #[test]
+    fn reject_unknown_currency_if_configured() {
+        let input = "\
+            :20:ref\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :62F:C090924EUR10,\r\n\
+            :64:C090924EUA10,\r\n";
+        let config = ParserConfig::new().validate_currencies(true);
+        assert!(parse_mt940_with_config(input, &ParserConfig::new()).is_ok());
+        let err = parse_mt940_with_config(input, &config).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnknownCurrencyError("EUA".to_string(), ErrorLocation::default())
+        );
+        assert!(parse_mt940_with_config(&input.replace("EUA", "EUR"), &config).is_ok());
+    }
+}
@@ -0,0 +1,181 @@
+//! Detection of transactions which were delivered more than once.
+
+use std::collections::HashMap;
+
+use crate::utils::fnv1a;
+use crate::writer::format_ext_debit_credit;
+use crate::{AccountKey, Message, StatementLine};
+
+impl StatementLine {
+    /// A hash identifying this transaction across statement files.
+    ///
+    /// It covers the value date, debit or credit, amount, transaction type, references,
+    /// supplementary details and tag `:86:`. Whitespace in the texts is collapsed as banks tend to
+    /// wrap tag `:86:` differently when resending it. Trailing zeros of the amount are ignored.
+    ///
+    /// The fingerprint is the same on every platform and across versions of this crate so it may
+    /// be stored.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::parse_mt940;
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+    ///     :86:Beneficiary name\r\n\
+    ///     :61:0909250925DR583,920NMSC1110030403010139//1234\r\n\
+    ///     :86:Beneficiary\r\n\
+    ///     name\r\n\
+    ///     :62F:C090930EUR53316,20\r\n";
+    ///
+    /// let message = &parse_mt940(input).unwrap()[0];
+    /// assert_eq!(
+    ///     message.statement_lines[0].fingerprint(),
+    ///     message.statement_lines[1].fingerprint()
+    /// );
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let amount = self.amount.normalize().to_string();
+        let parts = [
+            self.value_date.format("%Y-%m-%d").to_string(),
+            format_ext_debit_credit(&self.ext_debit_credit_indicator).to_string(),
+            amount,
+            self.transaction_type_ident_code.code().to_string(),
+            self.customer_ref.clone(),
+            self.bank_ref.clone().unwrap_or_default(),
+            collapse_whitespace(self.supplementary_details.as_deref()),
+            collapse_whitespace(self.information_to_account_owner.as_deref()),
+        ];
+        // Separate the parts with a byte which can't occur in MT940 so they can't run into each
+        // other.
+        fnv1a(parts.iter().flat_map(|part| part.bytes().chain([0x1f])))
+    }
+}
+
+fn collapse_whitespace(text: Option<&str>) -> String {
+    text.unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The statement lines of `messages` without those delivered before.
+///
+/// Statement lines are compared by [`StatementLine::fingerprint`]. A statement line is dropped if
+/// an earlier message about the same account, as told by [`Message::account_key`], already
+/// contains a statement line with the same fingerprint. Identical statement lines within the same
+/// message, e.g. two equal card payments on one day, are all kept unless an earlier message
+/// contains as many of them.
+///
+/// # Example
+/// ```
+/// use mt940::{dedup_messages, parse_mt940};
+///
+/// let september = "\
+///     :20:1\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:1\r\n\
+///     :60F:C090901EUR100,\r\n\
+///     :61:090925D10,NTRFNONREF\r\n\
+///     :61:090930D20,NTRFNONREF\r\n\
+///     :62F:C090930EUR70,\r\n";
+/// let resent = "\
+///     :20:2\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:2\r\n\
+///     :60F:C090924EUR90,\r\n\
+///     :61:090930D20,NTRFNONREF\r\n\
+///     :61:091001D30,NTRFNONREF\r\n\
+///     :62F:C091001EUR40,\r\n";
+///
+/// let messages = parse_mt940(&format!("{}{}", september, resent)).unwrap();
+/// let amounts = dedup_messages(&messages)
+///     .iter()
+///     .map(|(_, statement_line)| statement_line.amount.to_string())
+///     .collect::<Vec<_>>();
+/// assert_eq!(amounts, ["10", "20", "30"]);
+/// ```
+pub fn dedup_messages(messages: &[Message]) -> Vec<(&Message, &StatementLine)> {
+    let mut delivered_per_account: HashMap<AccountKey, HashMap<u64, usize>> = HashMap::new();
+    let mut unique = vec![];
+    for message in messages {
+        let delivered = delivered_per_account
+            .entry(message.account_key())
+            .or_default();
+        let mut in_message: HashMap<u64, usize> = HashMap::new();
+        for statement_line in &message.statement_lines {
+            let count = in_message.entry(statement_line.fingerprint()).or_default();
+            *count += 1;
+            if *count
+                > delivered
+                    .get(&statement_line.fingerprint())
+                    .copied()
+                    .unwrap_or(0)
+            {
+                unique.push((message, statement_line));
+            }
+        }
+        for (fingerprint, count) in in_message {
+            let delivered = delivered.entry(fingerprint).or_default();
+            *delivered = (*delivered).max(count);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    use super::*;
+    use crate::parse_mt940;
+
+    const MESSAGE: &str = "\
+        :20:ref\r\n\
+        :25:account\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR10,\r\n\
+        :61:090925D1,NTRFcoffee\r\n\
+        :61:090925D1,NTRFcoffee\r\n\
+        :61:090926D1,NTRFcoffee\r\n\
+        :62F:C090926EUR7,\r\n";
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let messages = parse_mt940(MESSAGE).unwrap();
+        let lines = &messages[0].statement_lines;
+        assert_eq!(lines[0].fingerprint(), 0xd9b6_e568_6a43_d39b);
+        assert_eq!(lines[0].fingerprint(), lines[1].fingerprint());
+        assert_ne!(lines[0].fingerprint(), lines[2].fingerprint());
+    }
+
+    #[test]
+    fn identical_lines_within_a_message_are_kept() {
+        let resent = MESSAGE.replace(":61:090926D1,NTRFcoffee\r\n", "");
+        let messages = parse_mt940(&format!("{}{}{}", MESSAGE, resent, MESSAGE)).unwrap();
+        assert_eq!(dedup_messages(&messages).len(), 3);
+
+        let more = MESSAGE.replace(":61:090926", ":61:090925");
+        let messages = parse_mt940(&format!("{}{}", MESSAGE, more)).unwrap();
+        let unique = dedup_messages(&messages);
+        assert_eq!(unique.len(), 4);
+        assert!(std::ptr::eq(unique[3].0, &messages[1]));
+    }
+
+    #[test]
+    fn lines_of_different_accounts_are_kept() {
+        let other_account = MESSAGE.replace(":25:account", ":25:other");
+        let messages = parse_mt940(&format!("{}{}", MESSAGE, other_account)).unwrap();
+        let unique = dedup_messages(&messages);
+        assert_eq!(unique.len(), 6);
+        assert!(std::ptr::eq(unique[5].0, &messages[1]));
+
+        let other_currency = MESSAGE.replace("EUR", "USD");
+        let messages = parse_mt940(&format!("{}{}", MESSAGE, other_currency)).unwrap();
+        assert_eq!(dedup_messages(&messages).len(), 6);
+    }
+}
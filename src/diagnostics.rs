@@ -0,0 +1,240 @@
+//! Collection of every problem in a statement instead of just the first one.
+
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::streaming::MessageBlocks;
+use crate::{Message, ParseError, ParserConfig, Span, Tag};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The statement conforms to MT940 but doesn't follow common practice.
+    ///
+    /// Only reported by [`lint`](crate::lint).
+    Info,
+    /// The statement is syntactically fine but its content is suspicious.
+    Warning,
+    /// Part of the statement doesn't conform to MT940 and had to be skipped.
+    Error,
+}
+
+/// A single problem found by [`diagnose_mt940`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    /// The tag the problem concerns, if it concerns a specific one.
+    pub tag: Option<String>,
+
+    /// Where in the statement the problem is.
+    pub span: Option<Span>,
+
+    /// Human readable description of the problem.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Describe `err` which occurred while parsing `statement`.
+    pub(crate) fn from_error(err: &ParseError, statement: &str) -> Diagnostic {
+        let span = err.span();
+        let tag = match err {
+            ParseError::RequiredTagNotFoundError(err, _) => Some(err.required_tag().to_string()),
+            ParseError::UnexpectedTagError(err, _) => Some(err.current_tag().to_string()),
+            ParseError::UnknownTagError(tag, _) => Some(tag.clone()),
+            ParseError::DuplicateTagError(err, _) => Some(err.tag().to_string()),
+            ParseError::MissingComponent { tag, .. } => Some(tag.to_string()),
+            _ => span.and_then(|span| tag_at(span, statement)),
+        };
+        Diagnostic {
+            severity: Severity::Error,
+            tag,
+            span,
+            message: err.without_location().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}", severity, self.message)?;
+        if let Some(span) = self.span {
+            write!(f, " (line {}, column {})", span.line, span.column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a MT940 statement as far as possible and report every problem found.
+///
+/// This recovers from errors the same way [`parse_mt940_lenient`] does and reports them as
+/// [`Severity::Error`]. Messages which could be parsed are additionally checked for:
+///
+/// - A closing balance which isn't the opening balance plus the statement lines.
+/// - Balances in different currencies.
+///
+/// These are reported as [`Severity::Warning`]. Diagnostics are in the order of their occurrence
+/// in the statement.
+///
+/// [`parse_mt940_lenient`]: crate::parse_mt940_lenient
+///
+/// # Example
+/// ```
+/// use mt940::{diagnose_mt940, Severity};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0913250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :62F:C090930EUR53126,94\r\n";
+///
+/// let diagnostics = diagnose_mt940(input);
+/// assert_eq!(diagnostics.len(), 2);
+/// assert_eq!(diagnostics[0].severity, Severity::Error);
+/// assert_eq!(diagnostics[0].tag.as_deref(), Some("61"));
+/// assert_eq!(diagnostics[0].span.unwrap().line, 5);
+/// assert_eq!(diagnostics[1].severity, Severity::Warning);
+/// assert_eq!(diagnostics[1].tag.as_deref(), Some("62F"));
+/// ```
+pub fn diagnose_mt940(statement: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    for block in MessageBlocks::new(statement) {
+        let fields = match block.and_then(|block| block.parse_fields()) {
+            Ok(fields) => fields,
+            Err(err) => {
+                diagnostics.push(Diagnostic::from_error(&err, statement));
+                continue;
+            }
+        };
+        let closing_balance = fields
+            .iter()
+            .find(|field| {
+                matches!(
+                    field.tag,
+                    Tag::ClosingBalance | Tag::IntermediateClosingBalance
+                )
+            })
+            .map(|field| (field.tag.to_string(), field.span));
+
+        let mut errors = vec![];
+        let message =
+            Message::from_fields_with_config(fields, &ParserConfig::default(), Some(&mut errors));
+        diagnostics.extend(
+            errors
+                .iter()
+                .map(|err| Diagnostic::from_error(err, statement)),
+        );
+        match message {
+            Ok(message) => {
+                let (tag, span) = closing_balance.unzip();
+                check_balances(&message, tag, span.flatten(), &mut diagnostics);
+            }
+            Err(err) => diagnostics.push(Diagnostic::from_error(&err, statement)),
+        }
+    }
+
+    diagnostics
+}
+
+/// Warn about balances of `message` which don't add up.
+pub(crate) fn check_balances(
+    message: &Message,
+    tag: Option<String>,
+    span: Option<Span>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let opening = &message.opening_balance;
+    let closing = &message.closing_balance;
+    let warning = |message| Diagnostic {
+        severity: Severity::Warning,
+        tag: tag.clone(),
+        span,
+        message,
+    };
+
+    if opening.iso_currency_code != closing.iso_currency_code {
+        diagnostics.push(warning(format!(
+            "Opening balance is in {} but closing balance is in {}",
+            opening.iso_currency_code, closing.iso_currency_code
+        )));
+        return;
+    }
+    let expected = opening.signed_amount() + message.net_change();
+    if expected != closing.signed_amount() {
+        diagnostics.push(warning(format!(
+            "Closing balance is {} but opening balance and statement lines add up to {}",
+            closing.signed_amount(),
+            expected
+        )));
+    }
+}
+
+/// The tag of the field starting at `span`.
+fn tag_at(span: Span, statement: &str) -> Option<String> {
+    let snippet = span.snippet(statement)?;
+    let (tag, _) = snippet.strip_prefix(':')?.split_once(':')?;
+    Some(tag.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:0909250925D1,NMSCref\r\n\
+        :86:info\r\n\
+        :62F:C090924EUR0,\r\n";
+
+    #[test]
+    fn no_diagnostics() {
+        assert_eq!(diagnose_mt940(MESSAGE), vec![]);
+    }
+
+    #[test]
+    fn collects_all_problems() {
+        let broken_line = MESSAGE.replace(":61:0909250925", ":61:0913250925");
+        let missing_tag = MESSAGE.replace(":25:b\r\n", "");
+        let input = format!("{}{}{}", broken_line, MESSAGE, missing_tag);
+        let diagnostics = diagnose_mt940(&input);
+
+        let summary = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.tag.as_deref(), d.span.map(|span| span.line)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (Severity::Error, Some("61"), Some(5)),
+                (Severity::Warning, Some("62F"), Some(7)),
+                (Severity::Error, Some("28C"), Some(16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn currency_mismatch() {
+        let input = MESSAGE.replace(":62F:C090924EUR", ":62F:C090924USD");
+        let diagnostics = diagnose_mt940(&input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning: Opening balance is in EUR but closing balance is in USD (line 7, column 1)"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Comparison of two parses of the same statement, e.g. an original and a corrected delivery.
+
+use std::fmt;
+
+use crate::writer::format_debit_credit;
+use crate::{AvailableBalance, Balance, Message, StatementLine};
+
+/// Differences between two lists of [`Message`]s as found by [`diff_messages`].
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct StatementDiff<'a> {
+    /// Messages only found in the new statement.
+    pub added_messages: Vec<&'a Message>,
+
+    /// Messages only found in the old statement.
+    pub removed_messages: Vec<&'a Message>,
+
+    /// Messages found in both statements which differ.
+    pub changed_messages: Vec<MessageDiff<'a>>,
+}
+
+/// Differences between two versions of the same [`Message`].
+#[derive(Debug, Eq, PartialEq)]
+pub struct MessageDiff<'a> {
+    pub old: &'a Message,
+    pub new: &'a Message,
+
+    /// Statement lines only found in the new message.
+    pub added_lines: Vec<&'a StatementLine>,
+
+    /// Statement lines only found in the old message.
+    pub removed_lines: Vec<&'a StatementLine>,
+
+    /// Old and new version of statement lines which have the same value date and references but
+    /// differ otherwise.
+    pub changed_lines: Vec<(&'a StatementLine, &'a StatementLine)>,
+
+    /// Old and new opening balance if it changed.
+    pub opening_balance: Option<(&'a Balance, &'a Balance)>,
+
+    /// Old and new closing balance if it changed.
+    pub closing_balance: Option<(&'a Balance, &'a Balance)>,
+
+    /// Old and new closing available balance if it changed.
+    pub closing_available_balance:
+        Option<(Option<&'a AvailableBalance>, Option<&'a AvailableBalance>)>,
+}
+
+impl StatementDiff<'_> {
+    /// Whether both statements contain the same messages.
+    pub fn is_empty(&self) -> bool {
+        self.added_messages.is_empty()
+            && self.removed_messages.is_empty()
+            && self.changed_messages.is_empty()
+    }
+}
+
+impl MessageDiff<'_> {
+    /// Whether the statement lines and balances of both messages are the same.
+    ///
+    /// Other differences such as in the transaction reference number or tag `:86:` of the message
+    /// itself are not considered.
+    pub fn is_empty(&self) -> bool {
+        self.added_lines.is_empty()
+            && self.removed_lines.is_empty()
+            && self.changed_lines.is_empty()
+            && self.opening_balance.is_none()
+            && self.closing_balance.is_none()
+            && self.closing_available_balance.is_none()
+    }
+}
+
+/// Compare two parses of the same statement.
+///
+/// Messages are matched by account, statement number and sequence number. Within a pair of
+/// messages, identical statement lines are matched first. Of the remaining ones, lines with the
+/// same value date, customer reference and bank reference are reported as changed and all others
+/// as added or removed.
+///
+/// # Example
+/// ```
+/// use mt940::{diff_messages, parse_mt940};
+///
+/// let old = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+/// let new = old
+///     .replace("DR583,92", "DR538,92")
+///     .replace("EUR53900,12", "EUR53945,12");
+///
+/// let old = parse_mt940(old).unwrap();
+/// let new = parse_mt940(&new).unwrap();
+/// let diff = diff_messages(&old, &new);
+/// assert_eq!(diff.changed_messages.len(), 1);
+/// assert_eq!(diff.changed_messages[0].changed_lines.len(), 1);
+/// assert!(diff.changed_messages[0].closing_balance.is_some());
+/// ```
+pub fn diff_messages<'a>(old: &'a [Message], new: &'a [Message]) -> StatementDiff<'a> {
+    let mut diff = StatementDiff::default();
+    let mut unmatched = old.iter().collect::<Vec<_>>();
+    for new_message in new {
+        match unmatched
+            .iter()
+            .position(|old_message| same_statement(old_message, new_message))
+        {
+            Some(position) => {
+                let message_diff = diff_message(unmatched.remove(position), new_message);
+                if !message_diff.is_empty() {
+                    diff.changed_messages.push(message_diff);
+                }
+            }
+            None => diff.added_messages.push(new_message),
+        }
+    }
+    diff.removed_messages = unmatched;
+    diff
+}
+
+fn same_statement(old: &Message, new: &Message) -> bool {
+    old.account_id == new.account_id
+        && old.statement_no == new.statement_no
+        && old.sequence_no == new.sequence_no
+}
+
+fn same_transaction(old: &StatementLine, new: &StatementLine) -> bool {
+    old.value_date == new.value_date
+        && old.customer_ref == new.customer_ref
+        && old.bank_ref == new.bank_ref
+}
+
+fn diff_message<'a>(old: &'a Message, new: &'a Message) -> MessageDiff<'a> {
+    let mut removed_lines = old.statement_lines.iter().collect::<Vec<_>>();
+    let mut added_lines = vec![];
+    for line in &new.statement_lines {
+        match removed_lines.iter().position(|old_line| *old_line == line) {
+            Some(position) => {
+                removed_lines.remove(position);
+            }
+            None => added_lines.push(line),
+        }
+    }
+
+    let mut changed_lines = vec![];
+    added_lines.retain(|new_line| {
+        match removed_lines
+            .iter()
+            .position(|old_line| same_transaction(old_line, new_line))
+        {
+            Some(position) => {
+                changed_lines.push((removed_lines.remove(position), *new_line));
+                false
+            }
+            None => true,
+        }
+    });
+
+    let changed = |old, new| (old != new).then_some((old, new));
+    MessageDiff {
+        old,
+        new,
+        added_lines,
+        removed_lines,
+        changed_lines,
+        opening_balance: changed(&old.opening_balance, &new.opening_balance),
+        closing_balance: changed(&old.closing_balance, &new.closing_balance),
+        closing_available_balance: (old.closing_available_balance != new.closing_available_balance)
+            .then_some((
+                old.closing_available_balance.as_ref(),
+                new.closing_available_balance.as_ref(),
+            )),
+    }
+}
+
+/// A report with one section per added, removed or changed message.
+///
+/// Removed statement lines are prefixed with `-`, added ones with `+`. Changed ones are shown as
+/// the old line prefixed with `~` followed by the new line.
+impl fmt::Display for StatementDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for message in &self.removed_messages {
+            writeln!(f, "- {}", heading(message))?;
+        }
+        for message in &self.added_messages {
+            writeln!(f, "+ {}", heading(message))?;
+        }
+        for message_diff in &self.changed_messages {
+            write!(f, "{}", message_diff)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MessageDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", heading(self.new))?;
+        if let Some((old, new)) = self.opening_balance {
+            writeln!(f, "  Opening balance: {} -> {}", balance(old), balance(new))?;
+        }
+        for line in &self.removed_lines {
+            writeln!(f, "- {}", line)?;
+        }
+        for line in &self.added_lines {
+            writeln!(f, "+ {}", line)?;
+        }
+        for (old, new) in &self.changed_lines {
+            writeln!(f, "~ {}", old)?;
+            writeln!(f, "  {}", new)?;
+        }
+        if let Some((old, new)) = self.closing_balance {
+            writeln!(f, "  Closing balance: {} -> {}", balance(old), balance(new))?;
+        }
+        if let Some((old, new)) = self.closing_available_balance {
+            let available = |balance: Option<&AvailableBalance>| match balance {
+                Some(balance) => format!(
+                    "{} {} {} {}",
+                    balance.date,
+                    format_debit_credit(&balance.debit_credit_indicator),
+                    balance.amount,
+                    balance.iso_currency_code
+                ),
+                None => "none".to_string(),
+            };
+            writeln!(
+                f,
+                "  Closing available balance: {} -> {}",
+                available(old),
+                available(new)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn balance(balance: &Balance) -> String {
+    format!(
+        "{} {} {} {}",
+        balance.date,
+        format_debit_credit(&balance.debit_credit_indicator),
+        balance.amount,
+        balance.iso_currency_code
+    )
+}
+
+fn heading(message: &Message) -> String {
+    let statement_no = match message.sequence_no {
+        Some(ref sequence_no) => format!("{}/{}", message.statement_no, sequence_no),
+        None => message.statement_no.clone(),
+    };
+    format!(
+        "Statement {} of {} ({})",
+        statement_no, message.account_id, message.transaction_ref_no
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    const MESSAGE: &str = "\
+        :20:ref\r\n\
+        :25:account\r\n\
+        :28C:1/1\r\n\
+        :60F:C090924EUR10,\r\n\
+        :61:090925D1,NTRFfirst\r\n\
+        :86:Alice\r\n\
+        :61:090926D2,NTRFsecond\r\n\
+        :62F:C090926EUR7,\r\n";
+
+    #[test]
+    fn same_statement_has_no_diff() {
+        let old = parse_mt940(MESSAGE).unwrap();
+        let new = parse_mt940(MESSAGE).unwrap();
+        let diff = diff_messages(&old, &new);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn added_removed_and_changed_lines() {
+        let old = parse_mt940(MESSAGE).unwrap();
+        let corrected = MESSAGE
+            .replace(":86:Alice", ":86:Bob")
+            .replace(":61:090926D2,NTRFsecond\r\n", ":61:090927C3,NTRFthird\r\n")
+            .replace(":62F:C090926EUR7,", ":62F:C090927EUR12,");
+        let other = MESSAGE.replace(":28C:1/1", ":28C:2/1");
+        let new = parse_mt940(&format!("{}{}", corrected, other)).unwrap();
+
+        let diff = diff_messages(&old, &new);
+        assert_eq!(diff.added_messages, vec![&new[1]]);
+        assert!(diff.removed_messages.is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "\
++ Statement 2/1 of account (ref)
+Statement 1/1 of account (ref)
+- 2009-09-26  D                 2
++ 2009-09-27  C                 3
+~ 2009-09-25  D                 1  Alice
+  2009-09-25  D                 1  Bob
+  Closing balance: 2009-09-26 C 7 EUR -> 2009-09-27 C 12 EUR
+"
+        );
+    }
+}
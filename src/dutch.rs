@@ -0,0 +1,227 @@
+//! Extraction of the slash-delimited keywords Dutch banks put into tag `:86:`.
+
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::StatementLine;
+
+/// All keywords we recognize as such.
+///
+/// Values may contain slashes themselves, so only these start a new keyword.
+const DUTCH_KEYWORDS: [&str; 30] = [
+    "ADDR",
+    "BENM",
+    "BIC",
+    "CDTRREF",
+    "CDTRREFTP",
+    "CHGS",
+    "CNTP",
+    "CREF",
+    "CSID",
+    "EREF",
+    "EXCH",
+    "FX",
+    "IBAN",
+    "ID",
+    "ISDT",
+    "ISSR",
+    "MARF",
+    "NAME",
+    "OCMT",
+    "ORDP",
+    "PREF",
+    "PURP",
+    "REMI",
+    "RTRN",
+    "SVCL",
+    "TRTP",
+    "ULTB",
+    "ULTC",
+    "ULTD",
+    "USTD",
+];
+
+/// The keywords contained in the information to the account owner of a [`StatementLine`] as
+/// used by Dutch banks such as ING, ABN AMRO and Rabobank.
+///
+/// Banks put these into tag `:86:` as keywords enclosed in slashes followed by their value, for
+/// instance `/TRTP/SEPA OVERBOEKING/IBAN/NL44RABO0123456789/BIC/RABONL2U/NAME/J DOE/REMI/Invoice
+/// 42`. ING gives IBAN, BIC, name and city of the counterparty as a single `/CNTP/` keyword.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DutchInformation {
+    /// Transaction type (`/TRTP/`)
+    pub transaction_type: Option<String>,
+
+    /// IBAN of the counterparty (`/IBAN/` or the first part of `/CNTP/`)
+    pub iban: Option<String>,
+
+    /// BIC of the counterparty (`/BIC/` or the second part of `/CNTP/`)
+    pub bic: Option<String>,
+
+    /// Name of the counterparty (`/NAME/` or the third part of `/CNTP/`)
+    pub name: Option<String>,
+
+    /// Remittance information (`/REMI/` or `/REMI/USTD//`)
+    pub remittance_info: Option<String>,
+
+    /// End-to-end reference (`/EREF/`)
+    pub end_to_end_ref: Option<String>,
+
+    /// Mandate reference of a direct debit (`/MARF/`)
+    pub mandate_ref: Option<String>,
+
+    /// Creditor identifier of a direct debit (`/CSID/`)
+    pub creditor_id: Option<String>,
+
+    /// All keywords with their values, including the ones above.
+    ///
+    /// Only the first value is kept of keywords which occur more than once.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl DutchInformation {
+    /// Extract the keywords from the contents of a tag `:86:`.
+    ///
+    /// Returns `None` if it doesn't start with a keyword.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::DutchInformation;
+    ///
+    /// let info = "/TRTP/SEPA OVERBOEKING/IBAN/NL44RABO0123456789/BIC/RABONL2U/NAME/J. DOE/REMI/Invoi\nce 42";
+    /// let information = DutchInformation::from_information(info).unwrap();
+    /// assert_eq!(information.transaction_type.as_deref(), Some("SEPA OVERBOEKING"));
+    /// assert_eq!(information.iban.as_deref(), Some("NL44RABO0123456789"));
+    /// assert_eq!(information.name.as_deref(), Some("J. DOE"));
+    /// assert_eq!(information.remittance_info.as_deref(), Some("Invoice 42"));
+    /// assert_eq!(information.fields["BIC"], "RABONL2U");
+    /// ```
+    pub fn from_information(info: &str) -> Option<DutchInformation> {
+        // Lines are wrapped at a fixed width, often in the middle of a word.
+        let joined: String = info.lines().collect();
+        let mut parts = joined.trim().strip_prefix('/')?.split('/').peekable();
+        if !parts.peek().is_some_and(|part| is_keyword(part)) {
+            return None;
+        }
+
+        let mut fields = BTreeMap::new();
+        while let Some(keyword) = parts.next() {
+            let mut value = vec![];
+            while let Some(part) = parts.next_if(|part| !is_keyword(part)) {
+                value.push(part);
+            }
+            // A trailing slash closes the last value rather than being part of it.
+            if value.last() == Some(&"") && parts.peek().is_none() {
+                value.pop();
+            }
+            fields
+                .entry(keyword.to_string())
+                .or_insert_with(|| value.join("/").trim().to_string());
+        }
+
+        let get = |keyword: &str| {
+            fields
+                .get(keyword)
+                .filter(|value| !value.is_empty())
+                .cloned()
+        };
+        let counterparty = fields
+            .get("CNTP")
+            .map(|value| value.split('/').map(str::trim).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let counterparty_part = |index: usize| {
+            counterparty
+                .get(index)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+
+        Some(DutchInformation {
+            transaction_type: get("TRTP"),
+            iban: get("IBAN").or_else(|| counterparty_part(0)),
+            bic: get("BIC").or_else(|| counterparty_part(1)),
+            name: get("NAME").or_else(|| counterparty_part(2)),
+            remittance_info: get("REMI")
+                .or_else(|| get("USTD").map(|value| value.trim_start_matches('/').to_string())),
+            end_to_end_ref: get("EREF"),
+            mandate_ref: get("MARF"),
+            creditor_id: get("CSID"),
+            fields,
+        })
+    }
+}
+
+fn is_keyword(part: &str) -> bool {
+    DUTCH_KEYWORDS.contains(&part)
+}
+
+impl StatementLine {
+    /// Keywords contained in the information to the account owner of this [`StatementLine`] as
+    /// used by Dutch banks.
+    ///
+    /// See [`DutchInformation::from_information`].
+    pub fn dutch_information(&self) -> Option<DutchInformation> {
+        self.information_to_account_owner
+            .as_deref()
+            .and_then(DutchInformation::from_information)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest(
+        info,
+        expected,
+        case(
+            "/EREF/01-01-2013 12:00 0030000987654321/BENM//NAME/CONTRA ACCOUN\nT HOLDER/REMI//ISDT/2013-07-11",
+            &[
+                ("BENM", ""),
+                ("EREF", "01-01-2013 12:00 0030000987654321"),
+                ("ISDT", "2013-07-11"),
+                ("NAME", "CONTRA ACCOUNT HOLDER"),
+                ("REMI", ""),
+            ],
+        ),
+        case(
+            "/CNTP/NL12INGB0001234567/INGBNL2A/J. Doe/Amsterdam/REMI/USTD//Invoice 42/",
+            &[
+                ("CNTP", "NL12INGB0001234567/INGBNL2A/J. Doe/Amsterdam"),
+                ("REMI", ""),
+                ("USTD", "/Invoice 42"),
+            ],
+        )
+    )]
+    fn dutch_information_fields(info: &str, expected: &[(&str, &str)]) {
+        let information = DutchInformation::from_information(info).unwrap();
+        let fields = information
+            .fields
+            .iter()
+            .map(|(keyword, value)| (keyword.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, expected);
+    }
+
+    #[test]
+    fn dutch_information_counterparty() {
+        let info = "/CNTP/NL12INGB0001234567/INGBNL2A/J. Doe/Amsterdam/REMI/USTD//Invoice 42/";
+        let information = DutchInformation::from_information(info).unwrap();
+        assert_eq!(information.iban.as_deref(), Some("NL12INGB0001234567"));
+        assert_eq!(information.bic.as_deref(), Some("INGBNL2A"));
+        assert_eq!(information.name.as_deref(), Some("J. Doe"));
+        assert_eq!(information.remittance_info.as_deref(), Some("Invoice 42"));
+    }
+
+    #[test]
+    fn dutch_information_none() {
+        for info in ["BEA   NR:XXX1234   21.05.11/12.54", "/FOO/bar", ""] {
+            assert_eq!(DutchInformation::from_information(info), None);
+        }
+    }
+}
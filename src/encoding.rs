@@ -0,0 +1,177 @@
+//! Decoding of statements which aren't valid UTF-8.
+
+use std::borrow::Cow;
+use std::io;
+
+use crate::{parse_mt940, Message, ParseError};
+
+/// Byte order mark some tools put in front of UTF-8 files.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Characters of Windows-1252 in the range `0x80` to `0x9F` where it differs from Latin-1.
+///
+/// The five bytes undefined in Windows-1252 are mapped to the C1 control characters like in
+/// Latin-1.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Character encoding of a statement passed to [`parse_mt940_bytes`].
+///
+/// Variants depend on the enabled features, so matching on this needs a wildcard arm.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8, optionally starting with a byte order mark.
+    Utf8,
+
+    /// ISO 8859-1 where every byte is the code point of the same value.
+    Latin1,
+
+    /// Windows code page 1252 which many banks use for their exports.
+    Windows1252,
+
+    /// Use UTF-8 if the input is valid UTF-8 and Windows-1252 otherwise.
+    #[cfg(feature = "encoding")]
+    Auto,
+}
+
+impl Encoding {
+    /// Decode `bytes` into text.
+    ///
+    /// Valid UTF-8 is borrowed from `bytes` with the byte order mark removed. Invalid UTF-8 is
+    /// reported as [`ParseError::IoError`] with [`io::ErrorKind::InvalidData`]. Latin-1 and
+    /// Windows-1252 always succeed as every byte maps to a character.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::Encoding;
+    ///
+    /// let bytes = b"Zahlung M\xFCller \x80";
+    /// assert_eq!(Encoding::Latin1.decode(bytes).unwrap(), "Zahlung Müller \u{80}");
+    /// assert_eq!(Encoding::Windows1252.decode(bytes).unwrap(), "Zahlung Müller €");
+    /// assert!(Encoding::Utf8.decode(bytes).is_err());
+    /// ```
+    pub fn decode(self, bytes: &[u8]) -> Result<Cow<'_, str>, ParseError> {
+        match self {
+            Encoding::Utf8 => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                std::str::from_utf8(bytes)
+                    .map(Cow::Borrowed)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+            }
+            Encoding::Latin1 => Ok(decode_single_byte(bytes, char::from)),
+            Encoding::Windows1252 => Ok(decode_single_byte(bytes, windows_1252_char)),
+            #[cfg(feature = "encoding")]
+            Encoding::Auto => detect_encoding(bytes).decode(bytes),
+        }
+    }
+}
+
+/// Guess the encoding of `bytes`.
+///
+/// Input which is valid UTF-8 is assumed to be UTF-8. Anything else is assumed to be Windows-1252,
+/// a superset of the printable characters of Latin-1, which is by far the most common encoding of
+/// statements that aren't UTF-8.
+#[cfg(feature = "encoding")]
+pub fn detect_encoding(bytes: &[u8]) -> Encoding {
+    if std::str::from_utf8(bytes).is_ok() {
+        Encoding::Utf8
+    } else {
+        Encoding::Windows1252
+    }
+}
+
+/// Decode a single-byte encoding, borrowing ASCII input.
+fn decode_single_byte(bytes: &[u8], to_char: fn(u8) -> char) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if text.is_ascii() => Cow::Borrowed(text),
+        _ => Cow::Owned(bytes.iter().copied().map(to_char).collect()),
+    }
+}
+
+fn windows_1252_char(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252_HIGH
+            .get(usize::from(byte - 0x80))
+            .copied()
+            .unwrap_or(char::from(byte)),
+        _ => char::from(byte),
+    }
+}
+
+/// Parse a MT940 statement given as raw bytes in the given [`Encoding`].
+///
+/// This saves decoding statements yourself which are often delivered as Latin-1 or Windows-1252
+/// and can't be read with `fs::read_to_string`. The decoded statement is parsed by
+/// [`parse_mt940`]. Note that characters like umlauts are not part of the SWIFT charset so you
+/// may still have to sanitize the decoded text using [`sanitizers`](crate::sanitizers).
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940_bytes, Encoding};
+///
+/// let input = b"\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:\x91Beneficiary name\r\n\
+///     :62F:C090930EUR53126,94\r\n";
+///
+/// let messages = parse_mt940_bytes(input, Encoding::Windows1252).unwrap();
+/// let info = messages[0].statement_lines[0].information_to_account_owner.as_deref();
+/// assert_eq!(info, Some("‘Beneficiary name"));
+/// ```
+pub fn parse_mt940_bytes(input: &[u8], encoding: Encoding) -> Result<Vec<Message>, ParseError> {
+    parse_mt940(&encoding.decode(input)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn utf8_bom_is_stripped() {
+        let decoded = Encoding::Utf8.decode(b"\xEF\xBB\xBF:20:a").unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(":20:a")));
+    }
+
+    #[test]
+    fn invalid_utf8_is_io_error() {
+        let err = Encoding::Utf8.decode(b":86:\xFC").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::IoError {
+                kind: io::ErrorKind::InvalidData,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn windows_1252_differs_from_latin1() {
+        let bytes = (0x7Eu8..=0xA0).collect::<Vec<_>>();
+        let latin1 = Encoding::Latin1.decode(&bytes).unwrap();
+        let windows_1252 = Encoding::Windows1252.decode(&bytes).unwrap();
+        assert_eq!(latin1.chars().count(), bytes.len());
+        assert_eq!(
+            windows_1252,
+            "~\u{7F}€\u{81}‚ƒ„…†‡ˆ‰Š‹Œ\u{8D}Ž\u{8F}\u{90}‘’“”•–—˜™š›œ\u{9D}žŸ\u{A0}"
+        );
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn detect() {
+        assert_eq!(detect_encoding("Müller".as_bytes()), Encoding::Utf8);
+        assert_eq!(detect_encoding(b"M\xFCller"), Encoding::Windows1252);
+        assert_eq!(Encoding::Auto.decode(b"M\xFCller").unwrap(), "Müller");
+    }
+}
@@ -1,6 +1,10 @@
+use std::fmt;
+
 use thiserror::Error;
 
-use crate::Rule;
+use crate::source::MessageSource;
+use crate::span::Span;
+use crate::{Rule, Tag};
 
 #[derive(Debug, Clone, Eq, PartialEq, Error)]
 pub enum DateParseError {
@@ -11,6 +15,9 @@ pub enum DateParseError {
         day: String,
     },
 
+    #[error("Time parsing failed for time: '{}:{}'", hour, minute)]
+    TimeOutOfRange { hour: String, minute: String },
+
     #[error("Pest parsing error: {}", _0)]
     PestParseError(Box<pest::error::Error<Rule>>),
 }
@@ -40,69 +47,314 @@ pub enum AmountParseError {
 }
 
 /// Error thrown when parsing fails.
+///
+/// Every variant carries an [`ErrorLocation`] telling where in the input the error occurred, as
+/// far as known. It doesn't take part in comparing errors, so errors can be compared with ones
+/// constructed with [`ErrorLocation::default`].
 #[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[non_exhaustive]
 pub enum ParseError {
-    #[error("Pest parsing error: {}", _0)]
-    PestParseError(Box<pest::error::Error<Rule>>),
+    #[error("Pest parsing error: {}{}", _0, _1)]
+    PestParseError(Box<pest::error::Error<Rule>>, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    UnexpectedTagError(UnexpectedTagError, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    DateParseError(Box<DateParseError>, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    RequiredTagNotFoundError(RequiredTagNotFoundError, ErrorLocation),
+
+    #[error("Unknown tag: '{}'{}", _0, _1)]
+    UnknownTagError(String, ErrorLocation),
+
+    /// A balance is in a currency which is not in ISO 4217.
+    ///
+    /// Only reported if [`ParserConfig::validate_currencies`] is on.
+    ///
+    /// [`ParserConfig::validate_currencies`]: crate::ParserConfig
+    #[error("Unknown currency: '{}'{}", _0, _1)]
+    UnknownCurrencyError(String, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    VariantNotFound(VariantNotFound, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    AmountParseError(AmountParseError, ErrorLocation),
+
+    #[error("{}{}", _0, _1)]
+    TrailerValidationError(TrailerValidationError, ErrorLocation),
+
+    /// A tag which starts a message is repeated within it.
+    ///
+    /// Only reported if [`ParserConfig::duplicate_transaction_ref`] is set to
+    /// [`DuplicateTransactionRef::Reject`].
+    ///
+    /// [`ParserConfig::duplicate_transaction_ref`]: crate::ParserConfig::duplicate_transaction_ref
+    /// [`DuplicateTransactionRef::Reject`]: crate::DuplicateTransactionRef::Reject
+    #[error("{}{}", _0, _1)]
+    DuplicateTagError(DuplicateTagError, ErrorLocation),
+
+    /// A component of a tag exceeds the length the SWIFT spec allows.
+    ///
+    /// Only reported if [`ParserConfig::strict_field_lengths`] is on.
+    ///
+    /// [`ParserConfig::strict_field_lengths`]: crate::ParserConfig::strict_field_lengths
+    #[error("{}{}", _0, _1)]
+    FieldLengthError(FieldLengthError, ErrorLocation),
+
+    /// A tag lacks a component which its grammar requires.
+    #[error("Missing {} in tag :{}:{}", component, tag, location)]
+    MissingComponent {
+        tag: Tag,
+        component: String,
+        location: ErrorLocation,
+    },
+
+    /// A statement parsed as a single [`Message`] contains several messages.
+    ///
+    /// [`Message`]: crate::Message
+    #[error("Expected a single message but found {}{}", _0, _1)]
+    MessageCountError(usize, ErrorLocation),
 
-    #[error("{}", _0)]
-    UnexpectedTagError(UnexpectedTagError),
+    #[error("I/O error: {}{}", message, location)]
+    IoError {
+        kind: std::io::ErrorKind,
+        message: String,
+        location: ErrorLocation,
+    },
+}
 
-    #[error("{}", _0)]
-    DateParseError(Box<DateParseError>),
+/// Where in the input a [`ParseError`] occurred.
+///
+/// This isn't taken into account when comparing errors. Use [`ParseError::span`] and
+/// [`ParseError::message_source`] to check it.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLocation(Option<Box<Location>>);
+
+#[derive(Debug, Clone, Default)]
+struct Location {
+    span: Option<Span>,
+    message: Option<MessageSource>,
+}
+
+impl ErrorLocation {
+    /// The offending part of the input, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.0.as_ref().and_then(|location| location.span)
+    }
+
+    /// The message the error occurred in, if known.
+    ///
+    /// Only recorded if [`ParserConfig::track_source`] is on and by [`parse_mt940_partial`].
+    ///
+    /// [`ParserConfig::track_source`]: crate::ParserConfig::track_source
+    /// [`parse_mt940_partial`]: crate::parse_mt940_partial
+    pub fn message_source(&self) -> Option<&MessageSource> {
+        self.0
+            .as_ref()
+            .and_then(|location| location.message.as_ref())
+    }
 
-    #[error("{}", _0)]
-    RequiredTagNotFoundError(RequiredTagNotFoundError),
+    fn get_mut(&mut self) -> &mut Location {
+        self.0.get_or_insert_with(Default::default)
+    }
+}
 
-    #[error("Unknown tag: '{}'", _0)]
-    UnknownTagError(String),
+impl PartialEq for ErrorLocation {
+    fn eq(&self, _other: &ErrorLocation) -> bool {
+        true
+    }
+}
 
-    #[error("{}", _0)]
-    VariantNotFound(VariantNotFound),
+impl Eq for ErrorLocation {}
 
-    #[error("{}", _0)]
-    AmountParseError(AmountParseError),
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = self.span() {
+            write!(f, " (line {}, column {})", span.line, span.column)?;
+        }
+        if let Some(message) = self.message_source() {
+            write!(f, " in {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl ParseError {
+    /// Where in the input this error occurred.
+    pub fn location(&self) -> &ErrorLocation {
+        match self {
+            ParseError::PestParseError(_, location)
+            | ParseError::UnexpectedTagError(_, location)
+            | ParseError::DateParseError(_, location)
+            | ParseError::RequiredTagNotFoundError(_, location)
+            | ParseError::UnknownTagError(_, location)
+            | ParseError::UnknownCurrencyError(_, location)
+            | ParseError::VariantNotFound(_, location)
+            | ParseError::AmountParseError(_, location)
+            | ParseError::TrailerValidationError(_, location)
+            | ParseError::DuplicateTagError(_, location)
+            | ParseError::FieldLengthError(_, location)
+            | ParseError::MissingComponent { location, .. }
+            | ParseError::MessageCountError(_, location)
+            | ParseError::IoError { location, .. } => location,
+        }
+    }
+
+    fn location_mut(&mut self) -> &mut ErrorLocation {
+        match self {
+            ParseError::PestParseError(_, location)
+            | ParseError::UnexpectedTagError(_, location)
+            | ParseError::DateParseError(_, location)
+            | ParseError::RequiredTagNotFoundError(_, location)
+            | ParseError::UnknownTagError(_, location)
+            | ParseError::UnknownCurrencyError(_, location)
+            | ParseError::VariantNotFound(_, location)
+            | ParseError::AmountParseError(_, location)
+            | ParseError::TrailerValidationError(_, location)
+            | ParseError::DuplicateTagError(_, location)
+            | ParseError::FieldLengthError(_, location)
+            | ParseError::MissingComponent { location, .. }
+            | ParseError::MessageCountError(_, location)
+            | ParseError::IoError { location, .. } => location,
+        }
+    }
+
+    /// Where in the input this error occurred, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.location().span()
+    }
+
+    /// The message this error occurred in, if known.
+    pub fn message_source(&self) -> Option<&MessageSource> {
+        self.location().message_source()
+    }
+
+    /// A copy of this error without its [`ErrorLocation`].
+    pub(crate) fn without_location(&self) -> ParseError {
+        let mut err = self.clone();
+        *err.location_mut() = ErrorLocation::default();
+        err
+    }
+
+    /// Attach `span` to this error unless it already has one.
+    pub fn with_span(mut self, span: Span) -> ParseError {
+        let location = self.location_mut().get_mut();
+        location.span = location.span.or(Some(span));
+        self
+    }
+
+    /// Like [`ParseError::with_span`] but does nothing if there is no `span`.
+    pub(crate) fn at(self, span: Option<Span>) -> ParseError {
+        match span {
+            Some(span) => self.with_span(span),
+            None => self,
+        }
+    }
+
+    /// Move the [`Span`] of this error from a part of the input to the whole input.
+    ///
+    /// See [`Span::shifted`].
+    pub(crate) fn shifted(self, offset: usize, lines: usize) -> ParseError {
+        self.map_span(|span| span.shifted(offset, lines))
+    }
+
+    /// Replace the [`Span`] of this error, if it has one, by `f` applied to it.
+    ///
+    /// Use this to point into the original input after sanitizing it, see
+    /// [`SanitizeReport::original_line`].
+    ///
+    /// [`SanitizeReport::original_line`]: crate::sanitizers::SanitizeReport::original_line
+    pub fn map_span(mut self, f: impl FnOnce(Span) -> Span) -> ParseError {
+        if let Some(ref mut location) = self.location_mut().0 {
+            location.span = location.span.map(f);
+        }
+        self
+    }
+
+    /// Attach `message` to this error unless it already has a [`MessageSource`].
+    pub(crate) fn in_message(mut self, message: MessageSource) -> ParseError {
+        let location = self.location_mut().get_mut();
+        location.message = location.message.take().or(Some(message));
+        self
+    }
+
+    /// Convert an error of the pest parser which ran on the whole input.
+    pub(crate) fn from_pest_error_with_span(err: Box<pest::error::Error<Rule>>) -> ParseError {
+        let span = Span::from_pest_error(&err);
+        ParseError::from(err).with_span(span)
+    }
 }
 
 impl From<pest::error::Error<Rule>> for ParseError {
     fn from(err: pest::error::Error<Rule>) -> ParseError {
-        ParseError::PestParseError(Box::new(err))
+        ParseError::PestParseError(Box::new(err), ErrorLocation::default())
     }
 }
 
 impl From<Box<pest::error::Error<Rule>>> for ParseError {
     fn from(err: Box<pest::error::Error<Rule>>) -> ParseError {
-        ParseError::PestParseError(err)
+        ParseError::PestParseError(err, ErrorLocation::default())
     }
 }
 
 impl From<DateParseError> for ParseError {
     fn from(err: DateParseError) -> ParseError {
-        ParseError::DateParseError(Box::new(err))
+        ParseError::DateParseError(Box::new(err), ErrorLocation::default())
     }
 }
 
 impl From<UnexpectedTagError> for ParseError {
     fn from(err: UnexpectedTagError) -> ParseError {
-        ParseError::UnexpectedTagError(err)
+        ParseError::UnexpectedTagError(err, ErrorLocation::default())
     }
 }
 
 impl From<RequiredTagNotFoundError> for ParseError {
     fn from(err: RequiredTagNotFoundError) -> ParseError {
-        ParseError::RequiredTagNotFoundError(err)
+        ParseError::RequiredTagNotFoundError(err, ErrorLocation::default())
+    }
+}
+
+impl From<DuplicateTagError> for ParseError {
+    fn from(err: DuplicateTagError) -> ParseError {
+        ParseError::DuplicateTagError(err, ErrorLocation::default())
     }
 }
 
 impl From<VariantNotFound> for ParseError {
     fn from(err: VariantNotFound) -> ParseError {
-        ParseError::VariantNotFound(err)
+        ParseError::VariantNotFound(err, ErrorLocation::default())
+    }
+}
+
+impl From<TrailerValidationError> for ParseError {
+    fn from(err: TrailerValidationError) -> ParseError {
+        ParseError::TrailerValidationError(err, ErrorLocation::default())
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(err: std::io::Error) -> ParseError {
+        ParseError::IoError {
+            kind: err.kind(),
+            message: err.to_string(),
+            location: ErrorLocation::default(),
+        }
+    }
+}
+
+impl From<FieldLengthError> for ParseError {
+    fn from(err: FieldLengthError) -> ParseError {
+        ParseError::FieldLengthError(err, ErrorLocation::default())
     }
 }
 
 impl From<AmountParseError> for ParseError {
     fn from(err: AmountParseError) -> ParseError {
-        ParseError::AmountParseError(err)
+        ParseError::AmountParseError(err, ErrorLocation::default())
     }
 }
 
@@ -114,27 +366,42 @@ impl From<AmountParseError> for ParseError {
 #[error(
     "Unexpected tag '{}' found. Expected one of '{:?}'. The tag before this one was '{}'.",
     current_tag,
-    expected_tags,
-    last_tag
+    .expected_tags.iter().map(Tag::as_str).collect::<Vec<_>>(),
+    .last_tag.as_ref().map_or("", Tag::as_str)
 )]
 pub struct UnexpectedTagError {
-    current_tag: String,
-    last_tag: String,
-    expected_tags: Vec<String>,
+    current_tag: Tag,
+    last_tag: Option<Tag>,
+    expected_tags: Vec<Tag>,
 }
 
 impl UnexpectedTagError {
     pub fn new(
-        current_tag: &str,
-        last_tag: &str,
-        expected_tags: Vec<String>,
+        current_tag: impl Into<Tag>,
+        last_tag: Option<Tag>,
+        expected_tags: Vec<Tag>,
     ) -> UnexpectedTagError {
         UnexpectedTagError {
-            current_tag: current_tag.to_string(),
-            last_tag: last_tag.to_string(),
+            current_tag: current_tag.into(),
+            last_tag,
             expected_tags,
         }
     }
+
+    /// The tag which wasn't expected.
+    pub fn current_tag(&self) -> &Tag {
+        &self.current_tag
+    }
+
+    /// The tag in front of the unexpected one, if it isn't the first tag of the message.
+    pub fn last_tag(&self) -> Option<&Tag> {
+        self.last_tag.as_ref()
+    }
+
+    /// The tags which would have been allowed instead.
+    pub fn expected_tags(&self) -> &[Tag] {
+        &self.expected_tags
+    }
 }
 
 /// Error thrown if a required tag was not found.
@@ -150,4 +417,191 @@ impl RequiredTagNotFoundError {
             required_tag: tag.to_string(),
         }
     }
+
+    /// The tag which is missing, e.g. `20`, or its alternatives such as `60F or 60M`.
+    pub fn required_tag(&self) -> &str {
+        &self.required_tag
+    }
+}
+
+/// Error thrown if a tag is repeated within a message, e.g. a second `:20:` before the closing
+/// balance.
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[error(
+    "Tag :{}: '{}' repeated within the message starting with '{}'",
+    tag,
+    value,
+    first_value
+)]
+pub struct DuplicateTagError {
+    tag: Tag,
+    first_value: String,
+    value: String,
+}
+
+impl DuplicateTagError {
+    pub fn new(tag: impl Into<Tag>, first_value: &str, value: &str) -> DuplicateTagError {
+        DuplicateTagError {
+            tag: tag.into(),
+            first_value: first_value.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// The tag which is repeated.
+    pub fn tag(&self) -> &Tag {
+        &self.tag
+    }
+
+    /// The value of the tag where it occurred first within the message.
+    pub fn first_value(&self) -> &str {
+        &self.first_value
+    }
+
+    /// The value of the repeated tag.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Error thrown if a component of a tag is longer than the SWIFT spec allows.
+///
+/// See [`Message::validate_lengths`](crate::Message::validate_lengths).
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[error(
+    "Component '{}' of tag :{}: exceeds its maximum length: {} > {}",
+    component,
+    tag,
+    length,
+    max_length
+)]
+pub struct FieldLengthError {
+    tag: Tag,
+    component: String,
+    length: usize,
+    max_length: usize,
+}
+
+impl FieldLengthError {
+    pub fn new(
+        tag: impl Into<Tag>,
+        component: &str,
+        length: usize,
+        max_length: usize,
+    ) -> FieldLengthError {
+        FieldLengthError {
+            tag: tag.into(),
+            component: component.to_string(),
+            length,
+            max_length,
+        }
+    }
+
+    /// The tag of the component, e.g. `61`.
+    pub fn tag(&self) -> &Tag {
+        &self.tag
+    }
+
+    /// The component which is too long, e.g. `customer_ref`.
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+
+    /// The actual length of the component.
+    ///
+    /// This is a number of characters except for the `lines` of tag `:86:`.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The maximum length the SWIFT spec allows.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+/// Error thrown when the trailer block `{5:}` of a SWIFT message doesn't check out.
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum TrailerValidationError {
+    #[error("Trailer block {{5:}} not found.")]
+    MissingTrailer,
+
+    #[error("Checksum {{CHK:}} not found in trailer.")]
+    MissingChecksum,
+
+    #[error("Invalid checksum: '{}'", _0)]
+    InvalidChecksum(String),
+
+    #[error("Message authentication code {{MAC:}} not found in trailer.")]
+    MissingMac,
+
+    #[error("Invalid message authentication code: '{}'", _0)]
+    InvalidMac(String),
+}
+
+/// Error thrown when the pages of a statement can't be merged.
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum MergeError {
+    #[error(
+        "Page {} of statement '{}' of account '{}' not found.",
+        sequence_no,
+        statement_no,
+        account_id
+    )]
+    MissingPage {
+        account_id: String,
+        statement_no: String,
+        sequence_no: u32,
+    },
+
+    #[error(
+        "Opening balance of page {} of statement '{}' of account '{}' doesn't match the closing balance of the previous page.",
+        sequence_no,
+        statement_no,
+        account_id
+    )]
+    BalanceMismatch {
+        account_id: String,
+        statement_no: String,
+        sequence_no: u32,
+    },
+}
+
+/// Error thrown when a statement in another format can't be converted into [`Message`]s.
+///
+/// [`Message`]: crate::Message
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum ImportError {
+    #[error("Invalid XML: {}", _0)]
+    Xml(String),
+
+    #[error("Required element '{}' not found.", _0)]
+    MissingElement(String),
+
+    #[error("Invalid value for element '{}': '{}'", element, value)]
+    InvalidValue { element: String, value: String },
+}
+
+/// Error thrown when a [`MessageBuilder`](crate::MessageBuilder) or
+/// [`StatementLineBuilder`](crate::StatementLineBuilder) can't build a valid value.
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum BuildError {
+    #[error("Required field '{}' not set.", _0)]
+    MissingField(String),
+
+    #[error("Invalid value for field '{}': '{}'", field, value)]
+    InvalidField { field: String, value: String },
+
+    #[error("Balance is in {} but the opening balance is in {}", found, expected)]
+    CurrencyMismatch { expected: String, found: String },
+
+    #[error(
+        "Closing balance is {} but opening balance and statement lines add up to {}",
+        found,
+        expected
+    )]
+    BalanceMismatch {
+        expected: rust_decimal::Decimal,
+        found: rust_decimal::Decimal,
+    },
 }
@@ -0,0 +1,351 @@
+//! Export into ISO 20022 camt.053 bank to customer statements.
+//!
+//! The output follows version `camt.053.001.02` which is still the most widely supported one.
+//! Every [`Message`] becomes a `<Stmt>`, every [`StatementLine`] an `<Ntry>`.
+//!
+//! Balances are mapped as follows:
+//!
+//! | MT940   | camt.053 |
+//! |---------|----------|
+//! | `:60F:` | `OPBD`   |
+//! | `:60M:` | `ITBD`   |
+//! | `:62F:` | `CLBD`   |
+//! | `:62M:` | `ITBD`   |
+//! | `:64:`  | `CLAV`   |
+//! | `:65:`  | `FWAV`   |
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::export::{escape_xml, push_element};
+use crate::{AccountId, ExtDebitOrCredit, Message, StatementLine};
+
+/// Namespace of the camt.053 version we write.
+const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02";
+
+/// Convert `messages` into a single camt.053 document.
+///
+/// MT940 doesn't carry a creation timestamp which camt.053 requires. Midnight of the last closing
+/// balance date is used instead.
+///
+/// # Example
+/// ```
+/// use mt940::export::camt053::to_camt053;
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let xml = to_camt053(&parse_mt940(input).unwrap());
+/// assert!(xml.contains("<Amt Ccy=\"EUR\">583.92</Amt>"));
+/// assert!(xml.contains("<Ustrd>Beneficiary name</Ustrd>"));
+/// ```
+pub fn to_camt053(messages: &[Message]) -> String {
+    let creation_date_time = messages
+        .last()
+        .map(|message| format_date_time(&message.closing_balance.date))
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!("<Document xmlns=\"{}\">\n", NAMESPACE));
+    out.push_str("  <BkToCstmrStmt>\n");
+    out.push_str("    <GrpHdr>\n");
+    let message_id = messages
+        .first()
+        .map_or("", |message| message.transaction_ref_no.as_str());
+    push_element(&mut out, 6, "MsgId", message_id);
+    push_element(&mut out, 6, "CreDtTm", &creation_date_time);
+    out.push_str("    </GrpHdr>\n");
+    for message in messages {
+        push_statement(&mut out, message);
+    }
+    out.push_str("  </BkToCstmrStmt>\n");
+    out.push_str("</Document>\n");
+    out
+}
+
+fn push_statement(out: &mut String, message: &Message) {
+    let currency = &message.opening_balance.iso_currency_code;
+    out.push_str("    <Stmt>\n");
+    push_element(out, 6, "Id", &message.transaction_ref_no);
+    if message.statement_no.bytes().all(|b| b.is_ascii_digit()) {
+        push_element(out, 6, "ElctrncSeqNb", &message.statement_no);
+    }
+    push_element(
+        out,
+        6,
+        "CreDtTm",
+        &format_date_time(&message.closing_balance.date),
+    );
+
+    out.push_str("      <Acct>\n");
+    out.push_str("        <Id>\n");
+    match message.parsed_account_id() {
+        AccountId::Iban(iban) => push_element(out, 10, "IBAN", &iban),
+        _ => {
+            out.push_str("          <Othr>\n");
+            push_element(out, 12, "Id", &message.account_id);
+            out.push_str("          </Othr>\n");
+        }
+    }
+    out.push_str("        </Id>\n");
+    push_element(out, 8, "Ccy", currency);
+    if let Some(ref bic) = message.account_identifier_bic {
+        out.push_str("        <Svcr>\n");
+        out.push_str("          <FinInstnId>\n");
+        push_element(out, 12, "BIC", bic);
+        out.push_str("          </FinInstnId>\n");
+        out.push_str("        </Svcr>\n");
+    }
+    out.push_str("      </Acct>\n");
+
+    let opening = &message.opening_balance;
+    push_balance(
+        out,
+        if opening.is_intermediate {
+            "ITBD"
+        } else {
+            "OPBD"
+        },
+        opening.signed_amount(),
+        &opening.iso_currency_code,
+        &opening.date,
+    );
+    let closing = &message.closing_balance;
+    push_balance(
+        out,
+        if closing.is_intermediate {
+            "ITBD"
+        } else {
+            "CLBD"
+        },
+        closing.signed_amount(),
+        &closing.iso_currency_code,
+        &closing.date,
+    );
+    if let Some(ref balance) = message.closing_available_balance {
+        push_balance(
+            out,
+            "CLAV",
+            balance.signed_amount(),
+            &balance.iso_currency_code,
+            &balance.date,
+        );
+    }
+    for balance in &message.forward_available_balances {
+        push_balance(
+            out,
+            "FWAV",
+            balance.signed_amount(),
+            &balance.iso_currency_code,
+            &balance.date,
+        );
+    }
+
+    for statement_line in &message.statement_lines {
+        push_entry(out, statement_line, currency);
+    }
+
+    if let Some(ref info) = message.information_to_account_owner {
+        push_element(out, 6, "AddtlStmtInf", &info.replace('\n', " "));
+    }
+    out.push_str("    </Stmt>\n");
+}
+
+fn push_balance(out: &mut String, code: &str, amount: Decimal, currency: &str, date: &NaiveDate) {
+    out.push_str("      <Bal>\n");
+    out.push_str("        <Tp>\n");
+    out.push_str("          <CdOrPrtry>\n");
+    push_element(out, 12, "Cd", code);
+    out.push_str("          </CdOrPrtry>\n");
+    out.push_str("        </Tp>\n");
+    push_amount(out, 8, amount, currency);
+    out.push_str("        <Dt>\n");
+    push_element(out, 10, "Dt", &format_date(date));
+    out.push_str("        </Dt>\n");
+    out.push_str("      </Bal>\n");
+}
+
+fn push_entry(out: &mut String, statement_line: &StatementLine, currency: &str) {
+    out.push_str("      <Ntry>\n");
+    push_amount(out, 8, statement_line.signed_amount(), currency);
+    if matches!(
+        statement_line.ext_debit_credit_indicator,
+        ExtDebitOrCredit::ReverseDebit | ExtDebitOrCredit::ReverseCredit
+    ) {
+        push_element(out, 8, "RvslInd", "true");
+    }
+    push_element(out, 8, "Sts", "BOOK");
+    if let Some(ref entry_date) = statement_line.entry_date {
+        out.push_str("        <BookgDt>\n");
+        push_element(out, 10, "Dt", &format_date(entry_date));
+        out.push_str("        </BookgDt>\n");
+    }
+    out.push_str("        <ValDt>\n");
+    push_element(out, 10, "Dt", &format_date(&statement_line.value_date));
+    out.push_str("        </ValDt>\n");
+    if let Some(ref bank_ref) = statement_line.bank_ref {
+        push_element(out, 8, "AcctSvcrRef", bank_ref);
+    }
+    out.push_str("        <BkTxCd>\n");
+    out.push_str("          <Prtry>\n");
+    push_element(
+        out,
+        12,
+        "Cd",
+        statement_line.transaction_type_ident_code.code(),
+    );
+    push_element(out, 12, "Issr", "SWIFT");
+    out.push_str("          </Prtry>\n");
+    out.push_str("        </BkTxCd>\n");
+
+    out.push_str("        <NtryDtls>\n");
+    out.push_str("          <TxDtls>\n");
+    out.push_str("            <Refs>\n");
+    let end_to_end_ref = statement_line
+        .parse_sepa_purpose()
+        .and_then(|purpose| purpose.end_to_end_ref);
+    match end_to_end_ref {
+        Some(ref end_to_end_ref) => push_element(out, 14, "EndToEndId", end_to_end_ref),
+        None => push_element(out, 14, "EndToEndId", &statement_line.customer_ref),
+    }
+    out.push_str("            </Refs>\n");
+    if let Some(ref info) = statement_line.information_to_account_owner {
+        out.push_str("            <RmtInf>\n");
+        for line in info.lines().filter(|line| !line.is_empty()) {
+            push_element(out, 14, "Ustrd", line);
+        }
+        out.push_str("            </RmtInf>\n");
+    }
+    out.push_str("          </TxDtls>\n");
+    out.push_str("        </NtryDtls>\n");
+    out.push_str("      </Ntry>\n");
+}
+
+/// Write an amount as an absolute value followed by the credit/debit indicator.
+fn push_amount(out: &mut String, indent: usize, amount: Decimal, currency: &str) {
+    out.push_str(&format!(
+        "{:indent$}<Amt Ccy=\"{}\">{}</Amt>\n",
+        "",
+        escape_xml(currency),
+        amount.abs(),
+        indent = indent
+    ));
+    let indicator = if amount < Decimal::ZERO {
+        "DBIT"
+    } else {
+        "CRDT"
+    };
+    push_element(out, indent, "CdtDbtInd", indicator);
+}
+
+fn format_date(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn format_date_time(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%dT00:00:00").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn camt053_document() {
+        let input = "\
+            :20:ref\r\n\
+            :25:NL91ABNA0417164300\r\n\
+            :28C:1\r\n\
+            :60M:D090924EUR1,\r\n\
+            :61:0909250924RD2,NTRFcustomer//bank\r\n\
+            :86:EREF+E2E SVWZ+Fish and Chips\r\n\
+            :62F:C090925EUR1,\r\n";
+        let xml = to_camt053(&parse_mt940(input).unwrap());
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <GrpHdr>
+      <MsgId>ref</MsgId>
+      <CreDtTm>2009-09-25T00:00:00</CreDtTm>
+    </GrpHdr>
+    <Stmt>
+      <Id>ref</Id>
+      <ElctrncSeqNb>1</ElctrncSeqNb>
+      <CreDtTm>2009-09-25T00:00:00</CreDtTm>
+      <Acct>
+        <Id>
+          <IBAN>NL91ABNA0417164300</IBAN>
+        </Id>
+        <Ccy>EUR</Ccy>
+      </Acct>
+      <Bal>
+        <Tp>
+          <CdOrPrtry>
+            <Cd>ITBD</Cd>
+          </CdOrPrtry>
+        </Tp>
+        <Amt Ccy="EUR">1</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Dt>
+          <Dt>2009-09-24</Dt>
+        </Dt>
+      </Bal>
+      <Bal>
+        <Tp>
+          <CdOrPrtry>
+            <Cd>CLBD</Cd>
+          </CdOrPrtry>
+        </Tp>
+        <Amt Ccy="EUR">1</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt>
+          <Dt>2009-09-25</Dt>
+        </Dt>
+      </Bal>
+      <Ntry>
+        <Amt Ccy="EUR">2</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <RvslInd>true</RvslInd>
+        <Sts>BOOK</Sts>
+        <BookgDt>
+          <Dt>2009-09-24</Dt>
+        </BookgDt>
+        <ValDt>
+          <Dt>2009-09-25</Dt>
+        </ValDt>
+        <AcctSvcrRef>bank</AcctSvcrRef>
+        <BkTxCd>
+          <Prtry>
+            <Cd>TRF</Cd>
+            <Issr>SWIFT</Issr>
+          </Prtry>
+        </BkTxCd>
+        <NtryDtls>
+          <TxDtls>
+            <Refs>
+              <EndToEndId>E2E</EndToEndId>
+            </Refs>
+            <RmtInf>
+              <Ustrd>EREF+E2E SVWZ+Fish and Chips</Ustrd>
+            </RmtInf>
+          </TxDtls>
+        </NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>
+"#;
+        assert_eq!(xml, expected);
+    }
+}
@@ -0,0 +1,129 @@
+//! Export of [`StatementLine`]s as CSV.
+//!
+//! Every [`StatementLine`] becomes one row. The columns are:
+//!
+//! | Column                         | Content                                              |
+//! |--------------------------------|------------------------------------------------------|
+//! | `account_id`                   | Tag `:25:` of the message                            |
+//! | `statement_no`                 | Statement number of tag `:28C:`                      |
+//! | `value_date`                   | Value date as `YYYY-MM-DD`                           |
+//! | `entry_date`                   | Entry date as `YYYY-MM-DD`, empty if not given       |
+//! | `debit_credit`                 | `D`, `C`, `RD` or `RC` as in tag `:61:`              |
+//! | `amount`                       | Amount with debits being negative, `.` as separator  |
+//! | `currency`                     | Currency of the opening balance of the message       |
+//! | `transaction_type`             | Transaction type identification code, e.g. `TRF`     |
+//! | `customer_ref`                 | Reference for the account owner                      |
+//! | `bank_ref`                     | Reference of the account servicing institution       |
+//! | `information_to_account_owner` | Tag `:86:` of the statement line                     |
+
+use std::io;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_derive::Serialize;
+
+use crate::writer::format_ext_debit_credit;
+use crate::{Message, StatementLine};
+
+/// A single row of the CSV output.
+#[derive(Serialize)]
+struct Row<'a> {
+    account_id: &'a str,
+    statement_no: &'a str,
+    value_date: NaiveDate,
+    entry_date: Option<NaiveDate>,
+    debit_credit: &'static str,
+    amount: Decimal,
+    currency: &'a str,
+    transaction_type: &'a str,
+    customer_ref: &'a str,
+    bank_ref: Option<&'a str>,
+    information_to_account_owner: Option<&'a str>,
+}
+
+impl<'a> Row<'a> {
+    fn new(message: &'a Message, statement_line: &'a StatementLine) -> Row<'a> {
+        Row {
+            account_id: &message.account_id,
+            statement_no: &message.statement_no,
+            value_date: statement_line.value_date,
+            entry_date: statement_line.entry_date,
+            debit_credit: format_ext_debit_credit(&statement_line.ext_debit_credit_indicator),
+            amount: statement_line.signed_amount(),
+            currency: &message.opening_balance.iso_currency_code,
+            transaction_type: statement_line.transaction_type_ident_code.code(),
+            customer_ref: &statement_line.customer_ref,
+            bank_ref: statement_line.bank_ref.as_deref(),
+            information_to_account_owner: statement_line.information_to_account_owner.as_deref(),
+        }
+    }
+}
+
+/// Write the [`StatementLine`]s of all `messages` as CSV with a header row to `writer`.
+pub fn write_csv<W: io::Write>(messages: &[Message], writer: W) -> Result<(), ::csv::Error> {
+    let mut csv_writer = ::csv::Writer::from_writer(writer);
+    for message in messages {
+        for statement_line in &message.statement_lines {
+            csv_writer.serialize(Row::new(message, statement_line))?;
+        }
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// Convert the [`StatementLine`]s of all `messages` into CSV with a header row.
+///
+/// # Example
+/// ```
+/// use mt940::export::csv::to_csv;
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let csv = to_csv(&parse_mt940(input).unwrap()).unwrap();
+/// assert_eq!(
+///     csv.lines().nth(1),
+///     Some("DABADKKK/111111-11111111,00001,2009-09-25,2009-09-25,D,-583.92,EUR,MSC,\
+///           1110030403010139,1234,Beneficiary name")
+/// );
+/// ```
+pub fn to_csv(messages: &[Message]) -> Result<String, ::csv::Error> {
+    let mut out = vec![];
+    write_csv(messages, &mut out)?;
+    String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn csv_header_and_quoting() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925RD1,NTRFref\r\n\
+            :86:first, line\r\n\
+            second line\r\n\
+            :62F:C090924EUR2,\r\n";
+        let csv = to_csv(&parse_mt940(input).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "account_id,statement_no,value_date,entry_date,debit_credit,amount,currency,\
+             transaction_type,customer_ref,bank_ref,information_to_account_owner\n\
+             b,1,2009-09-25,,RD,1,EUR,TRF,ref,,\"first, line\nsecond line\"\n"
+        );
+    }
+}
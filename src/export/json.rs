@@ -0,0 +1,376 @@
+//! Export of [`Message`]s as JSON with configurable amounts and dates.
+//!
+//! Serializing with `serde_json` directly writes amounts as strings in their shortest form, e.g.
+//! `"100"` or `"583.92"`, and dates as `YYYY-MM-DD`. [`to_json`] allows changing both, for
+//! instance for consumers which expect numbers or a fixed number of decimal places.
+
+use std::fmt::{self, Write};
+
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+
+use crate::Message;
+
+/// Keys whose values are amounts.
+const AMOUNT_KEYS: [&str; 1] = ["amount"];
+
+/// Keys whose values are dates.
+const DATE_KEYS: [&str; 3] = ["date", "value_date", "entry_date"];
+
+/// How to write amounts.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum AmountFormat {
+    /// A string in the shortest form such as `"100"` or `"583.92"`.
+    #[default]
+    String,
+
+    /// A string with exactly this many decimal places such as `"100.00"`.
+    ///
+    /// Amounts with more decimal places are rounded half to even.
+    Fixed(u32),
+
+    /// A JSON number.
+    ///
+    /// This goes through `f64` which represents every amount of up to 15 digits exactly enough to
+    /// be printed back the same way. Consumers might still parse it into a binary float though.
+    Number,
+}
+
+/// Options for [`to_json`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct JsonOptions {
+    amount_format: AmountFormat,
+    date_format: Option<String>,
+    pretty: bool,
+}
+
+impl JsonOptions {
+    /// Create [`JsonOptions`] which produce the same output as `serde_json`.
+    pub fn new() -> JsonOptions {
+        JsonOptions::default()
+    }
+
+    /// Choose how to write amounts.
+    pub fn amount_format(mut self, amount_format: AmountFormat) -> JsonOptions {
+        self.amount_format = amount_format;
+        self
+    }
+
+    /// Write dates with a [`chrono` format string](chrono::format::strftime) such as `%d.%m.%Y`
+    /// instead of ISO 8601.
+    ///
+    /// The format string may only ask for parts of a date. [`to_json`] fails otherwise.
+    pub fn date_format(mut self, date_format: &str) -> JsonOptions {
+        self.date_format = Some(date_format.to_string());
+        self
+    }
+
+    /// Indent the output.
+    pub fn pretty(mut self, pretty: bool) -> JsonOptions {
+        self.pretty = pretty;
+        self
+    }
+}
+
+/// Convert `messages` into JSON according to `options`.
+///
+/// Fails if the [date format](JsonOptions::date_format) is invalid or asks for the time.
+///
+/// # Example
+/// ```
+/// use mt940::export::json::{to_json, AmountFormat, JsonOptions};
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,\r\n\
+///     :62F:C090924EUR54484,\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// let options = JsonOptions::new()
+///     .amount_format(AmountFormat::Fixed(2))
+///     .date_format("%d.%m.%Y");
+/// let json = to_json(&messages, &options).unwrap();
+/// assert!(json.contains(r#""amount":"54484.00""#));
+/// assert!(json.contains(r#""date":"24.09.2009""#));
+///
+/// assert!(to_json(&messages, &JsonOptions::new().date_format("%H:%M")).is_err());
+/// ```
+pub fn to_json(messages: &[Message], options: &JsonOptions) -> serde_json::Result<String> {
+    let json = Json::new(messages, options)?;
+    if options.pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    }
+}
+
+/// Like [`to_json`] but returns the JSON as a [`Value`] for further processing.
+///
+/// The keys of objects are sorted rather than in the order of the fields.
+pub fn to_json_value(messages: &[Message], options: &JsonOptions) -> serde_json::Result<Value> {
+    Json::new(messages, options).map(Value::from)
+}
+
+/// Like [`Value`] but keeping the keys of objects in order.
+enum Json {
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+    /// Anything other than an array or an object.
+    Scalar(Value),
+}
+
+impl Json {
+    fn new(messages: &[Message], options: &JsonOptions) -> serde_json::Result<Json> {
+        if let Some(ref date_format) = options.date_format {
+            // Fail regardless of whether there are any dates to format.
+            format_date(&NaiveDate::default(), date_format)?;
+        }
+        // Go through text as a `Value` would lose the order of the fields.
+        let mut json: Json = serde_json::from_str(&serde_json::to_string(messages)?)?;
+        json.reformat(options)?;
+        Ok(json)
+    }
+
+    fn reformat(&mut self, options: &JsonOptions) -> serde_json::Result<()> {
+        match self {
+            Json::Array(values) => {
+                for value in values {
+                    value.reformat(options)?;
+                }
+            }
+            Json::Object(entries) => {
+                for (key, value) in entries {
+                    match value {
+                        Json::Scalar(value) if AMOUNT_KEYS.contains(&key.as_str()) => {
+                            reformat_amount(value, options.amount_format)
+                        }
+                        Json::Scalar(value) if DATE_KEYS.contains(&key.as_str()) => {
+                            if let Some(ref date_format) = options.date_format {
+                                reformat_date(value, date_format)?;
+                            }
+                        }
+                        // Whatever tag handlers stored is left alone.
+                        _ if key == "custom_fields" => (),
+                        _ => value.reformat(options)?,
+                    }
+                }
+            }
+            Json::Scalar(_) => (),
+        }
+        Ok(())
+    }
+}
+
+impl From<Json> for Value {
+    fn from(json: Json) -> Value {
+        match json {
+            Json::Array(values) => Value::Array(values.into_iter().map(Value::from).collect()),
+            Json::Object(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect(),
+            ),
+            Json::Scalar(value) => value,
+        }
+    }
+}
+
+impl Serialize for Json {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Json::Array(values) => serializer.collect_seq(values),
+            Json::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Json::Scalar(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Json, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::Null))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::Bool(v)))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::Number(v.into())))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::Number(v.into())))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Json, E> {
+        Ok(Json::Scalar(
+            serde_json::Number::from_f64(v).map_or(Value::Null, Value::Number),
+        ))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::String(v.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Json, E> {
+        Ok(Json::Scalar(Value::String(v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Json::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut entries = vec![];
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Json::Object(entries))
+    }
+}
+
+fn reformat_amount(value: &mut Value, amount_format: AmountFormat) {
+    let Some(amount) = value.as_str().and_then(|s| s.parse::<Decimal>().ok()) else {
+        return;
+    };
+    *value = match amount_format {
+        AmountFormat::String => return,
+        AmountFormat::Fixed(scale) => {
+            Value::String(format!("{:.*}", scale as usize, amount.round_dp(scale)))
+        }
+        AmountFormat::Number if amount.fract().is_zero() => match amount.to_i64() {
+            Some(amount) => Value::Number(amount.into()),
+            None => return,
+        },
+        AmountFormat::Number => match amount.to_f64().and_then(serde_json::Number::from_f64) {
+            Some(number) => Value::Number(number),
+            None => return,
+        },
+    };
+}
+
+fn reformat_date(value: &mut Value, date_format: &str) -> serde_json::Result<()> {
+    if let Some(date) = value
+        .as_str()
+        .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+    {
+        *value = Value::String(format_date(&date, date_format)?);
+    }
+    Ok(())
+}
+
+/// Format `date` according to `date_format`.
+///
+/// Unlike `date.format(date_format).to_string()`, this doesn't panic if the format string is
+/// malformed or asks for the time.
+fn format_date(date: &NaiveDate, date_format: &str) -> serde_json::Result<String> {
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.format(date_format)).map_err(|_| {
+        ser::Error::custom(format!(
+            "Invalid date format '{}', it may only contain parts of a date",
+            date_format
+        ))
+    })?;
+    Ok(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    const INPUT: &str = "\
+        :20:ref\r\n\
+        :25:account\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR10,\r\n\
+        :61:0909250925D1,505NTRFref\r\n\
+        :62F:C090925EUR8,495\r\n";
+
+    #[test]
+    fn default_options_match_serde() {
+        let messages = parse_mt940(INPUT).unwrap();
+        assert_eq!(
+            to_json(&messages, &JsonOptions::new()).unwrap(),
+            serde_json::to_string(&messages).unwrap()
+        );
+        assert_eq!(
+            to_json(&messages, &JsonOptions::new().pretty(true)).unwrap(),
+            serde_json::to_string_pretty(&messages).unwrap()
+        );
+    }
+
+    #[test]
+    fn amounts_and_dates() {
+        let messages = parse_mt940(INPUT).unwrap();
+
+        let options = JsonOptions::new().amount_format(AmountFormat::Fixed(2));
+        let value = to_json_value(&messages, &options).unwrap();
+        assert_eq!(value[0]["opening_balance"]["amount"], json!("10.00"));
+        assert_eq!(value[0]["statement_lines"][0]["amount"], json!("1.50"));
+
+        let options = JsonOptions::new()
+            .amount_format(AmountFormat::Number)
+            .date_format("%Y%m%d");
+        let value = to_json_value(&messages, &options).unwrap();
+        assert_eq!(value[0]["opening_balance"]["amount"], json!(10));
+        assert_eq!(value[0]["closing_balance"]["amount"], json!(8.495));
+        assert_eq!(value[0]["closing_balance"]["date"], json!("20090925"));
+        assert_eq!(
+            value[0]["statement_lines"][0]["entry_date"],
+            json!("20090925")
+        );
+    }
+
+    #[test]
+    fn invalid_date_formats() {
+        let messages = parse_mt940(INPUT).unwrap();
+        for date_format in ["%H:%M", "%d.%m.%Y %z", "%Q", "%"] {
+            let options = JsonOptions::new().date_format(date_format);
+            let err = to_json(&messages, &options).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Invalid date format '{}', it may only contain parts of a date",
+                    date_format
+                )
+            );
+            assert!(to_json(&[], &options).is_err());
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Export into the plain text accounting formats of hledger, ledger and beancount.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{Message, StatementLine};
+
+/// The plain text accounting syntax to write.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LedgerFormat {
+    /// Journal format of hledger which ledger understands as well.
+    #[default]
+    Hledger,
+    /// Beancount.
+    Beancount,
+}
+
+/// How to write transactions and which accounts to book them on.
+///
+/// Every [`StatementLine`] becomes a transaction between `account` and either `income_account`
+/// or `expense_account` depending on whether it's a credit or a debit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LedgerConfig {
+    pub(crate) format: LedgerFormat,
+    pub(crate) account: String,
+    pub(crate) income_account: String,
+    pub(crate) expense_account: String,
+}
+
+impl Default for LedgerConfig {
+    fn default() -> LedgerConfig {
+        LedgerConfig {
+            format: LedgerFormat::default(),
+            account: "Assets:Bank".to_string(),
+            income_account: "Income:Unknown".to_string(),
+            expense_account: "Expenses:Unknown".to_string(),
+        }
+    }
+}
+
+impl LedgerConfig {
+    /// Create a new [`LedgerConfig`] for `format` with default accounts.
+    pub fn new(format: LedgerFormat) -> LedgerConfig {
+        LedgerConfig {
+            format,
+            ..LedgerConfig::default()
+        }
+    }
+
+    /// The account of the statement itself, `Assets:Bank` by default.
+    pub fn account(mut self, account: &str) -> LedgerConfig {
+        self.account = account.to_string();
+        self
+    }
+
+    /// The account credits are booked against, `Income:Unknown` by default.
+    pub fn income_account(mut self, income_account: &str) -> LedgerConfig {
+        self.income_account = income_account.to_string();
+        self
+    }
+
+    /// The account debits are booked against, `Expenses:Unknown` by default.
+    pub fn expense_account(mut self, expense_account: &str) -> LedgerConfig {
+        self.expense_account = expense_account.to_string();
+        self
+    }
+}
+
+/// Convert the [`StatementLine`]s of `messages` into plain text accounting transactions.
+///
+/// The date of a transaction is its entry date if given and its value date otherwise. The payee
+/// is the first line of tag `:86:` and the remaining lines become the narration.
+///
+/// # Example
+/// ```
+/// use mt940::export::ledger::{to_ledger, LedgerConfig, LedgerFormat};
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let config = LedgerConfig::new(LedgerFormat::Hledger).account("Assets:Checking");
+/// assert_eq!(
+///     to_ledger(&parse_mt940(input).unwrap(), &config),
+///     "2009-09-25 Beneficiary name\n    \
+///          Assets:Checking  -583.92 EUR\n    \
+///          Expenses:Unknown\n"
+/// );
+/// ```
+pub fn to_ledger(messages: &[Message], config: &LedgerConfig) -> String {
+    let mut transactions = vec![];
+    for message in messages {
+        let currency = &message.opening_balance.iso_currency_code;
+        for statement_line in &message.statement_lines {
+            transactions.push(format_transaction(statement_line, currency, config));
+        }
+    }
+    transactions.join("\n")
+}
+
+fn format_transaction(
+    statement_line: &StatementLine,
+    currency: &str,
+    config: &LedgerConfig,
+) -> String {
+    let date = statement_line
+        .entry_date
+        .as_ref()
+        .unwrap_or(&statement_line.value_date);
+    let amount = statement_line.signed_amount();
+    let counter_account = if amount < Decimal::ZERO {
+        &config.expense_account
+    } else {
+        &config.income_account
+    };
+
+    let mut lines = statement_line
+        .information_to_account_owner
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let payee = lines
+        .next()
+        .unwrap_or(&statement_line.customer_ref)
+        .to_string();
+    let narration = lines.collect::<Vec<_>>().join(" ");
+
+    match config.format {
+        LedgerFormat::Hledger => {
+            let description = if narration.is_empty() {
+                payee
+            } else {
+                // hledger splits payee and note at the first pipe.
+                format!("{} | {}", payee.replace('|', " "), narration)
+            };
+            format!(
+                "{} {}\n    {}  {} {}\n    {}\n",
+                format_date(date),
+                description,
+                config.account,
+                amount,
+                currency,
+                counter_account
+            )
+        }
+        LedgerFormat::Beancount => format!(
+            "{} * \"{}\" \"{}\"\n  {}  {} {}\n  {}\n",
+            format_date(date),
+            escape_beancount(&payee),
+            escape_beancount(&narration),
+            config.account,
+            amount,
+            currency,
+            counter_account
+        ),
+    }
+}
+
+fn escape_beancount(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn format_date(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    const INPUT: &str = "\
+        :20:ref\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:090925C2,5NTRFcustomer\r\n\
+        :86:Payer\r\n\
+        Invoice 42\r\n\
+        :61:090926D1,NCHGfee\r\n\
+        :62F:C090926EUR2,5\r\n";
+
+    #[test]
+    fn ledger_hledger() {
+        let config = LedgerConfig::new(LedgerFormat::Hledger).income_account("Income:Sales");
+        assert_eq!(
+            to_ledger(&parse_mt940(INPUT).unwrap(), &config),
+            "2009-09-25 Payer | Invoice 42\n    Assets:Bank  2.5 EUR\n    Income:Sales\n\
+             \n\
+             2009-09-26 fee\n    Assets:Bank  -1 EUR\n    Expenses:Unknown\n"
+        );
+    }
+
+    #[test]
+    fn ledger_beancount() {
+        let config = LedgerConfig::new(LedgerFormat::Beancount).expense_account("Expenses:Fees");
+        assert_eq!(
+            to_ledger(&parse_mt940(INPUT).unwrap(), &config),
+            "2009-09-25 * \"Payer\" \"Invoice 42\"\n  Assets:Bank  2.5 EUR\n  Income:Unknown\n\
+             \n\
+             2009-09-26 * \"fee\" \"\"\n  Assets:Bank  -1 EUR\n  Expenses:Fees\n"
+        );
+    }
+}
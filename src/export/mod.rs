@@ -0,0 +1,61 @@
+//! Conversion of parsed [`Message`](crate::Message)s into other formats.
+//!
+//! Each format except JSON is behind a feature flag of the same name.
+
+#[cfg(feature = "csv")]
+pub mod csv;
+
+#[cfg(feature = "iso20022")]
+pub mod camt053;
+
+pub mod json;
+
+#[cfg(feature = "ledger")]
+pub mod ledger;
+
+#[cfg(feature = "ofx")]
+pub mod ofx;
+
+/// Write an indented XML element on its own line.
+#[cfg(any(feature = "iso20022", feature = "ofx"))]
+fn push_element(out: &mut String, indent: usize, name: &str, value: &str) {
+    out.push_str(&format!(
+        "{:indent$}<{name}>{}</{name}>\n",
+        "",
+        escape_xml(value),
+        indent = indent,
+        name = name
+    ));
+}
+
+/// Escape the characters with a special meaning in XML.
+#[cfg(any(feature = "iso20022", feature = "ofx"))]
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(all(test, any(feature = "iso20022", feature = "ofx")))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn escape_xml_special_characters() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Export into OFX 2 bank statement responses as understood by GnuCash, Microsoft Money and
+//! others.
+//!
+//! Every [`Message`] becomes a `<STMTTRNRS>`, every [`StatementLine`] a `<STMTTRN>`.
+
+use chrono::NaiveDate;
+
+use crate::export::push_element;
+use crate::{AccountId, Message, StatementLine, TransactionTypeIdentificationCode};
+
+/// Header of an OFX 2.2 document.
+const HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+    <?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n";
+
+/// Convert `messages` into an OFX document containing one bank statement per message.
+///
+/// The bank ID of an account is taken from tag `:25P:` or from the account identification if it
+/// starts with a BIC or bank code. It's left empty otherwise.
+///
+/// # Example
+/// ```
+/// use mt940::export::ofx::to_ofx;
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let ofx = to_ofx(&parse_mt940(input).unwrap());
+/// assert!(ofx.contains("<TRNAMT>-583.92</TRNAMT>"));
+/// assert!(ofx.contains("<BANKID>DABADKKK</BANKID>"));
+/// ```
+pub fn to_ofx(messages: &[Message]) -> String {
+    let server_date = messages
+        .last()
+        .map(|message| format_date(&message.closing_balance.date))
+        .unwrap_or_default();
+
+    let mut out = String::from(HEADER);
+    out.push_str("<OFX>\n");
+    out.push_str("  <SIGNONMSGSRSV1>\n");
+    out.push_str("    <SONRS>\n");
+    push_status(&mut out, 6);
+    push_element(&mut out, 6, "DTSERVER", &server_date);
+    push_element(&mut out, 6, "LANGUAGE", "ENG");
+    out.push_str("    </SONRS>\n");
+    out.push_str("  </SIGNONMSGSRSV1>\n");
+    out.push_str("  <BANKMSGSRSV1>\n");
+    for message in messages {
+        push_statement(&mut out, message);
+    }
+    out.push_str("  </BANKMSGSRSV1>\n");
+    out.push_str("</OFX>\n");
+    out
+}
+
+fn push_statement(out: &mut String, message: &Message) {
+    let (bank_id, account_id) = match message.parsed_account_id() {
+        AccountId::BicAndAccount { bic, account } => (Some(bic), account),
+        AccountId::BankCodeAndAccount { bank_code, account } => (Some(bank_code), account),
+        AccountId::Iban(iban) => (None, iban),
+        AccountId::Raw(raw) => (None, raw),
+    };
+    let bank_id = message.account_identifier_bic.clone().or(bank_id);
+
+    out.push_str("    <STMTTRNRS>\n");
+    push_element(out, 6, "TRNUID", &message.transaction_ref_no);
+    push_status(out, 6);
+    out.push_str("      <STMTRS>\n");
+    push_element(out, 8, "CURDEF", &message.opening_balance.iso_currency_code);
+    out.push_str("        <BANKACCTFROM>\n");
+    push_element(out, 10, "BANKID", bank_id.as_deref().unwrap_or_default());
+    push_element(out, 10, "ACCTID", &account_id);
+    push_element(out, 10, "ACCTTYPE", "CHECKING");
+    out.push_str("        </BANKACCTFROM>\n");
+
+    out.push_str("        <BANKTRANLIST>\n");
+    push_element(
+        out,
+        10,
+        "DTSTART",
+        &format_date(&message.opening_balance.date),
+    );
+    push_element(
+        out,
+        10,
+        "DTEND",
+        &format_date(&message.closing_balance.date),
+    );
+    for (i, statement_line) in message.statement_lines.iter().enumerate() {
+        push_transaction(out, statement_line, &fit_id(message, i));
+    }
+    out.push_str("        </BANKTRANLIST>\n");
+
+    out.push_str("        <LEDGERBAL>\n");
+    push_element(
+        out,
+        10,
+        "BALAMT",
+        &message.closing_balance.signed_amount().to_string(),
+    );
+    push_element(
+        out,
+        10,
+        "DTASOF",
+        &format_date(&message.closing_balance.date),
+    );
+    out.push_str("        </LEDGERBAL>\n");
+    if let Some(ref balance) = message.closing_available_balance {
+        out.push_str("        <AVAILBAL>\n");
+        push_element(out, 10, "BALAMT", &balance.signed_amount().to_string());
+        push_element(out, 10, "DTASOF", &format_date(&balance.date));
+        out.push_str("        </AVAILBAL>\n");
+    }
+    out.push_str("      </STMTRS>\n");
+    out.push_str("    </STMTTRNRS>\n");
+}
+
+/// The FITID of the statement line at `index` of `message`.
+///
+/// OFX clients use the FITID to detect duplicates on repeated imports and drop any transaction
+/// whose FITID they have seen before, so it has to be stable yet unique across statements. Banks
+/// reuse transaction reference numbers all the time, e.g. `NONREF`, so the FITID is made up of
+/// the account, the statement and sequence number, the date of the opening balance and the
+/// position of the statement line instead.
+fn fit_id(message: &Message, index: usize) -> String {
+    let statement = match message.sequence_no {
+        Some(ref sequence_no) => format!("{}/{}", message.statement_no, sequence_no),
+        None => message.statement_no.clone(),
+    };
+    format!(
+        "{}-{}-{}-{}",
+        message.account_id,
+        statement,
+        format_date(&message.opening_balance.date),
+        index + 1
+    )
+}
+
+fn push_transaction(out: &mut String, statement_line: &StatementLine, fit_id: &str) {
+    let amount = statement_line.signed_amount();
+    out.push_str("          <STMTTRN>\n");
+    push_element(out, 12, "TRNTYPE", transaction_type(statement_line));
+    push_element(
+        out,
+        12,
+        "DTPOSTED",
+        &format_date(
+            statement_line
+                .entry_date
+                .as_ref()
+                .unwrap_or(&statement_line.value_date),
+        ),
+    );
+    push_element(out, 12, "DTUSER", &format_date(&statement_line.value_date));
+    push_element(out, 12, "TRNAMT", &amount.to_string());
+    push_element(out, 12, "FITID", fit_id);
+    if let Some(ref info) = statement_line.information_to_account_owner {
+        // The memo is limited to 255 characters.
+        let memo: String = info.lines().collect::<Vec<_>>().join(" ");
+        push_element(out, 12, "MEMO", &memo.chars().take(255).collect::<String>());
+    }
+    out.push_str("          </STMTTRN>\n");
+}
+
+/// The OFX transaction type of a [`StatementLine`].
+///
+/// Fees, interest and cheques have their own types, everything else is a plain credit or debit.
+fn transaction_type(statement_line: &StatementLine) -> &'static str {
+    match statement_line.transaction_type_ident_code {
+        TransactionTypeIdentificationCode::CHG => "SRVCHG",
+        TransactionTypeIdentificationCode::INT => "INT",
+        TransactionTypeIdentificationCode::CHK => "CHECK",
+        _ if statement_line.signed_amount().is_sign_negative() => "DEBIT",
+        _ => "CREDIT",
+    }
+}
+
+/// Write a status signalling success.
+fn push_status(out: &mut String, indent: usize) {
+    out.push_str(&format!("{:indent$}<STATUS>\n", "", indent = indent));
+    push_element(out, indent + 2, "CODE", "0");
+    push_element(out, indent + 2, "SEVERITY", "INFO");
+    out.push_str(&format!("{:indent$}</STATUS>\n", "", indent = indent));
+}
+
+fn format_date(date: &NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn ofx_statement() {
+        let input = "\
+            :20:ref\r\n\
+            :25:NL91ABNA0417164300\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR3,\r\n\
+            :61:0909250924D1,NCHGcustomer//bank\r\n\
+            :86:Account\r\n\
+            fee\r\n\
+            :62F:C090925EUR2,\r\n\
+            :64:C090925EUR2,\r\n";
+        let ofx = to_ofx(&parse_mt940(input).unwrap());
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            r#"<OFX>
+  <SIGNONMSGSRSV1>
+    <SONRS>
+      <STATUS>
+        <CODE>0</CODE>
+        <SEVERITY>INFO</SEVERITY>
+      </STATUS>
+      <DTSERVER>20090925</DTSERVER>
+      <LANGUAGE>ENG</LANGUAGE>
+    </SONRS>
+  </SIGNONMSGSRSV1>
+  <BANKMSGSRSV1>
+    <STMTTRNRS>
+      <TRNUID>ref</TRNUID>
+      <STATUS>
+        <CODE>0</CODE>
+        <SEVERITY>INFO</SEVERITY>
+      </STATUS>
+      <STMTRS>
+        <CURDEF>EUR</CURDEF>
+        <BANKACCTFROM>
+          <BANKID></BANKID>
+          <ACCTID>NL91ABNA0417164300</ACCTID>
+          <ACCTTYPE>CHECKING</ACCTTYPE>
+        </BANKACCTFROM>
+        <BANKTRANLIST>
+          <DTSTART>20090924</DTSTART>
+          <DTEND>20090925</DTEND>
+          <STMTTRN>
+            <TRNTYPE>SRVCHG</TRNTYPE>
+            <DTPOSTED>20090924</DTPOSTED>
+            <DTUSER>20090925</DTUSER>
+            <TRNAMT>-1</TRNAMT>
+            <FITID>NL91ABNA0417164300-1-20090924-1</FITID>
+            <MEMO>Account fee</MEMO>
+          </STMTTRN>
+        </BANKTRANLIST>
+        <LEDGERBAL>
+          <BALAMT>2</BALAMT>
+          <DTASOF>20090925</DTASOF>
+        </LEDGERBAL>
+        <AVAILBAL>
+          <BALAMT>2</BALAMT>
+          <DTASOF>20090925</DTASOF>
+        </AVAILBAL>
+      </STMTRS>
+    </STMTTRNRS>
+  </BANKMSGSRSV1>
+</OFX>
+"#
+        );
+        assert_eq!(ofx, expected);
+    }
+
+    #[test]
+    fn ofx_fit_ids_unique_across_statements() {
+        let statement = |account: &str, statement_no: &str| {
+            format!(
+                ":20:NONREF\r\n\
+                 :25:{}\r\n\
+                 :28C:{}\r\n\
+                 :60F:C090924EUR3,\r\n\
+                 :61:0909250924D1,NCHGcustomer//bank\r\n\
+                 :61:0909250924D1,NCHGcustomer//bank\r\n\
+                 :62F:C090925EUR1,\r\n",
+                account, statement_no
+            )
+        };
+        let input = [
+            statement("NL91ABNA0417164300", "1/1"),
+            statement("NL91ABNA0417164300", "1/2"),
+            statement("NL91ABNA0417164300", "2"),
+            statement("DE89370400440532013000", "1/1"),
+        ]
+        .concat();
+        let ofx = to_ofx(&parse_mt940(&input).unwrap());
+        let fit_ids = ofx
+            .lines()
+            .filter(|line| line.contains("<FITID>"))
+            .collect::<Vec<_>>();
+        assert_eq!(fit_ids.len(), 8);
+        assert_eq!(fit_ids.iter().collect::<BTreeSet<_>>().len(), fit_ids.len());
+        assert_eq!(
+            fit_ids[3].trim(),
+            "<FITID>NL91ABNA0417164300-1/2-20090924-2</FITID>"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Generation of arbitrary but valid messages for fuzzing and property testing.
+//!
+//! The [`Arbitrary`] implementations only produce values which survive a round trip through
+//! [`serialize_mt940`] and [`parse_mt940`](crate::parse_mt940) unchanged. References and texts are
+//! made up of SWIFT characters, amounts fit into 15 characters and dates lie between 2000 and
+//! 2098 so their two-digit years are unambiguous.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use strum::IntoEnumIterator;
+
+use crate::{
+    serialize_mt940, AvailableBalance, Balance, DebitOrCredit, EntryType, ExtDebitOrCredit,
+    Message, StatementLine, TransactionTypeIdentificationCode,
+};
+
+const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Characters of references besides alphanumerics. `:` is left out as values must not look like a
+/// tag and `/` is only added where `//` doesn't separate the customer reference from the bank
+/// reference.
+const PUNCTUATION: &[u8] = b"-?().,+";
+
+/// Largest amount in cents which is generated. Sums of many of them still fit into 15 characters.
+const MAX_CENTS: i64 = 99_999_999_999;
+
+/// A list of arbitrary [`Message`]s along with their MT940 text.
+///
+/// # Example
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use mt940::{parse_mt940, ArbitraryStatement};
+///
+/// let mut u = Unstructured::new(b"some fuzzer input");
+/// let statement = ArbitraryStatement::arbitrary(&mut u).unwrap();
+/// assert_eq!(parse_mt940(&statement.text).unwrap(), statement.messages);
+/// ```
+#[derive(Debug)]
+pub struct ArbitraryStatement {
+    pub messages: Vec<Message>,
+
+    /// The messages serialized with [`serialize_mt940`].
+    pub text: String,
+}
+
+impl<'a> Arbitrary<'a> for ArbitraryStatement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let count = u.int_in_range(1..=3)?;
+        let messages = (0..count)
+            .map(|_| Message::arbitrary(u))
+            .collect::<Result<Vec<_>>>()?;
+        let text = serialize_mt940(&messages);
+        Ok(ArbitraryStatement { messages, text })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    /// The closing balance is the opening balance plus the statement lines.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let opening_balance = Balance::arbitrary(u)?;
+        let currency = opening_balance.iso_currency_code.clone();
+        let line_count = u.int_in_range(0..=8)?;
+        let statement_lines = (0..line_count)
+            .map(|_| StatementLine::arbitrary(u))
+            .collect::<Result<Vec<_>>>()?;
+        let closing = statement_lines
+            .iter()
+            .map(StatementLine::signed_amount)
+            .fold(opening_balance.signed_amount(), |sum, amount| sum + amount);
+        let closing_balance = Balance {
+            is_intermediate: u.arbitrary()?,
+            debit_credit_indicator: debit_credit_of(closing),
+            date: date(u)?,
+            iso_currency_code: currency.clone(),
+            amount: closing.abs(),
+        };
+        let closing_available_balance = if u.arbitrary()? {
+            Some(available_balance(u, &currency)?)
+        } else {
+            None
+        };
+        let forward_count = u.int_in_range(0..=2)?;
+        let forward_available_balances = (0..forward_count)
+            .map(|_| available_balance(u, &currency))
+            .collect::<Result<Vec<_>>>()?;
+
+        let account_identifier_bic = if u.arbitrary()? { Some(bic(u)?) } else { None };
+        Ok(Message {
+            transaction_ref_no: reference(u, 16, true)?,
+            ref_to_related_msg: optional(u, |u| reference(u, 16, true))?,
+            account_id: reference(u, 35, true)?,
+            account_identifier_bic,
+            statement_no: digits(u, 1, 5)?,
+            sequence_no: optional(u, |u| digits(u, 1, 5))?,
+            opening_balance,
+            statement_lines,
+            closing_balance,
+            closing_available_balance,
+            forward_available_balances,
+            information_to_account_owner: optional(u, information)?,
+            information_lines: vec![],
+            non_swift_fields: vec![],
+            custom_fields: BTreeMap::new(),
+            raw: None,
+            source: None,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for StatementLine {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let value_date = date(u)?;
+        let entry_date = if u.arbitrary()? {
+            let ordinal = u.int_in_range(1..=365)?;
+            Some(NaiveDate::from_yo_opt(value_date.year(), ordinal).ok_or(Error::IncorrectFormat)?)
+        } else {
+            None
+        };
+        let ext_debit_credit_indicator = match u.int_in_range(0..=3)? {
+            0 => ExtDebitOrCredit::Debit,
+            1 => ExtDebitOrCredit::Credit,
+            2 => ExtDebitOrCredit::ReverseDebit,
+            _ => ExtDebitOrCredit::ReverseCredit,
+        };
+        let funds_code = if u.ratio(1, 4)? {
+            Some(char::from(*u.choose(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ")?).to_string())
+        } else {
+            None
+        };
+        let entry_type = match u.int_in_range(0..=2)? {
+            0 => EntryType::Swift,
+            1 => EntryType::NonSwift,
+            _ => EntryType::FirstAdvice,
+        };
+        Ok(StatementLine {
+            value_date,
+            entry_date,
+            ext_debit_credit_indicator,
+            funds_code,
+            amount: amount(u)?,
+            transaction_type_ident_code: transaction_type(u, &entry_type)?,
+            entry_type,
+            customer_ref: reference(u, 16, false)?,
+            bank_ref: optional(u, |u| reference(u, 16, true))?,
+            supplementary_details: optional(u, |u| reference(u, 34, true))?,
+            information_to_account_owner: optional(u, information)?,
+            information_lines: vec![],
+            structured_information: None,
+            sepa_purpose: None,
+            raw: None,
+            index: None,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Balance {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Balance {
+            is_intermediate: u.arbitrary()?,
+            debit_credit_indicator: debit_credit(u)?,
+            date: date(u)?,
+            iso_currency_code: currency(u)?,
+            amount: amount(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for AvailableBalance {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let currency = currency(u)?;
+        available_balance(u, &currency)
+    }
+}
+
+fn available_balance(u: &mut Unstructured<'_>, currency: &str) -> Result<AvailableBalance> {
+    Ok(AvailableBalance {
+        debit_credit_indicator: debit_credit(u)?,
+        date: date(u)?,
+        iso_currency_code: currency.to_string(),
+        amount: amount(u)?,
+    })
+}
+
+fn optional<'a, T>(
+    u: &mut Unstructured<'a>,
+    generate: impl FnOnce(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Option<T>> {
+    if u.arbitrary()? {
+        generate(u).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn debit_credit(u: &mut Unstructured<'_>) -> Result<DebitOrCredit> {
+    Ok(if u.arbitrary()? {
+        DebitOrCredit::Debit
+    } else {
+        DebitOrCredit::Credit
+    })
+}
+
+fn debit_credit_of(amount: Decimal) -> DebitOrCredit {
+    if amount.is_sign_negative() {
+        DebitOrCredit::Debit
+    } else {
+        DebitOrCredit::Credit
+    }
+}
+
+fn date(u: &mut Unstructured<'_>) -> Result<NaiveDate> {
+    let year = u.int_in_range(2000..=2098)?;
+    let ordinal = u.int_in_range(1..=365)?;
+    NaiveDate::from_yo_opt(year, ordinal).ok_or(Error::IncorrectFormat)
+}
+
+fn amount(u: &mut Unstructured<'_>) -> Result<Decimal> {
+    Ok(Decimal::new(u.int_in_range(0..=MAX_CENTS)?, 2))
+}
+
+fn currency(u: &mut Unstructured<'_>) -> Result<String> {
+    let currency = *u.choose(&["EUR", "USD", "GBP", "CHF", "DKK", "SEK", "NOK", "PLN"])?;
+    Ok(currency.to_string())
+}
+
+fn bic(u: &mut Unstructured<'_>) -> Result<String> {
+    let mut bic = String::new();
+    for _ in 0..6 {
+        bic.push(char::from(*u.choose(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ")?));
+    }
+    let length = if u.arbitrary()? { 5 } else { 2 };
+    for _ in 0..length {
+        bic.push(char::from(
+            *u.choose(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")?,
+        ));
+    }
+    Ok(bic)
+}
+
+fn digits(u: &mut Unstructured<'_>, min: usize, max: usize) -> Result<String> {
+    let length = u.int_in_range(min..=max)?;
+    (0..length)
+        .map(|_| Ok(char::from(*u.choose(b"0123456789")?)))
+        .collect()
+}
+
+/// A reference of up to `max` characters which starts and ends with an alphanumeric character.
+fn reference(u: &mut Unstructured<'_>, max: usize, allow_slash: bool) -> Result<String> {
+    let length = u.int_in_range(1..=max)?;
+    let mut reference = String::with_capacity(length);
+    for i in 0..length {
+        let c = if i == 0 || i + 1 == length || u.ratio(3, 4)? {
+            *u.choose(ALPHANUMERIC)?
+        } else if allow_slash && u.ratio(1, 3)? {
+            b'/'
+        } else {
+            *u.choose(PUNCTUATION)?
+        };
+        reference.push(char::from(c));
+    }
+    Ok(reference)
+}
+
+/// Information for tag `:86:` which fits into a single tag.
+fn information(u: &mut Unstructured<'_>) -> Result<String> {
+    let line_count = u.int_in_range(1..=6)?;
+    let lines = (0..line_count)
+        .map(|_| {
+            let mut line = reference(u, 20, true)?;
+            while line.len() < 44 && u.arbitrary()? {
+                line.push(' ');
+                line.push_str(&reference(u, 20, true)?);
+            }
+            Ok(line)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// A transaction type identification code which is parsed back into the same variant.
+///
+/// SWIFT transfers use the message type, e.g. `103`, instead of a code.
+fn transaction_type(
+    u: &mut Unstructured<'_>,
+    entry_type: &EntryType,
+) -> Result<TransactionTypeIdentificationCode> {
+    let code = match entry_type {
+        EntryType::Swift => digits(u, 3, 3)?,
+        _ if u.ratio(3, 4)? => {
+            let known = TransactionTypeIdentificationCode::iter()
+                .filter(|code| !matches!(code, TransactionTypeIdentificationCode::NonStandard(_)))
+                .collect::<Vec<_>>();
+            return Ok(u.choose(&known)?.clone());
+        }
+        _ => (0..3)
+            .map(|_| {
+                Ok(char::from(
+                    *u.choose(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")?,
+                ))
+            })
+            .collect::<Result<String>>()?,
+    };
+    Ok(TransactionTypeIdentificationCode::from_str(&code)
+        .unwrap_or(TransactionTypeIdentificationCode::NonStandard(code)))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::*;
+    use crate::parse_mt940;
+
+    proptest! {
+        #[test]
+        fn arbitrary_statement_round_trips(data in proptest::collection::vec(any::<u8>(), 0..4096)) {
+            let mut u = Unstructured::new(&data);
+            let statement = ArbitraryStatement::arbitrary(&mut u).unwrap();
+            prop_assert_eq!(parse_mt940(&statement.text).unwrap(), statement.messages);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Extraction of the `?NN` subfields German banks put into tag `:86:`.
+
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::StatementLine;
+
+/// The subfields contained in the information to the account owner of a [`StatementLine`] as
+/// used by German banks such as Sparkasse, DKB and Commerzbank.
+///
+/// Banks put a three digit business transaction code into tag `:86:` followed by subfields which
+/// each start with `?` and a two digit number, for instance
+/// `166?00GUTSCHRIFT?20SVWZ+Invoice 42?30COBADEFFXXX?31DE89370400440532013000?32John Doe`.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StructuredInformationToAccountOwner {
+    /// Business transaction code (the text in front of the first subfield)
+    pub transaction_code: Option<String>,
+
+    /// Posting text (`?00`)
+    pub posting_text: Option<String>,
+
+    /// Primanota (`?10`)
+    pub primanota: Option<String>,
+
+    /// Purpose (`?20` to `?29` and `?60` to `?63`)
+    ///
+    /// These are joined without any separator as banks fill each of them up to a fixed width,
+    /// often in the middle of a word.
+    pub purpose: Option<String>,
+
+    /// BIC or bank code of the counterparty (`?30`)
+    pub counterparty_bic: Option<String>,
+
+    /// IBAN or account number of the counterparty (`?31`)
+    pub counterparty_account: Option<String>,
+
+    /// Name of the counterparty (`?32` and `?33`)
+    pub counterparty_name: Option<String>,
+
+    /// Text key extension (`?34`)
+    pub text_key_extension: Option<String>,
+
+    /// All subfields by their number with their values as they are, including the ones above.
+    ///
+    /// Only the first value is kept of subfields which occur more than once.
+    pub subfields: BTreeMap<String, String>,
+}
+
+impl StructuredInformationToAccountOwner {
+    /// Extract the subfields from the contents of a tag `:86:`.
+    ///
+    /// A `?` which isn't followed by two digits is taken as part of the text. Returns `None` if
+    /// there are no subfields.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::StructuredInformationToAccountOwner;
+    ///
+    /// let info = "166?00GUTSCHRIFT?100399?20EREF+EndToEndId?21SVWZ+Invoice 42\n\
+    ///             ?30COBADEFFXXX?31DE89370400440532013000?32John ?33Doe";
+    /// let information = StructuredInformationToAccountOwner::from_information(info).unwrap();
+    /// assert_eq!(information.transaction_code.as_deref(), Some("166"));
+    /// assert_eq!(information.posting_text.as_deref(), Some("GUTSCHRIFT"));
+    /// assert_eq!(information.purpose.as_deref(), Some("EREF+EndToEndIdSVWZ+Invoice 42"));
+    /// assert_eq!(information.counterparty_bic.as_deref(), Some("COBADEFFXXX"));
+    /// assert_eq!(information.counterparty_name.as_deref(), Some("John Doe"));
+    /// assert_eq!(information.subfields["10"], "0399");
+    /// ```
+    pub fn from_information(info: &str) -> Option<StructuredInformationToAccountOwner> {
+        // Lines are wrapped at a fixed width, often in the middle of a word.
+        let joined: String = info.lines().collect();
+        let mut parts = joined.split('?');
+        let transaction_code = parts.next().unwrap_or_default();
+
+        let mut subfields: Vec<(&str, String)> = vec![];
+        for part in parts {
+            match (part.get(..2), part.get(2..)) {
+                (Some(number), Some(value)) if number.bytes().all(|b| b.is_ascii_digit()) => {
+                    subfields.push((number, value.to_string()));
+                }
+                // Only a question mark within the text.
+                _ => match subfields.last_mut() {
+                    Some((_, value)) => {
+                        value.push('?');
+                        value.push_str(part);
+                    }
+                    None => return None,
+                },
+            }
+        }
+        if subfields.is_empty() {
+            return None;
+        }
+
+        let joined_values = |is_wanted: fn(&[u8]) -> bool| {
+            let value = subfields
+                .iter()
+                .filter(|(number, _)| is_wanted(number.as_bytes()))
+                .map(|(_, value)| value.as_str())
+                .collect::<String>();
+            Some(value.trim().to_string()).filter(|value| !value.is_empty())
+        };
+        let purpose = joined_values(|number| matches!(number, [b'2', _] | [b'6', b'0'..=b'3']));
+        let counterparty_name = joined_values(|number| matches!(number, b"32" | b"33"));
+
+        let mut fields = BTreeMap::new();
+        for (number, value) in subfields {
+            fields.entry(number.to_string()).or_insert(value);
+        }
+        let get = |number: &str| {
+            fields
+                .get(number)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        Some(StructuredInformationToAccountOwner {
+            transaction_code: Some(transaction_code.trim().to_string())
+                .filter(|code| !code.is_empty()),
+            posting_text: get("00"),
+            primanota: get("10"),
+            purpose,
+            counterparty_bic: get("30"),
+            counterparty_account: get("31"),
+            counterparty_name,
+            text_key_extension: get("34"),
+            subfields: fields,
+        })
+    }
+}
+
+impl StatementLine {
+    /// The German `?NN` subfields contained in the information to the account owner of this
+    /// [`StatementLine`].
+    ///
+    /// Unlike [`StatementLine::structured_information`], this doesn't depend on the
+    /// [`ParserConfig::information_format`] the statement was parsed with.
+    ///
+    /// See [`StructuredInformationToAccountOwner::from_information`].
+    ///
+    /// [`ParserConfig::information_format`]: crate::ParserConfig::information_format
+    pub fn parse_structured_information(&self) -> Option<StructuredInformationToAccountOwner> {
+        self.information_to_account_owner
+            .as_deref()
+            .and_then(StructuredInformationToAccountOwner::from_information)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn german_subfields() {
+        let info = "166?00GUTSCHRIFT?100399?20EREF+EndToEndIdTFNR20004000?2101?22SVWZ\n\
+                    +TO 13 TFNr 20004?30PBNKDEFF100?31DE42100100100043921105?32Richter\n\
+                    ?34997";
+        let expected = StructuredInformationToAccountOwner {
+            transaction_code: Some("166".to_string()),
+            posting_text: Some("GUTSCHRIFT".to_string()),
+            primanota: Some("0399".to_string()),
+            purpose: Some("EREF+EndToEndIdTFNR2000400001SVWZ+TO 13 TFNr 20004".to_string()),
+            counterparty_bic: Some("PBNKDEFF100".to_string()),
+            counterparty_account: Some("DE42100100100043921105".to_string()),
+            counterparty_name: Some("Richter".to_string()),
+            text_key_extension: Some("997".to_string()),
+            subfields: [
+                ("00", "GUTSCHRIFT"),
+                ("10", "0399"),
+                ("20", "EREF+EndToEndIdTFNR20004000"),
+                ("21", "01"),
+                ("22", "SVWZ+TO 13 TFNr 20004"),
+                ("30", "PBNKDEFF100"),
+                ("31", "DE42100100100043921105"),
+                ("32", "Richter"),
+                ("34", "997"),
+            ]
+            .into_iter()
+            .map(|(number, value)| (number.to_string(), value.to_string()))
+            .collect(),
+        };
+        assert_eq!(
+            StructuredInformationToAccountOwner::from_information(info),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn german_subfields_question_mark_in_text() {
+        let info = "166?20SVWZ+What?21 is this??30?31";
+        let information = StructuredInformationToAccountOwner::from_information(info).unwrap();
+        assert_eq!(information.purpose.as_deref(), Some("SVWZ+What is this?"));
+        assert_eq!(information.counterparty_bic, None);
+        assert_eq!(information.counterparty_account, None);
+    }
+
+    #[test]
+    fn german_subfields_none() {
+        for info in [
+            "Fees according to advice",
+            "Why?",
+            "/TRTP/SEPA OVERBOEKING",
+            "",
+        ] {
+            assert_eq!(
+                StructuredInformationToAccountOwner::from_information(info),
+                None
+            );
+        }
+    }
+}
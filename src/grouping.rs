@@ -0,0 +1,214 @@
+//! Grouping of messages by account and ordering them by statement number.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::Message;
+
+/// Identifies the account a [`Message`] is about.
+///
+/// Some banks report every currency of an account under the same account identification, so the
+/// currency is part of the key.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct AccountKey {
+    pub account_id: String,
+    /// The currency of the opening balance.
+    pub iso_currency_code: String,
+}
+
+impl Message {
+    /// The account this message is about.
+    pub fn account_key(&self) -> AccountKey {
+        AccountKey {
+            account_id: self.account_id.clone(),
+            iso_currency_code: self.opening_balance.iso_currency_code.clone(),
+        }
+    }
+
+    /// [`Message::statement_no`] as a number.
+    ///
+    /// Returns `None` if it is too large.
+    pub fn statement_number(&self) -> Option<u32> {
+        self.statement_no.parse().ok()
+    }
+
+    /// [`Message::sequence_no`] as a number.
+    ///
+    /// Returns `None` if there is none or it is too large.
+    pub fn sequence_number(&self) -> Option<u32> {
+        self.sequence_no.as_deref()?.parse().ok()
+    }
+}
+
+/// Group `messages` by the account they are about.
+///
+/// The messages of every account are ordered by [`sort_by_statement_number`].
+///
+/// # Example
+/// ```
+/// use mt940::{group_by_account, parse_mt940, statement_number_gaps};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00018/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090924EUR54484,04\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/222222-22222222\r\n\
+///     :28C:00002/001\r\n\
+///     :60F:C090924EUR0,\r\n\
+///     :62F:C090924EUR0,\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00016/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090924EUR54484,04\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// let groups = group_by_account(&messages);
+/// assert_eq!(groups.len(), 2);
+///
+/// let first_account = &messages[0].account_key();
+/// let statement_nos = groups[first_account]
+///     .iter()
+///     .map(|message| message.statement_no.as_str())
+///     .collect::<Vec<_>>();
+/// assert_eq!(statement_nos, ["00016", "00018"]);
+///
+/// let gaps = statement_number_gaps(&groups[first_account]);
+/// assert_eq!(gaps[0].missing(), 17..=17);
+/// ```
+pub fn group_by_account(messages: &[Message]) -> HashMap<AccountKey, Vec<&Message>> {
+    let mut groups: HashMap<AccountKey, Vec<&Message>> = HashMap::new();
+    for message in messages {
+        groups
+            .entry(message.account_key())
+            .or_default()
+            .push(message);
+    }
+    for group in groups.values_mut() {
+        sort_by_statement_number(group);
+    }
+    groups
+}
+
+/// Order `messages` by their statement number and the pages of a statement by their sequence
+/// number.
+///
+/// Messages without a sequence number are treated as the first page. Messages whose numbers
+/// can't be told keep their order relative to each other and go last.
+pub fn sort_by_statement_number(messages: &mut [&Message]) {
+    messages.sort_by_key(|message| {
+        (
+            message.statement_number().is_none(),
+            message.statement_number(),
+            message.sequence_number().unwrap_or(1),
+        )
+    });
+}
+
+/// Statement numbers missing in between two statements of an account.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct StatementGap {
+    /// The statement number in front of the gap.
+    pub previous: u32,
+    /// The statement number after the gap.
+    pub next: u32,
+}
+
+impl StatementGap {
+    /// The statement numbers which are missing.
+    pub fn missing(&self) -> RangeInclusive<u32> {
+        self.previous.saturating_add(1)..=self.next.saturating_sub(1)
+    }
+}
+
+/// Find the gaps in the numbering of `messages` which are ordered by
+/// [`sort_by_statement_number`] and are about the same account.
+///
+/// Statement numbers have to increase throughout, so statements of accounts whose numbering
+/// starts over, e.g. every year, need to be checked separately for every period. Messages whose
+/// statement number can't be told are skipped.
+pub fn statement_number_gaps(messages: &[&Message]) -> Vec<StatementGap> {
+    let numbers = messages
+        .iter()
+        .filter_map(|message| message.statement_number())
+        .collect::<Vec<_>>();
+    numbers
+        .windows(2)
+        .filter_map(|pair| match *pair {
+            [previous, next] if next > previous.saturating_add(1) => {
+                Some(StatementGap { previous, next })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    fn statement(account_id: &str, statement_no: &str, currency: &str) -> String {
+        format!(
+            ":20:a\r\n:25:{}\r\n:28C:{}\r\n:60F:C090924{}1,\r\n:62F:C090924{}1,\r\n",
+            account_id, statement_no, currency, currency
+        )
+    }
+
+    #[test]
+    fn groups_and_gaps() {
+        let input = [
+            statement("a", "5/2", "EUR"),
+            statement("a", "1", "EUR"),
+            statement("a", "5/1", "EUR"),
+            statement("a", "2", "USD"),
+            statement("b", "3", "EUR"),
+            statement("a", "2", "EUR"),
+            statement("a", "9", "EUR"),
+        ]
+        .concat();
+        let messages = parse_mt940(&input).unwrap();
+        let groups = group_by_account(&messages);
+        assert_eq!(groups.len(), 3);
+
+        let key = messages[0].account_key();
+        let numbers = groups[&key]
+            .iter()
+            .map(|message| (message.statement_number(), message.sequence_number()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numbers,
+            [
+                (Some(1), None),
+                (Some(2), None),
+                (Some(5), Some(1)),
+                (Some(5), Some(2)),
+                (Some(9), None)
+            ]
+        );
+
+        let gaps = statement_number_gaps(&groups[&key]);
+        assert_eq!(
+            gaps,
+            [
+                StatementGap {
+                    previous: 2,
+                    next: 5
+                },
+                StatementGap {
+                    previous: 5,
+                    next: 9
+                }
+            ]
+        );
+        assert_eq!(gaps[0].missing(), 3..=4);
+        assert!(statement_number_gaps(&groups[&messages[3].account_key()]).is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+//! Import of ISO 20022 camt.053 bank to customer statements and camt.052 account reports.
+//!
+//! This is the inverse of [`export::camt053`](crate::export::camt053). Every `<Stmt>` or `<Rpt>`
+//! becomes a [`Message`], every `<Ntry>` a [`StatementLine`]. Namespaces are ignored so that any
+//! version of either message can be read as long as it has the usual structure.
+//!
+//! Balances are mapped as follows:
+//!
+//! | camt.053       | MT940                                   |
+//! |----------------|-----------------------------------------|
+//! | `OPBD`, `PRCD` | `:60F:`                                 |
+//! | `ITBD`         | `:60M:` if it comes first, else `:62M:` |
+//! | `CLBD`         | `:62F:`                                 |
+//! | `CLAV`         | `:64:`                                  |
+//! | `FWAV`         | `:65:`                                  |
+//!
+//! Anything MT940 has no place for, such as the domain bank transaction codes, is dropped.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use roxmltree::{Document, Node};
+use rust_decimal::Decimal;
+
+use crate::{
+    AvailableBalance, Balance, DebitOrCredit, EntryType, ExtDebitOrCredit, ImportError, Message,
+    StatementLine, TransactionTypeIdentificationCode,
+};
+
+/// Convert a camt.053 or camt.052 document into [`Message`]s.
+///
+/// The statement number is taken from `<ElctrncSeqNb>` or `<LglSeqNb>` and defaults to `1`. The
+/// customer reference of a statement line is the end-to-end id of its first transaction or
+/// `NONREF`, its transaction type identification code is the proprietary bank transaction code
+/// if it's a SWIFT one or `MSC` otherwise.
+///
+/// # Example
+/// ```
+/// use mt940::export::camt053::to_camt053;
+/// use mt940::import::camt053::from_camt053;
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925D583,92NMSCNONREF//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// assert_eq!(from_camt053(&to_camt053(&messages)).unwrap(), messages);
+/// ```
+pub fn from_camt053(xml: &str) -> Result<Vec<Message>, ImportError> {
+    let document = Document::parse(xml).map_err(|err| ImportError::Xml(err.to_string()))?;
+    let root = document.root_element();
+    let container = child(root, "BkToCstmrStmt")
+        .or_else(|| child(root, "BkToCstmrAcctRpt"))
+        .ok_or_else(|| ImportError::MissingElement("BkToCstmrStmt".to_string()))?;
+    container
+        .children()
+        .filter(|node| matches!(node.tag_name().name(), "Stmt" | "Rpt"))
+        .map(statement)
+        .collect()
+}
+
+fn statement(node: Node<'_, '_>) -> Result<Message, ImportError> {
+    let account =
+        child(node, "Acct").ok_or_else(|| ImportError::MissingElement("Acct".to_string()))?;
+    let account_id = text(account, &["Id", "IBAN"])
+        .or_else(|| text(account, &["Id", "Othr", "Id"]))
+        .ok_or_else(|| ImportError::MissingElement("Acct/Id".to_string()))?;
+    let account_identifier_bic = text(account, &["Svcr", "FinInstnId", "BIC"])
+        .or_else(|| text(account, &["Svcr", "FinInstnId", "BICFI"]));
+    let statement_no = text(node, &["ElctrncSeqNb"])
+        .or_else(|| text(node, &["LglSeqNb"]))
+        .unwrap_or("1");
+
+    let mut opening_balance = None;
+    let mut closing_balance = None;
+    let mut closing_available_balance = None;
+    let mut forward_available_balances = vec![];
+    for node in children(node, "Bal") {
+        let code = required_text(node, &["Tp", "CdOrPrtry", "Cd"])?;
+        let balance = |is_intermediate| balance(node, is_intermediate);
+        match code {
+            "OPBD" | "PRCD" => opening_balance = Some(balance(false)?),
+            "ITBD" if opening_balance.is_none() => opening_balance = Some(balance(true)?),
+            "ITBD" => closing_balance = Some(balance(true)?),
+            "CLBD" => closing_balance = Some(balance(false)?),
+            "CLAV" => closing_available_balance = Some(available_balance(balance(false)?)),
+            "FWAV" => forward_available_balances.push(available_balance(balance(false)?)),
+            _ => (),
+        }
+    }
+
+    Ok(Message {
+        transaction_ref_no: required_text(node, &["Id"])?.to_string(),
+        ref_to_related_msg: None,
+        account_id: account_id.to_string(),
+        account_identifier_bic: account_identifier_bic.map(str::to_string),
+        statement_no: statement_no.to_string(),
+        sequence_no: None,
+        opening_balance: opening_balance
+            .ok_or_else(|| ImportError::MissingElement("Bal/OPBD".to_string()))?,
+        statement_lines: children(node, "Ntry")
+            .map(entry)
+            .collect::<Result<_, _>>()?,
+        closing_balance: closing_balance
+            .ok_or_else(|| ImportError::MissingElement("Bal/CLBD".to_string()))?,
+        closing_available_balance,
+        forward_available_balances,
+        information_to_account_owner: text(node, &["AddtlStmtInf"]).map(str::to_string),
+        information_lines: vec![],
+        non_swift_fields: vec![],
+        custom_fields: BTreeMap::new(),
+        raw: None,
+        source: None,
+    })
+}
+
+fn balance(node: Node<'_, '_>, is_intermediate: bool) -> Result<Balance, ImportError> {
+    let (debit_credit_indicator, iso_currency_code, amount) = amount(node)?;
+    Ok(Balance {
+        is_intermediate,
+        debit_credit_indicator,
+        date: date(node, "Dt")?,
+        iso_currency_code,
+        amount,
+    })
+}
+
+fn available_balance(balance: Balance) -> AvailableBalance {
+    AvailableBalance {
+        debit_credit_indicator: balance.debit_credit_indicator,
+        date: balance.date,
+        iso_currency_code: balance.iso_currency_code,
+        amount: balance.amount,
+    }
+}
+
+fn entry(node: Node<'_, '_>) -> Result<StatementLine, ImportError> {
+    let (debit_credit_indicator, _, amount) = amount(node)?;
+    let reversal = text(node, &["RvslInd"]) == Some("true");
+    let ext_debit_credit_indicator = match (debit_credit_indicator, reversal) {
+        (DebitOrCredit::Debit, false) => ExtDebitOrCredit::Debit,
+        (DebitOrCredit::Credit, false) => ExtDebitOrCredit::Credit,
+        (DebitOrCredit::Debit, true) => ExtDebitOrCredit::ReverseDebit,
+        (DebitOrCredit::Credit, true) => ExtDebitOrCredit::ReverseCredit,
+    };
+    let entry_date = match child(node, "BookgDt") {
+        Some(_) => Some(date(node, "BookgDt")?),
+        None => None,
+    };
+    let value_date = match (child(node, "ValDt"), entry_date) {
+        (None, Some(entry_date)) => entry_date,
+        _ => date(node, "ValDt")?,
+    };
+    let transaction_type_ident_code = text(node, &["BkTxCd", "Prtry", "Cd"])
+        .filter(|code| code.len() == 3 && code.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map_or(TransactionTypeIdentificationCode::MSC, |code| {
+            TransactionTypeIdentificationCode::from_str(code).unwrap_or_else(|_| {
+                TransactionTypeIdentificationCode::NonStandard(code.to_string())
+            })
+        });
+    let details = child(node, "NtryDtls").and_then(|details| child(details, "TxDtls"));
+    let customer_ref = details
+        .and_then(|details| text(details, &["Refs", "EndToEndId"]))
+        .unwrap_or("NONREF");
+    let information = details
+        .and_then(|details| child(details, "RmtInf"))
+        .map(|remittance| {
+            children(remittance, "Ustrd")
+                .filter_map(|node| node.text())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|information| !information.is_empty());
+
+    Ok(StatementLine {
+        value_date,
+        entry_date,
+        ext_debit_credit_indicator,
+        funds_code: None,
+        amount,
+        entry_type: EntryType::NonSwift,
+        transaction_type_ident_code,
+        customer_ref: customer_ref.to_string(),
+        bank_ref: text(node, &["AcctSvcrRef"]).map(str::to_string),
+        supplementary_details: None,
+        information_to_account_owner: information,
+        information_lines: vec![],
+        structured_information: None,
+        sepa_purpose: None,
+        raw: None,
+        index: None,
+    })
+}
+
+/// The `<Amt>` with its currency and the `<CdtDbtInd>` of `node`.
+fn amount(node: Node<'_, '_>) -> Result<(DebitOrCredit, String, Decimal), ImportError> {
+    let amount_node =
+        child(node, "Amt").ok_or_else(|| ImportError::MissingElement("Amt".to_string()))?;
+    let amount_text = amount_node.text().unwrap_or_default().trim();
+    let amount = Decimal::from_str(amount_text).map_err(|_| invalid("Amt", amount_text))?;
+    let currency = amount_node
+        .attribute("Ccy")
+        .ok_or_else(|| ImportError::MissingElement("Amt/@Ccy".to_string()))?;
+    let debit_credit_indicator = match required_text(node, &["CdtDbtInd"])? {
+        "CRDT" => DebitOrCredit::Credit,
+        "DBIT" => DebitOrCredit::Debit,
+        indicator => return Err(invalid("CdtDbtInd", indicator)),
+    };
+    Ok((debit_credit_indicator, currency.to_string(), amount))
+}
+
+/// The date in the `<Dt>` or `<DtTm>` within the element `name` of `node`.
+fn date(node: Node<'_, '_>, name: &str) -> Result<NaiveDate, ImportError> {
+    let value = text(node, &[name, "Dt"])
+        .or_else(|| text(node, &[name, "DtTm"]))
+        .ok_or_else(|| ImportError::MissingElement(format!("{}/Dt", name)))?;
+    value
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(|| invalid(name, value))
+}
+
+fn invalid(element: &str, value: &str) -> ImportError {
+    ImportError::InvalidValue {
+        element: element.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// The first child element of `node` with the local name `name`.
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// All child elements of `node` with the local name `name`.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// The trimmed text of the element at `path` below `node`.
+fn text<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(node, |node, name| child(node, name))
+        .and_then(|node| node.text())
+        .map(str::trim)
+}
+
+fn required_text<'a>(node: Node<'a, '_>, path: &[&str]) -> Result<&'a str, ImportError> {
+    text(node, path).ok_or_else(|| ImportError::MissingElement(path.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::export::camt053::to_camt053;
+    use crate::parse_mt940;
+
+    #[test]
+    fn export_round_trips() {
+        let input = "\
+            :20:ref\r\n\
+            :25P:NL91ABNA0417164300\r\n\
+            ABNANL2A\r\n\
+            :28C:7\r\n\
+            :60M:D090924EUR1,\r\n\
+            :61:0909250924RD2,NTRFNONREF//bank\r\n\
+            :86:first\r\n\
+            second\r\n\
+            :61:090925C3,5NCHGNONREF\r\n\
+            :62M:C090925EUR4,5\r\n\
+            :64:C090925EUR4,5\r\n\
+            :65:C090926EUR4,5\r\n\
+            :86:statement info\r\n";
+        let messages = parse_mt940(input).unwrap();
+        assert_eq!(from_camt053(&to_camt053(&messages)).unwrap(), messages);
+    }
+
+    #[test]
+    fn camt052_report() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.052.001.08">
+  <BkToCstmrAcctRpt>
+    <Rpt>
+      <Id>report</Id>
+      <Acct><Id><Othr><Id>12345678</Id></Othr></Id></Acct>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>PRCD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="CHF">10.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><DtTm>2023-05-01T08:00:00</DtTm></Dt>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="CHF">7.50</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2023-05-02</Dt></Dt>
+      </Bal>
+      <Ntry>
+        <Amt Ccy="CHF">2.50</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <BookgDt><Dt>2023-05-02</Dt></BookgDt>
+        <BkTxCd><Domn><Cd>PMNT</Cd></Domn></BkTxCd>
+        <NtryDtls><TxDtls><Refs><EndToEndId>E2E</EndToEndId></Refs></TxDtls></NtryDtls>
+      </Ntry>
+    </Rpt>
+  </BkToCstmrAcctRpt>
+</Document>"#;
+        let messages = from_camt053(xml).unwrap();
+        let message = &messages[0];
+        assert_eq!(message.account_id, "12345678");
+        assert_eq!(message.statement_no, "1");
+        assert_eq!(
+            message.opening_balance.date,
+            NaiveDate::from_ymd_opt(2023, 5, 1).unwrap()
+        );
+        let statement_line = &message.statement_lines[0];
+        assert_eq!(
+            statement_line.value_date,
+            statement_line.entry_date.unwrap()
+        );
+        assert_eq!(statement_line.signed_amount(), Decimal::new(-250, 2));
+        assert_eq!(
+            statement_line.transaction_type_ident_code,
+            TransactionTypeIdentificationCode::MSC
+        );
+        assert_eq!(statement_line.customer_ref, "E2E");
+    }
+
+    #[test]
+    fn invalid_documents() {
+        assert!(matches!(
+            from_camt053("<Document>"),
+            Err(ImportError::Xml(_))
+        ));
+        assert_eq!(
+            from_camt053("<Document><Other/></Document>"),
+            Err(ImportError::MissingElement("BkToCstmrStmt".to_string()))
+        );
+        let xml = "<Document><BkToCstmrStmt><Stmt><Id>a</Id><Acct><Id><IBAN>x</IBAN></Id></Acct>\
+            <Bal><Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp><Amt Ccy=\"EUR\">1,00</Amt>\
+            <CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2023-05-01</Dt></Dt></Bal>\
+            </Stmt></BkToCstmrStmt></Document>";
+        assert_eq!(
+            from_camt053(xml),
+            Err(ImportError::InvalidValue {
+                element: "Amt".to_string(),
+                value: "1,00".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Conversion of statements in other formats into [`Message`](crate::Message)s.
+//!
+//! Each format is behind a feature flag of the same name as its counterpart in
+//! [`export`](crate::export).
+
+#[cfg(feature = "iso20022")]
+pub mod camt053;
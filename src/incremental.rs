@@ -0,0 +1,256 @@
+//! Keeping a statement parsed while it is being edited.
+
+use std::ops::Range;
+
+use crate::streaming::{count_lines, MessageBlocks};
+use crate::{Message, ParseError};
+
+/// A message of an [`IncrementalParser`] along with where it is in the statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedMessage {
+    /// The bytes of the statement this message spans, up to the start of the next message.
+    pub range: Range<usize>,
+
+    /// Line the message starts at, starting at 1.
+    pub line: usize,
+
+    /// The message or why it couldn't be parsed.
+    pub result: Result<Message, ParseError>,
+}
+
+impl ParsedMessage {
+    /// Move this message by `offset` bytes and `lines` lines after text in front of it was
+    /// edited.
+    fn move_by(&mut self, offset: isize, lines: isize) {
+        self.range = self.range.start.saturating_add_signed(offset)
+            ..self.range.end.saturating_add_signed(offset);
+        self.line = self.line.saturating_add_signed(lines);
+        if let Err(ref mut err) = self.result {
+            *err = err.clone().map_span(|span| span.moved(offset, lines));
+        }
+    }
+}
+
+/// A MT940 statement which is kept parsed while it is being edited.
+///
+/// Messages are parsed like [`parse_mt940_iter`] does. After an [edit](IncrementalParser::edit)
+/// only the messages touched by it are parsed again, which keeps editors responsive even for
+/// large statements.
+///
+/// [`parse_mt940_iter`]: crate::parse_mt940_iter
+///
+/// # Example
+/// ```
+/// use mt940::IncrementalParser;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n";
+///
+/// let mut parser = IncrementalParser::new(input.repeat(3));
+/// assert!(parser.messages().iter().all(|message| message.result.is_ok()));
+///
+/// // Break the opening balance of the second message.
+/// let second = parser.messages()[1].range.clone();
+/// let start = second.start + input.find("EUR").unwrap();
+/// assert_eq!(parser.edit(start..start + 3, "EURO"), Some(0..2));
+///
+/// let err = parser.messages()[1].result.as_ref().unwrap_err();
+/// assert_eq!(err.span().unwrap().line, 9);
+/// assert!(parser.messages()[2].result.is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalParser {
+    statement: String,
+    preamble_error: Option<ParseError>,
+    messages: Vec<ParsedMessage>,
+}
+
+impl IncrementalParser {
+    pub fn new(statement: impl Into<String>) -> IncrementalParser {
+        let statement = statement.into();
+        let (preamble_error, messages) = parse_region(&statement, 0..statement.len(), 0);
+        IncrementalParser {
+            statement,
+            preamble_error,
+            messages,
+        }
+    }
+
+    /// The statement as of the last edit.
+    pub fn statement(&self) -> &str {
+        &self.statement
+    }
+
+    /// The messages of the statement in order.
+    pub fn messages(&self) -> &[ParsedMessage] {
+        &self.messages
+    }
+
+    /// What is wrong with the stuff in front of the first message, or that there is no message.
+    pub fn preamble_error(&self) -> Option<&ParseError> {
+        self.preamble_error.as_ref()
+    }
+
+    /// Replace `range` of the statement by `replacement` and parse the affected messages again.
+    ///
+    /// Returns the indices of the messages in [`IncrementalParser::messages`] which were parsed
+    /// again. Returns `None` without changing anything if `range` isn't within the statement or
+    /// doesn't start and end at character boundaries.
+    pub fn edit(&mut self, range: Range<usize>, replacement: &str) -> Option<Range<usize>> {
+        let removed = self.statement.get(range.clone())?;
+        let offset_delta = replacement.len() as isize - removed.len() as isize;
+        let lines_delta = count_lines(replacement) as isize - count_lines(removed) as isize;
+
+        let containing = |pos: usize| {
+            self.messages
+                .iter()
+                .rposition(|message| message.range.start <= pos)
+        };
+        // Messages start with a line with tag `:20:`. Editing that line may join a message with
+        // the previous one, so the previous one is parsed again as well.
+        let first = containing(range.start).map_or(0, |i| i.saturating_sub(1));
+        let mut end = containing(range.end).map_or(0, |i| i + 1);
+        let (region_start, lines) = match (first, self.messages.get(first)) {
+            (1.., Some(message)) => (message.range.start, message.line - 1),
+            _ => (0, 0),
+        };
+
+        self.statement.replace_range(range, replacement);
+        let parsed = loop {
+            let region_end = self
+                .messages
+                .get(end)
+                .map_or(self.statement.len(), |message| {
+                    message.range.start.saturating_add_signed(offset_delta)
+                });
+            let (preamble_error, parsed) =
+                parse_region(&self.statement, region_start..region_end, lines);
+            if region_start > 0 {
+                break parsed;
+            }
+            if parsed.is_empty() && end < self.messages.len() {
+                // Nothing in the region starts a message anymore so all of it is in front of
+                // the next message.
+                end += 1;
+                continue;
+            }
+            self.preamble_error = preamble_error;
+            break parsed;
+        };
+        for message in self.messages.iter_mut().skip(end) {
+            message.move_by(offset_delta, lines_delta);
+        }
+        let parsed_count = parsed.len();
+        self.messages.splice(first..end, parsed);
+        Some(first..first + parsed_count)
+    }
+}
+
+/// Parse the messages in `region` of `statement` which is preceded by `lines` lines.
+///
+/// `region` has to start at the beginning of the statement or of a message and end at the end of
+/// the statement or the beginning of a message.
+fn parse_region(
+    statement: &str,
+    region: Range<usize>,
+    lines: usize,
+) -> (Option<ParseError>, Vec<ParsedMessage>) {
+    let text = statement.get(region.clone()).unwrap_or_default();
+    let mut preamble_error = None;
+    let mut messages = vec![];
+    for block in MessageBlocks::new(text) {
+        let block = match block {
+            Ok(block) => block,
+            Err(err) => {
+                preamble_error = Some(err.shifted(region.start, lines));
+                continue;
+            }
+        };
+        let range = block.range();
+        messages.push(ParsedMessage {
+            range: range.start + region.start..range.end + region.start,
+            line: block.line() + lines,
+            result: block
+                .parse_fields()
+                .and_then(Message::from_fields)
+                .map_err(|err| err.shifted(region.start, lines)),
+        });
+    }
+    (preamble_error, messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::*;
+
+    const MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:0909250925D1,NMSCref\r\n\
+        :86:info\r\n\
+        :62F:C090924EUR0,\r\n";
+
+    fn assert_same_as_new(parser: &IncrementalParser) {
+        let expected = IncrementalParser::new(parser.statement());
+        assert_eq!(parser.messages(), expected.messages());
+        assert_eq!(parser.preamble_error(), expected.preamble_error());
+    }
+
+    #[test]
+    fn edits() {
+        let mut parser = IncrementalParser::new(MESSAGE.repeat(3));
+        assert_eq!(parser.messages().len(), 3);
+
+        // Join the second message with the first one.
+        let second = parser.messages()[1].range.start;
+        assert_eq!(parser.edit(second..second + 1, ""), Some(0..1));
+        assert_eq!(parser.messages().len(), 2);
+        assert_same_as_new(&parser);
+
+        // Split them again.
+        assert_eq!(parser.edit(second..second, ":"), Some(0..2));
+        assert_eq!(parser.messages().len(), 3);
+        assert_same_as_new(&parser);
+
+        // Add stuff in front.
+        assert_eq!(parser.edit(0..0, ":25:x\r\n"), Some(0..1));
+        assert!(parser.preamble_error().is_some());
+        assert_same_as_new(&parser);
+
+        let len = parser.statement().len();
+        assert_eq!(parser.edit(len..len + 1, "x"), None);
+        assert_eq!(parser.edit(0..parser.statement().len(), ""), Some(0..0));
+        assert!(parser.messages().is_empty());
+        assert_same_as_new(&parser);
+    }
+
+    proptest! {
+        #[test]
+        fn same_as_parsing_from_scratch(
+            edits in proptest::collection::vec(
+                (0..200usize, 0..20usize, "(:20:|:25:|\r\n|-|[a-c1])*"),
+                1..5,
+            ),
+        ) {
+            let mut parser = IncrementalParser::new(MESSAGE.repeat(3));
+            for (start, len, replacement) in edits {
+                let start = start.min(parser.statement().len());
+                let end = (start + len).min(parser.statement().len());
+                parser.edit(start..end, &replacement);
+
+                let expected = IncrementalParser::new(parser.statement());
+                prop_assert_eq!(parser.messages(), expected.messages());
+                prop_assert_eq!(parser.preamble_error(), expected.preamble_error());
+            }
+        }
+    }
+}
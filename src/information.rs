@@ -0,0 +1,43 @@
+//! Structured contents of tag `:86:` as selected by [`ParserConfig::information_format`].
+//!
+//! [`ParserConfig::information_format`]: crate::ParserConfig::information_format
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{DutchInformation, InformationFormat, NordicInformation};
+
+/// The contents of tag `:86:` of a [`StatementLine`] broken down according to the
+/// [`InformationFormat`] the statement was parsed with.
+///
+/// [`StatementLine`]: crate::StatementLine
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum StructuredInformation {
+    Nordic(NordicInformation),
+    Dutch(DutchInformation),
+}
+
+impl StructuredInformation {
+    /// Break `info` down according to `format`.
+    ///
+    /// Returns `None` if `info` doesn't contain anything in that format.
+    pub fn parse(format: InformationFormat, info: &str) -> Option<StructuredInformation> {
+        match format {
+            InformationFormat::Nordic => {
+                NordicInformation::from_information(info).map(StructuredInformation::Nordic)
+            }
+            InformationFormat::Dutch => {
+                DutchInformation::from_information(info).map(StructuredInformation::Dutch)
+            }
+        }
+    }
+
+    /// The [`InformationFormat`] this was parsed with.
+    pub(crate) fn format(&self) -> InformationFormat {
+        match self {
+            StructuredInformation::Nordic(_) => InformationFormat::Nordic,
+            StructuredInformation::Dutch(_) => InformationFormat::Dutch,
+        }
+    }
+}
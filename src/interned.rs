@@ -0,0 +1,358 @@
+//! Messages which share the text of fields that tend to repeat across a statement.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_derive::Serialize;
+
+use crate::{
+    parse_mt940_iter, AvailableBalance, Balance, DebitOrCredit, EntryType, ExtDebitOrCredit, Field,
+    Message, MessageSource, ParseError, SepaPurpose, StatementLine, StructuredInformation,
+    TransactionTypeIdentificationCode,
+};
+
+/// A cache handing out a single shared copy of every distinct string.
+///
+/// An [`Interner`] may be reused for several statements so that e.g. the account id is only kept
+/// once for all of them. Strings are only freed once the [`Interner`] and everything interned
+/// with it is dropped.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use mt940::Interner;
+///
+/// let mut interner = Interner::new();
+/// let eur = interner.intern("EUR");
+/// assert!(Arc::ptr_eq(&eur, &interner.intern("EUR")));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// The shared copy of `s`.
+    pub fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.insert(Arc::clone(&interned));
+        interned
+    }
+
+    /// The number of distinct strings interned so far.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Turn `message` into an [`InternedMessage`] sharing its repetitive fields.
+    pub fn message(&mut self, message: Message) -> InternedMessage {
+        InternedMessage {
+            transaction_ref_no: message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg.map(|s| self.intern(&s)),
+            account_id: self.intern(&message.account_id),
+            account_identifier_bic: message.account_identifier_bic.map(|s| self.intern(&s)),
+            statement_no: message.statement_no,
+            sequence_no: message.sequence_no,
+            opening_balance: self.balance(message.opening_balance),
+            statement_lines: message
+                .statement_lines
+                .into_iter()
+                .map(|line| self.statement_line(line))
+                .collect(),
+            closing_balance: self.balance(message.closing_balance),
+            closing_available_balance: message
+                .closing_available_balance
+                .map(|balance| self.available_balance(balance)),
+            forward_available_balances: message
+                .forward_available_balances
+                .into_iter()
+                .map(|balance| self.available_balance(balance))
+                .collect(),
+            information_to_account_owner: message.information_to_account_owner,
+            information_lines: message.information_lines,
+            non_swift_fields: message.non_swift_fields,
+            custom_fields: message.custom_fields,
+            raw: message.raw,
+            source: message.source,
+        }
+    }
+
+    fn statement_line(&mut self, line: StatementLine) -> InternedStatementLine {
+        InternedStatementLine {
+            value_date: line.value_date,
+            entry_date: line.entry_date,
+            ext_debit_credit_indicator: line.ext_debit_credit_indicator,
+            funds_code: line.funds_code.map(|s| self.intern(&s)),
+            amount: line.amount,
+            entry_type: line.entry_type,
+            transaction_type_ident_code: line.transaction_type_ident_code,
+            customer_ref: self.intern(&line.customer_ref),
+            bank_ref: line.bank_ref.map(|s| self.intern(&s)),
+            supplementary_details: line.supplementary_details,
+            information_to_account_owner: line.information_to_account_owner,
+            information_lines: line.information_lines,
+            structured_information: line.structured_information,
+            sepa_purpose: line.sepa_purpose,
+            raw: line.raw,
+            index: line.index,
+        }
+    }
+
+    fn balance(&mut self, balance: Balance) -> InternedBalance {
+        InternedBalance {
+            is_intermediate: balance.is_intermediate,
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: self.intern(&balance.iso_currency_code),
+            amount: balance.amount,
+        }
+    }
+
+    fn available_balance(&mut self, balance: AvailableBalance) -> InternedAvailableBalance {
+        InternedAvailableBalance {
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: self.intern(&balance.iso_currency_code),
+            amount: balance.amount,
+        }
+    }
+}
+
+/// A [`Message`] whose account, references and currency codes are shared via an [`Interner`].
+///
+/// Free text such as tag `:86:` is rarely repeated and kept as it is.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct InternedMessage {
+    pub transaction_ref_no: String,
+    pub ref_to_related_msg: Option<Arc<str>>,
+    pub account_id: Arc<str>,
+    pub account_identifier_bic: Option<Arc<str>>,
+    pub statement_no: String,
+    pub sequence_no: Option<String>,
+    pub opening_balance: InternedBalance,
+    pub statement_lines: Vec<InternedStatementLine>,
+    pub closing_balance: InternedBalance,
+    pub closing_available_balance: Option<InternedAvailableBalance>,
+    pub forward_available_balances: Vec<InternedAvailableBalance>,
+    pub information_to_account_owner: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub non_swift_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<MessageSource>,
+}
+
+/// A [`StatementLine`] whose currency and references are shared via an [`Interner`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct InternedStatementLine {
+    pub value_date: NaiveDate,
+    pub entry_date: Option<NaiveDate>,
+    pub ext_debit_credit_indicator: ExtDebitOrCredit,
+    pub funds_code: Option<Arc<str>>,
+    pub amount: Decimal,
+    pub entry_type: EntryType,
+    pub transaction_type_ident_code: TransactionTypeIdentificationCode,
+    pub customer_ref: Arc<str>,
+    pub bank_ref: Option<Arc<str>>,
+    pub supplementary_details: Option<String>,
+    pub information_to_account_owner: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_information: Option<StructuredInformation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sepa_purpose: Option<SepaPurpose>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+}
+
+/// A [`Balance`] whose currency code is shared via an [`Interner`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct InternedBalance {
+    pub is_intermediate: bool,
+    pub debit_credit_indicator: DebitOrCredit,
+    pub date: NaiveDate,
+    pub iso_currency_code: Arc<str>,
+    pub amount: Decimal,
+}
+
+/// An [`AvailableBalance`] whose currency code is shared via an [`Interner`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct InternedAvailableBalance {
+    pub debit_credit_indicator: DebitOrCredit,
+    pub date: NaiveDate,
+    pub iso_currency_code: Arc<str>,
+    pub amount: Decimal,
+}
+
+impl From<InternedMessage> for Message {
+    fn from(message: InternedMessage) -> Message {
+        Message {
+            transaction_ref_no: message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg.map(|s| s.to_string()),
+            account_id: message.account_id.to_string(),
+            account_identifier_bic: message.account_identifier_bic.map(|s| s.to_string()),
+            statement_no: message.statement_no,
+            sequence_no: message.sequence_no,
+            opening_balance: message.opening_balance.into(),
+            statement_lines: message
+                .statement_lines
+                .into_iter()
+                .map(StatementLine::from)
+                .collect(),
+            closing_balance: message.closing_balance.into(),
+            closing_available_balance: message.closing_available_balance.map(Into::into),
+            forward_available_balances: message
+                .forward_available_balances
+                .into_iter()
+                .map(AvailableBalance::from)
+                .collect(),
+            information_to_account_owner: message.information_to_account_owner,
+            information_lines: message.information_lines,
+            non_swift_fields: message.non_swift_fields,
+            custom_fields: message.custom_fields,
+            raw: message.raw,
+            source: message.source,
+        }
+    }
+}
+
+impl From<InternedStatementLine> for StatementLine {
+    fn from(line: InternedStatementLine) -> StatementLine {
+        StatementLine {
+            value_date: line.value_date,
+            entry_date: line.entry_date,
+            ext_debit_credit_indicator: line.ext_debit_credit_indicator,
+            funds_code: line.funds_code.map(|s| s.to_string()),
+            amount: line.amount,
+            entry_type: line.entry_type,
+            transaction_type_ident_code: line.transaction_type_ident_code,
+            customer_ref: line.customer_ref.to_string(),
+            bank_ref: line.bank_ref.map(|s| s.to_string()),
+            supplementary_details: line.supplementary_details,
+            information_to_account_owner: line.information_to_account_owner,
+            information_lines: line.information_lines,
+            structured_information: line.structured_information,
+            sepa_purpose: line.sepa_purpose,
+            raw: line.raw,
+            index: line.index,
+        }
+    }
+}
+
+impl From<InternedBalance> for Balance {
+    fn from(balance: InternedBalance) -> Balance {
+        Balance {
+            is_intermediate: balance.is_intermediate,
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code.to_string(),
+            amount: balance.amount,
+        }
+    }
+}
+
+impl From<InternedAvailableBalance> for AvailableBalance {
+    fn from(balance: InternedAvailableBalance) -> AvailableBalance {
+        AvailableBalance {
+            debit_credit_indicator: balance.debit_credit_indicator,
+            date: balance.date,
+            iso_currency_code: balance.iso_currency_code.to_string(),
+            amount: balance.amount,
+        }
+    }
+}
+
+/// Parse and validate a MT940 statement into [`InternedMessage`]s.
+///
+/// This saves memory for large statements as the account, references like `NONREF` and currency
+/// codes are kept once per `interner` instead of once per message or statement line. Messages are
+/// parsed one at a time, so only a single [`Message`] is fully allocated at any time.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use mt940::{parse_mt940_interned, Interner};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSCNONREF\r\n\
+///     :61:0909250925DR583,92NMSCNONREF\r\n\
+///     :62F:C090930EUR53317,20\r\n";
+///
+/// let mut interner = Interner::new();
+/// let messages = parse_mt940_interned(input, &mut interner).unwrap();
+/// let lines = &messages[0].statement_lines;
+/// assert!(Arc::ptr_eq(&lines[0].customer_ref, &lines[1].customer_ref));
+/// ```
+pub fn parse_mt940_interned(
+    statement: &str,
+    interner: &mut Interner,
+) -> Result<Vec<InternedMessage>, ParseError> {
+    parse_mt940_iter(statement)
+        .map(|message| message.map(|message| interner.message(message)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn same_as_parse_mt940() {
+        let message = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR2,\r\n\
+            :61:0909250925D1,NMSCNONREF//ref\r\n\
+            :86:info\r\n\
+            :61:0909250925D1,NMSCNONREF\r\n\
+            :62F:C090924EUR0,\r\n\
+            :64:C090924EUR0,\r\n";
+        let input = message.repeat(3);
+        let expected = parse_mt940(&input).unwrap();
+
+        let mut interner = Interner::new();
+        let interned = parse_mt940_interned(&input, &mut interner).unwrap();
+        assert_eq!(interner.len(), 4);
+        let (first, last) = (&interned[0], &interned[2]);
+        assert!(Arc::ptr_eq(&first.account_id, &last.account_id));
+        assert!(Arc::ptr_eq(
+            &first.statement_lines[0].customer_ref,
+            &last.statement_lines[1].customer_ref
+        ));
+
+        let messages = interned.into_iter().map(Message::from).collect::<Vec<_>>();
+        assert_eq!(messages, expected);
+    }
+}
@@ -0,0 +1,244 @@
+//! Parsing of MT940 statements which carries on in the face of errors.
+
+use crate::streaming::MessageBlocks;
+use crate::{Message, MessageSource, ParseError, ParserConfig};
+
+/// The result of [`parse_mt940_lenient`] and [`parse_mt940_partial`].
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ParseOutcome {
+    /// All [`Message`]s which could be parsed.
+    ///
+    /// Malformed optional tags are missing from these.
+    pub messages: Vec<Message>,
+
+    /// All errors encountered along the way in the order of their occurrence.
+    pub errors: Vec<ParseError>,
+}
+
+impl ParseOutcome {
+    /// Whether the statement was parsed without any errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Parse a MT940 statement while collecting errors instead of failing on the first one.
+///
+/// Other than [`parse_mt940`], this tries to salvage as much of the statement as possible:
+///
+/// - A malformed `:61:` is skipped together with its `:86:` tags.
+/// - A malformed `:21:`, `:86:`, `:64:` or `:65:` is skipped.
+/// - A message with a malformed or missing mandatory tag or with unknown or misplaced tags is
+///   skipped as a whole.
+///
+/// Every problem is recorded in [`ParseOutcome::errors`].
+///
+/// [`parse_mt940`]: crate::parse_mt940
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_lenient;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:11100304030101391234\r\n\
+///     :61:0913010930DR62,60NCHGcustomer id//bank id\r\n\
+///     :86:Fees according to advice\r\n\
+///     :62F:C090930EUR53126,94\r\n";
+///
+/// let outcome = parse_mt940_lenient(input);
+/// assert_eq!(outcome.messages[0].statement_lines.len(), 1);
+/// assert_eq!(outcome.errors.len(), 1);
+/// ```
+pub fn parse_mt940_lenient(statement: &str) -> ParseOutcome {
+    let mut outcome = ParseOutcome::default();
+
+    for block in MessageBlocks::new(statement) {
+        let message = block
+            .and_then(|block| block.parse_fields())
+            .and_then(|fields| {
+                Message::from_fields_with_config(
+                    fields,
+                    &ParserConfig::default(),
+                    Some(&mut outcome.errors),
+                )
+            });
+        match message {
+            Ok(message) => outcome.messages.push(message),
+            Err(err) => outcome.errors.push(err),
+        }
+    }
+
+    outcome
+}
+
+/// Parse a MT940 statement message by message and keep the messages which are valid.
+///
+/// Every message is parsed exactly like [`parse_mt940`] would, but a broken message doesn't
+/// discard the others. Its error is recorded in [`ParseOutcome::errors`] along with the
+/// [`MessageSource`] of the message. Unlike [`parse_mt940_lenient`], a message is either valid as
+/// a whole or left out.
+///
+/// [`parse_mt940`]: crate::parse_mt940
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_partial;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0913010930DR62,60NCHGcustomer id//bank id\r\n\
+///     :62F:C090930EUR54421,44\r\n\
+///     :20:3996-11-11111112\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00002/001\r\n\
+///     :60F:C090930EUR54421,44\r\n\
+///     :62F:C090930EUR54421,44\r\n";
+///
+/// let outcome = parse_mt940_partial(input);
+/// assert_eq!(outcome.messages.len(), 1);
+/// assert_eq!(outcome.messages[0].statement_no, "00002");
+/// assert_eq!(outcome.errors[0].message_source().unwrap().index, 0);
+/// ```
+pub fn parse_mt940_partial(statement: &str) -> ParseOutcome {
+    let mut outcome = ParseOutcome::default();
+
+    let mut index = 0;
+    for block in MessageBlocks::new(statement) {
+        let block = match block {
+            Ok(block) => block,
+            Err(err) => {
+                // Problems in front of the first message don't belong to any message.
+                outcome.errors.push(err);
+                continue;
+            }
+        };
+        let source = MessageSource {
+            label: None,
+            index,
+            line: Some(block.line()),
+        };
+        index += 1;
+        match block.parse_fields().and_then(Message::from_fields) {
+            Ok(message) => outcome.messages.push(message),
+            Err(err) => outcome.errors.push(err.in_message(source)),
+        }
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940, DateParseError, ErrorLocation, RequiredTagNotFoundError};
+
+    const MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:0909250925DR1,NMSCref\r\n\
+        :86:info\r\n\
+        :62F:C090924EUR1,\r\n";
+
+    #[test]
+    fn same_as_parse_mt940_if_valid() {
+        let input = MESSAGE.repeat(3);
+        let outcome = parse_mt940_lenient(&input);
+        assert!(outcome.is_ok());
+        assert_eq!(outcome.messages, parse_mt940(&input).unwrap());
+    }
+
+    #[test]
+    fn skips_broken_statement_line() {
+        let broken = MESSAGE.replace(":61:0909250925", ":61:0913250925");
+        let input = format!("{}{}", MESSAGE, broken);
+        let outcome = parse_mt940_lenient(&input);
+        assert_eq!(outcome.messages.len(), 2);
+        assert_eq!(outcome.messages[0].statement_lines.len(), 1);
+        assert_eq!(outcome.messages[1].statement_lines.len(), 0);
+        assert_eq!(outcome.messages[1].information_to_account_owner, None);
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(
+            outcome.errors[0],
+            ParseError::DateParseError(
+                Box::new(DateParseError::OutOfRange {
+                    year: "2009".to_string(),
+                    month: "13".to_string(),
+                    day: "25".to_string(),
+                }),
+                ErrorLocation::default()
+            )
+        );
+        let span = outcome.errors[0].span().unwrap();
+        assert_eq!(span.line, MESSAGE.lines().count() + 5);
+        assert_eq!(span.column, 1);
+        assert!(span.snippet(&input).unwrap().starts_with(":61:0913250925"));
+    }
+
+    #[test]
+    fn skips_broken_message() {
+        let broken = MESSAGE.replace(":62F:C090924EUR1,\r\n", "");
+        let input = format!("{}{}{}", MESSAGE, broken, MESSAGE);
+        let outcome = parse_mt940_lenient(&input);
+        assert_eq!(outcome.messages.len(), 2);
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(
+            outcome.errors[0],
+            ParseError::from(RequiredTagNotFoundError::new("62"))
+        );
+        assert_eq!(
+            outcome.errors[0].span().map(|span| span.line),
+            Some(MESSAGE.lines().count() + 1)
+        );
+    }
+
+    #[test]
+    fn partial_keeps_valid_messages() {
+        let broken = MESSAGE.replace(":61:0909250925", ":61:0913250925");
+        let input = format!("{}{}{}", MESSAGE, broken, MESSAGE);
+        let outcome = parse_mt940_partial(&input);
+        assert_eq!(outcome.messages.len(), 2);
+        assert!(outcome
+            .messages
+            .iter()
+            .all(|message| message.statement_lines.len() == 1));
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(
+            outcome.errors[0].message_source(),
+            Some(&MessageSource {
+                label: None,
+                index: 1,
+                line: Some(MESSAGE.lines().count() + 1),
+            })
+        );
+        assert_eq!(
+            outcome.errors[0].span().map(|span| span.line),
+            Some(MESSAGE.lines().count() + 5)
+        );
+
+        let outcome = parse_mt940_partial(&MESSAGE.repeat(2));
+        assert!(outcome.is_ok());
+        assert_eq!(outcome.messages, parse_mt940(&MESSAGE.repeat(2)).unwrap());
+    }
+
+    #[test]
+    fn no_messages() {
+        let outcome = parse_mt940_lenient("");
+        assert!(outcome.messages.is_empty());
+        assert_eq!(
+            outcome.errors,
+            vec![RequiredTagNotFoundError::new("20").into()]
+        );
+    }
+}
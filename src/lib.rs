@@ -53,29 +53,168 @@
 //! assert_eq!(input_parsed[0].transaction_ref_no, "ao...hallo...");
 //! ```
 
+// We parse untrusted input so we must never panic on it. Any failure has to be surfaced as a
+// `ParseError` instead.
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::panic,
+        clippy::indexing_slicing,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::unreachable
+    )
+)]
+
+mod account;
+mod anonymize;
+mod balance;
+mod borrowed;
+mod builder;
+mod config;
+mod continuity;
+mod counterparty;
+#[cfg(feature = "iso4217")]
+mod currency;
+mod dedup;
+mod diagnostics;
+mod diff;
+mod dutch;
+mod encoding;
 mod errors;
+pub mod export;
+#[cfg(feature = "arbitrary")]
+mod generator;
+mod german;
+mod grouping;
+pub mod import;
+mod incremental;
+mod information;
+mod interned;
+mod lenient;
+mod lint;
+mod merge;
+mod minor_units;
+mod mt942;
+mod nordic;
+#[cfg(feature = "python")]
+pub mod python;
+mod query;
+mod reference;
+pub mod report;
 pub mod sanitizers;
+mod scanner;
+#[cfg(feature = "schemars")]
+mod schema;
+mod sepa;
+mod source;
+mod span;
+mod streaming;
+mod summary;
+mod swift;
+mod tag;
 mod tag_parsers;
+#[cfg(feature = "time")]
+mod time_conversion;
 mod transaction_types;
 mod utils;
+mod validation;
+mod values;
+mod verbose;
+mod versioned;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod writer;
 
 use chrono::prelude::*;
-use log::debug;
+use pest::error::ErrorVariant;
+use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
 use rust_decimal::Decimal;
 use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
+pub use crate::account::{is_valid_iban, AccountId};
+pub use crate::anonymize::{anonymize, AnonymizeOptions};
+pub use crate::balance::{BalanceInfo, BalanceKind, BalanceStatus};
+pub use crate::borrowed::{
+    parse_mt940_borrowed, parse_mt940_borrowed_with_config, MessageRef, StatementLineRef,
+};
+pub use crate::builder::{MessageBuilder, StatementBuilder, StatementLineBuilder};
+pub use crate::config::{
+    Dialect, DuplicateTransactionRef, InformationFormat, NonSwiftTags, ParserConfig, TagHandler,
+};
+pub use crate::continuity::{check_continuity, ContinuityFinding};
+pub use crate::counterparty::Counterparty;
+#[cfg(feature = "iso4217")]
+pub use crate::currency::is_iso4217_currency;
+pub use crate::dedup::dedup_messages;
+pub use crate::diagnostics::{diagnose_mt940, Diagnostic, Severity};
+pub use crate::diff::{diff_messages, MessageDiff, StatementDiff};
+pub use crate::dutch::DutchInformation;
+#[cfg(feature = "encoding")]
+pub use crate::encoding::detect_encoding;
+pub use crate::encoding::{parse_mt940_bytes, Encoding};
 pub use crate::errors::{
-    DateParseError, ParseError, RequiredTagNotFoundError, UnexpectedTagError, VariantNotFound,
+    BuildError, DateParseError, DuplicateTagError, ErrorLocation, FieldLengthError, ImportError,
+    MergeError, ParseError, RequiredTagNotFoundError, TrailerValidationError, UnexpectedTagError,
+    VariantNotFound,
+};
+#[cfg(feature = "arbitrary")]
+pub use crate::generator::ArbitraryStatement;
+pub use crate::german::StructuredInformationToAccountOwner;
+pub use crate::grouping::{
+    group_by_account, sort_by_statement_number, statement_number_gaps, AccountKey, StatementGap,
 };
 
-use crate::tag_parsers::{
-    parse_20_tag, parse_21_tag, parse_25_tag, parse_28_tag, parse_60_tag, parse_61_tag,
-    parse_62_tag, parse_64_tag, parse_65_tag, parse_86_tag,
+use crate::borrowed::FieldRef;
+pub use crate::incremental::{IncrementalParser, ParsedMessage};
+pub use crate::information::StructuredInformation;
+pub use crate::interned::{
+    parse_mt940_interned, InternedAvailableBalance, InternedBalance, InternedMessage,
+    InternedStatementLine, Interner,
+};
+pub use crate::lenient::{parse_mt940_lenient, parse_mt940_partial, ParseOutcome};
+pub use crate::lint::{lint, LintFinding, LintKind};
+pub use crate::merge::{merge_messages, CombinedStatement};
+pub use crate::minor_units::{currency_exponent, to_minor_units};
+pub use crate::mt942::{parse_mt942, EntriesSummary, FloorLimitIndicator, InterimMessage};
+pub use crate::nordic::NordicInformation;
+pub use crate::query::{TransactionQuery, Transactions};
+pub use crate::reference::Reference;
+use crate::sanitizers::{
+    is_known_tag, strip_stuff_between_messages_reporting, swift_charset_reporting, LineMap,
+    LineStarts, SwiftCharsetOptions,
 };
+use crate::scanner::scan_field_refs;
+#[cfg(feature = "schemars")]
+pub use crate::schema::json_schema;
+pub use crate::sepa::SepaPurpose;
+pub use crate::source::MessageSource;
+pub use crate::span::Span;
+#[cfg(feature = "tokio")]
+pub use crate::streaming::parse_mt940_from_async_reader;
+pub use crate::streaming::{
+    parse_mt940_file, parse_mt940_from_reader, parse_mt940_iter, MessageIter, ReaderMessageIter,
+};
+pub use crate::summary::{
+    summarize, summarize_by_period, CurrencySummary, Period, PeriodSummary, Summary, Totals,
+};
+pub use crate::swift::{parse_swift_envelopes, ApplicationHeader, BasicHeader, SwiftEnvelope};
+pub use crate::tag::{allowed_successors, relaxed_successors, Tag, FIRST_TAGS};
+#[cfg(feature = "time")]
+pub use crate::time_conversion::{from_time_date, to_time_date};
 pub use crate::transaction_types::TransactionTypeIdentificationCode;
+pub use crate::values::{
+    parse_available_balance, parse_balance, parse_statement_line, parse_statement_number,
+};
+pub use crate::verbose::{parse_mt940_verbose, Subfield, VerboseField};
+pub use crate::versioned::{ParsedStatements, SCHEMA_VERSION};
+pub use crate::writer::{serialize_mt940, serialize_mt940_with_line_ending, LineEnding};
 
 /// A pest parser for parsing a MT940 structure and fields.
 #[derive(Parser)]
@@ -88,7 +227,11 @@ pub struct MT940Parser;
 ///
 /// For specific field documentation, see here:
 /// <http://www.sepaforcorporates.com/swift-for-corporates/account-statement-mt940-file-format-overview/>
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// Fields may be added in minor releases. Use [`MessageBuilder`] to construct messages.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct Message {
     /// Tag `:20:`
     pub transaction_ref_no: String,
@@ -96,9 +239,15 @@ pub struct Message {
     /// Tag `:21:`
     pub ref_to_related_msg: Option<String>,
 
-    /// Tag `:25:`
+    /// Tag `:25:` or the first line of tag `:25P:`
     pub account_id: String,
 
+    /// Second line of tag `:25P:`
+    ///
+    /// The BIC of the account servicing institution.
+    #[serde(default)]
+    pub account_identifier_bic: Option<String>,
+
     /// Tag `:28C:`
     pub statement_no: String,
     /// Optional part of tag `:28C:`
@@ -127,29 +276,129 @@ pub struct Message {
     pub closing_available_balance: Option<AvailableBalance>,
 
     /// Tag `:65:`
-    pub forward_available_balance: Option<AvailableBalance>,
+    ///
+    /// There might be one of these per future value date. A single `forward_available_balance`
+    /// as serialized by earlier versions is read as well.
+    #[serde(
+        default,
+        alias = "forward_available_balance",
+        deserialize_with = "deserialize_forward_available_balances"
+    )]
+    pub forward_available_balances: Vec<AvailableBalance>,
 
     /// Tag `:86:`
     ///
     /// A tag `:86:` not preceeded by a tag `:61` will provide information for the whole
     /// [`Message`] as opposed to just the `StatementLine`.
     pub information_to_account_owner: Option<String>,
+
+    /// The lines of the tags `:86:` making up [`Message::information_to_account_owner`].
+    ///
+    /// These are only kept if configured via [`ParserConfig::keep_information_lines`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<String>,
+
+    /// Tag `:NS:`
+    ///
+    /// Proprietary, non-SWIFT fields. These are only collected if configured via
+    /// [`ParserConfig::non_swift_tags`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub non_swift_fields: Vec<Field>,
+
+    /// Whatever the handlers registered with [`ParserConfig::tag_handler`] extracted from
+    /// proprietary tags.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+
+    /// The original text of this message.
+    ///
+    /// This is only kept if configured via [`ParserConfig::keep_raw`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+
+    /// Which message of which statement this is.
+    ///
+    /// This is only recorded if configured via [`ParserConfig::track_source`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<MessageSource>,
+}
+
+/// Either the list of forward available balances or the single, optional one which was
+/// serialized in its place before.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ForwardAvailableBalances {
+    Many(Vec<AvailableBalance>),
+    One(Option<AvailableBalance>),
+}
+
+fn deserialize_forward_available_balances<'de, D>(
+    deserializer: D,
+) -> Result<Vec<AvailableBalance>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let balances = match serde::Deserialize::deserialize(deserializer)? {
+        ForwardAvailableBalances::Many(balances) => balances,
+        ForwardAvailableBalances::One(balance) => balance.into_iter().collect(),
+    };
+    Ok(balances)
 }
 
 /// A `StatementLine` holds information contained in tag `:61:` and tag `:86:`.
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// Fields may be added in minor releases. Use [`StatementLineBuilder`] to construct statement
+/// lines.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct StatementLine {
     pub value_date: NaiveDate,
     pub entry_date: Option<NaiveDate>,
     pub ext_debit_credit_indicator: ExtDebitOrCredit,
+    /// The third character of the currency code, e.g. `R` for EUR, if given.
+    ///
+    /// It follows the debit/credit mark, so `DR` is a debit with funds code `R` and `RDR` a
+    /// reversal of a debit with funds code `R`.
     pub funds_code: Option<String>,
     pub amount: Decimal,
+    /// The character in front of the transaction type identification code.
+    #[serde(default)]
+    pub entry_type: EntryType,
     pub transaction_type_ident_code: TransactionTypeIdentificationCode,
     pub customer_ref: String,
     pub bank_ref: Option<String>,
+    /// Found on the line after the references or, if the bank reference is longer than what is
+    /// left of that line, right after it starting with `/`.
     pub supplementary_details: Option<String>,
     /// This information is contained in tag `:86:`
     pub information_to_account_owner: Option<String>,
+    /// The lines of the tags `:86:` making up `information_to_account_owner`.
+    ///
+    /// These are only kept if configured via [`ParserConfig::keep_information_lines`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub information_lines: Vec<String>,
+    /// `information_to_account_owner` broken down into its parts.
+    ///
+    /// This is only filled in if configured via [`ParserConfig::information_format`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_information: Option<StructuredInformation>,
+    /// SEPA references found in `information_to_account_owner`.
+    ///
+    /// This is only filled in if configured via [`ParserConfig::sepa_purpose`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sepa_purpose: Option<SepaPurpose>,
+    /// The original text of tag `:61:` and its tags `:86:`.
+    ///
+    /// This is only kept if configured via [`ParserConfig::keep_raw`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    /// Position of the statement line within its message, starting at 0.
+    ///
+    /// This is only recorded if configured via [`ParserConfig::track_source`], along with
+    /// [`Message::source`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
 }
 
 /// Represents a balance of an account in between statements or at the start of a statement.
@@ -158,7 +407,8 @@ pub struct StatementLine {
 /// have been continued from a previous bank statement. In that case, the [`Balance`] is said to be
 /// intermediate. This is signaled by `is_intermediate` being set to `true`. This is generally the
 /// case if this information is continued in tag `:60M:` as opposed to `:60F`.
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Balance {
     pub is_intermediate: bool,
     pub debit_credit_indicator: DebitOrCredit,
@@ -168,7 +418,8 @@ pub struct Balance {
 }
 
 /// Represents the currently available balance of an account.
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableBalance {
     pub debit_credit_indicator: DebitOrCredit,
     pub date: NaiveDate,
@@ -177,7 +428,8 @@ pub struct AvailableBalance {
 }
 
 /// Indiciates whether a transaction was `Debit` or `Credit`.
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum DebitOrCredit {
     Debit,
     Credit,
@@ -199,11 +451,16 @@ impl FromStr for DebitOrCredit {
 }
 
 /// Like [`DebitOrCredit`] with additional reverse variants.
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ExtDebitOrCredit {
+    /// `D`
     Debit,
+    /// `C`
     Credit,
+    /// `RC`: The reversal of a credit which debits the account.
     ReverseDebit,
+    /// `RD`: The reversal of a debit which credits the account.
     ReverseCredit,
 }
 
@@ -226,182 +483,243 @@ impl FromStr for ExtDebitOrCredit {
     }
 }
 
-impl Message {
-    /// Construct a new [`Message`] from a list of `[Field]`s.
-    ///
-    /// Must start with field `:20:`. Must not contain more than one `:20:` tag.
-    pub fn from_fields(fields: Vec<Field>) -> Result<Message, ParseError> {
-        // Only a few tags may follow after each specific tag.
-        let mut current_acceptable_tags: &[&str] = &["20"];
-        // TODO: Make this into an Enum instead of a bunch of strings.
-        let known_tags = [
-            "20", "21", "25", "28", "28C", "60M", "60F", "61", "86", "62M", "62F", "64", "65",
-        ];
+/// Indicates how a transaction in tag `:61:` came about.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum EntryType {
+    /// `S`: A SWIFT transfer. The transaction type identification code is the SWIFT message type
+    /// in this case, e.g. `103`.
+    Swift,
+    /// `N`: A non-SWIFT transfer.
+    #[default]
+    NonSwift,
+    /// `F`: A first advice.
+    FirstAdvice,
+}
 
-        let mut transaction_ref_no = None;
-        let mut ref_to_related_msg = None;
-        let mut account_id = None;
-        let mut statement_no = None;
-        let mut sequence_no = None;
-        let mut opening_balance = None;
-        let mut statement_lines = vec![];
-        let mut closing_balance = None;
-        let mut closing_available_balance = None;
-        let mut forward_available_balance = None;
-        let mut information_to_account_owner: Option<String> = None;
+impl FromStr for EntryType {
+    type Err = VariantNotFound;
 
-        let mut last_tag = String::default();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let entry_type = if s == "S" {
+            EntryType::Swift
+        } else if s == "N" {
+            EntryType::NonSwift
+        } else if s == "F" {
+            EntryType::FirstAdvice
+        } else {
+            return Err(VariantNotFound(s.into()));
+        };
+        Ok(entry_type)
+    }
+}
 
-        for field in fields {
-            debug!("Now parsing tag: {}", field.tag);
+impl DebitOrCredit {
+    /// Apply the sign implied by this indicator to `amount`.
+    pub(crate) fn apply_sign(&self, amount: Decimal) -> Decimal {
+        match self {
+            DebitOrCredit::Credit => amount,
+            DebitOrCredit::Debit => -amount,
+        }
+    }
+}
 
-            let current_acceptable_tags_owned = current_acceptable_tags
-                .iter()
-                .map(|x| x.to_string())
-                .collect();
+impl ExtDebitOrCredit {
+    /// Apply the sign implied by this indicator to `amount`, see [`StatementLine::signed_amount`].
+    pub(crate) fn apply_sign(&self, amount: Decimal) -> Decimal {
+        match self {
+            ExtDebitOrCredit::Credit | ExtDebitOrCredit::ReverseCredit => amount,
+            ExtDebitOrCredit::Debit | ExtDebitOrCredit::ReverseDebit => -amount,
+        }
+    }
+}
 
-            // We reject unknown tags.
-            if !known_tags.contains(&field.tag.as_str()) {
-                return Err(ParseError::UnknownTagError(field.tag));
-            }
+impl StatementLine {
+    /// The amount of this transaction with debits being negative.
+    ///
+    /// Reversals count the other way around: a reversed debit (`RD`) is positive while a reversed
+    /// credit (`RC`) is negative.
+    pub fn signed_amount(&self) -> Decimal {
+        self.ext_debit_credit_indicator.apply_sign(self.amount)
+    }
+}
 
-            // We reject unexpected tags.
-            if !current_acceptable_tags.contains(&field.tag.as_str()) {
-                return Err(UnexpectedTagError::new(
-                    &field.tag,
-                    &last_tag,
-                    current_acceptable_tags_owned,
-                )
-                .into());
-            }
+impl Balance {
+    /// The amount of this balance which is negative in case of a debit balance.
+    pub fn signed_amount(&self) -> Decimal {
+        self.debit_credit_indicator.apply_sign(self.amount)
+    }
+}
 
-            match field.tag.as_str() {
-                "20" => {
-                    transaction_ref_no = Some(parse_20_tag(&field)?);
-                    current_acceptable_tags = &["21", "25"];
-                }
-                "21" => {
-                    ref_to_related_msg = Some(parse_21_tag(&field)?);
-                    current_acceptable_tags = &["25"];
-                }
-                "25" => {
-                    account_id = Some(parse_25_tag(&field)?);
-                    current_acceptable_tags = &["28", "28C"];
-                }
-                "28" | "28C" => {
-                    let res = parse_28_tag(&field)?;
-                    statement_no = Some(res.0);
-                    sequence_no = res.1;
-                    current_acceptable_tags = &["60M", "60F"];
-                }
-                "60M" | "60F" => {
-                    opening_balance = Some(parse_60_tag(&field)?);
-                    current_acceptable_tags = &["61", "62M", "62F", "86"];
-                }
-                "61" => {
-                    let statement_line = parse_61_tag(&field)?;
-                    statement_lines.push(statement_line);
-                    current_acceptable_tags = &["61", "86", "62M", "62F"];
-                }
-                "86" => {
-                    let info_to_account_owner = parse_86_tag(&field)?;
-                    // If the last tag was either :61: or :86: then this tag belongs to that
-                    // previous tag and we'll attach the information to the previous tag.
-                    match last_tag.as_str() {
-                        "61" | "86" => {
-                            if let Some(sl) = statement_lines.last_mut() {
-                                if let Some(ref mut info) = sl.information_to_account_owner {
-                                    info.push_str(&info_to_account_owner);
-                                } else {
-                                    sl.information_to_account_owner = Some(info_to_account_owner);
-                                }
-                            }
-                        }
-                        "62M" | "62F" | "64" | "65" => {
-                            if let Some(ref mut info) = information_to_account_owner {
-                                info.push_str(&info_to_account_owner);
-                            } else {
-                                information_to_account_owner = Some(info_to_account_owner);
-                            }
-                        }
-                        _ => (),
-                    }
-                    current_acceptable_tags = &["61", "62M", "62F", "86"];
-                }
-                "62M" | "62F" => {
-                    closing_balance = Some(parse_62_tag(&field)?);
-                    current_acceptable_tags = &["64", "65", "86"];
-                }
-                "64" => {
-                    closing_available_balance = Some(parse_64_tag(&field)?);
-                    current_acceptable_tags = &["65", "86"];
-                }
-                "65" => {
-                    forward_available_balance = Some(parse_65_tag(&field)?);
-                    current_acceptable_tags = &["65", "86"];
-                }
-                _ => (),
-            }
+impl AvailableBalance {
+    /// The amount of this balance which is negative in case of a debit balance.
+    pub fn signed_amount(&self) -> Decimal {
+        self.debit_credit_indicator.apply_sign(self.amount)
+    }
+}
 
-            last_tag = field.tag;
-        }
+impl Message {
+    /// The sum of the signed amounts of all [`StatementLine`]s.
+    ///
+    /// For a consistent statement, this is the difference between closing and opening balance.
+    pub fn net_change(&self) -> Decimal {
+        self.statement_lines
+            .iter()
+            .map(StatementLine::signed_amount)
+            .sum()
+    }
 
-        let message = Message {
-            transaction_ref_no: transaction_ref_no
-                .ok_or_else(|| RequiredTagNotFoundError::new("20"))?,
-            ref_to_related_msg,
-            account_id: account_id.ok_or_else(|| RequiredTagNotFoundError::new("25"))?,
-            statement_no: statement_no.ok_or_else(|| RequiredTagNotFoundError::new("28C"))?,
-            sequence_no,
-            opening_balance: opening_balance.ok_or_else(|| RequiredTagNotFoundError::new("60"))?,
-            statement_lines,
-            closing_balance: closing_balance.ok_or_else(|| RequiredTagNotFoundError::new("62"))?,
-            closing_available_balance,
-            forward_available_balance,
-            information_to_account_owner,
-        };
+    /// Construct a new [`Message`] from a list of `[Field]`s.
+    ///
+    /// Must start with field `:20:`. Must not contain more than one `:20:` tag.
+    pub fn from_fields(fields: Vec<Field>) -> Result<Message, ParseError> {
+        Message::from_fields_with_config(fields, &ParserConfig::default(), None)
+    }
 
-        Ok(message)
+    /// Like [`Message::from_fields`] but relaxed according to `config`.
+    ///
+    /// If `errors` is given, any error in a tag which the [`Message`] can do without (`:21:`,
+    /// `:61:`, `:86:`, `:64:` and `:65:`) is pushed to it and the tag is skipped. A skipped `:61:`
+    /// takes its `:86:` tags along.
+    pub(crate) fn from_fields_with_config(
+        fields: Vec<Field>,
+        config: &ParserConfig,
+        errors: Option<&mut Vec<ParseError>>,
+    ) -> Result<Message, ParseError> {
+        let field_refs = fields.iter().map(FieldRef::from).collect::<Vec<_>>();
+        MessageRef::from_fields_with_config(&field_refs, config, errors).map(Message::from)
     }
 }
 
+/// Run `parse` on the fields of a message, pointing its error at the span it leaves behind.
+///
+/// `parse` is meant to keep the span pointed at the field being parsed and to reset it to
+/// `message_span` once it's done with the fields.
+pub(crate) fn with_current_span<T>(
+    message_span: Option<Span>,
+    parse: impl FnOnce(&mut Option<Span>) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    // Errors which concern the message as a whole point at its first field.
+    let mut current_span = message_span;
+    parse(&mut current_span).map_err(|err| err.at(current_span))
+}
+
 /// This is a generic struct that serves as a container for the first pass of the parser.
 ///
 /// It simply stores every field with absolutely no parsing or validation done on field values.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Field {
-    pub tag: String,
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub tag: Tag,
     pub value: String,
+
+    /// Where this field was found in the input, if it was parsed from one.
+    ///
+    /// This isn't taken into account when comparing or hashing fields.
+    #[serde(skip)]
+    pub span: Option<Span>,
+
+    /// The original text of this field from its tag up to the next field.
+    ///
+    /// This is only kept if configured via [`ParserConfig::keep_raw`] and isn't taken into account
+    /// when comparing or hashing fields either.
+    #[serde(skip)]
+    pub raw: Option<String>,
 }
 
 impl Field {
-    pub fn new(tag: &str, value: &str) -> Field {
+    pub fn new(tag: impl Into<Tag>, value: &str) -> Field {
         Field {
-            tag: tag.to_string(),
+            tag: tag.into(),
             value: value.to_string(),
+            span: None,
+            raw: None,
         }
     }
 }
 
+impl PartialEq for Field {
+    fn eq(&self, other: &Field) -> bool {
+        self.tag == other.tag && self.value == other.value
+    }
+}
+
+impl Eq for Field {}
+
+impl Hash for Field {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.value.hash(state);
+    }
+}
+
+/// Parse a MT940 statement which consists of exactly one message.
+///
+/// A statement with several messages results in [`ParseError::MessageCountError`].
+///
+/// # Example
+/// ```
+/// use mt940::Message;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090924EUR54484,04\r\n";
+///
+/// let message: Message = input.parse().unwrap();
+/// assert_eq!(message.statement_no, "00001");
+/// assert!(input.repeat(2).parse::<Message>().is_err());
+/// ```
+impl FromStr for Message {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let messages = parse_mt940(s)?;
+        let count = messages.len();
+        let mut messages = messages.into_iter();
+        match (messages.next(), messages.next()) {
+            (Some(message), None) => Ok(message),
+            _ => Err(ParseError::MessageCountError(
+                count,
+                ErrorLocation::default(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<&str> for Message {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 impl FromStr for Field {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parsed_field = MT940Parser::parse(Rule::field, s)?;
-        let inner = parsed_field.next().unwrap().into_inner();
-        let tag = inner.clone().next().unwrap().into_inner().as_str();
-        let value = inner
-            .clone()
-            .nth(1)
-            .unwrap()
-            .as_str()
-            .trim()
-            .replace("\r\n", "\n");
-        let field = Field::new(tag, &value);
-        Ok(field)
+        let pair = parsed_field.next().ok_or_else(|| {
+            pest::error::Error::new_from_pos(
+                ErrorVariant::CustomError {
+                    message: "Expected a field".to_string(),
+                },
+                pest::Position::from_start(s),
+            )
+        })?;
+        Ok(field_from_pair(pair)?)
     }
 }
 
+/// Build a [`Field`] from a `field` rule.
+fn field_from_pair(pair: Pair<Rule>) -> Result<Field, Box<pest::error::Error<Rule>>> {
+    FieldRef::from_pair(pair).map(Field::from)
+}
+
 /// Parse a MT940 statement to a list of its fields.
 ///
 /// ```ignore
@@ -418,6 +736,8 @@ impl FromStr for Field {
 /// The result will be a [`Vec`] of [`Field`]s.
 /// There is no validation of the contents of the [`Field`]s. The contents could be nonsensical.
 ///
+/// Lines may end in either `\r\n` or `\n`. Line breaks within values are always `\n`.
+///
 /// # Example
 /// ```
 /// use mt940::{parse_fields, Field};
@@ -443,6 +763,20 @@ impl FromStr for Field {
 /// assert_eq!(expected, input_parsed);
 /// ```
 pub fn parse_fields(statement: &str) -> Result<Vec<Field>, Box<pest::error::Error<Rule>>> {
+    Ok(parse_field_refs(statement)?
+        .into_iter()
+        .map(Field::from)
+        .collect())
+}
+
+/// Like [`parse_fields`] but borrowing the fields from `statement`.
+pub(crate) fn parse_field_refs(
+    statement: &str,
+) -> Result<Vec<FieldRef<'_>>, Box<pest::error::Error<Rule>>> {
+    if let Some(fields) = scan_field_refs(statement) {
+        return Ok(fields);
+    }
+    // Only invalid statements end up here, so pest can point out what is wrong with them.
     let parsed_fields = MT940Parser::parse(Rule::fields, statement)?;
 
     let mut fields = vec![];
@@ -450,22 +784,149 @@ pub fn parse_fields(statement: &str) -> Result<Vec<Field>, Box<pest::error::Erro
         if let Rule::EOI = parsed_field.as_rule() {
             break;
         }
-        let inner = parsed_field.into_inner();
-        let tag = inner.clone().next().unwrap().into_inner().as_str();
-        let value = inner
-            .clone()
-            .nth(1)
-            .unwrap()
-            .as_str()
-            .trim()
-            .replace("\r\n", "\n");
-        let field = Field::new(tag, &value);
-        fields.push(field);
+        fields.push(FieldRef::from_pair(parsed_field)?);
     }
 
     Ok(fields)
 }
 
+/// Fill the raw texts of `fields` which were parsed from `statement`, see [`Field::raw`].
+///
+/// Every field extends up to the start of the next one so that the raw texts of all fields add
+/// up to `statement` without anything in front of the first field.
+fn attach_raw<'a>(fields: &mut [FieldRef<'a>], statement: &'a str) {
+    let mut end = statement.len();
+    for field in fields.iter_mut().rev() {
+        if let Some(span) = field.span {
+            field.raw = statement.get(span.start..end);
+            end = span.start;
+        }
+    }
+}
+
+/// Take fields with an unknown tag which follow a tag `:86:` as lines of that tag `:86:`.
+///
+/// Their text is kept as it is, line break and tag included. See
+/// [`ParserConfig::escape_unknown_tags`].
+fn continue_unknown_tags<'a>(
+    fields: Vec<FieldRef<'a>>,
+    statement: &'a str,
+    is_known: impl Fn(&str) -> bool,
+) -> Vec<FieldRef<'a>> {
+    let mut continued: Vec<FieldRef<'a>> = Vec::with_capacity(fields.len());
+    for field in fields {
+        if let Some(last) = continued.last_mut() {
+            if last.tag == "86" && !is_known(field.tag) {
+                if let (Some(last_span), Some(span)) = (last.span, field.span) {
+                    // The value starts after the tag and its two colons.
+                    let value_start = last_span.start + last.tag.len() + 2;
+                    if let Some(value) = statement.get(value_start..span.end) {
+                        last.value = value.trim();
+                        last.span = Some(Span {
+                            end: span.end,
+                            ..last_span
+                        });
+                        continue;
+                    }
+                }
+            }
+        }
+        continued.push(field);
+    }
+    continued
+}
+
+/// Apply [`ParserConfig::duplicate_transaction_ref`] to every tag `:20:` which follows another
+/// one without a closing balance in between.
+fn handle_duplicate_transaction_refs(
+    fields: Vec<FieldRef<'_>>,
+    handling: DuplicateTransactionRef,
+) -> Result<Vec<FieldRef<'_>>, ParseError> {
+    if handling == DuplicateTransactionRef::NewMessage {
+        return Ok(fields);
+    }
+
+    let mut handled = Vec::with_capacity(fields.len());
+    // The transaction reference number of the message which has no closing balance yet.
+    let mut open_message: Option<&str> = None;
+    for field in fields {
+        match Tag::known(field.tag) {
+            Some(Tag::TransactionRef) => {
+                if let Some(first_value) = open_message {
+                    if handling == DuplicateTransactionRef::Reject {
+                        let err =
+                            DuplicateTagError::new(Tag::TransactionRef, first_value, field.value);
+                        return Err(ParseError::from(err).at(field.span));
+                    }
+                    continue;
+                }
+                open_message = Some(field.value);
+            }
+            Some(Tag::ClosingBalance | Tag::IntermediateClosingBalance) => open_message = None,
+            _ => (),
+        }
+        handled.push(field);
+    }
+    Ok(handled)
+}
+
+/// Group a list of [`Field`]s into one list per message.
+///
+/// Every message starts with a tag `:20:`. Fields in front of the first tag `:20:` result in a
+/// [`ParseError::RequiredTagNotFoundError`] pointing at the first of them, as does an empty list.
+/// The groups can be turned into [`Message`]s with [`Message::from_fields`].
+///
+/// # Example
+/// ```
+/// use mt940::{group_fields_into_messages, parse_fields, Message};
+///
+/// let input = "\
+///     :20:first\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :20:second\r\n\
+///     :25:DABADKKK/111111-11111111\r\n";
+///
+/// let fields = parse_fields(input).unwrap();
+/// let groups = group_fields_into_messages(fields).unwrap();
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[1][0].value, "second");
+///
+/// let stray = parse_fields(":25:DABADKKK/111111-11111111\r\n").unwrap();
+/// assert!(group_fields_into_messages(stray).is_err());
+/// ```
+pub fn group_fields_into_messages(fields: Vec<Field>) -> Result<Vec<Vec<Field>>, ParseError> {
+    group_into_messages(fields, |field| {
+        (field.tag == Tag::TransactionRef, field.span)
+    })
+}
+
+/// Implementation of [`group_fields_into_messages`] for any kind of field.
+///
+/// `inspect` tells whether a field is a tag `:20:` and where it is.
+fn group_into_messages<F>(
+    fields: Vec<F>,
+    inspect: impl Fn(&F) -> (bool, Option<Span>),
+) -> Result<Vec<Vec<F>>, ParseError> {
+    if fields.is_empty() {
+        return Err(RequiredTagNotFoundError::new("20").into());
+    }
+
+    let mut fields_per_message = vec![];
+
+    for field in fields {
+        let (is_transaction_ref, span) = inspect(&field);
+        if is_transaction_ref {
+            fields_per_message.push(vec![]);
+        }
+        match fields_per_message.last_mut() {
+            Some(message_fields) => message_fields.push(field),
+            None => return Err(ParseError::from(RequiredTagNotFoundError::new("20")).at(span)),
+        }
+    }
+
+    Ok(fields_per_message)
+}
+
 /// Parse and validate a MT940 statement.
 ///
 /// Result will be a [`Vec`] of all contained [`Message`]s.
@@ -475,8 +936,8 @@ pub fn parse_fields(statement: &str) -> Result<Vec<Field>, Box<pest::error::Erro
 /// # use chrono::prelude::*;
 /// # use rust_decimal::Decimal;
 /// # use std::str::FromStr;
-/// # use mt940::{Message, AvailableBalance, Balance, StatementLine};
-/// # use mt940::{DebitOrCredit, ExtDebitOrCredit, TransactionTypeIdentificationCode};
+/// # use mt940::{AvailableBalance, Balance};
+/// # use mt940::{DebitOrCredit, EntryType, ExtDebitOrCredit, TransactionTypeIdentificationCode};
 /// use mt940::parse_mt940;
 ///
 /// let input = "\
@@ -494,99 +955,203 @@ pub fn parse_fields(statement: &str) -> Result<Vec<Field>, Box<pest::error::Erro
 ///     :64:C090930EUR53189,31\r\n\
 ///     \r\n";
 ///
-/// let expected = vec![Message {
-///     transaction_ref_no: "3996-11-11111111".to_string(),
-///     ref_to_related_msg: None,
-///     account_id: "DABADKKK/111111-11111111".to_string(),
-///     statement_no: "00001".to_string(),
-///     sequence_no: Some("001".to_string()),
-///     opening_balance: Balance {
+/// let messages = parse_mt940(input).unwrap();
+/// assert_eq!(messages.len(), 1);
+/// let message = &messages[0];
+/// assert_eq!(message.transaction_ref_no, "3996-11-11111111");
+/// assert_eq!(message.account_id, "DABADKKK/111111-11111111");
+/// assert_eq!(message.statement_no, "00001");
+/// assert_eq!(message.sequence_no.as_deref(), Some("001"));
+/// assert_eq!(
+///     message.opening_balance,
+///     Balance {
 ///         is_intermediate: false,
 ///         debit_credit_indicator: DebitOrCredit::Credit,
 ///         date: NaiveDate::from_ymd(2009, 09, 24),
 ///         iso_currency_code: "EUR".to_string(),
 ///         amount: Decimal::from_str("54484.04").unwrap(),
-///     },
-///     statement_lines: vec![
-///         StatementLine {
-///             value_date: NaiveDate::from_ymd(2009, 09, 25),
-///             entry_date: Some(NaiveDate::from_ymd(2009, 09, 25)),
-///             ext_debit_credit_indicator: ExtDebitOrCredit::Debit,
-///             funds_code: Some("R".to_string()),
-///             amount: Decimal::from_str("583.92").unwrap(),
-///             transaction_type_ident_code: TransactionTypeIdentificationCode::MSC,
-///             customer_ref: "1110030403010139".to_string(),
-///             bank_ref: Some("1234".to_string()),
-///             supplementary_details: None,
-///             information_to_account_owner: Some(
-///                 "11100304030101391234\nBeneficiary name\nSomething else".to_string(),
-///             ),
-///         },
-///         StatementLine {
-///             value_date: NaiveDate::from_ymd(2009, 10, 01),
-///             entry_date: Some(NaiveDate::from_ymd(2009, 09, 30)),
-///             ext_debit_credit_indicator: ExtDebitOrCredit::Debit,
-///             funds_code: Some("R".to_string()),
-///             amount: Decimal::from_str("62.60").unwrap(),
-///             transaction_type_ident_code: TransactionTypeIdentificationCode::CHG,
-///             customer_ref: "customer id".to_string(),
-///             bank_ref: Some("bank id".to_string()),
-///             supplementary_details: None,
-///             information_to_account_owner: Some("Fees according to advice".to_string()),
-///         },
-///     ],
-///     closing_balance: Balance {
-///         is_intermediate: false,
-///         debit_credit_indicator: DebitOrCredit::Credit,
-///         date: NaiveDate::from_ymd(2009, 09, 30),
-///         iso_currency_code: "EUR".to_string(),
-///         amount: Decimal::from_str("53126.94").unwrap(),
-///     },
-///     closing_available_balance: Some(AvailableBalance {
+///     }
+/// );
+///
+/// let statement_line = &message.statement_lines[0];
+/// assert_eq!(statement_line.value_date, NaiveDate::from_ymd(2009, 09, 25));
+/// assert_eq!(statement_line.entry_date, Some(NaiveDate::from_ymd(2009, 09, 25)));
+/// assert_eq!(statement_line.ext_debit_credit_indicator, ExtDebitOrCredit::Debit);
+/// assert_eq!(statement_line.funds_code.as_deref(), Some("R"));
+/// assert_eq!(statement_line.amount, Decimal::from_str("583.92").unwrap());
+/// assert_eq!(statement_line.entry_type, EntryType::NonSwift);
+/// assert_eq!(
+///     statement_line.transaction_type_ident_code,
+///     TransactionTypeIdentificationCode::MSC
+/// );
+/// assert_eq!(statement_line.customer_ref, "1110030403010139");
+/// assert_eq!(statement_line.bank_ref.as_deref(), Some("1234"));
+/// assert_eq!(
+///     statement_line.information_to_account_owner.as_deref(),
+///     Some("11100304030101391234\nBeneficiary name\nSomething else")
+/// );
+/// assert_eq!(
+///     message.statement_lines[1].information_to_account_owner.as_deref(),
+///     Some("Fees according to advice")
+/// );
+///
+/// assert_eq!(message.closing_balance.amount, Decimal::from_str("53126.94").unwrap());
+/// assert_eq!(
+///     message.closing_available_balance,
+///     Some(AvailableBalance {
 ///         debit_credit_indicator: DebitOrCredit::Credit,
 ///         date: NaiveDate::from_ymd(2009, 09, 30),
 ///         iso_currency_code: "EUR".to_string(),
 ///         amount: Decimal::from_str("53189.31").unwrap(),
-///     }),
-///     forward_available_balance: None,
-///     information_to_account_owner: None,
-/// }];
-/// let input_parsed = parse_mt940(input).unwrap();
-/// assert_eq!(expected, input_parsed);
+///     })
+/// );
+/// assert!(message.forward_available_balances.is_empty());
+/// assert_eq!(message.information_to_account_owner, None);
 /// ```
 pub fn parse_mt940(statement: &str) -> Result<Vec<Message>, ParseError> {
-    let fields = parse_fields(statement)?;
-    if fields.is_empty() {
-        return Err(RequiredTagNotFoundError::new("20").into());
+    parse_mt940_with_config(statement, &ParserConfig::default())
+}
+
+/// Parse and validate a MT940 statement with a custom [`ParserConfig`].
+///
+/// See [`ParserConfig`] for an example.
+pub fn parse_mt940_with_config(
+    statement: &str,
+    config: &ParserConfig,
+) -> Result<Vec<Message>, ParseError> {
+    let input = statement;
+    let mut statement = Cow::Borrowed(statement);
+    let mut lines = LineMap::new(input);
+    if config.allow_non_swift_chars {
+        let (options, mut changes) = (SwiftCharsetOptions::default(), vec![]);
+        if let Cow::Owned(sanitized) = swift_charset_reporting(&statement, &options, &mut changes) {
+            lines.update(&mut changes, &sanitized);
+            statement = Cow::Owned(sanitized);
+        }
+    }
+    if config.strip_stuff_between_messages {
+        let mut changes = vec![];
+        let sanitized = strip_stuff_between_messages_reporting(&statement, &mut changes);
+        lines.update(&mut changes, &sanitized);
+        statement = Cow::Owned(sanitized);
     }
 
-    let mut fields_per_message = vec![];
+    // Spans have to point into the input we were given rather than the sanitized statement.
+    let starts = match statement {
+        Cow::Borrowed(_) => None,
+        Cow::Owned(ref sanitized) => Some((LineStarts::new(sanitized), LineStarts::new(input))),
+    };
+    let map_span = |span| match starts {
+        Some((ref sanitized, ref input)) => lines.map_span(span, sanitized, input),
+        None => span,
+    };
+    parse_message_refs(&statement, config, map_span, Message::from)
+}
 
-    let mut current_20_tag_index = -1i32;
-    for field in fields {
-        if field.tag == "20" {
-            current_20_tag_index += 1;
-            fields_per_message.push(vec![]);
-        }
-        if current_20_tag_index < 0 {
-            return Err(RequiredTagNotFoundError::new("20").into());
-        }
-        fields_per_message[current_20_tag_index as usize].push(field);
+/// Parse the messages of an already sanitized `statement` and turn them into `T` with
+/// `convert`.
+///
+/// `map_span` maps spans in `statement` to the input it was sanitized from. Raw texts are taken
+/// from `statement` all the same.
+pub(crate) fn parse_message_refs<'a, T: Send>(
+    statement: &'a str,
+    config: &ParserConfig,
+    map_span: impl Fn(Span) -> Span,
+    convert: impl Fn(MessageRef<'a>) -> T + Sync,
+) -> Result<Vec<T>, ParseError> {
+    let mut fields = parse_field_refs(statement)
+        .map_err(|err| ParseError::from_pest_error_with_span(err).map_span(&map_span))?;
+    if config.escape_unknown_tags {
+        let is_known = |tag: &str| is_known_tag(tag) || config.tag_handlers.get(tag).is_some();
+        fields = continue_unknown_tags(fields, statement, is_known);
+    }
+    if config.keep_raw {
+        attach_raw(&mut fields, statement);
+    }
+    for field in &mut fields {
+        field.span = field.span.map(&map_span);
     }
+    let fields = handle_duplicate_transaction_refs(fields, config.duplicate_transaction_ref)?;
+    let fields_per_message = group_into_messages(fields, |field| {
+        (Tag::TransactionRef == field.tag, field.span)
+    })?;
+
+    parse_messages(fields_per_message, config, convert)
+}
 
+/// Turn the fields of each message into messages.
+#[cfg(not(feature = "rayon"))]
+fn parse_messages<'a, T>(
+    fields_per_message: Vec<Vec<FieldRef<'a>>>,
+    config: &ParserConfig,
+    convert: impl Fn(MessageRef<'a>) -> T,
+) -> Result<Vec<T>, ParseError> {
     let mut messages = Vec::with_capacity(fields_per_message.len());
-    for mf in fields_per_message {
-        let m = Message::from_fields(mf)?;
-        messages.push(m);
+    for (index, mf) in fields_per_message.iter().enumerate() {
+        let m = parse_message(index, mf, config)?;
+        messages.push(convert(m));
     }
     Ok(messages)
 }
 
+/// Turn the fields of each message into messages in parallel.
+///
+/// Messages are independent of each other so this scales with the number of cores for
+/// statements with many messages. The error of the first failing message is returned, the same
+/// as when parsing serially.
+#[cfg(feature = "rayon")]
+fn parse_messages<'a, T: Send>(
+    fields_per_message: Vec<Vec<FieldRef<'a>>>,
+    config: &ParserConfig,
+    convert: impl Fn(MessageRef<'a>) -> T + Sync,
+) -> Result<Vec<T>, ParseError> {
+    use rayon::prelude::*;
+
+    let results: Vec<_> = fields_per_message
+        .par_iter()
+        .enumerate()
+        .map(|(index, mf)| parse_message(index, mf, config).map(&convert))
+        .collect();
+    results.into_iter().collect()
+}
+
+/// Turn the fields of the message at `index` into a [`MessageRef`] and record its source if
+/// configured.
+fn parse_message<'a>(
+    index: usize,
+    fields: &[FieldRef<'a>],
+    config: &ParserConfig,
+) -> Result<MessageRef<'a>, ParseError> {
+    if !config.track_source {
+        return MessageRef::from_fields_with_config(fields, config, None);
+    }
+    let source = MessageSource {
+        label: config.source_label.clone(),
+        index,
+        line: fields
+            .first()
+            .and_then(|field| field.span)
+            .map(|span| span.line),
+    };
+    match MessageRef::from_fields_with_config(fields, config, None) {
+        Ok(mut message) => {
+            message.source = Some(source);
+            for (index, statement_line) in message.statement_lines.iter_mut().enumerate() {
+                statement_line.index = Some(index);
+            }
+            Ok(message)
+        }
+        Err(err) => Err(err.in_message(source)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
     use proptest::{prop_assert_eq, prop_assume, proptest};
     use regex::Regex;
+    use rstest::rstest;
+    use std::collections::HashSet;
 
     use super::*;
 
@@ -623,7 +1188,608 @@ mod tests {
             prop_assume!(re_no_ws_in_front_or_end.is_match(&value), "Can't have a value that has whitespace in front or end");
 
             let parsed = parse_fields(&format!(":{}:{}", tag, value)).unwrap();
-            prop_assert_eq!((&parsed[0].tag, &parsed[0].value), (&tag, &value));
+            prop_assert_eq!((parsed[0].tag.as_str(), &parsed[0].value), (tag.as_str(), &value));
+        }
+    }
+
+    #[test]
+    fn parse_mt940_tag_25p() {
+        let input = "\
+            :20:a\r\n\
+            :25P:NL12ABNA0123456789\r\n\
+            ABNANL2A\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :62F:C090924EUR1,\r\n";
+        let parsed = parse_mt940(input).unwrap();
+        assert_eq!(parsed[0].account_id, "NL12ABNA0123456789");
+        assert_eq!(
+            parsed[0].account_identifier_bic,
+            Some("ABNANL2A".to_string())
+        );
+        assert_eq!(serialize_mt940(&parsed), input);
+    }
+
+    #[test]
+    fn signed_amounts() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:D090924EUR10,\r\n\
+            :61:0909250925C5,5NMSCref\r\n\
+            :61:0909250925D1,NMSCref\r\n\
+            :61:0909250925RD2,NMSCref\r\n\
+            :61:0909250925RC3,NMSCref\r\n\
+            :62F:D090925EUR6,5\r\n";
+        let parsed = parse_mt940(input).unwrap();
+        let message = &parsed[0];
+        let signed_amounts = message
+            .statement_lines
+            .iter()
+            .map(StatementLine::signed_amount)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signed_amounts,
+            vec![
+                Decimal::new(55, 1),
+                Decimal::new(-1, 0),
+                Decimal::new(2, 0),
+                Decimal::new(-3, 0),
+            ]
+        );
+        assert_eq!(
+            message.opening_balance.signed_amount(),
+            Decimal::new(-10, 0)
+        );
+        assert_eq!(message.net_change(), Decimal::new(35, 1));
+        assert_eq!(
+            message.opening_balance.signed_amount() + message.net_change(),
+            message.closing_balance.signed_amount()
+        );
+    }
+
+    #[test]
+    fn parse_mt940_multiple_forward_available_balances() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :62F:C090924EUR1,\r\n\
+            :65:C090925EUR2,\r\n\
+            :65:D090926EUR3,\r\n";
+        let parsed = parse_mt940(input).unwrap();
+        let dates = parsed[0]
+            .forward_available_balances
+            .iter()
+            .map(|b| b.date)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2009, 9, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2009, 9, 26).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_single_forward_available_balance() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :62F:C090924EUR1,\r\n\
+            :65:C090925EUR2,\r\n";
+        let message = parse_mt940(input).unwrap().remove(0);
+        let json = serde_json::to_string(&message).unwrap();
+        let balance = serde_json::to_string(&message.forward_available_balances[0]).unwrap();
+
+        let single = json.replace(
+            &format!(r#""forward_available_balances":[{}]"#, balance),
+            &format!(r#""forward_available_balance":{}"#, balance),
+        );
+        assert_ne!(single, json);
+        assert_eq!(serde_json::from_str::<Message>(&single).unwrap(), message);
+
+        let none = json.replace(
+            &format!(r#""forward_available_balances":[{}]"#, balance),
+            r#""forward_available_balance":null"#,
+        );
+        let read = serde_json::from_str::<Message>(&none).unwrap();
+        assert_eq!(read.forward_available_balances, vec![]);
+
+        let missing = json.replace(
+            &format!(r#","forward_available_balances":[{}]"#, balance),
+            "",
+        );
+        let read = serde_json::from_str::<Message>(&missing).unwrap();
+        assert_eq!(read.forward_available_balances, vec![]);
+    }
+
+    const CONFIG_TEST_MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR10,\r\n\
+        :NS:before\r\n\
+        :61:0909250925D4,NMSCref\r\n\
+        :NS:first\r\n\
+        :86:info\r\n\
+        :NS:second\r\n\
+        :61:0909260926C1,5NMSCref\r\n\
+        :99:unknown\r\n";
+
+    #[test]
+    fn config_strict_by_default() {
+        let err = parse_mt940_with_config(CONFIG_TEST_MESSAGE, &ParserConfig::new()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnknownTagError("NS".to_string(), ErrorLocation::default())
+        );
+        let span = err.span().unwrap();
+        assert_eq!((span.line, span.column), (5, 1));
+        assert_eq!(span.snippet(CONFIG_TEST_MESSAGE), Some(":NS:before"));
+    }
+
+    #[test]
+    fn config_relaxed() {
+        let config = ParserConfig::new()
+            .non_swift_tags(NonSwiftTags::AsInformation)
+            .ignore_unknown_tags(true)
+            .allow_missing_closing_balance(true);
+        let parsed = parse_mt940_with_config(CONFIG_TEST_MESSAGE, &config).unwrap();
+        assert_eq!(
+            parsed[0].information_to_account_owner,
+            Some("before".to_string())
+        );
+        assert_eq!(
+            parsed[0].statement_lines[0].information_to_account_owner,
+            Some("firstinfo\nsecond".to_string())
+        );
+        assert_eq!(
+            parsed[0].closing_balance,
+            Balance {
+                is_intermediate: false,
+                debit_credit_indicator: DebitOrCredit::Credit,
+                date: NaiveDate::from_ymd_opt(2009, 9, 26).unwrap(),
+                iso_currency_code: "EUR".to_string(),
+                amount: Decimal::new(75, 1),
+            }
+        );
+    }
+
+    #[test]
+    fn config_non_swift_tags() {
+        let config = ParserConfig::new()
+            .ignore_unknown_tags(true)
+            .allow_missing_closing_balance(true);
+
+        let parsed = parse_mt940_with_config(
+            CONFIG_TEST_MESSAGE,
+            &config.clone().non_swift_tags(NonSwiftTags::Ignore),
+        )
+        .unwrap();
+        assert_eq!(parsed[0].information_to_account_owner, None);
+        assert!(parsed[0].non_swift_fields.is_empty());
+
+        let parsed = parse_mt940_with_config(
+            CONFIG_TEST_MESSAGE,
+            &config.non_swift_tags(NonSwiftTags::Collect),
+        )
+        .unwrap();
+        assert_eq!(parsed[0].information_to_account_owner, None);
+        assert_eq!(
+            parsed[0].statement_lines[0].information_to_account_owner,
+            Some("info".to_string())
+        );
+        assert_eq!(
+            parsed[0].non_swift_fields,
+            vec![
+                Field::new("NS", "before"),
+                Field::new("NS", "first"),
+                Field::new("NS", "second"),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_relaxed_tag_order() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :21:c\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D4,NMSCref\r\n\
+            :64:C090925EUR6,\r\n\
+            :65:C090926EUR6,\r\n\
+            :62F:C090925EUR6,\r\n\
+            :86:info\r\n";
+        let err = parse_mt940(input).unwrap_err();
+        assert!(matches!(err, ParseError::UnexpectedTagError(..)));
+
+        let config = ParserConfig::new().relaxed_tag_order(true);
+        let parsed = parse_mt940_with_config(input, &config).unwrap();
+        let message = &parsed[0];
+        assert_eq!(message.ref_to_related_msg.as_deref(), Some("c"));
+        assert!(message.closing_available_balance.is_some());
+        assert_eq!(message.forward_available_balances.len(), 1);
+        assert_eq!(message.closing_balance.amount, Decimal::new(6, 0));
+        assert_eq!(
+            message.information_to_account_owner.as_deref(),
+            Some("info")
+        );
+    }
+
+    #[test]
+    fn config_duplicate_transaction_ref() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :20:again\r\n\
+            :60F:C090924EUR10,\r\n\
+            :62F:C090924EUR10,\r\n\
+            :20:next\r\n\
+            :25:b\r\n\
+            :28C:2\r\n\
+            :60F:C090924EUR10,\r\n\
+            :62F:C090924EUR10,\r\n";
+        let err = parse_mt940(input).unwrap_err();
+        assert!(matches!(err, ParseError::RequiredTagNotFoundError(..)));
+
+        let config = ParserConfig::new().duplicate_transaction_ref(DuplicateTransactionRef::Reject);
+        let err = parse_mt940_with_config(input, &config).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::DuplicateTagError(
+                DuplicateTagError::new("20", "a", "again"),
+                ErrorLocation::default()
+            )
+        );
+        assert_eq!(err.span().map(|span| span.line), Some(4));
+
+        let config = ParserConfig::new().duplicate_transaction_ref(DuplicateTransactionRef::Merge);
+        let parsed = parse_mt940_with_config(input, &config).unwrap();
+        assert_eq!(
+            parsed
+                .iter()
+                .map(|message| message.transaction_ref_no.as_str())
+                .collect::<Vec<_>>(),
+            ["a", "next"]
+        );
+    }
+
+    #[test]
+    fn config_tag_handler() {
+        let config = ParserConfig::new()
+            .non_swift_tags(NonSwiftTags::Ignore)
+            .allow_missing_closing_balance(true)
+            .tag_handler("99", |field, custom_fields| {
+                custom_fields.insert(field.tag.to_string(), field.value.clone().into());
+                Ok(())
+            })
+            .tag_handler("86", |_, _| {
+                Err(ParseError::UnknownTagError(
+                    "86".to_string(),
+                    ErrorLocation::default(),
+                ))
+            });
+        let parsed = parse_mt940_with_config(CONFIG_TEST_MESSAGE, &config).unwrap();
+        assert_eq!(
+            parsed[0].custom_fields,
+            BTreeMap::from([("99".to_string(), "unknown".into())])
+        );
+        assert_eq!(
+            parsed[0].statement_lines[0].information_to_account_owner,
+            Some("info".to_string())
+        );
+
+        let failing = config.tag_handler("99", |field, _| {
+            Err(ParseError::UnknownTagError(
+                field.tag.to_string(),
+                ErrorLocation::default(),
+            ))
+        });
+        let err = parse_mt940_with_config(CONFIG_TEST_MESSAGE, &failing).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnknownTagError("99".to_string(), ErrorLocation::default())
+        );
+    }
+
+    #[test]
+    fn config_year_pivot() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C991231EUR1,\r\n\
+            :61:9912310101C1,NMSCref\r\n\
+            :61:0001030103C1,NMSCref\r\n\
+            :62F:C000103EUR3,\r\n";
+        let parsed = parse_mt940(input).unwrap();
+        assert_eq!(parsed[0].opening_balance.date.year(), 2099);
+
+        let parsed = parse_mt940_with_config(input, &ParserConfig::new().year_pivot(50)).unwrap();
+        let message = &parsed[0];
+        assert_eq!(
+            message.opening_balance.date,
+            NaiveDate::from_ymd_opt(1999, 12, 31).unwrap()
+        );
+        assert_eq!(
+            message.statement_lines[0].entry_date,
+            NaiveDate::from_ymd_opt(1999, 1, 1)
+        );
+        assert_eq!(
+            message.statement_lines[1].value_date,
+            NaiveDate::from_ymd_opt(2000, 1, 3).unwrap()
+        );
+        assert_eq!(
+            message.closing_balance.date,
+            NaiveDate::from_ymd_opt(2000, 1, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn config_allow_non_swift_chars() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :62F:C090924EUR10,\r\n\
+            :86:Grüße=\r\n";
+        assert!(parse_mt940(input).is_err());
+        let config = ParserConfig::new().allow_non_swift_chars(true);
+        let parsed = parse_mt940_with_config(input, &config).unwrap();
+        assert_eq!(
+            parsed[0].information_to_account_owner,
+            Some("Grusse.".to_string())
+        );
+    }
+
+    #[test]
+    fn config_sanitizing_keeps_spans_in_input() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :62F:C090924EUR10,\r\n\
+            :86:Grüße\r\n\
+            -\r\n\
+            -\r\n\
+            :20:c\r\n\
+            :25:d\r\n\
+            :28C:2\r\n\
+            :60F:C091324EUR10,\r\n\
+            :62F:C090924EUR10,\r\n";
+        let config = ParserConfig::new()
+            .allow_non_swift_chars(true)
+            .strip_stuff_between_messages(true);
+        let err = parse_mt940_with_config(input, &config).unwrap_err();
+        let span = err.span().unwrap();
+        assert_eq!((span.line, span.column), (12, 1));
+        assert_eq!(span.snippet(input), Some(":60F:C091324EUR10,"));
+    }
+
+    #[test]
+    fn config_escape_unknown_tags() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D4,NMSCref\r\n\
+            :86:at 13\r\n\
+            :12:11\r\n\
+            :99:custom\r\n\
+            :62F:C090924EUR6,\r\n";
+        assert_eq!(
+            parse_mt940(input).unwrap_err(),
+            ParseError::UnknownTagError("12".to_string(), ErrorLocation::default())
+        );
+
+        let config = ParserConfig::new()
+            .escape_unknown_tags(true)
+            .tag_handler("99", |_, _| Ok(()));
+        let parsed = parse_mt940_with_config(input, &config).unwrap();
+        assert_eq!(
+            parsed[0].statement_lines[0].information_to_account_owner,
+            Some("at 13\n:12:11".to_string())
+        );
+    }
+
+    #[test]
+    fn config_keep_raw() {
+        let first = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D4,NMSCref\r\n\
+            :86:first\r\n\
+            line\r\n\
+            :86:second\r\n\
+            :62F:C090924EUR6,\r\n";
+        let second = ":20:c\r\n:25:d\r\n:28C:2\r\n:60F:C090924EUR6,\r\n:62F:C090924EUR6,\r\n\r\n";
+        let input = format!("preamble\r\n{}{}", first, second);
+
+        let parsed = parse_mt940(&input).unwrap();
+        assert_eq!(parsed[0].raw, None);
+        assert_eq!(parsed[0].statement_lines[0].raw, None);
+
+        let parsed = parse_mt940_with_config(&input, &ParserConfig::new().keep_raw(true)).unwrap();
+        assert_eq!(parsed[0].raw.as_deref(), Some(first));
+        assert_eq!(parsed[1].raw.as_deref(), Some(second));
+        assert_eq!(
+            parsed[0].statement_lines[0].raw.as_deref(),
+            Some(":61:0909250925D4,NMSCref\r\n:86:first\r\nline\r\n:86:second\r\n")
+        );
+    }
+
+    #[test]
+    fn config_keep_information_lines() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D4,NMSCref\r\n\
+            :86:first\r\n\
+            line\r\n\
+            :86:second\r\n\
+            :62F:C090924EUR6,\r\n\
+            :86:closing\r\n";
+
+        let parsed = parse_mt940(input).unwrap();
+        assert!(parsed[0].information_lines.is_empty());
+        assert!(parsed[0].statement_lines[0].information_lines.is_empty());
+
+        let config = ParserConfig::new()
+            .keep_information_lines(true)
+            .join_information_lines(true);
+        let parsed = parse_mt940_with_config(input, &config).unwrap();
+        let statement_line = &parsed[0].statement_lines[0];
+        assert_eq!(
+            statement_line.information_to_account_owner.as_deref(),
+            Some("firstlinesecond")
+        );
+        assert_eq!(
+            statement_line.information_lines,
+            ["first", "line", "second"]
+        );
+        assert_eq!(parsed[0].information_lines, ["closing"]);
+    }
+
+    #[test]
+    fn config_track_source() {
+        let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+        let input = message.repeat(2);
+
+        let parsed = parse_mt940(&input).unwrap();
+        assert_eq!(parsed[1].source, None);
+
+        let parsed =
+            parse_mt940_with_config(&input, &ParserConfig::new().track_source(true)).unwrap();
+        assert_eq!(
+            parsed[1].source,
+            Some(MessageSource {
+                label: None,
+                index: 1,
+                line: Some(6),
+            })
+        );
+
+        // Lines removed before parsing still count.
+        let input = format!("{}-\r\n-\r\n-\r\n{}", message, message);
+        let config = ParserConfig::new()
+            .track_source(true)
+            .strip_stuff_between_messages(true);
+        let parsed = parse_mt940_with_config(&input, &config).unwrap();
+        assert_eq!(
+            parsed[1].source.as_ref().and_then(|source| source.line),
+            Some(9)
+        );
+
+        let broken = message.replace(":60F:C090924", ":60F:C091324");
+        let input = format!("{}{}", message, broken);
+        let config = ParserConfig::new().source_label("a.sta");
+        let err = parse_mt940_with_config(&input, &config).unwrap_err();
+        assert!(matches!(err, ParseError::DateParseError(..)));
+        assert_eq!(err.span().map(|span| span.line), Some(9));
+        assert_eq!(
+            err.to_string(),
+            "Date parsing failed for date: '2009-13-24' (line 9, column 1) \
+             in message 2 starting at line 6 of a.sta"
+        );
+    }
+
+    #[test]
+    fn group_fields_before_first_message() {
+        let fields = parse_fields(":25:b\r\n:20:a\r\n:25:b\r\n").unwrap();
+        let err = group_fields_into_messages(fields).unwrap_err();
+        assert_eq!(err, ParseError::from(RequiredTagNotFoundError::new("20")));
+        assert_eq!(err.span().map(|span| span.line), Some(1));
+
+        assert!(group_fields_into_messages(vec![]).is_err());
+    }
+
+    #[test]
+    fn message_from_str() {
+        let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+        let single = Message::try_from(message).unwrap();
+        assert_eq!(single, parse_mt940(message).unwrap().remove(0));
+        assert_eq!(
+            message.repeat(3).parse::<Message>(),
+            Err(ParseError::MessageCountError(3, ErrorLocation::default()))
+        );
+        assert!(matches!(
+            "ignored\r\n".parse::<Message>(),
+            Err(ParseError::RequiredTagNotFoundError(..))
+        ));
+    }
+
+    #[test]
+    fn messages_can_be_deduplicated() {
+        let message = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:0909250925D1,NMSCref\r\n\
+            :62F:C090924EUR0,\r\n";
+        let other = message.replace(":28C:1", ":28C:2");
+        let input = format!("{}{}{}", message, other, message);
+        let parsed = parse_mt940(&input).unwrap();
+
+        let unique = parsed.iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), 2);
+        assert!(unique.contains(&parsed[0]));
+        assert!(unique.contains(&parsed[1]));
+
+        // Spans differ between the messages but aren't taken into account.
+        let fields = parse_fields(&input).unwrap();
+        assert_eq!(fields.iter().collect::<HashSet<_>>().len(), 7);
+    }
+
+    #[rstest]
+    #[case::after_balance(":62F:C090924EUR1,\r\n-\r\n", "")]
+    #[case::after_information(":62F:C090924EUR1,\r\n:86:info\r\n-\r\n", "info")]
+    #[case::without_newline(":62F:C090924EUR1,\r\n:86:info\r\n-", "info")]
+    #[case::part_of_information(":62F:C090924EUR1,\r\n:86:info\r\n-more\r\n", "info\n-more")]
+    fn message_terminator(#[case] end: &str, #[case] information: &str) {
+        let message = format!(":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n{}", end);
+        let input = format!("{}\r\n{}", message.trim_end(), message);
+        let parsed = parse_mt940(&input).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].closing_balance.amount, Decimal::ONE);
+        assert_eq!(
+            parsed[1]
+                .information_to_account_owner
+                .as_deref()
+                .unwrap_or_default(),
+            information
+        );
+    }
+
+    #[test]
+    fn first_error_of_many_messages() {
+        let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+        let broken = message.replace(":60F:C090924", ":60F:C091324");
+        let input = format!("{}{}{}{}", message, broken, message, broken);
+        let err = parse_mt940(&input).unwrap_err();
+        assert_eq!(err.span().map(|span| span.line), Some(9));
+    }
+
+    proptest! {
+        #[test]
+        fn parse_mt940_never_panics(input in r"(:[0-9]{2}[CFM]?:)?[0-9A-Za-z/\-\?:\(\)\.,‘\+\{\} \r\n]*") {
+            let _ = parse_mt940(&input);
         }
     }
 }
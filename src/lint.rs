@@ -0,0 +1,469 @@
+//! Conformance report of a whole statement combining all checks of this crate.
+
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[cfg(feature = "iso4217")]
+use crate::currency::exceeded_decimal_places;
+use crate::diagnostics::{check_balances, Diagnostic};
+use crate::sanitizers::is_swift_char;
+use crate::streaming::MessageBlocks;
+use crate::validation::validate_field_lengths;
+#[cfg(feature = "iso4217")]
+use crate::writer::format_amount;
+use crate::{Message, ParseError, ParserConfig, Severity, Span, Tag};
+
+/// What a [`LintFinding`] is about.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum LintKind {
+    /// The statement can't be parsed at this point.
+    Syntax,
+    /// Characters outside of the SWIFT character set.
+    NonSwiftCharacter,
+    /// A component which is longer than the spec allows.
+    FieldLength,
+    /// Balances which don't add up.
+    Balance,
+    /// Tags in an order which the spec doesn't allow but [`ParserConfig::relaxed_tag_order`]
+    /// accepts.
+    TagOrder,
+    /// Constructs which are allowed but uncommon, such as the deprecated tag `:28:`.
+    Style,
+    /// Amounts with more decimal places than their currency has, e.g. `JPY` with any. Only
+    /// checked with the `iso4217` feature.
+    DecimalPlaces,
+}
+
+/// A single problem found by [`lint`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub severity: Severity,
+
+    pub kind: LintKind,
+
+    /// The tag the problem concerns, if it concerns a specific one.
+    pub tag: Option<String>,
+
+    /// Where in the statement the problem is.
+    pub span: Option<Span>,
+
+    /// Human readable description of the problem.
+    pub message: String,
+}
+
+impl LintFinding {
+    fn from_diagnostic(kind: LintKind, diagnostic: Diagnostic) -> LintFinding {
+        LintFinding {
+            severity: diagnostic.severity,
+            kind,
+            tag: diagnostic.tag,
+            span: diagnostic.span,
+            message: diagnostic.message,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let diagnostic = Diagnostic {
+            severity: self.severity,
+            tag: self.tag.clone(),
+            span: self.span,
+            message: self.message.clone(),
+        };
+        diagnostic.fmt(f)
+    }
+}
+
+/// Check a MT940 statement against the spec and report every problem found.
+///
+/// Unlike parsing, this doesn't stop at the first problem. On top of what [`diagnose_mt940`]
+/// reports, this finds:
+///
+/// - Characters outside of the SWIFT character set ([`Severity::Error`]). The rest of the
+///   statement is checked as if they weren't there.
+/// - Lines of tag `:86:` which are longer than 65 characters and more than 6 lines in a tag
+///   ([`Severity::Warning`]).
+/// - Tags in an order which only [`ParserConfig::relaxed_tag_order`] accepts
+///   ([`Severity::Warning`]).
+/// - The deprecated tag `:28:` and information continued in another tag `:86:`
+///   ([`Severity::Info`]).
+/// - With the `iso4217` feature, amounts of tags `:60:`, `:61:` and `:62:` with more decimal
+///   places than their currency has ([`Severity::Error`]).
+///
+/// Findings are in the order of their occurrence in the statement.
+///
+/// [`diagnose_mt940`]: crate::diagnose_mt940
+///
+/// # Example
+/// ```
+/// use mt940::{lint, LintKind, Severity};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary Müller\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let findings = lint(input);
+/// assert_eq!(findings.len(), 2);
+/// assert_eq!(findings[0].severity, Severity::Info);
+/// assert_eq!(findings[0].tag.as_deref(), Some("28"));
+/// assert_eq!(findings[1].kind, LintKind::NonSwiftCharacter);
+/// assert_eq!(findings[1].span.unwrap().line, 6);
+/// assert_eq!(findings[1].span.unwrap().column, 18);
+/// ```
+pub fn lint(statement: &str) -> Vec<LintFinding> {
+    let mut findings = non_swift_characters(statement);
+    let sanitized = if findings.is_empty() {
+        None
+    } else {
+        Some(blank_non_swift_characters(statement))
+    };
+    let checked = sanitized.as_deref().unwrap_or(statement);
+
+    for block in MessageBlocks::new(checked) {
+        let fields = match block.and_then(|block| block.parse_fields()) {
+            Ok(fields) => fields,
+            Err(err) => {
+                findings.push(syntax_error(&err, checked));
+                continue;
+            }
+        };
+
+        let mut last_tag = None;
+        for field in &fields {
+            if field.tag == Tag::StatementNoDeprecated {
+                findings.push(LintFinding {
+                    severity: Severity::Info,
+                    kind: LintKind::Style,
+                    tag: Some(field.tag.to_string()),
+                    span: field.span,
+                    message: "Tag :28: is deprecated, use :28C: instead".to_string(),
+                });
+            }
+            if field.tag == Tag::InformationToAccountOwner
+                && last_tag == Some(&Tag::InformationToAccountOwner)
+            {
+                findings.push(LintFinding {
+                    severity: Severity::Info,
+                    kind: LintKind::Style,
+                    tag: Some(field.tag.to_string()),
+                    span: field.span,
+                    message: "Information is continued in another tag :86:".to_string(),
+                });
+            }
+            findings.extend(
+                validate_field_lengths(field)
+                    .into_iter()
+                    .map(|err| LintFinding {
+                        severity: Severity::Warning,
+                        kind: LintKind::FieldLength,
+                        tag: Some(field.tag.to_string()),
+                        span: field.span,
+                        message: err.to_string(),
+                    }),
+            );
+            last_tag = Some(&field.tag);
+        }
+
+        let closing_balance = fields
+            .iter()
+            .find(|field| {
+                matches!(
+                    field.tag,
+                    Tag::ClosingBalance | Tag::IntermediateClosingBalance
+                )
+            })
+            .map(|field| (field.tag.to_string(), field.span));
+        #[cfg(feature = "iso4217")]
+        let amount_fields = fields
+            .iter()
+            .map(|field| (field.tag.clone(), field.span))
+            .collect::<Vec<_>>();
+        let Some(message) = parse_message(fields, checked, &mut findings) else {
+            continue;
+        };
+        #[cfg(feature = "iso4217")]
+        findings.extend(decimal_places(&message, &amount_fields));
+        let mut diagnostics = vec![];
+        let (tag, span) = closing_balance.unzip();
+        check_balances(&message, tag, span.flatten(), &mut diagnostics);
+        findings.extend(
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| LintFinding::from_diagnostic(LintKind::Balance, diagnostic)),
+        );
+    }
+
+    if sanitized.is_some() {
+        for span in findings
+            .iter_mut()
+            .filter_map(|finding| finding.span.as_mut())
+        {
+            (span.line, span.column) = line_col(statement, span.start);
+        }
+    }
+    findings.sort_by_key(|finding| finding.span.map(|span| span.start));
+    findings
+}
+
+/// Parse the fields of a message and report the errors in it.
+///
+/// A message with tags out of order is parsed once more with relaxed tag order to find problems
+/// beyond that.
+fn parse_message(
+    fields: Vec<crate::Field>,
+    statement: &str,
+    findings: &mut Vec<LintFinding>,
+) -> Option<Message> {
+    let mut errors = vec![];
+    let strict = Message::from_fields_with_config(
+        fields.clone(),
+        &ParserConfig::default(),
+        Some(&mut errors),
+    );
+    let message = match strict {
+        Ok(message) => Some(message),
+        Err(err) if matches!(err, ParseError::UnexpectedTagError(..)) => {
+            errors.clear();
+            let config = ParserConfig::new().relaxed_tag_order(true);
+            match Message::from_fields_with_config(fields, &config, Some(&mut errors)) {
+                Ok(message) => {
+                    let mut finding = syntax_error(&err, statement);
+                    finding.severity = Severity::Warning;
+                    finding.kind = LintKind::TagOrder;
+                    findings.push(finding);
+                    Some(message)
+                }
+                Err(_) => {
+                    findings.push(syntax_error(&err, statement));
+                    None
+                }
+            }
+        }
+        Err(err) => {
+            findings.push(syntax_error(&err, statement));
+            None
+        }
+    };
+    findings.extend(errors.iter().map(|err| syntax_error(err, statement)));
+    message
+}
+
+/// Report amounts of `message` with more decimal places than their currency has.
+///
+/// `fields` are the tags of the message along with their spans.
+#[cfg(feature = "iso4217")]
+fn decimal_places(message: &Message, fields: &[(Tag, Option<Span>)]) -> Vec<LintFinding> {
+    let find = |tags: &'static [Tag]| fields.iter().filter(move |(tag, _)| tags.contains(tag));
+    let opening = &message.opening_balance;
+    let closing = &message.closing_balance;
+    let amounts = find(&[Tag::OpeningBalance, Tag::IntermediateOpeningBalance])
+        .take(1)
+        .map(|field| (field, opening.amount, &opening.iso_currency_code))
+        .chain(
+            find(&[Tag::StatementLine])
+                .zip(&message.statement_lines)
+                .map(|(field, line)| (field, line.amount, &opening.iso_currency_code)),
+        )
+        .chain(
+            find(&[Tag::ClosingBalance, Tag::IntermediateClosingBalance])
+                .take(1)
+                .map(|field| (field, closing.amount, &closing.iso_currency_code)),
+        );
+
+    amounts
+        .filter_map(|((tag, span), amount, currency)| {
+            let exponent = exceeded_decimal_places(amount, currency)?;
+            Some(LintFinding {
+                severity: Severity::Error,
+                kind: LintKind::DecimalPlaces,
+                tag: Some(tag.to_string()),
+                span: *span,
+                message: format!(
+                    "Amount {} has more than the {} decimal places of {}",
+                    format_amount(&amount),
+                    exponent,
+                    currency
+                ),
+            })
+        })
+        .collect()
+}
+
+fn syntax_error(err: &ParseError, statement: &str) -> LintFinding {
+    LintFinding::from_diagnostic(LintKind::Syntax, Diagnostic::from_error(err, statement))
+}
+
+/// Report every run of characters outside of the SWIFT character set.
+fn non_swift_characters(statement: &str) -> Vec<LintFinding> {
+    let mut findings: Vec<LintFinding> = vec![];
+    let mut line = 1;
+    let mut column = 1;
+    let mut previous_end = None;
+    for (offset, c) in statement.char_indices() {
+        if !is_swift_char(c) {
+            match findings.last_mut() {
+                Some(finding) if previous_end == Some(offset) => {
+                    finding.message.push(c);
+                    if let Some(ref mut span) = finding.span {
+                        span.end = offset + c.len_utf8();
+                    }
+                }
+                _ => findings.push(LintFinding {
+                    severity: Severity::Error,
+                    kind: LintKind::NonSwiftCharacter,
+                    tag: None,
+                    span: Some(Span {
+                        start: offset,
+                        end: offset + c.len_utf8(),
+                        line,
+                        column,
+                    }),
+                    message: format!("Characters outside of the SWIFT character set: {}", c),
+                }),
+            }
+            previous_end = Some(offset + c.len_utf8());
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    findings
+}
+
+/// Replace every byte of characters outside of the SWIFT character set with `.` so that byte
+/// offsets stay the same.
+fn blank_non_swift_characters(statement: &str) -> String {
+    statement
+        .chars()
+        .map(|c| {
+            if is_swift_char(c) {
+                c.to_string()
+            } else {
+                ".".repeat(c.len_utf8())
+            }
+        })
+        .collect()
+}
+
+/// Line and column of the byte `offset` of `statement`, both starting at 1.
+fn line_col(statement: &str, offset: usize) -> (usize, usize) {
+    let before = statement.get(..offset).unwrap_or(statement);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before.get(line_start..).unwrap_or_default().chars().count() + 1;
+    (before.matches('\n').count() + 1, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:0909250925D1,NMSCref\r\n\
+        :86:info\r\n\
+        :62F:C090924EUR0,\r\n";
+
+    fn summary(findings: &[LintFinding]) -> Vec<(Severity, LintKind, Option<usize>)> {
+        findings
+            .iter()
+            .map(|f| (f.severity, f.kind, f.span.map(|span| span.line)))
+            .collect()
+    }
+
+    #[test]
+    fn no_findings() {
+        assert_eq!(lint(MESSAGE), vec![]);
+    }
+
+    #[test]
+    fn collects_all_findings() {
+        let input = MESSAGE
+            .replace(":62F:C090924EUR0,", ":62F:C090924EUR5,")
+            .replace(
+                ":86:info\r\n",
+                &format!(":86:info\r\n:86:{}\r\n", "x".repeat(66)),
+            )
+            .replace(":20:a", ":20:ä");
+        assert_eq!(
+            summary(&lint(&input)),
+            vec![
+                (Severity::Error, LintKind::NonSwiftCharacter, Some(1)),
+                (Severity::Info, LintKind::Style, Some(7)),
+                (Severity::Warning, LintKind::FieldLength, Some(7)),
+                (Severity::Warning, LintKind::Balance, Some(8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_order() {
+        let input = MESSAGE.replace(
+            ":62F:C090924EUR0,\r\n",
+            ":64:C090924EUR0,\r\n:62F:C090924EUR0,\r\n",
+        );
+        let findings = lint(&input);
+        assert_eq!(
+            summary(&findings),
+            vec![(Severity::Warning, LintKind::TagOrder, Some(7))]
+        );
+
+        let broken = input.replace(":28C:1\r\n", "");
+        assert_eq!(
+            summary(&lint(&broken)),
+            vec![(Severity::Error, LintKind::Syntax, Some(3))]
+        );
+    }
+
+    #[test]
+    fn non_swift_characters_keep_positions() {
+        let input = MESSAGE.replace(":86:info", ":86:«info» ü");
+        let findings = lint(&input);
+        let positions = findings
+            .iter()
+            .map(|f| {
+                let span = f.span.unwrap();
+                (span.snippet(&input).unwrap(), span.line, span.column)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![("«", 6, 5), ("»", 6, 10), ("ü", 6, 12)]);
+    }
+    #[cfg(feature = "iso4217")]
+    #[test]
+    fn decimal_places() {
+        let input = MESSAGE
+            .replace("EUR1,", "JPY1,5")
+            .replace("D1,N", "D1,25N")
+            .replace("EUR0,", "JPY0,");
+        let findings = lint(&input);
+        assert_eq!(
+            summary(&findings),
+            vec![
+                (Severity::Error, LintKind::DecimalPlaces, Some(4)),
+                (Severity::Error, LintKind::DecimalPlaces, Some(5)),
+                (Severity::Warning, LintKind::Balance, Some(7)),
+            ]
+        );
+        assert_eq!(
+            findings[1].message,
+            "Amount 1,25 has more than the 0 decimal places of JPY"
+        );
+
+        assert!(lint(&MESSAGE.replace("D1,N", "D1,00N")).is_empty());
+    }
+}
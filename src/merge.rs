@@ -0,0 +1,240 @@
+//! Merging of statements which are split across multiple messages.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::errors::MergeError;
+use crate::utils::append_line;
+use crate::{AvailableBalance, Balance, Message, StatementLine};
+
+/// A logical statement made up of one or more pages.
+///
+/// Long statements are split across multiple [`Message`]s sharing the same account and statement
+/// number but with incrementing sequence numbers. Pages other than the first one open with an
+/// intermediate balance `:60M:` and pages other than the last one close with an intermediate
+/// balance `:62M:`.
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CombinedStatement {
+    pub account_id: String,
+    pub statement_no: String,
+
+    /// Opening balance of the first page.
+    pub opening_balance: Balance,
+
+    /// Statement lines of all pages in order.
+    pub statement_lines: Vec<StatementLine>,
+
+    /// Closing balance of the last page.
+    pub closing_balance: Balance,
+
+    /// Closing available balance of the last page.
+    pub closing_available_balance: Option<AvailableBalance>,
+
+    /// Forward available balances of the last page.
+    pub forward_available_balances: Vec<AvailableBalance>,
+
+    /// Message-level information of all pages, one per line.
+    pub information_to_account_owner: Option<String>,
+
+    /// Number of [`Message`]s this statement was merged from.
+    pub page_count: usize,
+}
+
+/// Merge the pages of statements into [`CombinedStatement`]s.
+///
+/// Messages are grouped by account and statement number and ordered by their sequence number.
+/// Statements are returned in the order of their first page in `messages`.
+///
+/// The sequence numbers of a statement must be consecutive and the closing balance of each page
+/// must be the opening balance of the next page.
+///
+/// # Example
+/// ```
+/// use mt940::{merge_messages, parse_mt940};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/002\r\n\
+///     :60M:C090925EUR53900,12\r\n\
+///     :61:0910010930DR62,60NCHGcustomer id//bank id\r\n\
+///     :62F:C090930EUR53837,52\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :62M:C090925EUR53900,12\r\n";
+///
+/// let statements = merge_messages(parse_mt940(input).unwrap()).unwrap();
+/// assert_eq!(statements.len(), 1);
+/// assert_eq!(statements[0].statement_lines.len(), 2);
+/// assert_eq!(statements[0].opening_balance.amount.to_string(), "54484.04");
+/// assert_eq!(statements[0].closing_balance.amount.to_string(), "53837.52");
+/// ```
+pub fn merge_messages(messages: Vec<Message>) -> Result<Vec<CombinedStatement>, MergeError> {
+    let mut groups: Vec<Vec<Message>> = vec![];
+    for message in messages {
+        let group = groups.iter_mut().find(|group| {
+            group.first().is_some_and(|first| {
+                first.account_id == message.account_id && first.statement_no == message.statement_no
+            })
+        });
+        match group {
+            Some(group) => group.push(message),
+            None => groups.push(vec![message]),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|mut pages| {
+            pages.sort_by_key(page_number);
+            check_pages_chain(&pages)?;
+            Ok(pages
+                .into_iter()
+                .map(CombinedStatement::from)
+                .reduce(CombinedStatement::append))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+impl From<Message> for CombinedStatement {
+    fn from(message: Message) -> CombinedStatement {
+        CombinedStatement {
+            account_id: message.account_id,
+            statement_no: message.statement_no,
+            opening_balance: message.opening_balance,
+            statement_lines: message.statement_lines,
+            closing_balance: message.closing_balance,
+            closing_available_balance: message.closing_available_balance,
+            forward_available_balances: message.forward_available_balances,
+            information_to_account_owner: message.information_to_account_owner,
+            page_count: 1,
+        }
+    }
+}
+
+impl CombinedStatement {
+    /// Append the following page `next`.
+    fn append(mut self, next: CombinedStatement) -> CombinedStatement {
+        self.statement_lines.extend(next.statement_lines);
+        self.closing_balance = next.closing_balance;
+        self.closing_available_balance = next.closing_available_balance;
+        self.forward_available_balances = next.forward_available_balances;
+        if let Some(info) = next.information_to_account_owner {
+            append_line(&mut self.information_to_account_owner, info);
+        }
+        self.page_count += next.page_count;
+        self
+    }
+}
+
+/// The sequence number of a page. Messages without one are treated as the first page.
+fn page_number(message: &Message) -> u32 {
+    message.sequence_number().unwrap_or(1)
+}
+
+/// Check that sorted `pages` have consecutive sequence numbers and chaining balances.
+fn check_pages_chain(pages: &[Message]) -> Result<(), MergeError> {
+    for pair in pages.windows(2) {
+        let [previous, next] = pair else {
+            continue;
+        };
+        let expected_page = page_number(previous) + 1;
+        if page_number(next) != expected_page {
+            return Err(MergeError::MissingPage {
+                account_id: next.account_id.clone(),
+                statement_no: next.statement_no.clone(),
+                sequence_no: expected_page,
+            });
+        }
+        if previous.closing_balance.signed_amount() != next.opening_balance.signed_amount()
+            || previous.closing_balance.iso_currency_code != next.opening_balance.iso_currency_code
+        {
+            return Err(MergeError::BalanceMismatch {
+                account_id: next.account_id.clone(),
+                statement_no: next.statement_no.clone(),
+                sequence_no: page_number(next),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    fn page(statement_no: &str, sequence_no: &str, opening: &str, closing: &str) -> String {
+        format!(
+            ":20:a\r\n:25:b\r\n:28C:{}/{}\r\n:60M:C090924EUR{}\r\n:62M:C090924EUR{}\r\n",
+            statement_no, sequence_no, opening, closing
+        )
+    }
+
+    #[test]
+    fn merge_single_pages() {
+        let input = format!(
+            "{}{}",
+            page("1", "1", "1,", "1,"),
+            page("2", "1", "1,", "1,")
+        );
+        let statements = merge_messages(parse_mt940(&input).unwrap()).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].page_count, 1);
+        assert_eq!(statements[1].statement_no, "2");
+    }
+
+    #[test]
+    fn merge_out_of_order_pages() {
+        let input = format!(
+            "{}{}{}",
+            page("1", "3", "3,", "4,"),
+            page("1", "1", "1,", "2,"),
+            page("1", "2", "2,", "3,")
+        );
+        let statements = merge_messages(parse_mt940(&input).unwrap()).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].page_count, 3);
+        assert_eq!(statements[0].opening_balance.amount, 1.into());
+        assert_eq!(statements[0].closing_balance.amount, 4.into());
+    }
+
+    #[test]
+    fn merge_missing_page() {
+        let input = format!(
+            "{}{}",
+            page("1", "1", "1,", "2,"),
+            page("1", "3", "3,", "4,")
+        );
+        assert_eq!(
+            merge_messages(parse_mt940(&input).unwrap()),
+            Err(MergeError::MissingPage {
+                account_id: "b".to_string(),
+                statement_no: "1".to_string(),
+                sequence_no: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn merge_balance_mismatch() {
+        let input = format!(
+            "{}{}",
+            page("1", "1", "1,", "2,"),
+            page("1", "2", "3,", "4,")
+        );
+        assert_eq!(
+            merge_messages(parse_mt940(&input).unwrap()),
+            Err(MergeError::BalanceMismatch {
+                account_id: "b".to_string(),
+                statement_no: "1".to_string(),
+                sequence_no: 2,
+            })
+        );
+    }
+}
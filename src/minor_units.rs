@@ -0,0 +1,155 @@
+//! Conversion of amounts to integer minor units such as cents.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::{AvailableBalance, Balance, StatementLine};
+
+/// Currencies with a number of decimal places other than 2 along with that number.
+///
+/// Taken from the minor units of ISO 4217.
+const CURRENCY_EXPONENTS: &[(&str, u32)] = &[
+    ("BHD", 3),
+    ("BIF", 0),
+    ("CLF", 4),
+    ("CLP", 0),
+    ("DJF", 0),
+    ("GNF", 0),
+    ("IQD", 3),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KMF", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("LYD", 3),
+    ("OMR", 3),
+    ("PYG", 0),
+    ("RWF", 0),
+    ("TND", 3),
+    ("UGX", 0),
+    ("UYI", 0),
+    ("UYW", 4),
+    ("VND", 0),
+    ("VUV", 0),
+    ("XAF", 0),
+    ("XOF", 0),
+    ("XPF", 0),
+];
+
+/// Currencies without minor units such as precious metals.
+const CURRENCIES_WITHOUT_MINOR_UNITS: &[&str] = &[
+    "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XDR", "XPD", "XPT", "XSU", "XUA", "XXX",
+];
+
+/// The number of decimal places of the currency `code` according to ISO 4217, e.g. 0 for `JPY`
+/// and 3 for `BHD`.
+///
+/// Codes must be upper case. Currencies which are not known otherwise are assumed to have 2
+/// decimal places. Returns `None` for currencies which don't have minor units at all, such as
+/// gold (`XAU`).
+///
+/// # Example
+/// ```
+/// use mt940::currency_exponent;
+///
+/// assert_eq!(currency_exponent("EUR"), Some(2));
+/// assert_eq!(currency_exponent("JPY"), Some(0));
+/// assert_eq!(currency_exponent("BHD"), Some(3));
+/// assert_eq!(currency_exponent("XAU"), None);
+/// ```
+pub fn currency_exponent(code: &str) -> Option<u32> {
+    if CURRENCIES_WITHOUT_MINOR_UNITS.contains(&code) {
+        return None;
+    }
+    let exponent = CURRENCY_EXPONENTS
+        .binary_search_by_key(&code, |&(currency, _)| currency)
+        .ok()
+        .and_then(|index| CURRENCY_EXPONENTS.get(index))
+        .map_or(2, |&(_, exponent)| exponent);
+    Some(exponent)
+}
+
+/// Convert `amount` in the currency `iso_currency_code` to an integer number of minor units,
+/// e.g. cents.
+///
+/// Returns `None` if the currency has no minor units, see [`currency_exponent`], if `amount` has
+/// more decimal places than the currency and would have to be rounded, or if it doesn't fit.
+///
+/// # Example
+/// ```
+/// use mt940::to_minor_units;
+/// use rust_decimal::Decimal;
+///
+/// assert_eq!(to_minor_units(Decimal::new(58392, 2), "EUR"), Some(58392));
+/// assert_eq!(to_minor_units(Decimal::new(1000, 0), "JPY"), Some(1000));
+/// assert_eq!(to_minor_units(Decimal::new(15, 1), "BHD"), Some(1500));
+/// assert_eq!(to_minor_units(Decimal::new(15, 1), "JPY"), None);
+/// ```
+pub fn to_minor_units(amount: Decimal, iso_currency_code: &str) -> Option<i64> {
+    let exponent = currency_exponent(iso_currency_code)?;
+    let scaled = amount.checked_mul(Decimal::from(10u64.checked_pow(exponent)?))?;
+    if !scaled.fract().is_zero() {
+        return None;
+    }
+    scaled.to_i64()
+}
+
+impl Balance {
+    /// [`Balance::amount`] in minor units of its currency, see [`to_minor_units`].
+    pub fn amount_minor_units(&self) -> Option<i64> {
+        to_minor_units(self.amount, &self.iso_currency_code)
+    }
+}
+
+impl AvailableBalance {
+    /// [`AvailableBalance::amount`] in minor units of its currency, see [`to_minor_units`].
+    pub fn amount_minor_units(&self) -> Option<i64> {
+        to_minor_units(self.amount, &self.iso_currency_code)
+    }
+}
+
+impl StatementLine {
+    /// [`StatementLine::amount`] in minor units of `iso_currency_code`, see [`to_minor_units`].
+    ///
+    /// Statement lines don't state their currency, so pass the one of the balances of their
+    /// message.
+    pub fn amount_minor_units(&self, iso_currency_code: &str) -> Option<i64> {
+        to_minor_units(self.amount, iso_currency_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn exponents_are_sorted_and_unique() {
+        assert!(CURRENCY_EXPONENTS
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[rstest(
+        amount,
+        currency,
+        expected,
+        case("583.92", "EUR", Some(58392)),
+        case("583.9", "EUR", Some(58390)),
+        case("583", "EUR", Some(58300)),
+        case("583.921", "EUR", None),
+        case("583.920", "EUR", Some(58392)),
+        case("583", "JPY", Some(583)),
+        case("583.1", "JPY", None),
+        case("583.921", "KWD", Some(583921)),
+        case("1", "XAU", None),
+        case("79228162514264337593543950335", "EUR", None)
+    )]
+    fn minor_units(amount: &str, currency: &str, expected: Option<i64>) {
+        let amount = amount.parse().unwrap();
+        assert_eq!(to_minor_units(amount, currency), expected);
+    }
+}
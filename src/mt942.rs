@@ -0,0 +1,296 @@
+//! Support for MT942 interim transaction reports.
+
+use chrono::prelude::*;
+use log::debug;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::errors::{ErrorLocation, ParseError, RequiredTagNotFoundError, UnexpectedTagError};
+use crate::tag_parsers::{
+    parse_13d_tag, parse_20_tag, parse_21_tag, parse_25_tag, parse_28_tag, parse_34f_tag,
+    parse_61_tag, parse_86_tag, parse_90_tag,
+};
+use crate::utils::append_information;
+use crate::{
+    group_fields_into_messages, parse_fields, with_current_span, DebitOrCredit, Field, Span,
+    StatementLine, Tag, FIRST_TAGS,
+};
+
+/// A single, parsed MT942 message.
+///
+/// MT942 messages are interim transaction reports that banks send during the day. They are
+/// similar to MT940 messages but don't contain any balances. Instead, they contain floor limits
+/// and summaries of the entries.
+///
+/// Fields may be added in minor releases.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct InterimMessage {
+    /// Tag `:20:`
+    pub transaction_ref_no: String,
+
+    /// Tag `:21:`
+    pub ref_to_related_msg: Option<String>,
+
+    /// Tag `:25:`
+    pub account_id: String,
+
+    /// Tag `:28C:`
+    pub statement_no: String,
+    /// Optional part of tag `:28C:`
+    pub sequence_no: Option<String>,
+
+    /// First tag `:34F:`
+    ///
+    /// In case there is only one tag `:34F:`, this is the floor limit for both debit and credit
+    /// entries.
+    pub debit_floor_limit: FloorLimitIndicator,
+
+    /// Second tag `:34F:`
+    pub credit_floor_limit: Option<FloorLimitIndicator>,
+
+    /// Tag `:13D:`
+    pub date_time_indication: DateTime<FixedOffset>,
+
+    /// Tag `:61:` and `:86:`
+    ///
+    /// Any `:86:` preceeded by `:61:` will provide more information to that `:61:`
+    pub statement_lines: Vec<StatementLine>,
+
+    /// Tag `:90D:`
+    pub debit_entries_summary: Option<EntriesSummary>,
+
+    /// Tag `:90C:`
+    pub credit_entries_summary: Option<EntriesSummary>,
+
+    /// Tag `:86:`
+    ///
+    /// A tag `:86:` not preceeded by a tag `:61` will provide information for the whole
+    /// [`InterimMessage`] as opposed to just the `StatementLine`.
+    pub information_to_account_owner: Option<String>,
+}
+
+/// Only entries with an amount above this limit are reported in an [`InterimMessage`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct FloorLimitIndicator {
+    pub iso_currency_code: String,
+    /// Only present if the limit applies to just debit or just credit entries.
+    pub debit_credit_indicator: Option<DebitOrCredit>,
+    pub amount: Decimal,
+}
+
+/// Number and sum of debit or credit entries of an [`InterimMessage`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct EntriesSummary {
+    pub number_of_entries: u32,
+    pub iso_currency_code: String,
+    pub amount: Decimal,
+}
+
+impl InterimMessage {
+    /// Construct a new [`InterimMessage`] from a list of `[Field]`s.
+    ///
+    /// Must start with field `:20:`. Must not contain more than one `:20:` tag.
+    pub fn from_fields(fields: Vec<Field>) -> Result<InterimMessage, ParseError> {
+        let message_span = fields.first().and_then(|field| field.span);
+        with_current_span(message_span, |current_span| {
+            InterimMessage::from_fields_tracking_span(fields, current_span)
+        })
+    }
+
+    /// Implementation of [`InterimMessage::from_fields`] which keeps `current_span` pointed at
+    /// the field being parsed.
+    fn from_fields_tracking_span(
+        fields: Vec<Field>,
+        current_span: &mut Option<Span>,
+    ) -> Result<InterimMessage, ParseError> {
+        let message_span = *current_span;
+        // Only a few tags may follow after each specific tag.
+        let mut current_acceptable_tags: &[Tag] = FIRST_TAGS;
+        let known_tags = [
+            Tag::TransactionRef,
+            Tag::RelatedRef,
+            Tag::AccountId,
+            Tag::StatementNoDeprecated,
+            Tag::StatementNo,
+            Tag::FloorLimit,
+            Tag::DateTimeIndication,
+            Tag::StatementLine,
+            Tag::InformationToAccountOwner,
+            Tag::DebitEntries,
+            Tag::CreditEntries,
+        ];
+        const AFTER_DATE_TIME: &[Tag] = &[
+            Tag::StatementLine,
+            Tag::InformationToAccountOwner,
+            Tag::DebitEntries,
+            Tag::CreditEntries,
+        ];
+
+        let mut transaction_ref_no = None;
+        let mut ref_to_related_msg = None;
+        let mut account_id = None;
+        let mut statement_no = None;
+        let mut sequence_no = None;
+        let mut debit_floor_limit = None;
+        let mut credit_floor_limit = None;
+        let mut date_time_indication = None;
+        let mut statement_lines: Vec<StatementLine> = vec![];
+        let mut debit_entries_summary = None;
+        let mut credit_entries_summary = None;
+        let mut information_to_account_owner: Option<String> = None;
+
+        let mut last_tag: Option<Tag> = None;
+
+        for field in fields {
+            debug!("Now parsing tag: {}", field.tag);
+            *current_span = field.span;
+
+            // We reject unknown tags.
+            if !known_tags.contains(&field.tag) {
+                return Err(ParseError::UnknownTagError(
+                    field.tag.into(),
+                    ErrorLocation::default(),
+                ));
+            }
+
+            // We reject unexpected tags.
+            if !current_acceptable_tags.contains(&field.tag) {
+                return Err(UnexpectedTagError::new(
+                    field.tag,
+                    last_tag,
+                    current_acceptable_tags.to_vec(),
+                )
+                .into());
+            }
+
+            match field.tag {
+                Tag::TransactionRef => {
+                    transaction_ref_no = Some(parse_20_tag(&field)?.to_string());
+                    current_acceptable_tags = &[Tag::RelatedRef, Tag::AccountId];
+                }
+                Tag::RelatedRef => {
+                    ref_to_related_msg = Some(parse_21_tag(&field)?.to_string());
+                    current_acceptable_tags = &[Tag::AccountId];
+                }
+                Tag::AccountId => {
+                    account_id = Some(parse_25_tag(&field)?.to_string());
+                    current_acceptable_tags = &[Tag::StatementNoDeprecated, Tag::StatementNo];
+                }
+                Tag::StatementNoDeprecated | Tag::StatementNo => {
+                    let res = parse_28_tag(&field)?;
+                    statement_no = Some(res.0.to_string());
+                    sequence_no = res.1.map(str::to_string);
+                    current_acceptable_tags = &[Tag::FloorLimit];
+                }
+                Tag::FloorLimit => {
+                    let floor_limit = parse_34f_tag(&field)?;
+                    if debit_floor_limit.is_none() {
+                        debit_floor_limit = Some(floor_limit);
+                        current_acceptable_tags = &[Tag::FloorLimit, Tag::DateTimeIndication];
+                    } else {
+                        credit_floor_limit = Some(floor_limit);
+                        current_acceptable_tags = &[Tag::DateTimeIndication];
+                    }
+                }
+                Tag::DateTimeIndication => {
+                    date_time_indication = Some(parse_13d_tag(&field)?);
+                    current_acceptable_tags = AFTER_DATE_TIME;
+                }
+                Tag::StatementLine => {
+                    let statement_line = StatementLine::from(parse_61_tag(&field, None)?);
+                    statement_lines.push(statement_line);
+                    current_acceptable_tags = AFTER_DATE_TIME;
+                }
+                Tag::InformationToAccountOwner => {
+                    let info_to_account_owner = parse_86_tag(&field)?.to_string();
+                    // If the last tag was either :61: or :86: then this tag belongs to that
+                    // previous tag and we'll attach the information to the previous tag.
+                    match (&last_tag, statement_lines.last_mut()) {
+                        (Some(Tag::StatementLine | Tag::InformationToAccountOwner), Some(sl)) => {
+                            append_information(
+                                &mut sl.information_to_account_owner,
+                                info_to_account_owner,
+                            )
+                        }
+                        _ => append_information(
+                            &mut information_to_account_owner,
+                            info_to_account_owner,
+                        ),
+                    }
+                    current_acceptable_tags = AFTER_DATE_TIME;
+                }
+                Tag::DebitEntries => {
+                    debit_entries_summary = Some(parse_90_tag(&field)?);
+                    current_acceptable_tags = &[Tag::CreditEntries, Tag::InformationToAccountOwner];
+                }
+                Tag::CreditEntries => {
+                    credit_entries_summary = Some(parse_90_tag(&field)?);
+                    current_acceptable_tags = &[Tag::InformationToAccountOwner];
+                }
+                _ => (),
+            }
+
+            last_tag = Some(field.tag);
+        }
+        *current_span = message_span;
+
+        let message = InterimMessage {
+            transaction_ref_no: transaction_ref_no
+                .ok_or_else(|| RequiredTagNotFoundError::new("20"))?,
+            ref_to_related_msg,
+            account_id: account_id.ok_or_else(|| RequiredTagNotFoundError::new("25"))?,
+            statement_no: statement_no.ok_or_else(|| RequiredTagNotFoundError::new("28C"))?,
+            sequence_no,
+            debit_floor_limit: debit_floor_limit
+                .ok_or_else(|| RequiredTagNotFoundError::new("34F"))?,
+            credit_floor_limit,
+            date_time_indication: date_time_indication
+                .ok_or_else(|| RequiredTagNotFoundError::new("13D"))?,
+            statement_lines,
+            debit_entries_summary,
+            credit_entries_summary,
+            information_to_account_owner,
+        };
+
+        Ok(message)
+    }
+}
+
+/// Parse and validate a MT942 interim transaction report.
+///
+/// Result will be a [`Vec`] of all contained [`InterimMessage`]s.
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt942;
+///
+/// let input = "\
+///     :20:CGNGHKLI0290980\r\n\
+///     :25:GJB0291077111\r\n\
+///     :28C:03917/00001\r\n\
+///     :34F:EURD0,00\r\n\
+///     :34F:EURC0,00\r\n\
+///     :13D:1610301730+0000\r\n\
+///     :61:1610301031D0,42NCHGNONREF//055001022000001\r\n\
+///     :86:Costs\r\n\
+///     :90D:1EUR0,42\r\n\
+///     :90C:0EUR0,\r\n";
+///
+/// let input_parsed = parse_mt942(input).unwrap();
+/// assert_eq!(input_parsed[0].statement_lines.len(), 1);
+/// assert_eq!(input_parsed[0].debit_entries_summary.as_ref().unwrap().number_of_entries, 1);
+/// ```
+pub fn parse_mt942(statement: &str) -> Result<Vec<InterimMessage>, ParseError> {
+    let fields = parse_fields(statement).map_err(ParseError::from_pest_error_with_span)?;
+    let fields_per_message = group_fields_into_messages(fields)?;
+
+    let mut messages = Vec::with_capacity(fields_per_message.len());
+    for mf in fields_per_message {
+        let m = InterimMessage::from_fields(mf)?;
+        messages.push(m);
+    }
+    Ok(messages)
+}
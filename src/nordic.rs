@@ -0,0 +1,122 @@
+//! Extraction of the information Nordic banks put into tag `:86:`.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::StatementLine;
+
+/// The information in tag `:86:` of a [`StatementLine`] as laid out by Nordic banks such as
+/// Danske Bank.
+///
+/// The first line holds the message to the account owner, a reference or both separated by ` / `.
+/// A line consisting of digits only is a structured creditor reference such as a Norwegian KID or
+/// a Finnish reference number. The following lines hold the name and address of the
+/// counterparty.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NordicInformation {
+    pub message: Option<String>,
+    pub reference: Option<String>,
+    pub counterparty: Option<String>,
+    /// The lines after the name of the counterparty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub counterparty_address: Vec<String>,
+}
+
+impl NordicInformation {
+    /// Extract the parts of the contents of a tag `:86:`.
+    ///
+    /// Returns `None` if it is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::NordicInformation;
+    ///
+    /// let info = "DB faktura 1234567\nTestkunde\nHOLMENS KANAL 2-12\n1192  KOBENHAVN H";
+    /// let information = NordicInformation::from_information(info).unwrap();
+    /// assert_eq!(information.message.as_deref(), Some("DB faktura 1234567"));
+    /// assert_eq!(information.counterparty.as_deref(), Some("Testkunde"));
+    /// assert_eq!(information.counterparty_address.len(), 2);
+    ///
+    /// let info = "Overforing med melding / DBTS 1234365263";
+    /// let information = NordicInformation::from_information(info).unwrap();
+    /// assert_eq!(information.message.as_deref(), Some("Overforing med melding"));
+    /// assert_eq!(information.reference.as_deref(), Some("DBTS 1234365263"));
+    /// ```
+    pub fn from_information(info: &str) -> Option<NordicInformation> {
+        let mut lines = info.lines().map(str::trim).filter(|line| !line.is_empty());
+        let first = lines.next()?;
+
+        let (message, reference) = if first.bytes().all(|b| b.is_ascii_digit()) {
+            (None, Some(first))
+        } else if let Some((message, reference)) = first.split_once(" / ") {
+            (Some(message.trim()), Some(reference.trim()))
+        } else {
+            (Some(first), None)
+        };
+
+        Some(NordicInformation {
+            message: message.map(str::to_string),
+            reference: reference.map(str::to_string),
+            counterparty: lines.next().map(str::to_string),
+            counterparty_address: lines.map(str::to_string).collect(),
+        })
+    }
+}
+
+impl StatementLine {
+    /// The information in tag `:86:` of this [`StatementLine`] as laid out by Nordic banks.
+    ///
+    /// See [`NordicInformation::from_information`].
+    pub fn nordic_information(&self) -> Option<NordicInformation> {
+        self.information_to_account_owner
+            .as_deref()
+            .and_then(NordicInformation::from_information)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940_with_config, Dialect, ParserConfig, StructuredInformation};
+
+    #[test]
+    fn reference_number() {
+        let info = "11100304030101391234\nBeneficiary name\nBeneficiary name";
+        let expected = NordicInformation {
+            reference: Some("11100304030101391234".to_string()),
+            counterparty: Some("Beneficiary name".to_string()),
+            counterparty_address: vec!["Beneficiary name".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(NordicInformation::from_information(info), Some(expected));
+        assert_eq!(NordicInformation::from_information(" \n "), None);
+    }
+
+    #[test]
+    fn danske_bank_dialect() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:0909250925D1,NMSCTeleService//1111252358\r\n\
+            :86:TeleService 1111252358\r\n\
+            :61:0909250925D1,NMSCref\r\n\
+            :62F:C090924EUR0,\r\n";
+        let messages = parse_mt940_with_config(input, &Dialect::DanskeBank.into()).unwrap();
+        let lines = &messages[0].statement_lines;
+        assert_eq!(
+            lines[0].structured_information,
+            Some(StructuredInformation::Nordic(NordicInformation {
+                message: Some("TeleService 1111252358".to_string()),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(lines[1].structured_information, None);
+
+        let messages = parse_mt940_with_config(input, &ParserConfig::new()).unwrap();
+        assert_eq!(messages[0].statement_lines[0].structured_information, None);
+    }
+}
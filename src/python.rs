@@ -0,0 +1,316 @@
+//! Bindings for Python via PyO3.
+//!
+//! The Python module `mt940` provides `parse_mt940` and `sanitize` as well as the classes
+//! `Message`, `StatementLine`, `Balance` and `AvailableBalance` whose attributes mirror the Rust
+//! structs. Dates are `datetime.date` and amounts are `decimal.Decimal`. Debit and credit
+//! indicators are given as in the statement, i.e. `D`, `C`, `RD` or `RC`.
+//!
+//! Build and install the module with [maturin](https://www.maturin.rs/):
+//!
+//! ```sh
+//! maturin develop --features python
+//! ```
+//!
+//! ```python
+//! import mt940
+//!
+//! for message in mt940.parse_mt940(mt940.sanitize(statement)):
+//!     print(message.account_id, message.closing_balance.amount)
+//! ```
+
+use chrono::NaiveDate;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::writer::{format_debit_credit, format_ext_debit_credit};
+use crate::{AvailableBalance, Balance, Message, ParseError, StatementLine};
+
+/// A parsed MT940 message.
+#[pyclass(
+    name = "Message",
+    module = "mt940",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyMessage {
+    transaction_ref_no: String,
+    ref_to_related_msg: Option<String>,
+    account_id: String,
+    account_identifier_bic: Option<String>,
+    statement_no: String,
+    sequence_no: Option<String>,
+    opening_balance: PyBalance,
+    statement_lines: Vec<PyStatementLine>,
+    closing_balance: PyBalance,
+    closing_available_balance: Option<PyAvailableBalance>,
+    forward_available_balances: Vec<PyAvailableBalance>,
+    information_to_account_owner: Option<String>,
+}
+
+#[pymethods]
+impl PyMessage {
+    fn __repr__(&self) -> String {
+        format!(
+            "Message(transaction_ref_no={:?}, account_id={:?}, statement_no={:?})",
+            self.transaction_ref_no, self.account_id, self.statement_no
+        )
+    }
+}
+
+impl From<Message> for PyMessage {
+    fn from(message: Message) -> PyMessage {
+        PyMessage {
+            transaction_ref_no: message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg,
+            account_id: message.account_id,
+            account_identifier_bic: message.account_identifier_bic,
+            statement_no: message.statement_no,
+            sequence_no: message.sequence_no,
+            opening_balance: message.opening_balance.into(),
+            statement_lines: message
+                .statement_lines
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            closing_balance: message.closing_balance.into(),
+            closing_available_balance: message.closing_available_balance.map(Into::into),
+            forward_available_balances: message
+                .forward_available_balances
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            information_to_account_owner: message.information_to_account_owner,
+        }
+    }
+}
+
+/// A single transaction of tag `:61:` along with its tag `:86:`.
+#[pyclass(
+    name = "StatementLine",
+    module = "mt940",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyStatementLine {
+    value_date: NaiveDate,
+    entry_date: Option<NaiveDate>,
+    ext_debit_credit_indicator: &'static str,
+    funds_code: Option<String>,
+    amount: Decimal,
+    signed_amount: Decimal,
+    transaction_type_ident_code: String,
+    customer_ref: String,
+    bank_ref: Option<String>,
+    supplementary_details: Option<String>,
+    information_to_account_owner: Option<String>,
+}
+
+#[pymethods]
+impl PyStatementLine {
+    fn __repr__(&self) -> String {
+        format!(
+            "StatementLine(value_date={}, amount={}, customer_ref={:?})",
+            self.value_date, self.signed_amount, self.customer_ref
+        )
+    }
+}
+
+impl From<StatementLine> for PyStatementLine {
+    fn from(statement_line: StatementLine) -> PyStatementLine {
+        PyStatementLine {
+            value_date: statement_line.value_date,
+            entry_date: statement_line.entry_date,
+            ext_debit_credit_indicator: format_ext_debit_credit(
+                &statement_line.ext_debit_credit_indicator,
+            ),
+            amount: statement_line.amount,
+            signed_amount: statement_line.signed_amount(),
+            transaction_type_ident_code: statement_line.transaction_type_ident_code.code().into(),
+            funds_code: statement_line.funds_code,
+            customer_ref: statement_line.customer_ref,
+            bank_ref: statement_line.bank_ref,
+            supplementary_details: statement_line.supplementary_details,
+            information_to_account_owner: statement_line.information_to_account_owner,
+        }
+    }
+}
+
+/// An opening or closing balance.
+#[pyclass(
+    name = "Balance",
+    module = "mt940",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyBalance {
+    is_intermediate: bool,
+    debit_credit_indicator: &'static str,
+    date: NaiveDate,
+    iso_currency_code: String,
+    amount: Decimal,
+    signed_amount: Decimal,
+}
+
+#[pymethods]
+impl PyBalance {
+    fn __repr__(&self) -> String {
+        format!(
+            "Balance(date={}, amount={} {})",
+            self.date, self.signed_amount, self.iso_currency_code
+        )
+    }
+}
+
+impl From<Balance> for PyBalance {
+    fn from(balance: Balance) -> PyBalance {
+        PyBalance {
+            is_intermediate: balance.is_intermediate,
+            debit_credit_indicator: format_debit_credit(&balance.debit_credit_indicator),
+            date: balance.date,
+            amount: balance.amount,
+            signed_amount: balance.signed_amount(),
+            iso_currency_code: balance.iso_currency_code,
+        }
+    }
+}
+
+/// A closing available or forward available balance.
+#[pyclass(
+    name = "AvailableBalance",
+    module = "mt940",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyAvailableBalance {
+    debit_credit_indicator: &'static str,
+    date: NaiveDate,
+    iso_currency_code: String,
+    amount: Decimal,
+    signed_amount: Decimal,
+}
+
+#[pymethods]
+impl PyAvailableBalance {
+    fn __repr__(&self) -> String {
+        format!(
+            "AvailableBalance(date={}, amount={} {})",
+            self.date, self.signed_amount, self.iso_currency_code
+        )
+    }
+}
+
+impl From<AvailableBalance> for PyAvailableBalance {
+    fn from(balance: AvailableBalance) -> PyAvailableBalance {
+        PyAvailableBalance {
+            debit_credit_indicator: format_debit_credit(&balance.debit_credit_indicator),
+            date: balance.date,
+            amount: balance.amount,
+            signed_amount: balance.signed_amount(),
+            iso_currency_code: balance.iso_currency_code,
+        }
+    }
+}
+
+impl From<ParseError> for PyErr {
+    fn from(err: ParseError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Parse a MT940 statement into a list of `Message`s.
+///
+/// Raises `ValueError` if the statement can't be parsed.
+#[pyfunction]
+fn parse_mt940(statement: &str) -> PyResult<Vec<PyMessage>> {
+    let messages = crate::parse_mt940(statement)?;
+    Ok(messages.into_iter().map(Into::into).collect())
+}
+
+/// Try to make a statement conform to MT940 before parsing it.
+#[pyfunction]
+fn sanitize(statement: &str) -> String {
+    crate::sanitizers::sanitize(statement)
+}
+
+/// The Python module `mt940`.
+#[pymodule]
+fn mt940(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse_mt940, module)?)?;
+    module.add_function(wrap_pyfunction!(sanitize, module)?)?;
+    module.add_class::<PyMessage>()?;
+    module.add_class::<PyStatementLine>()?;
+    module.add_class::<PyBalance>()?;
+    module.add_class::<PyAvailableBalance>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    #[test]
+    fn parse_from_python() {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "mt940").unwrap();
+            mt940(&module).unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("mt940", module).unwrap();
+            py.run(
+                cr#"
+statement = """:20:3996-11-11111111
+:25:DABADKKK/111111-11111111
+:28C:00001/001
+:60F:C090924EUR54484,04
+:61:0909250925DR583,92NMSC1110030403010139//1234
+:86:Beneficiary name
+:62F:C090930EUR53900,12
+"""
+[message] = mt940.parse_mt940(mt940.sanitize(statement))
+[line] = message.statement_lines
+result = (message.account_id, str(line.signed_amount), line.value_date.isoformat())
+try:
+    mt940.parse_mt940(":20:incomplete")
+except ValueError as err:
+    error = str(err)
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let result: (String, String, String) = locals
+                .get_item("result")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                (
+                    "DABADKKK/111111-11111111".to_string(),
+                    "-583.92".to_string(),
+                    "2009-09-25".to_string()
+                )
+            );
+            let error: String = locals
+                .get_item("error")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(error.contains("line 1"), "{}", error);
+        });
+    }
+}
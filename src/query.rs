@@ -0,0 +1,331 @@
+//! Filtering of the statement lines of parsed messages.
+
+use std::slice;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{Message, StatementLine, TransactionTypeIdentificationCode};
+
+/// Iterate over the statement lines of several messages.
+///
+/// This is implemented for slices of [`Message`]s and therefore also available on `Vec<Message>`.
+///
+/// # Example
+/// ```
+/// use chrono::NaiveDate;
+/// use mt940::{parse_mt940, Transactions, TransactionTypeIdentificationCode};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NTRF1110030403010139//1234\r\n\
+///     :61:0910010930DR62,60NCHGcustomer id//bank id\r\n\
+///     :61:0910020930CR100,NTRFcustomer id//bank id\r\n\
+///     :62F:C091002EUR53937,52\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// let transfers = messages
+///     .transactions()
+///     .between(
+///         NaiveDate::from_ymd_opt(2009, 9, 1).unwrap(),
+///         NaiveDate::from_ymd_opt(2009, 10, 31).unwrap(),
+///     )
+///     .debits()
+///     .with_code(TransactionTypeIdentificationCode::TRF)
+///     .map(|(_, statement_line)| statement_line.amount.to_string())
+///     .collect::<Vec<_>>();
+/// assert_eq!(transfers, ["583.92"]);
+/// ```
+pub trait Transactions {
+    /// All statement lines along with the message they belong to.
+    fn transactions(&self) -> TransactionQuery<'_>;
+}
+
+impl Transactions for [Message] {
+    fn transactions(&self) -> TransactionQuery<'_> {
+        TransactionQuery {
+            messages: self.iter(),
+            current: None,
+            filter: Filter::default(),
+        }
+    }
+}
+
+/// Whether only debits or only credits are wanted.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Sign {
+    Debit,
+    Credit,
+}
+
+/// Iterator over `(&Message, &StatementLine)` which can be narrowed down by chaining filters.
+///
+/// Filters can be combined freely. Chaining the same filter twice replaces the earlier one.
+#[derive(Debug, Clone)]
+pub struct TransactionQuery<'a> {
+    messages: slice::Iter<'a, Message>,
+    current: Option<(&'a Message, slice::Iter<'a, StatementLine>)>,
+    filter: Filter,
+}
+
+/// The criteria of a [`TransactionQuery`]. Unset ones match every statement line.
+#[derive(Debug, Clone, Default)]
+struct Filter {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    sign: Option<Sign>,
+    code: Option<TransactionTypeIdentificationCode>,
+    min_amount: Option<Decimal>,
+    max_amount: Option<Decimal>,
+}
+
+impl TransactionQuery<'_> {
+    /// Only statement lines with a value date from `from` to `to`, both inclusive.
+    pub fn between(mut self, from: NaiveDate, to: NaiveDate) -> Self {
+        self.filter.from = Some(from);
+        self.filter.to = Some(to);
+        self
+    }
+
+    /// Only statement lines with a value date on or after `from`.
+    pub fn since(mut self, from: NaiveDate) -> Self {
+        self.filter.from = Some(from);
+        self
+    }
+
+    /// Only statement lines with a value date on or before `to`.
+    pub fn until(mut self, to: NaiveDate) -> Self {
+        self.filter.to = Some(to);
+        self
+    }
+
+    /// Only statement lines which reduce the balance, i.e. debits and reversed credits.
+    pub fn debits(mut self) -> Self {
+        self.filter.sign = Some(Sign::Debit);
+        self
+    }
+
+    /// Only statement lines which increase the balance, i.e. credits and reversed debits.
+    pub fn credits(mut self) -> Self {
+        self.filter.sign = Some(Sign::Credit);
+        self
+    }
+
+    /// Only statement lines with the given transaction type identification code.
+    pub fn with_code(mut self, code: TransactionTypeIdentificationCode) -> Self {
+        self.filter.code = Some(code);
+        self
+    }
+
+    /// Only statement lines with an amount of at least `amount`, regardless of debit or credit.
+    pub fn min_amount(mut self, amount: Decimal) -> Self {
+        self.filter.min_amount = Some(amount);
+        self
+    }
+
+    /// Only statement lines with an amount of at most `amount`, regardless of debit or credit.
+    pub fn max_amount(mut self, amount: Decimal) -> Self {
+        self.filter.max_amount = Some(amount);
+        self
+    }
+}
+
+impl Filter {
+    fn matches(&self, statement_line: &StatementLine) -> bool {
+        let date = statement_line.value_date;
+        let sign = if statement_line.signed_amount().is_sign_negative() {
+            Sign::Debit
+        } else {
+            Sign::Credit
+        };
+        self.from.is_none_or(|from| date >= from)
+            && self.to.is_none_or(|to| date <= to)
+            && self.sign.is_none_or(|wanted| sign == wanted)
+            && self
+                .code
+                .as_ref()
+                .is_none_or(|code| *code == statement_line.transaction_type_ident_code)
+            && self
+                .min_amount
+                .is_none_or(|min| statement_line.amount >= min)
+            && self
+                .max_amount
+                .is_none_or(|max| statement_line.amount <= max)
+    }
+}
+
+impl<'a> Iterator for TransactionQuery<'a> {
+    type Item = (&'a Message, &'a StatementLine);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((message, ref mut statement_lines)) = self.current {
+                for statement_line in statement_lines.by_ref() {
+                    if self.filter.matches(statement_line) {
+                        return Some((message, statement_line));
+                    }
+                }
+            }
+            let message = self.messages.next()?;
+            self.current = Some((message, message.statement_lines.iter()));
+        }
+    }
+}
+
+impl Message {
+    /// The statement lines of this message along with their line number and this message.
+    ///
+    /// Line numbers are the position of a statement line within its message starting at 1, so
+    /// together with the position of the message they refer to a statement line as in "message 3,
+    /// line 12". Use [`Message::statement_line`] to look a line up again.
+    ///
+    /// With [`ParserConfig::track_source`], the position of the message is recorded in
+    /// [`Message::source`] and that of the statement line in [`StatementLine::index`].
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::{parse_mt940_with_config, ParserConfig};
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :61:0909250925DR583,92NTRF1110030403010139//1234\r\n\
+    ///     :61:0910010930DR62,60NCHGcustomer id//bank id\r\n\
+    ///     :62F:C091001EUR53837,52\r\n";
+    ///
+    /// let config = ParserConfig::new().track_source(true);
+    /// let messages = parse_mt940_with_config(input, &config).unwrap();
+    /// for message in &messages {
+    ///     let message_no = message.source.as_ref().unwrap().index + 1;
+    ///     for (line_no, statement_line, message) in message.lines_with_context() {
+    ///         if statement_line.amount > 100.into() {
+    ///             println!("Large amount in message {}, line {}", message_no, line_no);
+    ///         }
+    ///         assert_eq!(statement_line.index, Some(line_no - 1));
+    ///         assert_eq!(message.statement_line(line_no), Some(statement_line));
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`ParserConfig::track_source`]: crate::ParserConfig::track_source
+    pub fn lines_with_context(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (usize, &StatementLine, &Message)> + ExactSizeIterator
+    {
+        self.statement_lines
+            .iter()
+            .enumerate()
+            .map(move |(index, statement_line)| (index + 1, statement_line, self))
+    }
+
+    /// The statement line with the line number `line_no` as counted by
+    /// [`Message::lines_with_context`].
+    ///
+    /// Returns `None` if there is no such line.
+    pub fn statement_line(&self, line_no: usize) -> Option<&StatementLine> {
+        self.statement_lines.get(line_no.checked_sub(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940, parse_mt940_with_config, ParserConfig};
+
+    fn references(query: TransactionQuery<'_>) -> Vec<&str> {
+        query
+            .map(|(_, statement_line)| statement_line.customer_ref.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn filters_across_messages() {
+        let input = "\
+            :20:first\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFa\r\n\
+            :61:090926RC2,NCHGb\r\n\
+            :62F:C090926EUR7,\r\n\
+            :20:second\r\n\
+            :25:account\r\n\
+            :28C:2\r\n\
+            :60F:C090926EUR7,\r\n\
+            :62F:C090926EUR7,\r\n\
+            :20:third\r\n\
+            :25:account\r\n\
+            :28C:3\r\n\
+            :60F:C090926EUR7,\r\n\
+            :61:090927C30,NTRFc\r\n\
+            :61:090928RD4,NTRFd\r\n\
+            :62F:C090928EUR41,\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2009, 9, day).unwrap();
+
+        assert_eq!(references(messages.transactions()), ["a", "b", "c", "d"]);
+        assert_eq!(references(messages.transactions().debits()), ["a", "b"]);
+        assert_eq!(references(messages.transactions().credits()), ["c", "d"]);
+        assert_eq!(
+            references(messages.transactions().between(date(26), date(27))),
+            ["b", "c"]
+        );
+        assert_eq!(references(messages.transactions().since(date(28))), ["d"]);
+        assert_eq!(
+            references(
+                messages
+                    .transactions()
+                    .with_code(TransactionTypeIdentificationCode::TRF)
+                    .min_amount(Decimal::new(2, 0))
+                    .max_amount(Decimal::new(10, 0))
+            ),
+            ["d"]
+        );
+        let (message, _) = messages.transactions().credits().next().unwrap();
+        assert_eq!(message.transaction_ref_no, "third");
+    }
+
+    #[test]
+    fn line_numbers() {
+        let input = "\
+            :20:a\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFa\r\n\
+            :61:090926D2,NCHGb\r\n\
+            :62F:C090926EUR7,\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let message = &messages[0];
+        let lines = message
+            .lines_with_context()
+            .map(|(line_no, statement_line, line_message)| {
+                assert!(std::ptr::eq(line_message, message));
+                (line_no, statement_line.customer_ref.as_str())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [(1, "a"), (2, "b")]);
+        assert_eq!(message.lines_with_context().next_back().unwrap().0, 2);
+
+        assert_eq!(message.statement_line(0), None);
+        assert_eq!(message.statement_line(2), message.statement_lines.get(1));
+        assert_eq!(message.statement_line(3), None);
+        assert_eq!(message.statement_lines[1].index, None);
+
+        let config = ParserConfig::new().track_source(true);
+        let messages = parse_mt940_with_config(input, &config).unwrap();
+        let indexes = messages[0]
+            .statement_lines
+            .iter()
+            .map(|statement_line| statement_line.index)
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, [Some(0), Some(1)]);
+    }
+}
@@ -0,0 +1,138 @@
+//! Interpretation of the references in tag `:61:`.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::StatementLine;
+
+/// Placeholders banks put into a reference which is mandatory but unknown.
+const NO_REFERENCE: [&str; 4] = ["NONREF", "NOTPROVIDED", "NOT PROVIDED", "NONE"];
+
+/// A customer or bank reference with the common conventions resolved.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Reference {
+    /// There is no reference, e.g. it's empty or `NONREF`.
+    NonRef,
+
+    /// SEPA end-to-end reference given with the prefix `EREF+`.
+    EndToEnd(String),
+
+    /// Customer reference given with the prefix `KREF+`.
+    Customer(String),
+
+    /// Any other reference as it is.
+    Raw(String),
+}
+
+impl Reference {
+    /// Interpret a reference.
+    ///
+    /// Anything after a `//` is dropped along with surrounding whitespace. Placeholders such as
+    /// `NONREF` or `NOTPROVIDED` are recognized regardless of case.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::Reference;
+    ///
+    /// assert_eq!(Reference::parse("NONREF"), Reference::NonRef);
+    /// assert_eq!(Reference::parse("notprovided//1234"), Reference::NonRef);
+    /// assert_eq!(
+    ///     Reference::parse("EREF+E2E-4711"),
+    ///     Reference::EndToEnd("E2E-4711".to_string())
+    /// );
+    /// assert_eq!(Reference::parse("INV 42"), Reference::Raw("INV 42".to_string()));
+    /// ```
+    pub fn parse(reference: &str) -> Reference {
+        let reference = reference
+            .split_once("//")
+            .map_or(reference, |(reference, _)| reference)
+            .trim();
+        if reference.is_empty()
+            || NO_REFERENCE
+                .iter()
+                .any(|placeholder| placeholder.eq_ignore_ascii_case(reference))
+        {
+            return Reference::NonRef;
+        }
+        if let Some(end_to_end) = reference.strip_prefix("EREF+") {
+            return match Reference::parse(end_to_end) {
+                Reference::NonRef => Reference::NonRef,
+                _ => Reference::EndToEnd(end_to_end.trim().to_string()),
+            };
+        }
+        if let Some(customer) = reference.strip_prefix("KREF+") {
+            return match Reference::parse(customer) {
+                Reference::NonRef => Reference::NonRef,
+                _ => Reference::Customer(customer.trim().to_string()),
+            };
+        }
+        Reference::Raw(reference.to_string())
+    }
+
+    /// The reference without its prefix or `None` for [`Reference::NonRef`].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Reference::NonRef => None,
+            Reference::EndToEnd(reference)
+            | Reference::Customer(reference)
+            | Reference::Raw(reference) => Some(reference),
+        }
+    }
+}
+
+impl StatementLine {
+    /// The customer reference interpreted by [`Reference::parse`].
+    pub fn customer_reference(&self) -> Reference {
+        Reference::parse(&self.customer_ref)
+    }
+
+    /// The bank reference interpreted by [`Reference::parse`], if there is one.
+    pub fn bank_reference(&self) -> Option<Reference> {
+        self.bank_ref.as_deref().map(Reference::parse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[rstest]
+    #[case("", Reference::NonRef)]
+    #[case("NONREF", Reference::NonRef)]
+    #[case("nonref", Reference::NonRef)]
+    #[case("NOT PROVIDED", Reference::NonRef)]
+    #[case("EREF+NOTPROVIDED", Reference::NonRef)]
+    #[case("KREF+", Reference::NonRef)]
+    #[case("EREF+ 123 ", Reference::EndToEnd("123".to_string()))]
+    #[case("KREF+ABC//DEF", Reference::Customer("ABC".to_string()))]
+    #[case("1110030403010139 // 1234", Reference::Raw("1110030403010139".to_string()))]
+    fn parse_reference(#[case] input: &str, #[case] expected: Reference) {
+        assert_eq!(Reference::parse(input), expected);
+    }
+
+    #[test]
+    fn references_of_statement_line() {
+        let input = "\
+            :20:ref\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFNONREF//KREF+4711\r\n\
+            :61:090925D1,NTRFEREF+E2E\r\n\
+            :62F:C090924EUR8,\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let [first, second] = messages[0].statement_lines.as_slice() else {
+            panic!("expected two statement lines");
+        };
+        assert_eq!(first.customer_reference(), Reference::NonRef);
+        assert_eq!(
+            first.bank_reference(),
+            Some(Reference::Customer("4711".to_string()))
+        );
+        assert_eq!(second.customer_reference().as_str(), Some("E2E"));
+        assert_eq!(second.bank_reference(), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Human readable renderings of [`Message`]s for quick inspection.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+
+use crate::writer::{format_debit_credit, format_ext_debit_credit};
+use crate::{Balance, Message, StatementLine};
+
+/// Maximum number of characters of tag `:86:` shown per row of a table.
+const INFORMATION_WIDTH: usize = 40;
+
+/// Width of the amount column of [`Message`] and [`StatementLine`] summaries. MT940 amounts have up
+/// to 15 characters.
+const AMOUNT_WIDTH: usize = 15;
+
+/// Render `messages` as plain text tables, one per message.
+///
+/// Only the first line of tag `:86:` is shown and it's cut off after 40 characters.
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940;
+/// use mt940::report::to_table;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let table = to_table(&parse_mt940(input).unwrap());
+/// assert!(table.contains("2009-09-25  -583.92  MSC   1110030403010139  Beneficiary name"));
+/// ```
+pub fn to_table(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(message_table)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn message_table(message: &Message) -> String {
+    let statement_no = match message.sequence_no {
+        Some(ref sequence_no) => format!("{}/{}", message.statement_no, sequence_no),
+        None => message.statement_no.clone(),
+    };
+    let mut out = format!(
+        "Statement {} of {} ({})\n",
+        statement_no, message.account_id, message.transaction_ref_no
+    );
+    out.push_str(&format!(
+        "Opening balance {}: {} {}\n\n",
+        message.opening_balance.date,
+        message.opening_balance.signed_amount(),
+        message.opening_balance.iso_currency_code
+    ));
+
+    let header = ["Value date", "Amount", "Type", "Reference", "Information"].map(str::to_string);
+    let rows = message
+        .statement_lines
+        .iter()
+        .map(|statement_line| {
+            [
+                statement_line.value_date.to_string(),
+                statement_line.signed_amount().to_string(),
+                statement_line
+                    .transaction_type_ident_code
+                    .code()
+                    .to_string(),
+                statement_line.customer_ref.clone(),
+                information_summary(statement_line),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = [0; 5];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Amounts are right-aligned so that the decimal places line up.
+                if column == 1 {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "\nClosing balance {}: {} {}\n",
+        message.closing_balance.date,
+        message.closing_balance.signed_amount(),
+        message.closing_balance.iso_currency_code
+    ));
+    out
+}
+
+/// The first line of tag `:86:` of `statement_line` cut off after 40 characters.
+///
+/// Banks usually put the name of the counterparty there.
+fn information_summary(statement_line: &StatementLine) -> String {
+    statement_line
+        .information_to_account_owner
+        .as_deref()
+        .and_then(|info| info.lines().next())
+        .unwrap_or_default()
+        .chars()
+        .take(INFORMATION_WIDTH)
+        .collect()
+}
+
+/// Write a single aligned row of a summary.
+fn write_row(
+    f: &mut fmt::Formatter<'_>,
+    date: &impl fmt::Display,
+    debit_credit: &str,
+    amount: &Decimal,
+    currency: Option<&str>,
+    text: &str,
+) -> fmt::Result {
+    let mut row = format!(
+        "{}  {:<2}  {:>width$}",
+        date,
+        debit_credit,
+        amount,
+        width = AMOUNT_WIDTH
+    );
+    if let Some(currency) = currency {
+        row.push(' ');
+        row.push_str(currency);
+    }
+    row.push_str("  ");
+    row.push_str(text);
+    f.write_str(row.trim_end())
+}
+
+fn write_balance(f: &mut fmt::Formatter<'_>, balance: &Balance, text: &str) -> fmt::Result {
+    write_row(
+        f,
+        &balance.date,
+        format_debit_credit(&balance.debit_credit_indicator),
+        &balance.amount,
+        Some(&balance.iso_currency_code),
+        text,
+    )
+}
+
+/// A summary of the message with one aligned row per balance and statement line.
+///
+/// This is meant for logs and terminals. Use [`Message::to_mt940_string`] to get MT940.
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:Beneficiary name\r\n\
+///     :62F:C090930EUR53900,12\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// assert_eq!(
+///     messages[0].to_string(),
+///     "\
+/// Statement 00001/001 of DABADKKK/111111-11111111 (3996-11-11111111)
+/// 2009-09-24  C          54484.04 EUR  Opening balance
+/// 2009-09-25  D            583.92 EUR  Beneficiary name
+/// 2009-09-30  C          53900.12 EUR  Closing balance"
+/// );
+/// ```
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Statement {}", self.statement_no)?;
+        if let Some(ref sequence_no) = self.sequence_no {
+            write!(f, "/{}", sequence_no)?;
+        }
+        writeln!(f, " of {} ({})", self.account_id, self.transaction_ref_no)?;
+        write_balance(f, &self.opening_balance, "Opening balance")?;
+        for statement_line in &self.statement_lines {
+            writeln!(f)?;
+            write_row(
+                f,
+                &statement_line.value_date,
+                format_ext_debit_credit(&statement_line.ext_debit_credit_indicator),
+                &statement_line.amount,
+                Some(&self.opening_balance.iso_currency_code),
+                &information_summary(statement_line),
+            )?;
+        }
+        writeln!(f)?;
+        write_balance(f, &self.closing_balance, "Closing balance")
+    }
+}
+
+/// A single aligned row with value date, debit or credit, amount and the first line of tag `:86:`.
+impl fmt::Display for StatementLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_row(
+            f,
+            &self.value_date,
+            format_ext_debit_credit(&self.ext_debit_credit_indicator),
+            &self.amount,
+            None,
+            &information_summary(self),
+        )
+    }
+}
+
+/// Render `messages` as YAML.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(messages: &[Message]) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn table() {
+        let input = "\
+            :20:ref\r\n\
+            :25:account\r\n\
+            :28C:1/2\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925C12,5NTRFcustomer\r\n\
+            :86:Payer\r\n\
+            Invoice 42\r\n\
+            :61:090926D1,NCHGfee\r\n\
+            :62F:C090926EUR12,5\r\n";
+        assert_eq!(
+            to_table(&parse_mt940(input).unwrap()),
+            "\
+Statement 1/2 of account (ref)
+Opening balance 2009-09-24: 1 EUR
+
+Value date  Amount  Type  Reference  Information
+2009-09-25    12.5  TRF   customer   Payer
+2009-09-26      -1  CHG   fee
+
+Closing balance 2009-09-26: 12.5 EUR
+"
+        );
+    }
+
+    #[test]
+    fn statement_line_display() {
+        let input = "\
+            :20:ref\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :61:090925RC12,5NTRFcustomer\r\n\
+            :61:090926D1,NCHGfee\r\n\
+            :86:Bank\r\n\
+            :62F:D090926EUR12,5\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let lines = messages[0]
+            .statement_lines
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "2009-09-25  RC             12.5",
+                "2009-09-26  D                 1  Bank",
+            ]
+        );
+    }
+}
@@ -1,21 +1,811 @@
 //! This module contains a collection of sanitizers which is really just a fancy way of saying
 //! that this is a bunch of functions which take strings, change them, and give them back.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
 use deunicode::deunicode_char;
 use pest::Parser;
 
+use crate::writer::{split_tag_86_line, LineEnding, TAG_86_LINE_LENGTH, TAG_86_MAX_LINES};
 use crate::MT940Parser;
 use crate::Rule;
+use crate::Span;
+use crate::Tag;
+
+/// Maximum length of the supplementary details of tag `:61:`.
+const SUPPLEMENTARY_DETAILS_LENGTH: usize = 34;
+
+/// Everything the sanitizers changed about an input.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SanitizeReport {
+    /// All changes ordered by line.
+    pub changes: Vec<SanitizeChange>,
+}
+
+impl SanitizeReport {
+    /// Whether the input was left as it was.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The line of the original input which `line` of the sanitized output came from.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::sanitizers::sanitize_with_report;
+    ///
+    /// let input = ":20:a\r\n-\r\n:20:b\r\n";
+    /// let (sanitized, report) = sanitize_with_report(input);
+    /// assert_eq!(sanitized, ":20:a\r\n:20:b\r\n");
+    /// assert_eq!(report.original_line(2), 3);
+    /// ```
+    pub fn original_line(&self, line: usize) -> usize {
+        let mut sanitized_lines = 0;
+        let mut original_line = 0;
+        loop {
+            original_line += 1;
+            let lines = self
+                .changes
+                .iter()
+                .filter(|change| change.line == original_line)
+                .find_map(|change| change.kind.resulting_lines())
+                .unwrap_or(1);
+            sanitized_lines += lines;
+            if sanitized_lines >= line {
+                return original_line;
+            }
+        }
+    }
+}
+
+/// A single change made by a sanitizer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SanitizeChange {
+    /// Line of the original input, starting at 1.
+    pub line: usize,
+    pub kind: SanitizeChangeKind,
+}
+
+/// What was changed and why.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SanitizeChangeKind {
+    /// A character outside of the SWIFT charset was transliterated or replaced according to the
+    /// [`ReplacementPolicy`], a dot by default. The replacement is empty if it was removed.
+    ReplacedChar {
+        /// Column of the character, starting at 1.
+        column: usize,
+        original: char,
+        replacement: String,
+    },
+    /// A character outside of the SWIFT charset was kept because of [`ReplacementPolicy::Keep`].
+    KeptChar {
+        /// Column of the character, starting at 1.
+        column: usize,
+        original: char,
+    },
+    /// A line between messages was removed.
+    RemovedBetweenMessages { content: String },
+    /// A line of tag `:86:` beyond the 6 allowed was removed.
+    RemovedExcessTag86Line { content: String },
+    /// A line of tag `:86:` was cut at 65 characters.
+    TruncatedTag86Line { removed: String },
+    /// A line of tag `:86:` longer than 65 characters was wrapped into several lines.
+    WrappedTag86Line { lines: usize },
+    /// A line of tag `:86:` beyond the 6 allowed was moved into a new tag `:86:`.
+    ContinuedTag86,
+    /// The supplementary details of tag `:61:` were cut at 34 characters.
+    TruncatedSupplementaryDetails { removed: String },
+    /// A line of tag `:86:` starting with something that looks like a tag but isn't a known one
+    /// was indented by a space to keep it from starting a new field.
+    EscapedTag { tag: String },
+    /// An amount was rewritten with a comma as the decimal separator and without thousands
+    /// separators.
+    NormalizedAmount {
+        original: String,
+        normalized: String,
+    },
+    /// Any other change, e.g. by a custom [`Sanitizer`].
+    Other { description: String },
+}
+
+impl SanitizeChangeKind {
+    /// How many lines the line this change was made on turned into, if the change affected it.
+    fn resulting_lines(&self) -> Option<usize> {
+        match self {
+            SanitizeChangeKind::RemovedBetweenMessages { .. }
+            | SanitizeChangeKind::RemovedExcessTag86Line { .. } => Some(0),
+            SanitizeChangeKind::WrappedTag86Line { lines } => Some(*lines),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SanitizeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SanitizeChangeKind::ReplacedChar {
+                column,
+                original,
+                ref replacement,
+            } => write!(
+                f,
+                "line {}, column {}: replaced non-SWIFT character '{}' with '{}'",
+                self.line, column, original, replacement
+            ),
+            SanitizeChangeKind::KeptChar { column, original } => write!(
+                f,
+                "line {}, column {}: kept non-SWIFT character '{}'",
+                self.line, column, original
+            ),
+            SanitizeChangeKind::RemovedBetweenMessages { ref content } => write!(
+                f,
+                "line {}: removed '{}' between messages",
+                self.line, content
+            ),
+            SanitizeChangeKind::RemovedExcessTag86Line { ref content } => write!(
+                f,
+                "line {}: removed '{}' exceeding the 6 lines of tag 86",
+                self.line, content
+            ),
+            SanitizeChangeKind::TruncatedTag86Line { ref removed } => write!(
+                f,
+                "line {}: removed '{}' exceeding the 65 characters of a line of tag 86",
+                self.line, removed
+            ),
+            SanitizeChangeKind::WrappedTag86Line { lines } => write!(
+                f,
+                "line {}: wrapped line of tag 86 into {} lines",
+                self.line, lines
+            ),
+            SanitizeChangeKind::ContinuedTag86 => write!(
+                f,
+                "line {}: continued tag 86 in a new tag 86",
+                self.line
+            ),
+            SanitizeChangeKind::TruncatedSupplementaryDetails { ref removed } => write!(
+                f,
+                "line {}: removed '{}' exceeding the 34 characters of the supplementary details of tag 61",
+                self.line, removed
+            ),
+            SanitizeChangeKind::EscapedTag { ref tag } => write!(
+                f,
+                "line {}: escaped unknown tag '{}' within tag 86",
+                self.line, tag
+            ),
+            SanitizeChangeKind::NormalizedAmount {
+                ref original,
+                ref normalized,
+            } => write!(
+                f,
+                "line {}: normalized amount '{}' to '{}'",
+                self.line, original, normalized
+            ),
+            SanitizeChangeKind::Other { ref description } => {
+                write!(f, "line {}: {}", self.line, description)
+            }
+        }
+    }
+}
 
 /// Run all sanitizers on the input in a useful order.
 ///
 /// If you don't really care exactly _how_ you're input is sanitized and just want it to work, this
 /// is probably the function to use. Be aware that it's possible that some data could be truncated
-/// in order to make valid statements.
+/// in order to make valid statements. Use [`sanitize_with_report`] to find out.
+///
+/// This runs [`SanitizerPipeline::default`].
 pub fn sanitize(s: &str) -> String {
-    let s1 = to_swift_charset(s);
-    let s2 = strip_stuff_between_messages(&s1);
-    strip_excess_tag86_lines(&s2)
+    SanitizerPipeline::default().sanitize(s)
+}
+
+/// Like [`sanitize`] but also reports every change made to the input.
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::{sanitize_with_report, SanitizeChangeKind};
+///
+/// let input = ":20:a\r\n:86:Grüße\r\n";
+/// let (sanitized, report) = sanitize_with_report(input);
+/// assert_eq!(sanitized, ":20:a\r\n:86:Grusse\r\n");
+/// assert_eq!(report.changes.len(), 2);
+/// assert_eq!(report.changes[0].line, 2);
+/// assert_eq!(
+///     report.changes[0].kind,
+///     SanitizeChangeKind::ReplacedChar {
+///         column: 7,
+///         original: 'ü',
+///         replacement: "u".to_string(),
+///     }
+/// );
+/// ```
+pub fn sanitize_with_report(s: &str) -> (String, SanitizeReport) {
+    SanitizerPipeline::default().sanitize_with_report(s)
+}
+
+/// A single step of a [`SanitizerPipeline`].
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::{SanitizeChange, Sanitizer, SanitizerPipeline};
+///
+/// /// My bank writes `EURO` instead of `EUR`.
+/// struct FixCurrency;
+///
+/// impl Sanitizer for FixCurrency {
+///     fn name(&self) -> &str {
+///         "fix_currency"
+///     }
+///
+///     fn sanitize(&self, input: &str, _changes: &mut Vec<SanitizeChange>) -> String {
+///         input.replace("EURO", "EUR")
+///     }
+/// }
+///
+/// let pipeline = SanitizerPipeline::default().push(FixCurrency);
+/// assert_eq!(pipeline.sanitize(":60F:C090924EURO1,\r\n"), ":60F:C090924EUR1,\r\n");
+/// ```
+pub trait Sanitizer {
+    /// Name of this step which is used to remove it from a [`SanitizerPipeline`].
+    fn name(&self) -> &str;
+
+    /// Sanitize `input`, recording what was changed in `changes`.
+    ///
+    /// Line numbers in `changes` refer to `input`. Removed and wrapped lines have to be recorded as
+    /// such for the line numbers of later steps to be mapped back to the original input correctly.
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String;
+}
+
+/// [`to_swift_charset`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwiftCharset;
+
+impl Sanitizer for SwiftCharset {
+    fn name(&self) -> &str {
+        "to_swift_charset"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        swift_charset_reporting(input, &SwiftCharsetOptions::default(), changes).into_owned()
+    }
+}
+
+/// How [`SwiftCharsetOptions`] replaces characters outside of the SWIFT charset.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Transliteration {
+    /// Transliterate into as many characters as it takes, e.g. `ß` into `ss`.
+    #[default]
+    Full,
+
+    /// Replace every character with exactly one character so that lengths and columns stay the
+    /// same.
+    ///
+    /// Characters which would be transliterated into several characters, such as `ß`, are left to
+    /// the [`ReplacementPolicy`] instead. Lengths only stay the same if it replaces them with a
+    /// single character, which the default does.
+    SingleChar,
+}
+
+/// What [`SwiftCharsetOptions`] does with characters which can't be transliterated, such as `!`.
+#[derive(Clone)]
+pub enum ReplacementPolicy {
+    /// Replace them with this character which has to be part of the SWIFT charset.
+    Char(char),
+
+    /// Remove them.
+    Remove,
+
+    /// Keep them as they are and only report them as [`SanitizeChangeKind::KeptChar`].
+    ///
+    /// The result won't parse unless the parser allows non-SWIFT characters.
+    Keep,
+
+    /// Replace them with whatever the function returns or remove them if it returns `None`.
+    ///
+    /// The function should only return characters of the SWIFT charset.
+    Custom(Arc<dyn Fn(char) -> Option<String> + Send + Sync>),
+}
+
+impl ReplacementPolicy {
+    /// Replace characters with whatever `f` returns, see [`ReplacementPolicy::Custom`].
+    pub fn custom(f: impl Fn(char) -> Option<String> + Send + Sync + 'static) -> ReplacementPolicy {
+        ReplacementPolicy::Custom(Arc::new(f))
+    }
+}
+
+impl Default for ReplacementPolicy {
+    fn default() -> ReplacementPolicy {
+        ReplacementPolicy::Char('.')
+    }
+}
+
+impl fmt::Debug for ReplacementPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplacementPolicy::Char(c) => f.debug_tuple("Char").field(c).finish(),
+            ReplacementPolicy::Remove => f.write_str("Remove"),
+            ReplacementPolicy::Keep => f.write_str("Keep"),
+            ReplacementPolicy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Two custom policies are only equal if they share the very same function.
+impl PartialEq for ReplacementPolicy {
+    fn eq(&self, other: &ReplacementPolicy) -> bool {
+        match (self, other) {
+            (ReplacementPolicy::Char(c), ReplacementPolicy::Char(other_c)) => c == other_c,
+            (ReplacementPolicy::Remove, ReplacementPolicy::Remove)
+            | (ReplacementPolicy::Keep, ReplacementPolicy::Keep) => true,
+            (ReplacementPolicy::Custom(f), ReplacementPolicy::Custom(other_f)) => {
+                Arc::ptr_eq(f, other_f)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ReplacementPolicy {}
+
+/// What [`SwiftCharsetOptions`] does about lines which are too long for their tag afterwards.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LengthLimits {
+    /// Leave them as they are.
+    #[default]
+    Ignore,
+
+    /// Cut lines of tag `:86:` at 65 characters and supplementary details of tag `:61:` at 34.
+    Truncate,
+
+    /// Wrap them like [`wrap_long_lines`] does.
+    Wrap,
+}
+
+/// A configurable variant of [`to_swift_charset`].
+///
+/// Transliteration can turn a single character into several, e.g. `ß` into `ss`, which pushes
+/// lines over the length their tag allows. These options either keep lengths as they are or
+/// enforce the limits after converting. The step is named like [`SwiftCharset`] so it can take
+/// its place in a [`SanitizerPipeline`].
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::{LengthLimits, SanitizerPipeline, SwiftCharsetOptions, Transliteration};
+///
+/// let input = format!(":86:{}ß\r\n", "x".repeat(64));
+///
+/// let options = SwiftCharsetOptions::new().transliteration(Transliteration::SingleChar);
+/// assert_eq!(options.convert(&input), format!(":86:{}.\r\n", "x".repeat(64)));
+///
+/// let options = SwiftCharsetOptions::new().length_limits(LengthLimits::Truncate);
+/// assert_eq!(options.convert(&input), format!(":86:{}s\r\n", "x".repeat(64)));
+///
+/// let pipeline = SanitizerPipeline::default()
+///     .without("to_swift_charset")
+///     .insert(0, SwiftCharsetOptions::new().length_limits(LengthLimits::Wrap));
+/// assert_eq!(
+///     pipeline.sanitize(&input),
+///     format!(":86:{}s\r\ns\r\n", "x".repeat(64))
+/// );
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SwiftCharsetOptions {
+    transliteration: Transliteration,
+    replacement: ReplacementPolicy,
+    length_limits: LengthLimits,
+}
+
+impl SwiftCharsetOptions {
+    /// Create [`SwiftCharsetOptions`] which convert exactly like [`to_swift_charset`].
+    pub fn new() -> SwiftCharsetOptions {
+        SwiftCharsetOptions::default()
+    }
+
+    /// Choose how to replace characters outside of the SWIFT charset.
+    pub fn transliteration(mut self, transliteration: Transliteration) -> SwiftCharsetOptions {
+        self.transliteration = transliteration;
+        self
+    }
+
+    /// Choose what to do with characters which can't be transliterated instead of replacing them
+    /// with a dot.
+    ///
+    /// ```
+    /// use mt940::sanitizers::{ReplacementPolicy, SwiftCharsetOptions};
+    ///
+    /// let options = SwiftCharsetOptions::new().replacement(ReplacementPolicy::Char(' '));
+    /// assert_eq!(options.convert(":86:Müller & Söhne\r\n"), ":86:Muller   Sohne\r\n");
+    ///
+    /// let options = SwiftCharsetOptions::new().replacement(ReplacementPolicy::custom(|c| {
+    ///     (c == '&').then(|| "+".to_string())
+    /// }));
+    /// assert_eq!(options.convert(":86:Müller & Söhne!\r\n"), ":86:Muller + Sohne\r\n");
+    /// ```
+    pub fn replacement(mut self, replacement: ReplacementPolicy) -> SwiftCharsetOptions {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Choose what to do about lines which are too long after converting.
+    pub fn length_limits(mut self, length_limits: LengthLimits) -> SwiftCharsetOptions {
+        self.length_limits = length_limits;
+        self
+    }
+
+    /// Convert `s` to the SWIFT charset according to these options.
+    ///
+    /// If `s` already conforms and lengths are ignored, it's returned as is without allocating.
+    pub fn convert<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        self.convert_reporting(s, &mut vec![])
+    }
+
+    fn convert_reporting<'a>(&self, s: &'a str, changes: &mut Vec<SanitizeChange>) -> Cow<'a, str> {
+        let converted = swift_charset_reporting(s, self, changes);
+        match self.length_limits {
+            LengthLimits::Ignore => converted,
+            LengthLimits::Truncate => {
+                Cow::Owned(truncate_long_lines_reporting(&converted, changes))
+            }
+            LengthLimits::Wrap => Cow::Owned(wrap_long_lines_reporting(&converted, changes)),
+        }
+    }
+}
+
+impl Sanitizer for SwiftCharsetOptions {
+    fn name(&self) -> &str {
+        "to_swift_charset"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        self.convert_reporting(input, changes).into_owned()
+    }
+}
+
+/// [`strip_stuff_between_messages`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripStuffBetweenMessages;
+
+impl Sanitizer for StripStuffBetweenMessages {
+    fn name(&self) -> &str {
+        "strip_stuff_between_messages"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        strip_stuff_between_messages_reporting(input, changes)
+    }
+}
+
+/// [`strip_excess_tag86_lines`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripExcessTag86Lines;
+
+impl Sanitizer for StripExcessTag86Lines {
+    fn name(&self) -> &str {
+        "strip_excess_tag86_lines"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        strip_excess_tag86_lines_reporting(input, changes)
+    }
+}
+
+/// [`escape_unknown_tags`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EscapeUnknownTags;
+
+impl Sanitizer for EscapeUnknownTags {
+    fn name(&self) -> &str {
+        "escape_unknown_tags"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        escape_unknown_tags_reporting(input, is_known_tag, changes)
+    }
+}
+
+/// [`normalize_amounts`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeAmounts;
+
+impl Sanitizer for NormalizeAmounts {
+    fn name(&self) -> &str {
+        "normalize_amounts"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        normalize_amounts_reporting(input, changes)
+    }
+}
+
+/// [`wrap_long_lines`] as a [`Sanitizer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WrapLongLines;
+
+impl Sanitizer for WrapLongLines {
+    fn name(&self) -> &str {
+        "wrap_long_lines"
+    }
+
+    fn sanitize(&self, input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+        wrap_long_lines_reporting(input, changes)
+    }
+}
+
+/// A plain function as a [`Sanitizer`] which doesn't report its changes.
+pub struct FnSanitizer<F> {
+    name: String,
+    f: F,
+}
+
+impl<F: Fn(&str) -> String> FnSanitizer<F> {
+    /// Create a step named `name` which runs `f`.
+    pub fn new(name: &str, f: F) -> FnSanitizer<F> {
+        FnSanitizer {
+            name: name.to_string(),
+            f,
+        }
+    }
+}
+
+impl<F: Fn(&str) -> String> Sanitizer for FnSanitizer<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn sanitize(&self, input: &str, _changes: &mut Vec<SanitizeChange>) -> String {
+        (self.f)(input)
+    }
+}
+
+/// A sequence of [`Sanitizer`]s which are run one after another.
+///
+/// The default pipeline is what [`sanitize`] runs. It can be extended with custom steps and
+/// built-in steps can be removed or moved.
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::{FnSanitizer, SanitizerPipeline};
+///
+/// // Keep all lines of tag 86 but fix up everything else, starting with our own step.
+/// let strip_bom = FnSanitizer::new("strip_bom", |s: &str| {
+///     s.trim_start_matches('\u{feff}').to_string()
+/// });
+/// let pipeline = SanitizerPipeline::default()
+///     .without("strip_excess_tag86_lines")
+///     .insert(0, strip_bom);
+/// assert_eq!(
+///     pipeline.names().collect::<Vec<_>>(),
+///     [
+///         "strip_bom",
+///         "to_swift_charset",
+///         "strip_stuff_between_messages"
+///     ]
+/// );
+/// assert_eq!(pipeline.sanitize("\u{feff}:20:a\r\n"), ":20:a\r\n");
+/// ```
+pub struct SanitizerPipeline {
+    steps: Vec<Box<dyn Sanitizer + Send + Sync>>,
+    line_ending: Option<LineEnding>,
+}
+
+impl Default for SanitizerPipeline {
+    fn default() -> SanitizerPipeline {
+        SanitizerPipeline::new()
+            .push(SwiftCharset)
+            .push(StripStuffBetweenMessages)
+            .push(StripExcessTag86Lines)
+            .line_ending(LineEnding::CrLf)
+    }
+}
+
+impl fmt::Debug for SanitizerPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl SanitizerPipeline {
+    /// Create a new pipeline without any steps.
+    pub fn new() -> SanitizerPipeline {
+        SanitizerPipeline {
+            steps: vec![],
+            line_ending: None,
+        }
+    }
+
+    /// Terminate all lines of the output with `line_ending` after running all steps.
+    ///
+    /// Steps are free to write either line ending, so without this, the output may contain both.
+    /// The default pipeline writes `\r\n`.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::sanitizers::SanitizerPipeline;
+    /// use mt940::LineEnding;
+    ///
+    /// let pipeline = SanitizerPipeline::default().line_ending(LineEnding::Lf);
+    /// assert_eq!(pipeline.sanitize(":20:a\r\n:86:Grüße\n"), ":20:a\n:86:Grusse\n");
+    /// ```
+    pub fn line_ending(mut self, line_ending: LineEnding) -> SanitizerPipeline {
+        self.line_ending = Some(line_ending);
+        self
+    }
+
+    /// Append `step` to the end of the pipeline.
+    pub fn push<S: Sanitizer + Send + Sync + 'static>(mut self, step: S) -> SanitizerPipeline {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Insert `step` at `index`.
+    ///
+    /// If `index` is past the end, the step is appended.
+    pub fn insert<S: Sanitizer + Send + Sync + 'static>(
+        mut self,
+        index: usize,
+        step: S,
+    ) -> SanitizerPipeline {
+        self.steps
+            .insert(index.min(self.steps.len()), Box::new(step));
+        self
+    }
+
+    /// Remove all steps named `name`.
+    pub fn without(mut self, name: &str) -> SanitizerPipeline {
+        self.steps.retain(|step| step.name() != name);
+        self
+    }
+
+    /// The names of all steps in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().map(|step| step.name())
+    }
+
+    /// Run all steps on `input`.
+    pub fn sanitize(&self, input: &str) -> String {
+        self.sanitize_with_report(input).0
+    }
+
+    /// Run all steps on `input` and report every change they made.
+    ///
+    /// Line numbers in the report refer to `input`.
+    pub fn sanitize_with_report(&self, input: &str) -> (String, SanitizeReport) {
+        let mut text = input.to_string();
+        let mut lines = LineMap::new(input);
+        let mut changes = vec![];
+
+        for step in &self.steps {
+            let mut step_changes = vec![];
+            let sanitized = step.sanitize(&text, &mut step_changes);
+            lines.update(&mut step_changes, &sanitized);
+            changes.extend(step_changes);
+            text = sanitized;
+        }
+
+        if let Some(line_ending) = self.line_ending {
+            text = normalize_line_endings(&text, line_ending);
+        }
+
+        changes.sort_by_key(|change| change.line);
+        (text, SanitizeReport { changes })
+    }
+}
+
+/// Keeps track of the line of the original input each line of sanitized text came from.
+#[derive(Debug, Clone)]
+pub(crate) struct LineMap {
+    /// The line of the original input for each line of the sanitized text.
+    origins: Vec<usize>,
+}
+
+impl LineMap {
+    pub(crate) fn new(input: &str) -> LineMap {
+        LineMap {
+            origins: (1..=input.lines().count()).collect(),
+        }
+    }
+
+    /// Account for a step which turned the text into `sanitized`, making `changes`.
+    ///
+    /// The lines of `changes` are moved from the text the step ran on to the original input.
+    pub(crate) fn update(&mut self, changes: &mut [SanitizeChange], sanitized: &str) {
+        let resulting_lines = changes
+            .iter()
+            .filter_map(|change| Some((change.line, change.kind.resulting_lines()?)))
+            .rev()
+            .collect::<HashMap<_, _>>();
+        let origins = self
+            .origins
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &origin)| {
+                let lines = resulting_lines.get(&(i + 1)).copied().unwrap_or(1);
+                std::iter::repeat_n(origin, lines)
+            })
+            .collect::<Vec<_>>();
+        for change in changes {
+            change.line = self.original_line(change.line);
+        }
+        // Only trust our bookkeeping if it adds up. Otherwise, the step moved lines without
+        // telling us.
+        if origins.len() == sanitized.lines().count() {
+            self.origins = origins;
+        }
+    }
+
+    /// The line of the original input which `line` of the sanitized text came from.
+    pub(crate) fn original_line(&self, line: usize) -> usize {
+        match line.checked_sub(1).and_then(|i| self.origins.get(i)) {
+            Some(&origin) => origin,
+            // Past the last line, e.g. at the very end of the text.
+            None => match self.origins.last() {
+                Some(&last) => last + line.saturating_sub(self.origins.len()),
+                None => line,
+            },
+        }
+    }
+
+    /// Move `span` from the `sanitized` text to the original `input`.
+    ///
+    /// Offsets within a line are kept, so they are off on lines where characters were replaced by
+    /// a different number of bytes.
+    pub(crate) fn map_span(&self, span: Span, sanitized: &LineStarts, input: &LineStarts) -> Span {
+        let map_offset = |offset: usize| {
+            let line = sanitized.line(offset);
+            let offset_in_line = offset - sanitized.start(line);
+            input.offset(self.original_line(line), offset_in_line)
+        };
+        let start = map_offset(span.start);
+        Span {
+            start,
+            end: map_offset(span.end).max(start),
+            line: self.original_line(span.line),
+            column: span.column,
+        }
+    }
+}
+
+/// Byte offsets of the lines of a text.
+#[derive(Debug, Clone)]
+pub(crate) struct LineStarts<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineStarts<'a> {
+    pub(crate) fn new(text: &'a str) -> LineStarts<'a> {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineStarts { text, starts }
+    }
+
+    /// The line containing `offset`, starting at 1.
+    fn line(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Byte offset of the start of `line`.
+    fn start(&self, line: usize) -> usize {
+        let index = line
+            .saturating_sub(1)
+            .min(self.starts.len().saturating_sub(1));
+        self.starts.get(index).copied().unwrap_or(0)
+    }
+
+    /// Byte offset `offset_in_line` bytes into `line`, kept within the line.
+    fn offset(&self, line: usize, offset_in_line: usize) -> usize {
+        let start = self.start(line);
+        let end = self.starts.get(line).copied().unwrap_or(self.text.len());
+        let mut offset = (start + offset_in_line).min(end);
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
 }
 
 /// Try to make a given input conform to the SWIFT MT101 allowed charset.
@@ -24,39 +814,181 @@ pub fn sanitize(s: &str) -> String {
 /// like 'ä', 'ö', 'ü', 'ú' and so and converts them into their sensible ASCII equivalents.
 /// Any remaining non-SWIFT characters (like '!', '=', etc) will be replaced with a dot ('.') each.
 /// [SWIFT MT101 characters reference here](http://www.sepaforcorporates.com/swift-for-corporates/quick-guide-swift-mt101-format/).
-pub fn to_swift_charset(s: &str) -> String {
-    // Parse the string char by char and see whether it's a conforming swift char. If it isn't,
-    // we'll want to run deunicode.
-    s.chars()
-        .map(|x| {
-            let char_as_string = x.to_string();
-            let parsed = MT940Parser::parse(Rule::swift_char, &char_as_string);
-            // If parsing succeeds, we already have a SWIFT charset allowable character, yay!
-            // However, if it doesn't, we'll have to be sensible and smart about it...
-            if parsed.is_ok() {
-                char_as_string.clone()
-            } else {
-                // This is the first attempt to make a non-SWIFT character into an allowed character.
-                let deunicoded = if x == 'ä' {
-                    // Due to https://github.com/kornelski/deunicode/issues/15, we'll deunicode 'ä'
-                    // ourselves.
-                    "a".to_string()
-                } else {
-                    deunicode_char(x).unwrap_or(".").to_string()
-                };
-                // Also note that we have to use the `Rule::swift_chars` here because a single
-                // Unicode character might be deunicoded to multiple ASCII chars!
-                let parsed_after_deunicode = MT940Parser::parse(Rule::swift_chars, &deunicoded);
-                if parsed_after_deunicode.is_ok() {
-                    deunicoded.clone()
-                } else {
-                    // If all else fails, we can only replace this character with a dot and move
-                    // on.
-                    ".".to_string()
+///
+/// If `s` already conforms, it's returned as is without allocating.
+///
+/// See [`SwiftCharsetOptions`] to keep lines within the lengths their tags allow.
+pub fn to_swift_charset(s: &str) -> Cow<'_, str> {
+    swift_charset_reporting(s, &SwiftCharsetOptions::default(), &mut vec![])
+}
+
+pub(crate) fn swift_charset_reporting<'a>(
+    s: &'a str,
+    options: &SwiftCharsetOptions,
+    changes: &mut Vec<SanitizeChange>,
+) -> Cow<'a, str> {
+    // Most input is fine already in which case there's nothing to do.
+    if s.chars().all(is_swift_char) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut line = 1;
+    let mut column = 0;
+    let mut sanitized = String::with_capacity(s.len());
+    // Go through the string char by char and see whether it's a conforming swift char. If it
+    // isn't, we'll want to run deunicode.
+    for x in s.chars() {
+        column += 1;
+        if x == '\n' {
+            line += 1;
+            column = 0;
+        }
+        // If it's already a SWIFT charset allowable character, yay! However, if it isn't, we'll
+        // have to be sensible and smart about it...
+        if is_swift_char(x) {
+            sanitized.push(x);
+            continue;
+        }
+        // This is the first attempt to make a non-SWIFT character into an allowed character.
+        let deunicoded = if x == 'ä' {
+            // Due to https://github.com/kornelski/deunicode/issues/15, we'll deunicode 'ä'
+            // ourselves.
+            "a"
+        } else {
+            deunicode_char(x).unwrap_or(".")
+        };
+        // Also note that we have to check every char here because a single Unicode character
+        // might be deunicoded to multiple ASCII chars!
+        let fits = match options.transliteration {
+            Transliteration::Full => !deunicoded.is_empty(),
+            Transliteration::SingleChar => deunicoded.chars().count() == 1,
+        };
+        let replacement = if fits && deunicoded.chars().all(is_swift_char) {
+            deunicoded.to_string()
+        } else {
+            // If all else fails, it's up to the policy what to do with this character.
+            match options.replacement {
+                ReplacementPolicy::Char(c) => c.to_string(),
+                ReplacementPolicy::Remove => String::new(),
+                ReplacementPolicy::Keep => {
+                    sanitized.push(x);
+                    changes.push(SanitizeChange {
+                        line,
+                        kind: SanitizeChangeKind::KeptChar {
+                            column,
+                            original: x,
+                        },
+                    });
+                    continue;
                 }
+                ReplacementPolicy::Custom(ref f) => f(x).unwrap_or_default(),
             }
+        };
+        sanitized.push_str(&replacement);
+        changes.push(SanitizeChange {
+            line,
+            kind: SanitizeChangeKind::ReplacedChar {
+                column,
+                original: x,
+                replacement,
+            },
+        });
+    }
+    Cow::Owned(sanitized)
+}
+
+/// A character outside of the SWIFT charset found by [`validate_swift_charset`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CharsetViolation {
+    /// 1-based line of the character.
+    pub line: usize,
+
+    /// 1-based column of the character, counted in characters.
+    pub column: usize,
+
+    /// Byte offset of the character in the input.
+    pub offset: usize,
+
+    pub character: char,
+}
+
+impl fmt::Display for CharsetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: non-SWIFT character {:?}",
+            self.line, self.column, self.character
+        )
+    }
+}
+
+/// Check that `s` only consists of characters of the SWIFT charset without changing anything.
+///
+/// Unlike [`to_swift_charset`], this is meant for rejecting non-conforming input. Every offending
+/// character is reported along with its position.
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::validate_swift_charset;
+///
+/// assert_eq!(validate_swift_charset(":86:Gruss\r\n"), Ok(()));
+///
+/// let violations = validate_swift_charset(":20:a\r\n:86:Grüße\r\n").unwrap_err();
+/// assert_eq!(violations.len(), 2);
+/// assert_eq!((violations[0].line, violations[0].column), (2, 7));
+/// assert_eq!(violations[0].character, 'ü');
+/// ```
+pub fn validate_swift_charset(s: &str) -> Result<(), Vec<CharsetViolation>> {
+    let mut line = 1;
+    let mut column = 0;
+    let violations = s
+        .char_indices()
+        .filter_map(|(offset, character)| {
+            column += 1;
+            if character == '\n' {
+                line += 1;
+                column = 0;
+            }
+            (!is_swift_char(character)).then_some(CharsetViolation {
+                line,
+                column,
+                offset,
+                character,
+            })
         })
-        .collect()
+        .collect::<Vec<_>>();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Whether `c` is allowed by the SWIFT charset.
+///
+/// This mirrors `swift_char` in the grammar.
+pub(crate) fn is_swift_char(c: char) -> bool {
+    matches!(
+        c,
+        'a'..='z'
+            | 'A'..='Z'
+            | '0'..='9'
+            | '/'
+            | '-'
+            | '?'
+            | ':'
+            | '('
+            | ')'
+            | '.'
+            | ','
+            | '‘'
+            | '+'
+            | '{'
+            | '}'
+            | ' '
+            | '\r'
+            | '\n'
+    )
 }
 
 /// Remove stuff between messages.
@@ -74,122 +1006,506 @@ pub fn to_swift_charset(s: &str) -> String {
 /// ...
 /// ```
 ///
-/// However, this makes them uncompliant.
+/// A single `-` terminates a message in SWIFT and is accepted by the parser as is. Anything else,
+/// such as `--` or empty lines, makes them uncompliant.
 ///
 /// This sanitizer gets rid of that.
 pub fn strip_stuff_between_messages(s: &str) -> String {
-    // Find all :20: tags and remove any non-tag lines before those.
-    let total_lines = s.lines().count();
-    let mut lines_with_tag_20 = vec![];
-    let mut lines_with_tags = vec![];
-    let mut last_tag = "20";
+    strip_stuff_between_messages_reporting(s, &mut vec![])
+}
+
+pub(crate) fn strip_stuff_between_messages_reporting(
+    s: &str,
+    changes: &mut Vec<SanitizeChange>,
+) -> String {
+    // Find all :20: tags and remove any non-tag lines before those. Non-tag lines are held back
+    // until we know what follows them.
+    let mut output = String::with_capacity(s.len() + s.len() / 32);
+    let mut pending: Vec<(usize, &str)> = vec![];
+    let mut last_tag = None;
 
-    // Do one pass to find all the indices of tag 20 and non-tag 20 lines.
     for (i, line) in s.lines().enumerate() {
         let parsed = MT940Parser::parse(Rule::field, line);
-        if let Ok(mut parsed) = parsed {
-            last_tag = parsed
-                .next()
-                .unwrap()
-                .into_inner()
-                .next()
-                .unwrap()
-                .into_inner()
-                .next()
-                .unwrap()
-                .as_str();
-            if last_tag == "20" {
-                lines_with_tag_20.push(i);
-            }
-            lines_with_tags.push(i);
+        let tag = parsed.ok().and_then(|mut parsed| {
+            let field = parsed.next()?;
+            let tag = field.into_inner().next()?;
+            tag.into_inner().next()
+        });
+        let Some(tag) = tag else {
+            pending.push((i, line));
+            continue;
+        };
+        if tag.as_str() == "20" {
+            remove_between_messages(pending.drain(..), changes);
+        } else {
+            push_lines(&mut output, pending.drain(..).map(|(_, line)| line));
+        }
+        push_lines(&mut output, std::iter::once(line));
+        last_tag = Some(tag.as_str());
+    }
+
+    // There is a special case to handle for the end of the file:
+    // If the very last tag is a tag 86 then we'll allow any non-tag lines after it towards the end
+    // of the file. However, if the last tag is a non-86-tag then we'll remove any additional lines
+    // up to the last tag. Without any tags at all, the first line is kept.
+    match last_tag {
+        Some("86") => push_lines(&mut output, pending.into_iter().map(|(_, line)| line)),
+        Some(_) => remove_between_messages(pending.into_iter(), changes),
+        None => {
+            let mut pending = pending.into_iter();
+            push_lines(
+                &mut output,
+                pending.next().map(|(_, line)| line).into_iter(),
+            );
+            remove_between_messages(pending, changes);
         }
     }
 
-    let mut lines_to_delete = vec![];
+    output
+}
 
-    // Do a second pass to figure out which lines we don't want.
-    for tag_20_index in lines_with_tag_20 {
-        // From the tag 20 index, travel upwards until either hitting another tag's index or
-        // until hitting index 0.
-        let mut i = tag_20_index;
-        while i > 0 {
-            i -= 1;
+/// Record the removal of `lines` between messages.
+fn remove_between_messages<'a>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    changes: &mut Vec<SanitizeChange>,
+) {
+    changes.extend(lines.map(|(i, content)| SanitizeChange {
+        line: i + 1,
+        kind: SanitizeChangeKind::RemovedBetweenMessages {
+            content: content.to_string(),
+        },
+    }));
+}
 
-            // Terminate on the first line that contains another tag.
-            if lines_with_tags.contains(&i) {
-                break;
-            } else {
-                lines_to_delete.push(i);
+/// Keep lines of tag `:86:` which start with something that looks like a tag from starting a new
+/// field.
+///
+/// Free text sometimes has a line break right in front of something like `:12:`, e.g. in the
+/// middle of a time. Such lines are indented by a space unless their tag is part of MT940 or
+/// MT942 or is `:NS:`, in which case they can't be told apart from a new field and are left alone.
+///
+/// As this changes the text of tag `:86:`, it isn't part of [`sanitize`]. Prefer
+/// [`ParserConfig::escape_unknown_tags`] which keeps such lines as they are.
+///
+/// [`ParserConfig::escape_unknown_tags`]: crate::ParserConfig::escape_unknown_tags
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::escape_unknown_tags;
+///
+/// let input = ":86:Paid on 2017-01-01T13\r\n:12:11\r\n:62F:C160301EUR1194,00\r\n";
+/// let expected = ":86:Paid on 2017-01-01T13\r\n :12:11\r\n:62F:C160301EUR1194,00\r\n";
+/// assert_eq!(escape_unknown_tags(input), expected);
+/// ```
+pub fn escape_unknown_tags(input: &str) -> String {
+    escape_unknown_tags_reporting(input, is_known_tag, &mut vec![])
+}
+
+/// Whether `tag` starts a new field even within tag `:86:`.
+pub(crate) fn is_known_tag(tag: &str) -> bool {
+    tag == "NS" || Tag::known(tag).is_some()
+}
+
+fn escape_unknown_tags_reporting(
+    input: &str,
+    is_known: impl Fn(&str) -> bool,
+    changes: &mut Vec<SanitizeChange>,
+) -> String {
+    let mut output = String::with_capacity(input.len() + input.len() / 32);
+    let mut in_tag_86 = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .map(|(tag, _)| tag)
+            .filter(|tag| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric()));
+        match tag {
+            Some(tag) if in_tag_86 && !is_known(tag) => {
+                changes.push(SanitizeChange {
+                    line: i + 1,
+                    kind: SanitizeChangeKind::EscapedTag {
+                        tag: tag.to_string(),
+                    },
+                });
+                output.push(' ');
             }
+            Some(tag) => in_tag_86 = tag == "86",
+            None => (),
         }
+        push_lines(&mut output, std::iter::once(line));
     }
 
-    // There is a special case to handle for the end of the file:
-    // If the very last tag is a tag 86 then we'll allow any non-tag lines after it towards the end
-    // of the file. However, if the last tag is a non-86-tag then we'll remove any additional lines
-    // up to the last tag.
-    if last_tag != "86" {
-        let last_tag_index = *lines_with_tags.last().unwrap_or(&0) + 1;
-        lines_to_delete.extend(last_tag_index..total_lines);
-    }
-
-    // Do a third pass to actually copy only the wanted lines from the input to the output.
-    s.lines()
-        .enumerate()
-        .filter(|&(i, _contents)| (!lines_to_delete.contains(&i)))
-        .map(|(_i, contents)| contents)
-        .chain(std::iter::once(""))
-        .collect::<Vec<&str>>()
-        .join("\r\n")
+    output
 }
 
 /// Remove excess lines on tag 86 statements beyond the 6 allowed.
 ///
 /// Note that you potentially lose information with this sanitizer.
 pub fn strip_excess_tag86_lines(input: &str) -> String {
-    let mut lines_to_delete = vec![];
+    strip_excess_tag86_lines_reporting(input, &mut vec![])
+}
 
-    // Get a list of lines where tag 86 messages start.
-    let tag_86_lines = input.lines().enumerate().filter_map(|(line, contents)| {
-        if contents.starts_with(":86:") {
-            Some(line)
-        } else {
-            None
+fn strip_excess_tag86_lines_reporting(input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+    let mut output = String::with_capacity(input.len() + input.len() / 32);
+    // Number of additional lines of the current tag 86 or `None` if we're not in one.
+    let mut additional_lines = None;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.starts_with(':') {
+            additional_lines = line.starts_with(":86:").then_some(0);
+        } else if let Some(ref mut count) = additional_lines {
+            *count += 1;
+            // Only 5 additional lines are allowed (6 in total counting the first line).
+            if *count > 5 {
+                changes.push(SanitizeChange {
+                    line: i + 1,
+                    kind: SanitizeChangeKind::RemovedExcessTag86Line {
+                        content: line.to_string(),
+                    },
+                });
+                continue;
+            }
         }
-    });
+        push_lines(&mut output, std::iter::once(line));
+    }
 
-    for line_no in tag_86_lines {
-        let lines = input.lines().skip(line_no + 1);
+    output
+}
 
-        // Find all lines excess of the 5 allowed additional lines (6 in total counting the skipped line above).
-        let to_delete = lines
-            .enumerate()
-            .take_while(|(_, contents)| !contents.starts_with(':'))
-            .filter_map(move |(line, _)| {
-                if line >= 5 {
-                    Some(line + line_no + 1)
-                } else {
-                    None
+/// Wrap lines which are too long for their tag.
+///
+/// Lines of tag `:86:` longer than 65 characters are wrapped onto new lines. If a tag `:86:` ends
+/// up with more than 6 lines, it is continued in another tag `:86:`. Supplementary details of tag
+/// `:61:` can't be continued and are cut at 34 characters instead.
+///
+/// Use this instead of [`strip_excess_tag86_lines`] to keep all information. It isn't part of
+/// [`sanitize`] but can be swapped in via [`SanitizerPipeline`]:
+///
+/// ```
+/// use mt940::sanitizers::{SanitizerPipeline, WrapLongLines};
+///
+/// let pipeline = SanitizerPipeline::default()
+///     .without("strip_excess_tag86_lines")
+///     .push(WrapLongLines);
+/// ```
+///
+/// ```
+/// use mt940::sanitizers::wrap_long_lines;
+///
+/// let input = format!(":86:{}\r\n", "x".repeat(70));
+/// let expected = format!(":86:{}\r\n{}\r\n", "x".repeat(65), "x".repeat(5));
+/// assert_eq!(wrap_long_lines(&input), expected);
+/// ```
+pub fn wrap_long_lines(input: &str) -> String {
+    wrap_long_lines_reporting(input, &mut vec![])
+}
+
+fn wrap_long_lines_reporting(input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+    let mut lines = vec![];
+    // Number of lines of the current tag :86: or `None` if we're not in one.
+    let mut tag_86_lines = None;
+    let mut after_tag_61 = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let line_no = i + 1;
+        if line.starts_with(':') {
+            after_tag_61 = line.starts_with(":61:");
+            if !line.starts_with(":86:") {
+                tag_86_lines = None;
+                lines.push(line.to_string());
+                continue;
+            }
+            tag_86_lines = Some(0);
+        }
+
+        match tag_86_lines {
+            Some(ref mut count) => {
+                let content = line.strip_prefix(":86:").filter(|_| *count == 0);
+                let chunks = split_tag_86_line(content.unwrap_or(line));
+                if chunks.len() > 1 {
+                    changes.push(SanitizeChange {
+                        line: line_no,
+                        kind: SanitizeChangeKind::WrappedTag86Line {
+                            lines: chunks.len(),
+                        },
+                    });
+                }
+                for chunk in chunks {
+                    if *count == 0 {
+                        lines.push(format!(":86:{}", chunk));
+                    } else if *count % TAG_86_MAX_LINES == 0 {
+                        changes.push(SanitizeChange {
+                            line: line_no,
+                            kind: SanitizeChangeKind::ContinuedTag86,
+                        });
+                        lines.push(format!(":86:{}", chunk));
+                    } else {
+                        lines.push(chunk);
+                    }
+                    *count += 1;
+                }
+            }
+            None if after_tag_61 && !line.starts_with(':') => {
+                // This is the line of supplementary details. There is only one.
+                after_tag_61 = false;
+                match line.char_indices().nth(SUPPLEMENTARY_DETAILS_LENGTH) {
+                    Some((cut, _)) => {
+                        let (kept, removed) = line.split_at(cut);
+                        changes.push(SanitizeChange {
+                            line: line_no,
+                            kind: SanitizeChangeKind::TruncatedSupplementaryDetails {
+                                removed: removed.to_string(),
+                            },
+                        });
+                        lines.push(kept.to_string());
+                    }
+                    None => lines.push(line.to_string()),
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(""))
+        .collect::<Vec<&str>>()
+        .join("\r\n")
+}
+
+/// Cut lines of tag `:86:` at 65 characters and supplementary details of tag `:61:` at 34.
+fn truncate_long_lines_reporting(input: &str, changes: &mut Vec<SanitizeChange>) -> String {
+    let mut in_tag_86 = false;
+    let mut after_tag_61 = false;
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (prefix, content) = if line.starts_with(':') {
+                after_tag_61 = line.starts_with(":61:");
+                in_tag_86 = line.starts_with(":86:");
+                match line.strip_prefix(":86:") {
+                    Some(content) => (":86:", content),
+                    None => return line.to_string(),
                 }
+            } else if in_tag_86 || after_tag_61 {
+                ("", line)
+            } else {
+                return line.to_string();
+            };
+            let max_length = if in_tag_86 {
+                TAG_86_LINE_LENGTH
+            } else {
+                // This is the line of supplementary details. There is only one.
+                after_tag_61 = false;
+                SUPPLEMENTARY_DETAILS_LENGTH
+            };
+            let Some((cut, _)) = content.char_indices().nth(max_length) else {
+                return line.to_string();
+            };
+            let (kept, removed) = content.split_at(cut);
+            let removed = removed.to_string();
+            changes.push(SanitizeChange {
+                line: i + 1,
+                kind: if in_tag_86 {
+                    SanitizeChangeKind::TruncatedTag86Line { removed }
+                } else {
+                    SanitizeChangeKind::TruncatedSupplementaryDetails { removed }
+                },
             });
+            format!("{}{}", prefix, kept)
+        })
+        .chain(std::iter::once(String::new()))
+        .collect::<Vec<String>>()
+        .join("\r\n")
+}
 
-        lines_to_delete.extend(to_delete);
-    }
+/// Rewrite amounts of balances and statement lines into the form MT940 requires.
+///
+/// Amounts in tags `:60:`, `:61:`, `:62:`, `:64:` and `:65:` which use `.` as the decimal
+/// separator or contain thousands separators (`.`, `,` or `'`) get a comma as the decimal
+/// separator and lose their thousands separators. The last separator is taken as the decimal
+/// separator unless it occurs more than once. Amounts which are already valid are left alone.
+///
+/// That guess is wrong for amounts like `1.000` meaning a thousand, so this isn't part of
+/// [`sanitize`]. Add it via [`SanitizerPipeline`] if your bank writes amounts like this:
+///
+/// ```
+/// use mt940::sanitizers::{NormalizeAmounts, SanitizerPipeline};
+///
+/// let pipeline = SanitizerPipeline::default().push(NormalizeAmounts);
+/// ```
+///
+/// ```
+/// use mt940::sanitizers::normalize_amounts;
+///
+/// let input = ":60F:C090924EUR1.234.567,89\r\n:62F:C090924EUR1,234.5\r\n";
+/// let expected = ":60F:C090924EUR1234567,89\r\n:62F:C090924EUR1234,5\r\n";
+/// assert_eq!(normalize_amounts(input), expected);
+/// ```
+pub fn normalize_amounts(input: &str) -> String {
+    normalize_amounts_reporting(input, &mut vec![])
+}
 
+fn normalize_amounts_reporting(input: &str, changes: &mut Vec<SanitizeChange>) -> String {
     input
         .lines()
         .enumerate()
-        .filter(|&(line, _contents)| (!lines_to_delete.contains(&line)))
-        .map(|(_line, contents)| contents)
-        .chain(std::iter::once(""))
-        .collect::<Vec<&str>>()
+        .map(|(i, line)| {
+            let Some((start, end)) = amount_position(line) else {
+                return line.to_string();
+            };
+            let (Some(before), Some(original), Some(rest)) =
+                (line.get(..start), line.get(start..end), line.get(end..))
+            else {
+                return line.to_string();
+            };
+            let normalized = normalize_amount(original);
+            if normalized == original {
+                return line.to_string();
+            }
+            changes.push(SanitizeChange {
+                line: i + 1,
+                kind: SanitizeChangeKind::NormalizedAmount {
+                    original: original.to_string(),
+                    normalized: normalized.clone(),
+                },
+            });
+            format!("{}{}{}", before, normalized, rest)
+        })
+        .chain(std::iter::once(String::new()))
+        .collect::<Vec<String>>()
         .join("\r\n")
 }
 
+/// Byte offsets of the amount in a line of a balance tag or tag `:61:`.
+fn amount_position(line: &str) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let is_digits = |range: std::ops::Range<usize>| {
+        bytes
+            .get(range)
+            .is_some_and(|digits| digits.iter().all(u8::is_ascii_digit))
+    };
+
+    let start = if let Some(tag_len) = [":60F:", ":60M:", ":62F:", ":62M:", ":64:", ":65:"]
+        .iter()
+        .find(|tag| line.starts_with(*tag))
+        .map(|tag| tag.len())
+    {
+        // Debit/credit mark, date and currency code.
+        let currency_start = tag_len + 7;
+        let valid = matches!(bytes.get(tag_len), Some(b'C' | b'D'))
+            && is_digits(tag_len + 1..currency_start)
+            && bytes
+                .get(currency_start..currency_start + 3)
+                .is_some_and(|code| code.iter().all(u8::is_ascii_alphabetic));
+        if !valid {
+            return None;
+        }
+        currency_start + 3
+    } else if line.starts_with(":61:") {
+        let mut position = 4;
+        if !is_digits(position..position + 6) {
+            return None;
+        }
+        position += 6;
+        if is_digits(position..position + 4) {
+            position += 4;
+        }
+        position += match bytes.get(position..position + 2) {
+            Some(b"RC" | b"RD") => 2,
+            _ if matches!(bytes.get(position), Some(b'C' | b'D')) => 1,
+            _ => return None,
+        };
+        if bytes.get(position).is_some_and(u8::is_ascii_alphabetic) {
+            position += 1;
+        }
+        position
+    } else {
+        return None;
+    };
+
+    let len = bytes
+        .get(start..)?
+        .iter()
+        .take_while(|&&b| b.is_ascii_digit() || matches!(b, b'.' | b',' | b'\''))
+        .count();
+    Some((start, start + len))
+}
+
+/// Rewrite `amount` with a comma as the decimal separator and without thousands separators.
+///
+/// Amounts with a single comma and no other separators are returned unchanged.
+fn normalize_amount(amount: &str) -> String {
+    let is_separator = |c: char| matches!(c, '.' | ',' | '\'');
+    if !amount.contains(['.', '\'']) && amount.matches(',').count() <= 1 {
+        return amount.to_string();
+    }
+
+    // The last `.` or `,` is the decimal separator unless it's used more than once.
+    let decimal_position = amount.rfind(['.', ',']).filter(|&i| {
+        amount
+            .get(i..=i)
+            .is_some_and(|separator| amount.matches(separator).count() == 1)
+    });
+    let (integer, fraction) = decimal_position
+        .and_then(|i| Some((amount.get(..i)?, amount.get(i + 1..)?)))
+        .unwrap_or((amount, ""));
+    let integer = integer.replace(is_separator, "");
+    format!(
+        "{},{}",
+        if integer.is_empty() { "0" } else { &integer },
+        fraction
+    )
+}
+
+/// Terminate all lines of `input` with `line_ending`.
+///
+/// Lines may end in `\r\n`, `\n` or a lone `\r`. A missing line ending at the end of `input` is
+/// not added.
+///
+/// # Example
+/// ```
+/// use mt940::sanitizers::normalize_line_endings;
+/// use mt940::LineEnding;
+///
+/// let input = ":20:a\r\n:25:b\n:28C:1\r:60F:C090924EUR1,";
+/// assert_eq!(
+///     normalize_line_endings(input, LineEnding::Lf),
+///     ":20:a\n:25:b\n:28C:1\n:60F:C090924EUR1,"
+/// );
+/// ```
+pub fn normalize_line_endings(input: &str, line_ending: LineEnding) -> String {
+    let mut output = String::with_capacity(input.len() + input.len() / 32);
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                output.push_str(line_ending.as_str());
+            }
+            '\n' => output.push_str(line_ending.as_str()),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Append `lines` to `output`, each terminated with `\r\n`.
+fn push_lines<'a>(output: &mut String, lines: impl Iterator<Item = &'a str>) {
+    for line in lines {
+        output.push_str(line);
+        output.push_str("\r\n");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
-    use proptest::{prop_assert, proptest};
+    use proptest::{prop_assert, prop_assert_eq, proptest};
     use rstest::rstest;
 
     use super::*;
@@ -203,6 +1519,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_swift_charset_positions() {
+        let violations = validate_swift_charset("a!\r\n€b\r\nc").unwrap_err();
+        assert_eq!(
+            violations,
+            vec![
+                CharsetViolation {
+                    line: 1,
+                    column: 2,
+                    offset: 1,
+                    character: '!',
+                },
+                CharsetViolation {
+                    line: 2,
+                    column: 1,
+                    offset: 4,
+                    character: '€',
+                },
+            ]
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "line 2, column 1: non-SWIFT character '€'"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn is_swift_char_matches_grammar(c in proptest::char::any()) {
+            let mut buffer = [0; 4];
+            let parsed = MT940Parser::parse(Rule::swift_char, c.encode_utf8(&mut buffer));
+            prop_assert_eq!(is_swift_char(c), parsed.is_ok());
+        }
+    }
+
+    #[test]
+    fn to_swift_charset_borrows_conforming_input() {
+        assert!(matches!(to_swift_charset(":20:abc\r\n"), Cow::Borrowed(_)));
+    }
+
     #[test]
     fn to_swift_charset_sanitize_sentence() {
         let input = "hällö waß íst lös";
@@ -233,6 +1589,288 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn sanitize_reports_changes() {
+        let input = "\
+            :20:a\r\n\
+            :86:1\r\n\
+            2\r\n\
+            3\r\n\
+            4\r\n\
+            5\r\n\
+            6\r\n\
+            7!\r\n\
+            :62F:C090924EUR1,\r\n\
+            -\r\n\
+            :20:b\r\n\
+            :86:1\r\n\
+            2\r\n\
+            3\r\n\
+            4\r\n\
+            5\r\n\
+            6\r\n\
+            7\r\n";
+        let (sanitized, report) = sanitize_with_report(input);
+        assert_eq!(sanitized, sanitize(input));
+        let changes = report
+            .changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "line 8, column 2: replaced non-SWIFT character '!' with '.'",
+                "line 8: removed '7.' exceeding the 6 lines of tag 86",
+                "line 10: removed '-' between messages",
+                "line 18: removed '7' exceeding the 6 lines of tag 86",
+            ]
+        );
+    }
+
+    #[test]
+    fn pipeline_maps_lines_of_later_steps() {
+        let input = ":20:a\r\n-\r\n:20:b\r\n:86:Grüße\r\n";
+        let mark_umlauts = FnSanitizer::new("mark", |s: &str| s.replace("ü", "ü!"));
+        let pipeline = SanitizerPipeline::new()
+            .push(StripStuffBetweenMessages)
+            .push(mark_umlauts)
+            .push(SwiftCharset);
+        let (sanitized, report) = pipeline.sanitize_with_report(input);
+        assert_eq!(sanitized, ":20:a\r\n:20:b\r\n:86:Gru.sse\r\n");
+        let lines = report
+            .changes
+            .iter()
+            .map(|change| change.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 4, 4, 4]);
+    }
+
+    #[test]
+    fn pipeline_without_steps() {
+        let pipeline = SanitizerPipeline::default()
+            .without("to_swift_charset")
+            .without("strip_stuff_between_messages")
+            .without("strip_excess_tag86_lines");
+        assert_eq!(pipeline.names().count(), 0);
+        assert_eq!(pipeline.sanitize("äöü"), "äöü");
+    }
+
+    #[test]
+    fn wrap_long_lines_continues_tag_86() {
+        let input = format!(
+            ":61:0909250925D1,NMSCref\r\n{}\r\n:86:{}\r\n2\r\n3\r\n4\r\n5\r\n6\r\n:62F:x\r\n",
+            "s".repeat(40),
+            "x".repeat(70)
+        );
+        let (sanitized, report) = SanitizerPipeline::new()
+            .push(WrapLongLines)
+            .sanitize_with_report(&input);
+        let expected = format!(
+            ":61:0909250925D1,NMSCref\r\n{}\r\n:86:{}\r\nxxxxx\r\n2\r\n3\r\n4\r\n5\r\n:86:6\r\n:62F:x\r\n",
+            "s".repeat(34),
+            "x".repeat(65)
+        );
+        assert_eq!(sanitized, expected);
+        let changes = report
+            .changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "line 2: removed 'ssssss' exceeding the 34 characters of the supplementary details of tag 61",
+                "line 3: wrapped line of tag 86 into 2 lines",
+                "line 8: continued tag 86 in a new tag 86",
+            ]
+        );
+    }
+
+    #[test]
+    fn replacement_policies() {
+        let input = ":86:a!b\r\n";
+        let convert = |replacement| {
+            let (sanitized, report) = SanitizerPipeline::new()
+                .push(SwiftCharsetOptions::new().replacement(replacement))
+                .sanitize_with_report(input);
+            let changes = report
+                .changes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            (sanitized, changes)
+        };
+
+        assert_eq!(convert(ReplacementPolicy::default()).0, ":86:a.b\r\n");
+        assert_eq!(
+            convert(ReplacementPolicy::Remove),
+            (
+                ":86:ab\r\n".to_string(),
+                vec!["line 1, column 6: replaced non-SWIFT character '!' with ''".to_string()]
+            )
+        );
+        assert_eq!(
+            convert(ReplacementPolicy::Keep),
+            (
+                input.to_string(),
+                vec!["line 1, column 6: kept non-SWIFT character '!'".to_string()]
+            )
+        );
+        assert_eq!(
+            convert(ReplacementPolicy::custom(|c| Some(format!(
+                "({})",
+                c as u32
+            ))))
+            .0,
+            ":86:a(33)b\r\n"
+        );
+    }
+
+    #[test]
+    fn swift_charset_options_keep_lengths() {
+        let input = format!(
+            ":61:0909250925D1,NMSCref\r\n{}Æ\r\n:86:{}ß\r\nÆ\r\n",
+            "s".repeat(33),
+            "x".repeat(64)
+        );
+
+        let options = SwiftCharsetOptions::new().transliteration(Transliteration::SingleChar);
+        let converted = options.convert(&input);
+        assert_eq!(converted.len(), input.len() - 3);
+        assert_eq!(converted.chars().count(), input.chars().count());
+
+        let (sanitized, report) = SanitizerPipeline::new()
+            .push(SwiftCharsetOptions::new().length_limits(LengthLimits::Truncate))
+            .sanitize_with_report(&input);
+        let expected = format!(
+            ":61:0909250925D1,NMSCref\r\n{}A\r\n:86:{}s\r\nAE\r\n",
+            "s".repeat(33),
+            "x".repeat(64)
+        );
+        assert_eq!(sanitized, expected);
+        let changes = report
+            .changes
+            .iter()
+            .filter(|change| !matches!(change.kind, SanitizeChangeKind::ReplacedChar { .. }))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "line 2: removed 'E' exceeding the 34 characters of the supplementary details of tag 61",
+                "line 3: removed 's' exceeding the 65 characters of a line of tag 86",
+            ]
+        );
+    }
+
+    #[rstest(
+        input,
+        expected,
+        case("1234,56", "1234,56"),
+        case("1234", "1234"),
+        case("1234.56", "1234,56"),
+        case("1.234,56", "1234,56"),
+        case("1,234.56", "1234,56"),
+        case("1'234.5", "1234,5"),
+        case("1.234.567", "1234567,"),
+        case("1,234,567", "1234567,"),
+        case(".5", "0,5")
+    )]
+    fn normalize_amount_separators(input: &str, expected: &str) {
+        assert_eq!(normalize_amount(input), expected);
+    }
+
+    #[test]
+    fn normalize_amounts_in_tags() {
+        let input = "\
+            :20:1.000,00\r\n\
+            :60F:C090924EUR1.000,00\r\n\
+            :61:0909250925DR1,000.50NMSC1.5//2.5\r\n\
+            :61:090925RC2.5NMSCref\r\n\
+            :86:1.000,00\r\n\
+            :62M:D090924EUR2.000,00\r\n";
+        let expected = "\
+            :20:1.000,00\r\n\
+            :60F:C090924EUR1000,00\r\n\
+            :61:0909250925DR1000,50NMSC1.5//2.5\r\n\
+            :61:090925RC2,5NMSCref\r\n\
+            :86:1.000,00\r\n\
+            :62M:D090924EUR2000,00\r\n";
+        let (sanitized, report) = SanitizerPipeline::new()
+            .push(NormalizeAmounts)
+            .sanitize_with_report(input);
+        assert_eq!(sanitized, expected);
+        let lines = report
+            .changes
+            .iter()
+            .map(|change| change.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 3, 4, 6]);
+        assert_eq!(
+            report.changes[1].to_string(),
+            "line 3: normalized amount '1,000.50' to '1000,50'"
+        );
+    }
+
+    #[test]
+    fn escape_unknown_tags_in_tag_86() {
+        let input = "\
+            :20:a\r\n\
+            :12:before tag 86\r\n\
+            :86:at 13\r\n\
+            :12:11\r\n\
+            :A1B:x\r\n\
+            ::y\r\n\
+            :NS:kept\r\n\
+            :62F:C090924EUR1,\r\n\
+            :12:after tag 86\r\n";
+        let expected = "\
+            :20:a\r\n\
+            :12:before tag 86\r\n\
+            :86:at 13\r\n \
+            :12:11\r\n \
+            :A1B:x\r\n\
+            ::y\r\n\
+            :NS:kept\r\n\
+            :62F:C090924EUR1,\r\n\
+            :12:after tag 86\r\n";
+        let (sanitized, report) = SanitizerPipeline::new()
+            .push(EscapeUnknownTags)
+            .sanitize_with_report(input);
+        assert_eq!(sanitized, expected);
+        let escaped = report
+            .changes
+            .iter()
+            .map(|change| (change.line, change.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            escaped,
+            [
+                (
+                    4,
+                    SanitizeChangeKind::EscapedTag {
+                        tag: "12".to_string()
+                    }
+                ),
+                (
+                    5,
+                    SanitizeChangeKind::EscapedTag {
+                        tag: "A1B".to_string()
+                    }
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn sanitize_reports_nothing_for_valid_input() {
+        let input = ":20:a\r\n:86:b\r\n";
+        let (sanitized, report) = sanitize_with_report(input);
+        assert_eq!(sanitized, input);
+        assert!(report.is_empty());
+    }
+
     #[test]
     fn strip_stuff_between_messages_success() {
         let input = "\
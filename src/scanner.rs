@@ -0,0 +1,205 @@
+//! A fast path for splitting a statement into its fields without going through pest.
+//!
+//! This accepts exactly what the `fields` rule of the grammar accepts and yields the same fields
+//! and spans. Anything it doesn't accept is left to pest so that errors are reported the same way.
+
+use crate::borrowed::FieldRef;
+use crate::Span;
+
+/// Split `statement` into its fields like the `fields` rule does.
+///
+/// Returns `None` if `statement` doesn't match the rule.
+pub(crate) fn scan_field_refs(statement: &str) -> Option<Vec<FieldRef<'_>>> {
+    if statement.is_empty() {
+        return None;
+    }
+
+    let bytes = statement.as_bytes();
+    let mut fields = vec![];
+    let mut position = Position::default();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if let Some(value_start) = tag_end(bytes, pos) {
+            let value_end = field_value_end(bytes, value_start);
+            if value_end > value_start {
+                let start = position.advance(statement, pos);
+                fields.push(FieldRef {
+                    tag: statement.get(pos + 1..value_start - 1)?,
+                    value: statement.get(value_start..value_end)?.trim(),
+                    span: Some(Span {
+                        start: pos,
+                        end: value_end,
+                        line: start.line,
+                        column: start.column,
+                    }),
+                    raw: None,
+                });
+                pos = value_end;
+                continue;
+            }
+        }
+        pos += swift_char_len(bytes, pos)?;
+    }
+
+    Some(fields)
+}
+
+/// Line and column of a byte offset, counted the way pest does.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Position {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl Position {
+    /// Move forward to `offset` in `input`.
+    ///
+    /// Only `\n` and `\r\n` start a new line. Columns are counted in characters.
+    fn advance(&mut self, input: &str, offset: usize) -> Position {
+        let skipped = input.get(self.offset..offset).unwrap_or_default();
+        let mut chars = skipped.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\n' || (c == '\r' && chars.next_if_eq(&'\n').is_some()) {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = offset;
+        *self
+    }
+}
+
+/// Length of the `NEWLINE` at `pos`, if there is one.
+fn newline_len(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes.get(pos..)? {
+        [b'\n', ..] => Some(1),
+        [b'\r', b'\n', ..] => Some(2),
+        [b'\r', ..] => Some(1),
+        _ => None,
+    }
+}
+
+/// Length of the `swift_char` at `pos`, if there is one.
+fn swift_char_len(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes.get(pos..)? {
+        [c, ..] if c.is_ascii_alphanumeric() || b"/-?:().,+{} ".contains(c) => Some(1),
+        // U+2018, the left single quotation mark.
+        [0xe2, 0x80, 0x98, ..] => Some(3),
+        _ => newline_len(bytes, pos),
+    }
+}
+
+/// End of the `tag` starting at `pos`, if there is one.
+fn tag_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    if bytes.get(pos) != Some(&b':') {
+        return None;
+    }
+    let tag_len = bytes
+        .get(pos + 1..)?
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .count();
+    if tag_len == 0 || bytes.get(pos + 1 + tag_len) != Some(&b':') {
+        return None;
+    }
+    Some(pos + tag_len + 2)
+}
+
+/// End of the `statement_field_value` starting at `pos`.
+///
+/// The value ends in front of a line starting with a tag, in front of a message terminator or at
+/// the first character which isn't a `swift_char`.
+fn field_value_end(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        if let Some(len) = newline_len(bytes, pos) {
+            if tag_end(bytes, pos + len).is_some() || is_message_terminator(bytes, pos + len) {
+                return pos;
+            }
+        }
+        match swift_char_len(bytes, pos) {
+            Some(len) => pos += len,
+            None => return pos,
+        }
+    }
+}
+
+/// Whether a `message_terminator` follows its leading `NEWLINE` at `pos`.
+fn is_message_terminator(bytes: &[u8], mut pos: usize) -> bool {
+    if bytes.get(pos) != Some(&b'-') {
+        return false;
+    }
+    pos += 1;
+    while let Some(len) = newline_len(bytes, pos) {
+        pos += len;
+    }
+    pos == bytes.len()
+        || bytes
+            .get(pos..)
+            .is_some_and(|rest| rest.starts_with(b":20:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::Parser;
+    use pretty_assertions::assert_eq;
+    use proptest::{prop_assert_eq, proptest};
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{MT940Parser, Rule};
+
+    /// The fields as the `fields` rule finds them.
+    fn pest_field_refs(statement: &str) -> Option<Vec<FieldRef<'_>>> {
+        let parsed = MT940Parser::parse(Rule::fields, statement).ok()?;
+        parsed
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(|pair| FieldRef::from_pair(pair).ok())
+            .collect()
+    }
+
+    fn summary(fields: Option<Vec<FieldRef<'_>>>) -> Option<Vec<(&str, &str, Option<Span>)>> {
+        fields.map(|fields| {
+            fields
+                .into_iter()
+                .map(|field| (field.tag, field.value, field.span))
+                .collect()
+        })
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::fields(":20:a\r\n:25:b\r\nc\r\n")]
+    #[case::preamble("foo\r\nbar:20:a\n:25:b")]
+    #[case::empty_value(":20:\r\n:25:b")]
+    #[case::terminator(":20:a\r\n-\r\n:20:b\r\n-")]
+    #[case::dash_in_value(":20:a\r\n-b\r\n-\r\n:25:c")]
+    #[case::carriage_returns(":20:a\rb\r\r\n:86:‘c’\r\n")]
+    #[case::invalid_char(":20:a\r\n:25:b_c\r\n")]
+    #[case::colons("::20::a:\r\n:2 5:b\r\n:25:\r\n:28C:")]
+    fn same_as_pest(#[case] input: &str) {
+        assert_eq!(
+            summary(scan_field_refs(input)),
+            summary(pest_field_refs(input))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn same_as_pest_for_any_input(input in "([:\r\n -]|:2[05]:|[a-c_‘]){0,40}") {
+            prop_assert_eq!(summary(scan_field_refs(&input)), summary(pest_field_refs(&input)));
+        }
+    }
+}
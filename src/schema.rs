@@ -0,0 +1,54 @@
+//! JSON Schema of the JSON representation of parsed messages.
+
+use schemars::{schema_for, Schema};
+
+use crate::Message;
+
+/// JSON Schema of a list of [`Message`]s as serialized by `serde_json`.
+///
+/// This describes the output of `mt940 convert` and `sta2json` for consumers in other languages.
+///
+/// # Example
+/// ```
+/// let schema = mt940::json_schema();
+/// assert_eq!(schema.get("type").unwrap(), "array");
+/// ```
+pub fn json_schema() -> Schema {
+    schema_for!(Vec<Message>)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn schema_describes_messages() {
+        let schema = serde_json::to_value(json_schema()).unwrap();
+        let message = &schema["$defs"]["Message"];
+        assert!(message["required"]
+            .as_array()
+            .unwrap()
+            .contains(&"statement_lines".into()));
+        assert!(message["properties"].get("raw").is_some());
+        assert_eq!(
+            schema["$defs"]["StatementLine"]["properties"]["value_date"]["format"],
+            "date"
+        );
+
+        let messages = parse_mt940(
+            ":20:ref\r\n:25:account\r\n:28C:1\r\n:60F:C090924EUR10,\r\n:62F:C090924EUR10,\r\n",
+        )
+        .unwrap();
+        let json = serde_json::to_value(&messages).unwrap();
+        for property in json[0].as_object().unwrap().keys() {
+            assert!(
+                message["properties"].get(property).is_some(),
+                "{}",
+                property
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Extraction of SEPA references from tag `:86:`.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{StatementLine, StructuredInformationToAccountOwner};
+
+/// All SEPA keywords we recognize in the order of the fields in [`SepaPurpose`].
+const SEPA_KEYWORDS: [&str; 7] = [
+    "EREF+", "KREF+", "MREF+", "CRED+", "SVWZ+", "ABWA+", "ABWE+",
+];
+
+/// SEPA references contained in the information to the account owner of a [`StatementLine`].
+///
+/// Banks put these into tag `:86:` as keywords followed by a `+`, for instance
+/// `EREF+1234SVWZ+Invoice 42`. German banks additionally spread the purpose across the `?20` to
+/// `?29` and `?60` to `?63` subfields.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SepaPurpose {
+    /// End-to-end reference (`EREF+`)
+    pub end_to_end_ref: Option<String>,
+
+    /// Customer reference (`KREF+`)
+    pub customer_ref: Option<String>,
+
+    /// Mandate reference of a direct debit (`MREF+`)
+    pub mandate_ref: Option<String>,
+
+    /// Creditor identifier of a direct debit (`CRED+`)
+    pub creditor_id: Option<String>,
+
+    /// Unstructured remittance information (`SVWZ+`)
+    pub remittance_info: Option<String>,
+
+    /// Differing originator (`ABWA+`)
+    pub differing_originator: Option<String>,
+
+    /// Differing beneficiary (`ABWE+`)
+    pub differing_beneficiary: Option<String>,
+}
+
+impl SepaPurpose {
+    /// Extract SEPA references from the contents of a tag `:86:`.
+    ///
+    /// Returns `None` if no SEPA keyword could be found.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::SepaPurpose;
+    ///
+    /// let info = "166?00GUTSCHRIFT?100399?20EREF+EndToEndId?21SVWZ+Invoice 42";
+    /// let purpose = SepaPurpose::from_information(info).unwrap();
+    /// assert_eq!(purpose.end_to_end_ref, Some("EndToEndId".to_string()));
+    /// assert_eq!(purpose.remittance_info, Some("Invoice 42".to_string()));
+    /// ```
+    pub fn from_information(info: &str) -> Option<SepaPurpose> {
+        let purpose = purpose_text(info);
+
+        let mut keyword_positions: Vec<(usize, usize)> = SEPA_KEYWORDS
+            .iter()
+            .enumerate()
+            .flat_map(|(keyword_index, keyword)| {
+                purpose
+                    .match_indices(keyword)
+                    .map(move |(position, _)| (position, keyword_index))
+            })
+            .collect();
+        if keyword_positions.is_empty() {
+            return None;
+        }
+        keyword_positions.sort_unstable();
+
+        let mut values: [Option<String>; SEPA_KEYWORDS.len()] = Default::default();
+        for (i, &(position, keyword_index)) in keyword_positions.iter().enumerate() {
+            let start = position + SEPA_KEYWORDS.get(keyword_index).map_or(0, |k| k.len());
+            let end = keyword_positions
+                .get(i + 1)
+                .map_or(purpose.len(), |&(next, _)| next);
+            let value = purpose.get(start..end).unwrap_or_default().trim();
+            if let Some(slot) = values.get_mut(keyword_index) {
+                if slot.is_none() && !value.is_empty() {
+                    *slot = Some(value.to_string());
+                }
+            }
+        }
+
+        let [end_to_end_ref, customer_ref, mandate_ref, creditor_id, remittance_info, differing_originator, differing_beneficiary] =
+            values;
+        Some(SepaPurpose {
+            end_to_end_ref,
+            customer_ref,
+            mandate_ref,
+            creditor_id,
+            remittance_info,
+            differing_originator,
+            differing_beneficiary,
+        })
+    }
+}
+
+impl StatementLine {
+    /// SEPA references found in tag `:86:` of this [`StatementLine`].
+    ///
+    /// Unlike [`StatementLine::sepa_purpose`], this doesn't depend on the
+    /// [`ParserConfig::sepa_purpose`] the statement was parsed with.
+    ///
+    /// See [`SepaPurpose::from_information`].
+    ///
+    /// [`ParserConfig::sepa_purpose`]: crate::ParserConfig::sepa_purpose
+    pub fn parse_sepa_purpose(&self) -> Option<SepaPurpose> {
+        self.information_to_account_owner
+            .as_deref()
+            .and_then(SepaPurpose::from_information)
+    }
+}
+
+/// Get the purpose part of a tag `:86:`.
+///
+/// If the information is structured into `?NN` subfields, only the purpose subfields `?20` to
+/// `?29` and `?60` to `?63` are kept. Otherwise, the whole text is the purpose.
+fn purpose_text(info: &str) -> String {
+    match StructuredInformationToAccountOwner::from_information(info) {
+        Some(information) => information.purpose.unwrap_or_default(),
+        None => info.lines().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940, parse_mt940_with_config, ParserConfig};
+
+    #[test]
+    fn sepa_purpose_from_subfields() {
+        let info = "166?00GUTSCHRIFT?100399?20EREF+EndToEndIdTFNR20004000?2101?22SVWZ\n\
+                    +TO 13 TFNr 20004?30PBNKDEFF100?31DE42100100100043921105?32Richter";
+        let expected = SepaPurpose {
+            end_to_end_ref: Some("EndToEndIdTFNR2000400001".to_string()),
+            remittance_info: Some("TO 13 TFNr 20004".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(SepaPurpose::from_information(info), Some(expected));
+    }
+
+    #[test]
+    fn sepa_purpose_unstructured() {
+        let info = "EREF+E2E-1 MREF+M-7 CRED+DE98ZZZ09999999999\nSVWZ+Rent March ABWA+Someone";
+        let expected = SepaPurpose {
+            end_to_end_ref: Some("E2E-1".to_string()),
+            mandate_ref: Some("M-7".to_string()),
+            creditor_id: Some("DE98ZZZ09999999999".to_string()),
+            remittance_info: Some("Rent March".to_string()),
+            differing_originator: Some("Someone".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(SepaPurpose::from_information(info), Some(expected));
+    }
+
+    #[test]
+    fn config_sepa_purpose() {
+        let input = "\
+            :20:a\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFa\r\n\
+            :86:EREF+E2E-1 SVWZ+Rent March\r\n\
+            :62F:C090926EUR9,\r\n";
+        let parsed = parse_mt940(input).unwrap();
+        assert_eq!(parsed[0].statement_lines[0].sepa_purpose, None);
+
+        let parsed =
+            parse_mt940_with_config(input, &ParserConfig::new().sepa_purpose(true)).unwrap();
+        let statement_line = &parsed[0].statement_lines[0];
+        assert_eq!(
+            statement_line.sepa_purpose,
+            statement_line.parse_sepa_purpose()
+        );
+        assert_eq!(
+            statement_line
+                .sepa_purpose
+                .as_ref()
+                .and_then(|purpose| purpose.end_to_end_ref.as_deref()),
+            Some("E2E-1")
+        );
+    }
+
+    #[test]
+    fn sepa_purpose_none() {
+        assert_eq!(
+            SepaPurpose::from_information("Fees according to advice"),
+            None
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Where a message was found when processing many statements at once.
+
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Which message of which statement a [`Message`] or [`ParseError`] belongs to.
+///
+/// This is only recorded if configured via [`ParserConfig::track_source`] or
+/// [`ParserConfig::source_label`]. [`parse_mt940_partial`] always records it for errors.
+///
+/// [`parse_mt940_partial`]: crate::parse_mt940_partial
+/// [`Message`]: crate::Message
+/// [`ParseError`]: crate::ParseError
+/// [`ParserConfig::track_source`]: crate::ParserConfig::track_source
+/// [`ParserConfig::source_label`]: crate::ParserConfig::source_label
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MessageSource {
+    /// The label given to [`ParserConfig::source_label`], e.g. a file name.
+    ///
+    /// [`ParserConfig::source_label`]: crate::ParserConfig::source_label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Position of the message within the statement, starting at 0.
+    pub index: usize,
+
+    /// Line the message starts at, starting at 1.
+    ///
+    /// This refers to the input given to the parser, even if lines in front of the message were
+    /// removed by [`ParserConfig::strip_stuff_between_messages`].
+    ///
+    /// Unknown for messages built from [`Field`]s which weren't parsed from a statement.
+    ///
+    /// [`ParserConfig::strip_stuff_between_messages`]:
+    ///     crate::ParserConfig::strip_stuff_between_messages
+    /// [`Field`]: crate::Field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for MessageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message {}", self.index + 1)?;
+        if let Some(line) = self.line {
+            write!(f, " starting at line {}", line)?;
+        }
+        if let Some(ref label) = self.label {
+            write!(f, " of {}", label)?;
+        }
+        Ok(())
+    }
+}
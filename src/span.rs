@@ -0,0 +1,85 @@
+//! Positions of fields and errors within the parsed input.
+
+use pest::error::{InputLocation, LineColLocation};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::Rule;
+
+/// A range of the parsed input.
+///
+/// Offsets refer to the input given to the parser, even if it sanitized the input first, e.g.
+/// because of [`ParserConfig::allow_non_swift_chars`]. In that case, offsets within lines where
+/// characters were replaced may be off a bit.
+///
+/// [`ParserConfig::allow_non_swift_chars`]: crate::ParserConfig::allow_non_swift_chars
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    /// Byte offset of the start.
+    pub start: usize,
+    /// Byte offset of the end, exclusive.
+    pub end: usize,
+    /// Line of the start, starting at 1.
+    pub line: usize,
+    /// Column of the start in characters, starting at 1.
+    pub column: usize,
+}
+
+impl Span {
+    /// The text of `input` covered by this [`Span`].
+    ///
+    /// `input` has to be the same text which was parsed.
+    pub fn snippet<'a>(&self, input: &'a str) -> Option<&'a str> {
+        input.get(self.start..self.end)
+    }
+
+    pub(crate) fn from_pest(span: pest::Span<'_>) -> Span {
+        let (line, column) = span.start_pos().line_col();
+        Span {
+            start: span.start(),
+            end: span.end(),
+            line,
+            column,
+        }
+    }
+
+    pub(crate) fn from_pest_error(err: &pest::error::Error<Rule>) -> Span {
+        let (start, end) = match err.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span(span) => span,
+        };
+        let (line, column) = match err.line_col {
+            LineColLocation::Pos(line_col) => line_col,
+            LineColLocation::Span(line_col, _) => line_col,
+        };
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Move this [`Span`] from a part of the input to the whole input.
+    ///
+    /// The part has to start at the beginning of a line, `offset` bytes and `lines` lines into the
+    /// whole input.
+    pub(crate) fn shifted(self, offset: usize, lines: usize) -> Span {
+        Span {
+            start: self.start + offset,
+            end: self.end + offset,
+            line: self.line + lines,
+            column: self.column,
+        }
+    }
+
+    /// Move this [`Span`] by `offset` bytes and `lines` lines, e.g. after text in front of it was
+    /// edited.
+    pub(crate) fn moved(self, offset: isize, lines: isize) -> Span {
+        Span {
+            start: self.start.saturating_add_signed(offset),
+            end: self.end.saturating_add_signed(offset),
+            line: self.line.saturating_add_signed(lines),
+            column: self.column,
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! Lazy, message-by-message parsing of MT940 statements.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::Path;
+
+#[cfg(feature = "tokio")]
+use futures_util::Stream;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead};
+
+use crate::{parse_fields, Field, Message, ParseError, RequiredTagNotFoundError};
+
+/// An iterator over the [`Message`]s of a MT940 statement.
+///
+/// Created by [`parse_mt940_iter`]. Every message is only parsed once it is requested.
+#[derive(Debug, Clone)]
+pub struct MessageIter<'a> {
+    blocks: MessageBlocks<'a>,
+}
+
+impl Iterator for MessageIter<'_> {
+    type Item = Result<Message, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.blocks
+            .next()
+            .map(|block| block.and_then(|block| Message::from_fields(block.parse_fields()?)))
+    }
+}
+
+/// The raw text of a single message and where it starts in the whole statement.
+#[derive(Debug, Clone)]
+pub(crate) struct MessageBlock<'a> {
+    text: &'a str,
+    /// Byte offset of the start of `text`.
+    offset: usize,
+    /// Number of lines in front of `text`.
+    lines: usize,
+}
+
+impl MessageBlock<'_> {
+    /// The bytes of the whole statement this message spans.
+    pub(crate) fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
+
+    /// Line this message starts at, starting at 1.
+    pub(crate) fn line(&self) -> usize {
+        self.lines + 1
+    }
+
+    /// Split this message into its [`Field`]s with spans relative to the whole statement.
+    pub(crate) fn parse_fields(&self) -> Result<Vec<Field>, ParseError> {
+        let fields = parse_fields(self.text).map_err(|err| {
+            ParseError::from_pest_error_with_span(err).shifted(self.offset, self.lines)
+        })?;
+        Ok(fields
+            .into_iter()
+            .map(|mut field| {
+                field.span = field.span.map(|span| span.shifted(self.offset, self.lines));
+                field
+            })
+            .collect())
+    }
+}
+
+/// An iterator over the raw text of each message of a MT940 statement.
+#[derive(Debug, Clone)]
+pub(crate) struct MessageBlocks<'a> {
+    remaining: &'a str,
+    /// Byte offset of `remaining` in the whole statement.
+    offset: usize,
+    /// Number of lines in front of `remaining`.
+    lines: usize,
+    pending_error: Option<ParseError>,
+}
+
+impl<'a> MessageBlocks<'a> {
+    pub(crate) fn new(statement: &'a str) -> MessageBlocks<'a> {
+        match find_message_start(statement, 0) {
+            Some(start) => {
+                let (preamble, remaining) = statement.split_at(start);
+                MessageBlocks {
+                    remaining,
+                    offset: start,
+                    lines: count_lines(preamble),
+                    pending_error: check_preamble(preamble),
+                }
+            }
+            None => MessageBlocks {
+                remaining: "",
+                offset: statement.len(),
+                lines: 0,
+                pending_error: Some(RequiredTagNotFoundError::new("20").into()),
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for MessageBlocks<'a> {
+    type Item = Result<MessageBlock<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.pending_error.take() {
+            return Some(Err(err));
+        }
+        if self.remaining.is_empty() {
+            return None;
+        }
+        // The current message starts at the very beginning so we search for the next one
+        // starting from the second character.
+        let end = find_message_start(self.remaining, 1).unwrap_or(self.remaining.len());
+        let (text, remaining) = self.remaining.split_at(end);
+        let block = MessageBlock {
+            text,
+            offset: self.offset,
+            lines: self.lines,
+        };
+        self.remaining = remaining;
+        self.offset += text.len();
+        self.lines += count_lines(text);
+        Some(Ok(block))
+    }
+}
+
+/// An iterator over the [`Message`]s of a MT940 statement read from a [`BufRead`].
+///
+/// Created by [`parse_mt940_from_reader`]. Input is only read up to the end of the requested
+/// message.
+#[derive(Debug)]
+pub struct ReaderMessageIter<R> {
+    reader: R,
+    /// First line of the next message which was already read.
+    next_line: Option<String>,
+    /// Byte offset and number of lines in front of `next_line`.
+    next_line_position: (usize, usize),
+    /// Number of bytes and lines read so far.
+    position: (usize, usize),
+    started: bool,
+    done: bool,
+}
+
+impl<R> ReaderMessageIter<R> {
+    fn new(reader: R) -> ReaderMessageIter<R> {
+        ReaderMessageIter {
+            reader,
+            next_line: None,
+            next_line_position: (0, 0),
+            position: (0, 0),
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Add `line` to `block` unless it starts the next message. Returns whether it does.
+    fn push_line(&mut self, line: String, block: &mut String) -> bool {
+        let line_position = self.position;
+        self.position = (
+            self.position.0 + line.len(),
+            self.position.1 + count_lines(&line),
+        );
+        if line.starts_with(":20:") {
+            self.next_line = Some(line);
+            self.next_line_position = line_position;
+            return true;
+        }
+        block.push_str(&line);
+        false
+    }
+
+    /// Check what was read in front of the first message.
+    fn check_start(&mut self, preamble: Result<String, ParseError>) -> Option<ParseError> {
+        let preamble = match preamble {
+            Ok(preamble) => preamble,
+            Err(err) => {
+                self.done = true;
+                return Some(err);
+            }
+        };
+        if self.next_line.is_none() {
+            self.done = true;
+            return Some(RequiredTagNotFoundError::new("20").into());
+        }
+        check_preamble(&preamble)
+    }
+
+    /// Parse the message which was read, starting at `first_line_position`.
+    fn finish_message(
+        &mut self,
+        first_line_position: (usize, usize),
+        message: Result<String, ParseError>,
+    ) -> Result<Message, ParseError> {
+        let message = message.inspect_err(|_| self.done = true)?;
+        let (offset, lines) = first_line_position;
+        let block = MessageBlock {
+            text: &message,
+            offset,
+            lines,
+        };
+        block.parse_fields().and_then(Message::from_fields)
+    }
+}
+
+impl<R: BufRead> ReaderMessageIter<R> {
+    /// Read lines onto `block` until the start of the next message or the end of the input.
+    fn read_until_next_message(&mut self, mut block: String) -> Result<String, ParseError> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 || self.push_line(line, &mut block) {
+                return Ok(block);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderMessageIter<R> {
+    type Item = Result<Message, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            let preamble = self.read_until_next_message(String::new());
+            if let Some(err) = self.check_start(preamble) {
+                return Some(Err(err));
+            }
+        }
+        let first_line = self.next_line.take()?;
+        let position = self.next_line_position;
+        let message = self.read_until_next_message(first_line);
+        Some(self.finish_message(position, message))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncBufRead + Unpin> ReaderMessageIter<R> {
+    /// Like [`ReaderMessageIter::read_until_next_message`] but for asynchronous readers.
+    async fn read_until_next_message_async(
+        &mut self,
+        mut block: String,
+    ) -> Result<String, ParseError> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).await? == 0 || self.push_line(line, &mut block) {
+                return Ok(block);
+            }
+        }
+    }
+
+    /// Like [`Iterator::next`] but for asynchronous readers.
+    async fn next_async(&mut self) -> Option<Result<Message, ParseError>> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            let preamble = self.read_until_next_message_async(String::new()).await;
+            if let Some(err) = self.check_start(preamble) {
+                return Some(Err(err));
+            }
+        }
+        let first_line = self.next_line.take()?;
+        let position = self.next_line_position;
+        let message = self.read_until_next_message_async(first_line).await;
+        Some(self.finish_message(position, message))
+    }
+}
+
+/// Check the stuff in front of the first message.
+///
+/// It is ignored unless it contains fields which would then not belong to any message. As the
+/// preamble is followed by the tag `:20:` of the first message, it has to be parsed along with that
+/// tag to split it into fields the same way [`parse_mt940`] does. A header like `:940:` on a line
+/// of its own is only a field if something other than a tag follows it.
+///
+/// [`parse_mt940`]: crate::parse_mt940
+fn check_preamble(preamble: &str) -> Option<ParseError> {
+    match parse_fields(&format!("{}:20:", preamble)) {
+        Ok(fields) => fields
+            .first()
+            .map(|field| ParseError::from(RequiredTagNotFoundError::new("20")).at(field.span)),
+        _ => None,
+    }
+}
+
+pub(crate) fn count_lines(s: &str) -> usize {
+    s.bytes().filter(|&b| b == b'\n').count()
+}
+
+/// Find the byte offset of the next line starting with a tag `:20:` at or after `from`.
+fn find_message_start(s: &str, from: usize) -> Option<usize> {
+    if from == 0 && s.starts_with(":20:") {
+        return Some(0);
+    }
+    s.get(from.saturating_sub(1)..)?
+        .find("\n:20:")
+        .map(|i| i + from.saturating_sub(1) + 1)
+}
+
+/// Lazily parse and validate a MT940 statement.
+///
+/// This returns an iterator which parses one [`Message`] at a time. This is useful for very large
+/// statements as not all messages have to be kept in memory at once. Other than [`parse_mt940`],
+/// a malformed message doesn't prevent any subsequent messages from being parsed.
+///
+/// [`parse_mt940`]: crate::parse_mt940
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_iter;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n\
+///     :20:3996-11-11111112\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00002/001\r\n\
+///     :60F:C090930EUR54484,04\r\n\
+///     :62F:C091001EUR54484,04\r\n";
+///
+/// let mut messages = parse_mt940_iter(input);
+/// assert_eq!(messages.next().unwrap().unwrap().statement_no, "00001");
+/// assert_eq!(messages.next().unwrap().unwrap().statement_no, "00002");
+/// assert!(messages.next().is_none());
+/// ```
+pub fn parse_mt940_iter(statement: &str) -> MessageIter<'_> {
+    MessageIter {
+        blocks: MessageBlocks::new(statement),
+    }
+}
+
+/// Lazily parse and validate a MT940 statement from a reader.
+///
+/// The input is read incrementally while iterating so that the statement doesn't have to be read
+/// into memory as a whole. See [`parse_mt940_iter`] for details.
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_from_reader;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n";
+///
+/// let messages = parse_mt940_from_reader(input.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(messages[0].transaction_ref_no, "3996-11-11111111");
+/// ```
+pub fn parse_mt940_from_reader<R: Read>(reader: R) -> ReaderMessageIter<BufReader<R>> {
+    ReaderMessageIter::new(BufReader::new(reader))
+}
+
+/// Lazily parse and validate a MT940 statement from an asynchronous reader.
+///
+/// This is the asynchronous counterpart of [`parse_mt940_from_reader`]. The input is read
+/// incrementally and every message is yielded by the returned [`Stream`] as soon as the start of
+/// the next one or the end of the input was read.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use mt940::parse_mt940_from_async_reader;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n";
+///
+/// let messages = parse_mt940_from_async_reader(input.as_bytes())
+///     .collect::<Vec<_>>()
+///     .await;
+/// assert_eq!(messages[0].as_ref().unwrap().transaction_ref_no, "3996-11-11111111");
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub fn parse_mt940_from_async_reader<R: AsyncRead + Unpin>(
+    reader: R,
+) -> impl Stream<Item = Result<Message, ParseError>> {
+    let messages = ReaderMessageIter::new(tokio::io::BufReader::new(reader));
+    futures_util::stream::unfold(messages, |mut messages| async move {
+        let message = messages.next_async().await?;
+        Some((message, messages))
+    })
+}
+
+/// Size of the chunks [`parse_mt940_file`] reads.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Lazily parse and validate a MT940 statement from a file.
+///
+/// The file is read in chunks while iterating so that memory use stays flat no matter how large
+/// the file is. See [`parse_mt940_iter`] for details. Only opening the file fails right away,
+/// errors while reading are returned by the iterator as [`ParseError::IoError`].
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_file;
+///
+/// let messages = parse_mt940_file("tests/data/mt940/full/danskebank/MT940_DK_Example.sta")
+///     .unwrap()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(messages[0].transaction_ref_no, "3996-1234567890");
+/// ```
+pub fn parse_mt940_file<P: AsRef<Path>>(path: P) -> io::Result<ReaderMessageIter<BufReader<File>>> {
+    let file = File::open(path)?;
+    Ok(ReaderMessageIter::new(BufReader::with_capacity(
+        FILE_CHUNK_SIZE,
+        file,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn find_message_start_lines() {
+        let input = ":20:a\r\n:25:b\r\n:20:c\r\n";
+        assert_eq!(find_message_start(input, 0), Some(0));
+        assert_eq!(find_message_start(input, 1), Some(14));
+        assert_eq!(find_message_start(input, 15), None);
+        assert_eq!(find_message_start("foo\n:20:a", 0), Some(4));
+    }
+
+    #[test]
+    fn fields_before_first_message() {
+        let input = ":25:b\r\n:20:a\r\n";
+        let mut messages = parse_mt940_iter(input);
+        let err = messages.next().unwrap().unwrap_err();
+        assert_eq!(err, ParseError::from(RequiredTagNotFoundError::new("20")));
+        assert_eq!(err.span().map(|span| span.line), Some(1));
+        assert!(messages.next().unwrap().is_err());
+        assert_eq!(messages.next(), None);
+    }
+
+    #[test]
+    fn no_messages() {
+        let mut messages = parse_mt940_iter("");
+        assert_eq!(
+            messages.next(),
+            Some(Err(RequiredTagNotFoundError::new("20").into()))
+        );
+        assert_eq!(messages.next(), None);
+    }
+
+    #[test]
+    fn error_span_in_later_message() {
+        let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+        let broken = message.replace("EUR1,", "EUR1,x");
+        let input = format!("{}{}", message, broken);
+        let expected_line = message.lines().count() + 4;
+
+        let err = parse_mt940_iter(&input).nth(1).unwrap().unwrap_err();
+        let span = err.span().unwrap();
+        assert_eq!(span.line, expected_line);
+        assert_eq!(span.snippet(&input), Some(":60F:C090924EUR1,x"));
+
+        let err = parse_mt940_from_reader(input.as_bytes())
+            .nth(1)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.span(), Some(span));
+    }
+
+    proptest! {
+        #[test]
+        fn same_as_parse_mt940(count in 1..5usize, preamble in "[a-z ]{0,10}\r\n") {
+            let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+            let input = format!("{}{}", preamble, message.repeat(count));
+            let expected = parse_mt940(&input).unwrap();
+            let parsed = parse_mt940_iter(&input).collect::<Result<Vec<_>, _>>().unwrap();
+            prop_assert_eq!(&parsed, &expected);
+            let read = parse_mt940_from_reader(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+            prop_assert_eq!(read, expected);
+        }
+    }
+
+    #[test]
+    fn reader_fields_before_first_message() {
+        let input = ":25:b\r\n:20:a\r\n";
+        let mut messages = parse_mt940_from_reader(input.as_bytes());
+        let err = messages.next().unwrap().unwrap_err();
+        assert_eq!(err, ParseError::from(RequiredTagNotFoundError::new("20")));
+        assert_eq!(err.span().map(|span| span.line), Some(1));
+        assert!(messages.next().unwrap().is_err());
+        assert_eq!(messages.next(), None);
+    }
+
+    #[test]
+    fn reader_invalid_utf8() {
+        let input: &[u8] = b":20:a\r\n:25:\xff\r\n";
+        let mut messages = parse_mt940_from_reader(input);
+        assert!(matches!(
+            messages.next(),
+            Some(Err(ParseError::IoError {
+                kind: std::io::ErrorKind::InvalidData,
+                ..
+            }))
+        ));
+        assert_eq!(messages.next(), None);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_reader() {
+        use futures_util::StreamExt;
+
+        let message = ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,\r\n";
+        let broken = message.replace("EUR1,", "EUR1,x");
+        let input = format!("preamble\r\n{}{}{}", message, broken, message);
+
+        let expected = parse_mt940_from_reader(input.as_bytes()).collect::<Vec<_>>();
+        let read = parse_mt940_from_async_reader(input.as_bytes())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(read, expected);
+        assert!(read[1].is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//! Totals over the statement lines of many messages.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::grouping::{group_by_account, AccountKey};
+use crate::{Message, StatementLine};
+
+/// Totals of statement lines per currency and per account as computed by [`summarize`].
+///
+/// Amounts are never added up across currencies. Messages are assigned to the currency of their
+/// opening balance.
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// Keyed by currency.
+    pub currencies: BTreeMap<String, CurrencySummary>,
+
+    /// Keyed by account id and then by currency.
+    pub accounts: BTreeMap<String, BTreeMap<String, CurrencySummary>>,
+}
+
+/// Totals of statement lines in one currency.
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CurrencySummary {
+    pub totals: Totals,
+
+    /// Earliest value date of all statement lines.
+    pub first_date: Option<NaiveDate>,
+
+    /// Latest value date of all statement lines.
+    pub last_date: Option<NaiveDate>,
+
+    /// Totals per transaction type identification code such as `TRF` or `CHG`.
+    pub by_code: BTreeMap<String, Totals>,
+}
+
+/// Number and sum of debits and credits.
+///
+/// Reversals count towards the side whose balance they change, i.e. a reversed debit is a credit.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Totals {
+    pub credit_count: usize,
+    pub debit_count: usize,
+
+    /// Sum of all credits. Never negative.
+    pub credits: Decimal,
+
+    /// Sum of all debits. Never positive.
+    pub debits: Decimal,
+}
+
+impl Totals {
+    /// Number of statement lines.
+    pub fn count(&self) -> usize {
+        self.credit_count + self.debit_count
+    }
+
+    /// Credits and debits added up.
+    pub fn net(&self) -> Decimal {
+        self.credits + self.debits
+    }
+
+    fn add(&mut self, amount: Decimal) {
+        if amount.is_sign_negative() {
+            self.debit_count += 1;
+            self.debits += amount;
+        } else {
+            self.credit_count += 1;
+            self.credits += amount;
+        }
+    }
+}
+
+impl CurrencySummary {
+    fn add(&mut self, statement_line: &StatementLine) {
+        let amount = statement_line.signed_amount();
+        self.totals.add(amount);
+        self.by_code
+            .entry(
+                statement_line
+                    .transaction_type_ident_code
+                    .code()
+                    .to_string(),
+            )
+            .or_default()
+            .add(amount);
+        let date = statement_line.value_date;
+        self.first_date = Some(self.first_date.map_or(date, |first| first.min(date)));
+        self.last_date = Some(self.last_date.map_or(date, |last| last.max(date)));
+    }
+}
+
+/// Add up the statement lines of all messages per currency and per account.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, summarize};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NTRF1110030403010139//1234\r\n\
+///     :61:0910010930DR62,60NCHGcustomer id//bank id\r\n\
+///     :61:0910020930CR100,NTRFcustomer id//bank id\r\n\
+///     :62F:C091002EUR53937,52\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// let summary = summarize(&messages);
+/// let euro = &summary.currencies["EUR"];
+/// assert_eq!(euro.totals.count(), 3);
+/// assert_eq!(euro.totals.net().to_string(), "-546.52");
+/// assert_eq!(euro.by_code["TRF"].debit_count, 1);
+/// ```
+pub fn summarize(messages: &[Message]) -> Summary {
+    let mut summary = Summary::default();
+    for message in messages {
+        let currency = &message.opening_balance.iso_currency_code;
+        let by_currency = summary.currencies.entry(currency.clone()).or_default();
+        let by_account = summary
+            .accounts
+            .entry(message.account_id.clone())
+            .or_default()
+            .entry(currency.clone())
+            .or_default();
+        for statement_line in &message.statement_lines {
+            by_currency.add(statement_line);
+            by_account.add(statement_line);
+        }
+    }
+    summary
+}
+
+/// The length of the periods of [`summarize_by_period`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Period {
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+}
+
+impl Period {
+    /// The first day of the period `date` is in.
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date.week(Weekday::Mon).first_day(),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// The first day of the period after the one starting at `start`.
+    fn next(self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Period::Day => start.checked_add_days(Days::new(1)),
+            Period::Week => start.checked_add_days(Days::new(7)),
+            Period::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+/// Totals of the statement lines of an account within one period as computed by
+/// [`summarize_by_period`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PeriodSummary {
+    /// The first day of the period.
+    pub start: NaiveDate,
+
+    pub totals: Totals,
+
+    /// The balance at the end of the period. Negative for a debit balance.
+    pub closing_balance: Decimal,
+}
+
+/// Add up the statement lines of every account per day, week or month.
+///
+/// Statement lines are assigned to periods by their value date. The closing balance of each
+/// period is the opening balance of the first statement of the account plus all statement lines
+/// up to the end of the period. Periods without any statement lines are included as well, so
+/// there is a summary for every period from the opening balance up to the last closing balance or
+/// statement line.
+///
+/// Messages are grouped and ordered by [`group_by_account`].
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, summarize_by_period, Period};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NTRF1110030403010139//1234\r\n\
+///     :61:0911020930DR62,60NCHGcustomer id//bank id\r\n\
+///     :62F:C091102EUR53837,52\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// let summaries = summarize_by_period(&messages, Period::Month);
+/// let months = &summaries[&messages[0].account_key()];
+/// assert_eq!(months.len(), 3);
+/// assert_eq!(months[0].closing_balance.to_string(), "53900.12");
+/// assert_eq!(months[1].totals.count(), 0);
+/// assert_eq!(months[1].closing_balance.to_string(), "53900.12");
+/// assert_eq!(months[2].closing_balance.to_string(), "53837.52");
+/// ```
+pub fn summarize_by_period(
+    messages: &[Message],
+    period: Period,
+) -> BTreeMap<AccountKey, Vec<PeriodSummary>> {
+    group_by_account(messages)
+        .into_iter()
+        .map(|(account, group)| (account, summarize_account_by_period(&group, period)))
+        .collect()
+}
+
+/// Summarize the messages of one account ordered by statement number per period.
+fn summarize_account_by_period(messages: &[&Message], period: Period) -> Vec<PeriodSummary> {
+    let Some(first) = messages.first() else {
+        return vec![];
+    };
+    let mut statement_lines = messages
+        .iter()
+        .flat_map(|message| &message.statement_lines)
+        .collect::<Vec<_>>();
+    statement_lines.sort_by_key(|statement_line| statement_line.value_date);
+
+    let dates = statement_lines
+        .iter()
+        .map(|statement_line| statement_line.value_date)
+        .chain(messages.iter().map(|message| message.closing_balance.date))
+        .chain([first.opening_balance.date]);
+    let first_date = dates.clone().min().unwrap_or(first.opening_balance.date);
+    let last_date = dates.max().unwrap_or(first_date);
+
+    let mut summaries = vec![];
+    let mut balance = first.opening_balance.signed_amount();
+    let mut statement_lines = statement_lines.into_iter().peekable();
+    let mut start = Some(period.start(first_date));
+    while let Some(period_start) = start.filter(|&start| start <= last_date) {
+        start = period.next(period_start);
+        let mut totals = Totals::default();
+        while let Some(statement_line) = statement_lines
+            .next_if(|statement_line| start.is_none_or(|end| statement_line.value_date < end))
+        {
+            let amount = statement_line.signed_amount();
+            totals.add(amount);
+            balance += amount;
+        }
+        summaries.push(PeriodSummary {
+            start: period_start,
+            totals,
+            closing_balance: balance,
+        });
+    }
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    #[test]
+    fn totals_per_currency_and_account() {
+        let input = "\
+            :20:first\r\n\
+            :25:checking\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFa\r\n\
+            :61:090923RD2,50NCHGb\r\n\
+            :62F:C090926EUR11,5\r\n\
+            :20:second\r\n\
+            :25:savings\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090930C5,NTRFc\r\n\
+            :62F:C090930EUR15,\r\n\
+            :20:third\r\n\
+            :25:checking\r\n\
+            :28C:2\r\n\
+            :60F:C090924USD10,\r\n\
+            :62F:C090924USD10,\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let summary = summarize(&messages);
+        let date = |day| NaiveDate::from_ymd_opt(2009, 9, day);
+
+        let euro = &summary.currencies["EUR"];
+        assert_eq!(
+            euro.totals,
+            Totals {
+                credit_count: 2,
+                debit_count: 1,
+                credits: Decimal::new(75, 1),
+                debits: Decimal::new(-1, 0),
+            }
+        );
+        assert_eq!(euro.totals.net(), Decimal::new(65, 1));
+        assert_eq!((euro.first_date, euro.last_date), (date(23), date(30)));
+        assert_eq!(euro.by_code["TRF"].count(), 2);
+        assert_eq!(euro.by_code["CHG"].credits, Decimal::new(25, 1));
+
+        let checking = &summary.accounts["checking"];
+        assert_eq!(checking["EUR"].totals.count(), 2);
+        assert_eq!(checking["USD"], CurrencySummary::default());
+        assert_eq!(summary.accounts["savings"]["EUR"].totals.count(), 1);
+    }
+
+    #[test]
+    fn totals_per_period() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:2\r\n\
+            :60F:C090927EUR8,\r\n\
+            :61:090928D1,NTRFc\r\n\
+            :61:091004C5,NTRFd\r\n\
+            :62F:C091004EUR12,\r\n\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:090925D1,NTRFa\r\n\
+            :61:090926D1,NCHGb\r\n\
+            :62F:C090926EUR8,\r\n";
+        let messages = parse_mt940(input).unwrap();
+        let account = messages[0].account_key();
+
+        let summaries = summarize_by_period(&messages, Period::Week);
+        let weeks = summaries[&account]
+            .iter()
+            .map(|summary| {
+                (
+                    summary.start.day(),
+                    summary.totals.debit_count,
+                    summary.totals.credit_count,
+                    summary.closing_balance,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(weeks, [(21, 2, 0, 8.into()), (28, 1, 1, 12.into())]);
+
+        let summaries = summarize_by_period(&messages, Period::Day);
+        let days = &summaries[&account];
+        assert_eq!(days.len(), 11);
+        assert_eq!(days[0].start, NaiveDate::from_ymd_opt(2009, 9, 24).unwrap());
+        assert_eq!(days[1].closing_balance, Decimal::from(9));
+        assert_eq!(days[10].closing_balance, Decimal::from(12));
+    }
+}
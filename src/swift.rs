@@ -0,0 +1,422 @@
+//! Support for statements wrapped in the SWIFT FIN block structure.
+//!
+//! Statements which come straight out of the SWIFT network look like this:
+//!
+//! ```ignore
+//! {1:F01BANKBEBBAXXX0000000000}{2:O9401200190101BANKDEFFAXXX00000000001901011200N}{4:
+//! :20:...
+//! -}{5:{CHK:123456789ABC}}
+//! ```
+//!
+//! The actual MT940 message is contained in block 4.
+
+use pest::iterators::Pair;
+use pest::Parser;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::errors::TrailerValidationError;
+use crate::{parse_mt940, Field, MT940Parser, Message, ParseError, Rule};
+
+/// A MT940 message together with the SWIFT blocks surrounding it.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SwiftEnvelope {
+    /// Block `{1:}`
+    pub basic_header: BasicHeader,
+
+    /// Block `{2:}`
+    pub application_header: Option<ApplicationHeader>,
+
+    /// Block `{3:}`
+    pub user_header: Vec<Field>,
+
+    /// Block `{4:}`
+    ///
+    /// This is the raw MT940 message without the trailing `-`.
+    pub text: String,
+
+    /// Block `{5:}`
+    ///
+    /// This is `None` if the block is missing altogether rather than empty.
+    pub trailer: Option<Vec<Field>>,
+}
+
+/// The basic header identifying the logical terminal and the session the message was sent in.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BasicHeader {
+    /// `F` for FIN messages.
+    pub application_id: String,
+    /// `01` for FIN messages.
+    pub service_id: String,
+    /// BIC8 followed by a terminal code and the branch code.
+    pub logical_terminal: String,
+    pub session_number: String,
+    pub sequence_number: String,
+}
+
+/// The application header which is different for messages sent to and received from SWIFT.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ApplicationHeader {
+    /// A message sent to SWIFT.
+    Input {
+        message_type: String,
+        receiver_address: String,
+        priority: Option<String>,
+    },
+    /// A message delivered by SWIFT.
+    Output {
+        message_type: String,
+        input_time: String,
+        /// Date, logical terminal, session number and sequence number of the sender.
+        message_input_reference: String,
+        output_date: String,
+        output_time: String,
+        priority: Option<String>,
+    },
+}
+
+impl ApplicationHeader {
+    /// The message type, e.g. `"940"`.
+    pub fn message_type(&self) -> &str {
+        match self {
+            ApplicationHeader::Input { message_type, .. }
+            | ApplicationHeader::Output { message_type, .. } => message_type,
+        }
+    }
+}
+
+impl SwiftEnvelope {
+    /// The BIC of the sender of the message.
+    ///
+    /// For output messages this is taken from the message input reference. Otherwise the sender is
+    /// the logical terminal of the basic header.
+    pub fn sender_bic(&self) -> String {
+        match &self.application_header {
+            Some(ApplicationHeader::Output {
+                message_input_reference,
+                ..
+            }) => {
+                let logical_terminal = message_input_reference
+                    .chars()
+                    .skip(6)
+                    .take(12)
+                    .collect::<String>();
+                bic_from_logical_terminal(&logical_terminal)
+            }
+            _ => bic_from_logical_terminal(&self.basic_header.logical_terminal),
+        }
+    }
+
+    /// The value of `{CHK:}` in the trailer.
+    pub fn checksum(&self) -> Option<&str> {
+        self.trailer
+            .iter()
+            .flatten()
+            .find(|field| field.tag == "CHK")
+            .map(|field| field.value.as_str())
+    }
+
+    /// The value of `{MAC:}` in the trailer.
+    pub fn mac(&self) -> Option<&str> {
+        self.trailer
+            .iter()
+            .flatten()
+            .find(|field| field.tag == "MAC")
+            .map(|field| field.value.as_str())
+    }
+
+    /// Check that the trailer is complete.
+    ///
+    /// A message which was truncated in transit is missing its trailer block `{5:}`. Otherwise,
+    /// the trailer has to contain a checksum `{CHK:}` of 12 hexadecimal digits and, if `require_mac`
+    /// is set, a message authentication code `{MAC:}` of 8 hexadecimal digits.
+    ///
+    /// Note that the checksum itself can't be recalculated since SWIFT doesn't publish the
+    /// algorithm.
+    pub fn validate_trailer(&self, require_mac: bool) -> Result<(), TrailerValidationError> {
+        if self.trailer.is_none() {
+            return Err(TrailerValidationError::MissingTrailer);
+        }
+
+        let checksum = self
+            .checksum()
+            .ok_or(TrailerValidationError::MissingChecksum)?;
+        if !is_hex_of_len(checksum, 12) {
+            return Err(TrailerValidationError::InvalidChecksum(
+                checksum.to_string(),
+            ));
+        }
+
+        match self.mac() {
+            Some(mac) if !is_hex_of_len(mac, 8) => {
+                Err(TrailerValidationError::InvalidMac(mac.to_string()))
+            }
+            None if require_mac => Err(TrailerValidationError::MissingMac),
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse and validate the MT940 message contained in block 4.
+    pub fn parse_mt940(&self) -> Result<Vec<Message>, ParseError> {
+        parse_mt940(&self.text)
+    }
+}
+
+fn is_hex_of_len(s: &str, len: usize) -> bool {
+    s.len() == len && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// A logical terminal has a terminal code in between the BIC8 and the branch code.
+fn bic_from_logical_terminal(logical_terminal: &str) -> String {
+    logical_terminal
+        .chars()
+        .take(8)
+        .chain(logical_terminal.chars().skip(9))
+        .collect()
+}
+
+/// Parse a list of messages wrapped in SWIFT blocks.
+///
+/// Use [`SwiftEnvelope::parse_mt940`] to parse the contained MT940 messages.
+///
+/// # Example
+/// ```
+/// use mt940::parse_swift_envelopes;
+///
+/// let input = "\
+///     {1:F01BANKBEBBAXXX1234567890}\
+///     {2:O9401200190101BANKDEFFAXXX12345678901901011200N}\
+///     {4:\r\n\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n\
+///     -}\
+///     {5:{CHK:123456789ABC}}";
+///
+/// let envelopes = parse_swift_envelopes(input).unwrap();
+/// assert_eq!(envelopes[0].sender_bic(), "BANKDEFFXXX");
+/// assert_eq!(envelopes[0].checksum(), Some("123456789ABC"));
+///
+/// let messages = envelopes[0].parse_mt940().unwrap();
+/// assert_eq!(messages[0].transaction_ref_no, "3996-11-11111111");
+/// ```
+pub fn parse_swift_envelopes(input: &str) -> Result<Vec<SwiftEnvelope>, ParseError> {
+    let parsed_envelopes = MT940Parser::parse(Rule::envelopes, input)?;
+
+    let mut envelopes = vec![];
+    for parsed_envelope in parsed_envelopes {
+        if let Rule::EOI = parsed_envelope.as_rule() {
+            break;
+        }
+        envelopes.push(envelope_from_pair(parsed_envelope));
+    }
+
+    Ok(envelopes)
+}
+
+fn envelope_from_pair(pair: Pair<Rule>) -> SwiftEnvelope {
+    let mut envelope = SwiftEnvelope {
+        basic_header: BasicHeader::default(),
+        application_header: None,
+        user_header: vec![],
+        text: String::new(),
+        trailer: None,
+    };
+
+    for block in pair.into_inner() {
+        match block.as_rule() {
+            Rule::basic_header_block => envelope.basic_header = basic_header_from_pair(block),
+            Rule::application_header_block => {
+                envelope.application_header =
+                    block.into_inner().next().map(application_header_from_pair)
+            }
+            Rule::user_header_block => envelope.user_header = sub_blocks_from_pair(block),
+            Rule::text_block => {
+                envelope.text = block
+                    .into_inner()
+                    .next()
+                    .map(|text| text.as_str().to_string())
+                    .unwrap_or_default()
+            }
+            Rule::trailer_block => envelope.trailer = Some(sub_blocks_from_pair(block)),
+            _ => (),
+        }
+    }
+
+    envelope
+}
+
+fn basic_header_from_pair(pair: Pair<Rule>) -> BasicHeader {
+    let mut basic_header = BasicHeader::default();
+    for p in pair.into_inner() {
+        let value = p.as_str().to_string();
+        match p.as_rule() {
+            Rule::application_id => basic_header.application_id = value,
+            Rule::service_id => basic_header.service_id = value,
+            Rule::logical_terminal => basic_header.logical_terminal = value,
+            Rule::session_number => basic_header.session_number = value,
+            Rule::sequence_number => basic_header.sequence_number = value,
+            _ => (),
+        }
+    }
+    basic_header
+}
+
+fn application_header_from_pair(pair: Pair<Rule>) -> ApplicationHeader {
+    let is_input = pair.as_rule() == Rule::input_application_header;
+
+    let mut message_type = String::new();
+    let mut receiver_address = String::new();
+    let mut input_time = String::new();
+    let mut message_input_reference = String::new();
+    let mut output_date = String::new();
+    let mut output_time = String::new();
+    let mut priority = None;
+    for p in pair.into_inner() {
+        let value = p.as_str().to_string();
+        match p.as_rule() {
+            Rule::message_type => message_type = value,
+            Rule::logical_terminal => receiver_address = value,
+            Rule::input_time => input_time = value,
+            Rule::message_input_reference => message_input_reference = value,
+            Rule::output_date => output_date = value,
+            Rule::output_time => output_time = value,
+            Rule::priority => priority = Some(value),
+            _ => (),
+        }
+    }
+
+    if is_input {
+        ApplicationHeader::Input {
+            message_type,
+            receiver_address,
+            priority,
+        }
+    } else {
+        ApplicationHeader::Output {
+            message_type,
+            input_time,
+            message_input_reference,
+            output_date,
+            output_time,
+            priority,
+        }
+    }
+}
+
+fn sub_blocks_from_pair(pair: Pair<Rule>) -> Vec<Field> {
+    pair.into_inner()
+        .map(|sub_block| {
+            let mut inner = sub_block.into_inner();
+            let tag = inner.next().map(|p| p.as_str()).unwrap_or_default();
+            let value = inner.next().map(|p| p.as_str()).unwrap_or_default();
+            Field::new(tag, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_envelope() {
+        let input = "\
+            {1:F01BANKBEBBAXXX2222123456}\
+            {2:I940BANKDEFFXXXXN}\
+            {3:{108:MUR12345}}\
+            {4:\r\n\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR1,\r\n\
+            :62F:C090924EUR1,\r\n\
+            -}\
+            {5:{MAC:12345678}{CHK:123456789ABC}}\r\n";
+        let expected = SwiftEnvelope {
+            basic_header: BasicHeader {
+                application_id: "F".to_string(),
+                service_id: "01".to_string(),
+                logical_terminal: "BANKBEBBAXXX".to_string(),
+                session_number: "2222".to_string(),
+                sequence_number: "123456".to_string(),
+            },
+            application_header: Some(ApplicationHeader::Input {
+                message_type: "940".to_string(),
+                receiver_address: "BANKDEFFXXXX".to_string(),
+                priority: Some("N".to_string()),
+            }),
+            user_header: vec![Field::new("108", "MUR12345")],
+            text: ":20:a\r\n:25:b\r\n:28C:1\r\n:60F:C090924EUR1,\r\n:62F:C090924EUR1,".to_string(),
+            trailer: Some(vec![
+                Field::new("MAC", "12345678"),
+                Field::new("CHK", "123456789ABC"),
+            ]),
+        };
+
+        let envelopes = parse_swift_envelopes(input).unwrap();
+        assert_eq!(envelopes, vec![expected]);
+        assert_eq!(envelopes[0].sender_bic(), "BANKBEBBXXX");
+        assert_eq!(envelopes[0].validate_trailer(true), Ok(()));
+        assert_eq!(envelopes[0].parse_mt940().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_multiple_envelopes() {
+        let envelope = "{1:F01BANKBEBBAXXX2222123456}{4:\r\n:20:a\r\n-}";
+        let input = format!("\u{1}{}\u{3}\r\n\u{1}{}\u{3}", envelope, envelope);
+        let envelopes = parse_swift_envelopes(&input).unwrap();
+        assert_eq!(envelopes.len(), 2);
+        assert_eq!(envelopes[1].application_header, None);
+        assert_eq!(envelopes[1].text, ":20:a");
+        assert_eq!(envelopes[1].checksum(), None);
+        assert_eq!(envelopes[1].trailer, None);
+
+        let with_empty_trailer = format!("{}{{5:}}", envelope);
+        let envelopes = parse_swift_envelopes(&with_empty_trailer).unwrap();
+        assert_eq!(envelopes[0].trailer, Some(vec![]));
+    }
+
+    #[test]
+    fn validate_trailer() {
+        let envelope = "{1:F01BANKBEBBAXXX2222123456}{4:\r\n:20:a\r\n-}";
+        let validate = |trailer: &str, require_mac: bool| {
+            parse_swift_envelopes(&format!("{}{}", envelope, trailer)).unwrap()[0]
+                .validate_trailer(require_mac)
+        };
+
+        assert_eq!(
+            validate("", false),
+            Err(TrailerValidationError::MissingTrailer)
+        );
+        assert_eq!(
+            validate("{5:}", false),
+            Err(TrailerValidationError::MissingChecksum)
+        );
+        assert_eq!(
+            validate("{5:{TNG:}}", false),
+            Err(TrailerValidationError::MissingChecksum)
+        );
+        assert_eq!(
+            validate("{5:{CHK:123}}", false),
+            Err(TrailerValidationError::InvalidChecksum("123".to_string()))
+        );
+        assert_eq!(validate("{5:{CHK:123456789ABC}}", false), Ok(()));
+        assert_eq!(
+            validate("{5:{CHK:123456789ABC}}", true),
+            Err(TrailerValidationError::MissingMac)
+        );
+        assert_eq!(
+            validate("{5:{MAC:XYZ}{CHK:123456789ABC}}", false),
+            Err(TrailerValidationError::InvalidMac("XYZ".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_envelope_without_text_block() {
+        assert!(parse_swift_envelopes("{1:F01BANKBEBBAXXX2222123456}").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! The tags of MT940 and MT942 and the order they may come in.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// A tag such as `:61:` without its colons.
+///
+/// Tags which are neither part of MT940 nor of MT942 are kept as they are in [`Tag::Unknown`].
+///
+/// # Example
+/// ```
+/// use mt940::Tag;
+///
+/// let tag = Tag::from("61");
+/// assert_eq!(tag, Tag::StatementLine);
+/// assert_eq!(tag.as_str(), "61");
+/// assert_eq!(Tag::from("99"), Tag::Unknown("99".to_string()));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Tag {
+    /// `:20:`
+    TransactionRef,
+    /// `:21:`
+    RelatedRef,
+    /// `:25:`
+    AccountId,
+    /// `:25P:`, the account along with the BIC of the account servicing institution.
+    AccountIdWithBic,
+    /// `:28:`, the deprecated predecessor of `:28C:`.
+    StatementNoDeprecated,
+    /// `:28C:`
+    StatementNo,
+    /// `:60F:`
+    OpeningBalance,
+    /// `:60M:`
+    IntermediateOpeningBalance,
+    /// `:61:`
+    StatementLine,
+    /// `:86:`
+    InformationToAccountOwner,
+    /// `:62F:`
+    ClosingBalance,
+    /// `:62M:`
+    IntermediateClosingBalance,
+    /// `:64:`
+    ClosingAvailableBalance,
+    /// `:65:`
+    ForwardAvailableBalance,
+    /// `:34F:`, the floor limit of MT942.
+    FloorLimit,
+    /// `:13D:`, the date and time of a MT942 message.
+    DateTimeIndication,
+    /// `:90D:`, the number and sum of debit entries of a MT942 message.
+    DebitEntries,
+    /// `:90C:`, the number and sum of credit entries of a MT942 message.
+    CreditEntries,
+    /// Any other tag, e.g. the proprietary `:NS:`.
+    Unknown(String),
+}
+
+impl Tag {
+    /// The tags of MT940 in the order they appear in a message.
+    pub const MT940: [Tag; 14] = [
+        Tag::TransactionRef,
+        Tag::RelatedRef,
+        Tag::AccountId,
+        Tag::AccountIdWithBic,
+        Tag::StatementNoDeprecated,
+        Tag::StatementNo,
+        Tag::IntermediateOpeningBalance,
+        Tag::OpeningBalance,
+        Tag::StatementLine,
+        Tag::InformationToAccountOwner,
+        Tag::IntermediateClosingBalance,
+        Tag::ClosingBalance,
+        Tag::ClosingAvailableBalance,
+        Tag::ForwardAvailableBalance,
+    ];
+
+    /// The tag as it is written in between the colons, e.g. `61`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Tag::TransactionRef => "20",
+            Tag::RelatedRef => "21",
+            Tag::AccountId => "25",
+            Tag::AccountIdWithBic => "25P",
+            Tag::StatementNoDeprecated => "28",
+            Tag::StatementNo => "28C",
+            Tag::OpeningBalance => "60F",
+            Tag::IntermediateOpeningBalance => "60M",
+            Tag::StatementLine => "61",
+            Tag::InformationToAccountOwner => "86",
+            Tag::ClosingBalance => "62F",
+            Tag::IntermediateClosingBalance => "62M",
+            Tag::ClosingAvailableBalance => "64",
+            Tag::ForwardAvailableBalance => "65",
+            Tag::FloorLimit => "34F",
+            Tag::DateTimeIndication => "13D",
+            Tag::DebitEntries => "90D",
+            Tag::CreditEntries => "90C",
+            Tag::Unknown(tag) => tag,
+        }
+    }
+
+    /// The tag written as `tag` unless it is unknown.
+    pub fn known(tag: &str) -> Option<Tag> {
+        let tag = match tag {
+            "20" => Tag::TransactionRef,
+            "21" => Tag::RelatedRef,
+            "25" => Tag::AccountId,
+            "25P" => Tag::AccountIdWithBic,
+            "28" => Tag::StatementNoDeprecated,
+            "28C" => Tag::StatementNo,
+            "60F" => Tag::OpeningBalance,
+            "60M" => Tag::IntermediateOpeningBalance,
+            "61" => Tag::StatementLine,
+            "86" => Tag::InformationToAccountOwner,
+            "62F" => Tag::ClosingBalance,
+            "62M" => Tag::IntermediateClosingBalance,
+            "64" => Tag::ClosingAvailableBalance,
+            "65" => Tag::ForwardAvailableBalance,
+            "34F" => Tag::FloorLimit,
+            "13D" => Tag::DateTimeIndication,
+            "90D" => Tag::DebitEntries,
+            "90C" => Tag::CreditEntries,
+            _ => return None,
+        };
+        Some(tag)
+    }
+
+    /// Whether this tag is part of MT940.
+    pub fn is_mt940(&self) -> bool {
+        Tag::MT940.contains(self)
+    }
+
+    /// Whether every MT940 message has to contain this tag or its alternative, e.g. `:60M:`
+    /// instead of `:60F:`.
+    pub fn is_mandatory(&self) -> bool {
+        matches!(
+            self,
+            Tag::TransactionRef
+                | Tag::AccountId
+                | Tag::AccountIdWithBic
+                | Tag::StatementNoDeprecated
+                | Tag::StatementNo
+                | Tag::OpeningBalance
+                | Tag::IntermediateOpeningBalance
+                | Tag::ClosingBalance
+                | Tag::IntermediateClosingBalance
+        )
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(tag: &str) -> Tag {
+        Tag::known(tag).unwrap_or_else(|| Tag::Unknown(tag.to_string()))
+    }
+}
+
+impl From<String> for Tag {
+    fn from(tag: String) -> Tag {
+        Tag::known(&tag).unwrap_or(Tag::Unknown(tag))
+    }
+}
+
+impl From<Tag> for String {
+    fn from(tag: Tag) -> String {
+        match tag {
+            Tag::Unknown(tag) => tag,
+            tag => tag.as_str().to_string(),
+        }
+    }
+}
+
+impl FromStr for Tag {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Tag::from(s))
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The tags a MT940 message may start with.
+pub const FIRST_TAGS: &[Tag] = &[Tag::TransactionRef];
+
+/// The tags which may follow after `tag` in a MT940 message.
+///
+/// Unknown tags don't take part in the order of tags, so nothing may follow them.
+///
+/// # Example
+/// ```
+/// use mt940::{allowed_successors, Tag};
+///
+/// assert_eq!(
+///     allowed_successors("61"),
+///     [
+///         Tag::StatementLine,
+///         Tag::InformationToAccountOwner,
+///         Tag::IntermediateClosingBalance,
+///         Tag::ClosingBalance,
+///     ]
+/// );
+/// assert!(allowed_successors("99").is_empty());
+/// ```
+pub fn allowed_successors(tag: &str) -> &'static [Tag] {
+    use Tag::*;
+    match tag {
+        "20" => &[RelatedRef, AccountId, AccountIdWithBic],
+        "21" => &[AccountId, AccountIdWithBic],
+        "25" | "25P" => &[StatementNoDeprecated, StatementNo],
+        "28" | "28C" => &[IntermediateOpeningBalance, OpeningBalance],
+        "60M" | "60F" => &[
+            StatementLine,
+            IntermediateClosingBalance,
+            ClosingBalance,
+            InformationToAccountOwner,
+        ],
+        "61" => &[
+            StatementLine,
+            InformationToAccountOwner,
+            IntermediateClosingBalance,
+            ClosingBalance,
+        ],
+        "86" => &[
+            StatementLine,
+            IntermediateClosingBalance,
+            ClosingBalance,
+            InformationToAccountOwner,
+        ],
+        "62M" | "62F" => &[
+            ClosingAvailableBalance,
+            ForwardAvailableBalance,
+            InformationToAccountOwner,
+        ],
+        "64" => &[ForwardAvailableBalance, InformationToAccountOwner],
+        "65" => &[ForwardAvailableBalance, InformationToAccountOwner],
+        _ => &[],
+    }
+}
+
+/// Like [`allowed_successors`] but for [`ParserConfig::relaxed_tag_order`].
+///
+/// `:21:` may follow `:25:` and the closing balance as well as the available balances may come in
+/// any order.
+///
+/// [`ParserConfig::relaxed_tag_order`]: crate::ParserConfig::relaxed_tag_order
+pub fn relaxed_successors(tag: &str) -> &'static [Tag] {
+    use Tag::*;
+    match tag {
+        "20" => &[RelatedRef, AccountId, AccountIdWithBic],
+        "21" => &[
+            AccountId,
+            AccountIdWithBic,
+            StatementNoDeprecated,
+            StatementNo,
+        ],
+        "25" | "25P" => &[RelatedRef, StatementNoDeprecated, StatementNo],
+        "28" | "28C" => &[IntermediateOpeningBalance, OpeningBalance],
+        "60M" | "60F" | "61" | "86" => &[
+            StatementLine,
+            InformationToAccountOwner,
+            IntermediateClosingBalance,
+            ClosingBalance,
+            ClosingAvailableBalance,
+            ForwardAvailableBalance,
+        ],
+        "62M" | "62F" => &[
+            ClosingAvailableBalance,
+            ForwardAvailableBalance,
+            InformationToAccountOwner,
+        ],
+        "64" => &[
+            IntermediateClosingBalance,
+            ClosingBalance,
+            ForwardAvailableBalance,
+            InformationToAccountOwner,
+        ],
+        "65" => &[
+            IntermediateClosingBalance,
+            ClosingBalance,
+            ClosingAvailableBalance,
+            ForwardAvailableBalance,
+            InformationToAccountOwner,
+        ],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for tag in Tag::MT940 {
+            assert_eq!(Tag::from(tag.as_str()), tag);
+            assert_eq!(String::from(tag.clone()), tag.to_string());
+            assert!(tag.is_mt940());
+        }
+        assert_eq!(Tag::from("NS").as_str(), "NS");
+        assert!(!Tag::FloorLimit.is_mt940());
+    }
+
+    #[test]
+    fn successors_are_mt940_tags() {
+        for tag in Tag::MT940 {
+            for successor in allowed_successors(tag.as_str()) {
+                assert!(successor.is_mt940());
+                assert!(relaxed_successors(tag.as_str()).contains(successor));
+            }
+        }
+    }
+
+    #[test]
+    fn serde() {
+        let tags = vec![Tag::StatementLine, Tag::Unknown("NS".to_string())];
+        let json = serde_json::to_string(&tags).unwrap();
+        assert_eq!(json, r#"["61","NS"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Tag>>(&json).unwrap(), tags);
+    }
+}
@@ -1,78 +1,103 @@
 use chrono::prelude::*;
+use pest::iterators::{Pair, Pairs};
 use pest::Parser;
 use std::str::FromStr;
 
-use crate::errors::RequiredTagNotFoundError;
-use crate::utils::{date_from_mt940_date, decimal_from_mt940_amount};
+use crate::borrowed::{FieldRef, StatementLineRef};
+use crate::errors::{AmountParseError, DateParseError, ErrorLocation, RequiredTagNotFoundError};
+use crate::utils::{
+    date_from_mt940_date, date_from_mt940_date_with_pivot, date_from_parts,
+    decimal_from_mt940_amount,
+};
 use crate::MT940Parser;
 use crate::Rule;
 use crate::{
-    AvailableBalance, Balance, DebitOrCredit, ExtDebitOrCredit, Field, ParseError, StatementLine,
-    TransactionTypeIdentificationCode,
+    AvailableBalance, Balance, DebitOrCredit, EntriesSummary, EntryType, ExtDebitOrCredit,
+    FloorLimitIndicator, ParseError, Tag, TransactionTypeIdentificationCode,
 };
 
-pub fn parse_20_tag(field: &Field) -> Result<String, ParseError> {
-    if field.tag != "20" {
-        return Err(RequiredTagNotFoundError::new("20").into());
-    }
-    let parsed_field = MT940Parser::parse(Rule::tag_20_field, &field.value);
-    let transaction_ref_no = parsed_field?.as_str().to_string();
+pub fn parse_20_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<&'a str, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::TransactionRef])?;
+    let parsed_field = MT940Parser::parse(Rule::tag_20_field, field.value);
+    let transaction_ref_no = parsed_field?.as_str();
     Ok(transaction_ref_no)
 }
 
-pub fn parse_21_tag(field: &Field) -> Result<String, ParseError> {
-    if field.tag != "21" {
-        return Err(RequiredTagNotFoundError::new("21").into());
-    }
-    let parsed_field = MT940Parser::parse(Rule::tag_21_field, &field.value);
-    let ref_to_related_msg = parsed_field?.as_str().to_string();
+pub fn parse_21_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<&'a str, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::RelatedRef])?;
+    let parsed_field = MT940Parser::parse(Rule::tag_21_field, field.value);
+    let ref_to_related_msg = parsed_field?.as_str();
     Ok(ref_to_related_msg)
 }
 
-pub fn parse_25_tag(field: &Field) -> Result<String, ParseError> {
-    if field.tag != "25" {
-        return Err(RequiredTagNotFoundError::new("21").into());
-    }
-    let parsed_field = MT940Parser::parse(Rule::tag_25_field, &field.value);
-    let account_id = parsed_field?.as_str().to_string();
+pub fn parse_25_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<&'a str, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::AccountId])?;
+    let parsed_field = MT940Parser::parse(Rule::tag_25_field, field.value);
+    let account_id = parsed_field?.as_str();
     Ok(account_id)
 }
 
-pub fn parse_28_tag(field: &Field) -> Result<(String, Option<String>), ParseError> {
-    if field.tag != "28" && field.tag != "28C" {
-        return Err(RequiredTagNotFoundError::new("28 or 28C").into());
+pub fn parse_25p_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<(&'a str, &'a str), ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::AccountIdWithBic])?;
+    let mut account_id = None;
+    let mut identifier_code = None;
+    let pairs = parse_components(Rule::tag_25p_field, field)?;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::account_no_line => account_id = Some(pair.as_str()),
+            Rule::identifier_code => identifier_code = Some(pair.as_str()),
+            _ => (),
+        };
     }
+    Ok((
+        required(account_id, field, "account id")?,
+        required(identifier_code, field, "identifier code")?,
+    ))
+}
+
+pub fn parse_28_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+) -> Result<(&'a str, Option<&'a str>), ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::StatementNoDeprecated, Tag::StatementNo])?;
     let mut statement_no = None;
     let mut sequence_no = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_28c_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_28c_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
-            Rule::statement_no => statement_no = Some(pair.as_str().to_string()),
-            Rule::sequence_no => sequence_no = Some(pair.as_str().to_string()),
+            Rule::statement_no => statement_no = Some(pair.as_str()),
+            Rule::sequence_no => sequence_no = Some(pair.as_str()),
             _ => (),
         };
     }
-    Ok((statement_no.unwrap(), sequence_no))
+    Ok((required(statement_no, field, "statement no")?, sequence_no))
 }
 
-pub fn parse_60_tag(field: &Field) -> Result<Balance, ParseError> {
-    if field.tag != "60M" && field.tag != "60F" {
-        return Err(RequiredTagNotFoundError::new("60").into());
-    }
-    let is_intermediate = field.tag.as_str() == "60M";
+pub fn parse_60_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+    year_pivot: Option<u8>,
+) -> Result<Balance, ParseError> {
+    let field = field.into();
+    expect_tag(
+        &field,
+        &[Tag::OpeningBalance, Tag::IntermediateOpeningBalance],
+    )?;
+    let is_intermediate = Tag::IntermediateOpeningBalance == field.tag;
     let mut debit_credit_indicator = None;
     let mut date = None;
     let mut iso_currency_code = None;
     let mut amount = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_60_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_60_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
             Rule::debit_credit_indicator => {
                 debit_credit_indicator = Some(DebitOrCredit::from_str(pair.as_str())?);
             }
-            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::date => date = Some(date_from_mt940_date_with_pivot(pair.as_str(), year_pivot)?),
             Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
             Rule::amount => {
                 amount = Some(decimal_from_mt940_amount(pair.as_str())?);
@@ -82,32 +107,34 @@ pub fn parse_60_tag(field: &Field) -> Result<Balance, ParseError> {
     }
     let opening_balance = Balance {
         is_intermediate,
-        debit_credit_indicator: debit_credit_indicator.unwrap(),
-        date: date.unwrap(),
-        iso_currency_code: iso_currency_code.unwrap(),
-        amount: amount.unwrap(),
+        debit_credit_indicator: required(debit_credit_indicator, field, "debit credit indicator")?,
+        date: required(date, field, "date")?,
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        amount: required(amount, field, "amount")?,
     };
     Ok(opening_balance)
 }
 
-pub fn parse_61_tag(field: &Field) -> Result<StatementLine, ParseError> {
-    if field.tag != "61" {
-        return Err(RequiredTagNotFoundError::new("61").into());
-    }
+pub fn parse_61_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+    year_pivot: Option<u8>,
+) -> Result<StatementLineRef<'a>, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::StatementLine])?;
     let mut date = None;
     let mut short_date = None;
     let mut ext_debit_credit_indicator = None;
     let mut funds_code = None;
     let mut amount = None;
+    let mut entry_type = None;
     let mut transaction_type_ident_code = None;
     let mut customer_ref = None;
     let mut bank_ref = None;
     let mut supplementary_details = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_61_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_61_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
-            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::date => date = Some(date_from_mt940_date_with_pivot(pair.as_str(), year_pivot)?),
             Rule::short_date => {
                 let mut month = None;
                 let mut day = None;
@@ -115,7 +142,7 @@ pub fn parse_61_tag(field: &Field) -> Result<StatementLine, ParseError> {
                     match p.as_rule() {
                         Rule::month => month = Some(p.as_str()),
                         Rule::day => day = Some(p.as_str()),
-                        _ => unreachable!(),
+                        _ => (),
                     }
                 }
                 // Since we only get month and day from the short date, we'll have
@@ -126,28 +153,30 @@ pub fn parse_61_tag(field: &Field) -> Result<StatementLine, ParseError> {
                 // which would then result in this the entry date ending up as
                 // 2018-01-01 even though it should be 2019-01-01. I'll not be too
                 // smart about this for now but I'll keep an eye on this.
-                short_date = Some(
-                    NaiveDate::from_ymd_opt(
-                        date.unwrap().year(),
-                        month.unwrap().parse().unwrap(),
-                        day.unwrap().parse().unwrap(),
-                    )
-                    .unwrap(),
-                );
+                let year = required(date, field, "date")?.year();
+                short_date = Some(date_from_parts(
+                    &year.to_string(),
+                    required(month, field, "month")?,
+                    required(day, field, "day")?,
+                )?);
             }
             Rule::ext_debit_credit_indicator => {
                 ext_debit_credit_indicator = Some(ExtDebitOrCredit::from_str(pair.as_str())?);
             }
             Rule::funds_code => {
-                funds_code = Some(pair.as_str().to_string());
+                funds_code = Some(pair.as_str());
             }
             Rule::amount => {
                 amount = Some(decimal_from_mt940_amount(pair.as_str())?);
             }
             Rule::transaction_type_ident_code => {
                 // The actual transaction type ident code begins after the first
-                // character. The first character is either "N" or "F".
-                let actual_type_ident_code_str = &pair.as_str()[1..];
+                // character. The first character is either "N", "F" or "S".
+                let mut ident_code_chars = pair.as_str().chars();
+                if let Some(prefix) = ident_code_chars.next() {
+                    entry_type = Some(EntryType::from_str(&prefix.to_string())?);
+                }
+                let actual_type_ident_code_str = ident_code_chars.as_str();
                 match TransactionTypeIdentificationCode::from_str(actual_type_ident_code_str) {
                     Ok(t) => transaction_type_ident_code = Some(t),
                     Err(strum::ParseError::VariantNotFound) => {
@@ -163,58 +192,75 @@ pub fn parse_61_tag(field: &Field) -> Result<StatementLine, ParseError> {
                 };
             }
             Rule::customer_ref => {
-                customer_ref = Some(pair.as_str().to_string());
+                customer_ref = Some(pair.as_str());
             }
             Rule::bank_ref => {
-                bank_ref = Some(pair.as_str().to_string());
+                bank_ref = Some(pair.as_str());
             }
             Rule::supplementary_details => {
-                supplementary_details = Some(pair.as_str().to_string());
+                supplementary_details = Some(pair.as_str());
             }
             _ => (),
         }
     }
-    let statement_line = StatementLine {
-        value_date: date.unwrap(),
+    let statement_line = StatementLineRef {
+        value_date: required(date, field, "date")?,
         entry_date: short_date,
-        ext_debit_credit_indicator: ext_debit_credit_indicator.unwrap(),
+        ext_debit_credit_indicator: required(
+            ext_debit_credit_indicator,
+            field,
+            "ext debit credit indicator",
+        )?,
         funds_code,
-        amount: amount.unwrap(),
-        transaction_type_ident_code: transaction_type_ident_code.unwrap(),
-        customer_ref: customer_ref.unwrap(),
+        amount: required(amount, field, "amount")?,
+        entry_type: required(entry_type, field, "entry type")?,
+        transaction_type_ident_code: required(
+            transaction_type_ident_code,
+            field,
+            "transaction type ident code",
+        )?,
+        customer_ref: required(customer_ref, field, "customer ref")?,
         bank_ref,
         supplementary_details,
         information_to_account_owner: None,
+        information_lines: vec![],
+        structured_information: None,
+        sepa_purpose: None,
+        raw: None,
+        index: None,
     };
     Ok(statement_line)
 }
 
-pub fn parse_86_tag(field: &Field) -> Result<String, ParseError> {
-    if field.tag != "86" {
-        return Err(RequiredTagNotFoundError::new("86").into());
-    }
-    let parsed_field = MT940Parser::parse(Rule::tag_86_field, &field.value);
-    let information_to_account_owner = parsed_field?.as_str().to_string();
+pub fn parse_86_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<&'a str, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::InformationToAccountOwner])?;
+    let parsed_field = MT940Parser::parse(Rule::tag_86_field, field.value);
+    let information_to_account_owner = parsed_field?.as_str();
     Ok(information_to_account_owner)
 }
 
-pub fn parse_62_tag(field: &Field) -> Result<Balance, ParseError> {
-    if field.tag != "62M" && field.tag != "62F" {
-        return Err(RequiredTagNotFoundError::new("62").into());
-    }
-    let is_intermediate = field.tag.as_str() == "62M";
+pub fn parse_62_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+    year_pivot: Option<u8>,
+) -> Result<Balance, ParseError> {
+    let field = field.into();
+    expect_tag(
+        &field,
+        &[Tag::ClosingBalance, Tag::IntermediateClosingBalance],
+    )?;
+    let is_intermediate = Tag::IntermediateClosingBalance == field.tag;
     let mut debit_credit_indicator = None;
     let mut date = None;
     let mut iso_currency_code = None;
     let mut amount = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_62_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_62_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
             Rule::debit_credit_indicator => {
                 debit_credit_indicator = Some(DebitOrCredit::from_str(pair.as_str())?);
             }
-            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::date => date = Some(date_from_mt940_date_with_pivot(pair.as_str(), year_pivot)?),
             Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
             Rule::amount => {
                 amount = Some(decimal_from_mt940_amount(pair.as_str())?);
@@ -224,30 +270,31 @@ pub fn parse_62_tag(field: &Field) -> Result<Balance, ParseError> {
     }
     let closing_balance = Balance {
         is_intermediate,
-        debit_credit_indicator: debit_credit_indicator.unwrap(),
-        date: date.unwrap(),
-        iso_currency_code: iso_currency_code.unwrap(),
-        amount: amount.unwrap(),
+        debit_credit_indicator: required(debit_credit_indicator, field, "debit credit indicator")?,
+        date: required(date, field, "date")?,
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        amount: required(amount, field, "amount")?,
     };
     Ok(closing_balance)
 }
 
-pub fn parse_64_tag(field: &Field) -> Result<AvailableBalance, ParseError> {
-    if field.tag != "64" {
-        return Err(RequiredTagNotFoundError::new("64").into());
-    }
+pub fn parse_64_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+    year_pivot: Option<u8>,
+) -> Result<AvailableBalance, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::ClosingAvailableBalance])?;
     let mut debit_credit_indicator = None;
     let mut date = None;
     let mut iso_currency_code = None;
     let mut amount = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_64_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_64_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
             Rule::debit_credit_indicator => {
                 debit_credit_indicator = Some(DebitOrCredit::from_str(pair.as_str())?);
             }
-            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::date => date = Some(date_from_mt940_date_with_pivot(pair.as_str(), year_pivot)?),
             Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
             Rule::amount => {
                 amount = Some(decimal_from_mt940_amount(pair.as_str())?);
@@ -256,30 +303,31 @@ pub fn parse_64_tag(field: &Field) -> Result<AvailableBalance, ParseError> {
         };
     }
     let closing_available_balance = AvailableBalance {
-        debit_credit_indicator: debit_credit_indicator.unwrap(),
-        date: date.unwrap(),
-        iso_currency_code: iso_currency_code.unwrap(),
-        amount: amount.unwrap(),
+        debit_credit_indicator: required(debit_credit_indicator, field, "debit credit indicator")?,
+        date: required(date, field, "date")?,
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        amount: required(amount, field, "amount")?,
     };
     Ok(closing_available_balance)
 }
 
-pub fn parse_65_tag(field: &Field) -> Result<AvailableBalance, ParseError> {
-    if field.tag != "65" {
-        return Err(RequiredTagNotFoundError::new("65").into());
-    }
+pub fn parse_65_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+    year_pivot: Option<u8>,
+) -> Result<AvailableBalance, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::ForwardAvailableBalance])?;
     let mut debit_credit_indicator = None;
     let mut date = None;
     let mut iso_currency_code = None;
     let mut amount = None;
-    let parsed_field = MT940Parser::parse(Rule::tag_65_field, &field.value);
-    let pairs = parsed_field?.next().unwrap().into_inner();
+    let pairs = parse_components(Rule::tag_65_field, field)?;
     for pair in pairs {
         match pair.as_rule() {
             Rule::debit_credit_indicator => {
                 debit_credit_indicator = Some(DebitOrCredit::from_str(pair.as_str())?);
             }
-            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::date => date = Some(date_from_mt940_date_with_pivot(pair.as_str(), year_pivot)?),
             Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
             Rule::amount => {
                 amount = Some(decimal_from_mt940_amount(pair.as_str())?);
@@ -288,14 +336,174 @@ pub fn parse_65_tag(field: &Field) -> Result<AvailableBalance, ParseError> {
         };
     }
     let forward_available_balance = AvailableBalance {
-        debit_credit_indicator: debit_credit_indicator.unwrap(),
-        date: date.unwrap(),
-        iso_currency_code: iso_currency_code.unwrap(),
-        amount: amount.unwrap(),
+        debit_credit_indicator: required(debit_credit_indicator, field, "debit credit indicator")?,
+        date: required(date, field, "date")?,
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        amount: required(amount, field, "amount")?,
     };
     Ok(forward_available_balance)
 }
 
+pub fn parse_34f_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+) -> Result<FloorLimitIndicator, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::FloorLimit])?;
+    let mut iso_currency_code = None;
+    let mut debit_credit_indicator = None;
+    let mut amount = None;
+    let pairs = parse_components(Rule::tag_34f_field, field)?;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
+            Rule::debit_credit_indicator => {
+                debit_credit_indicator = Some(DebitOrCredit::from_str(pair.as_str())?);
+            }
+            Rule::amount => {
+                amount = Some(decimal_from_mt940_amount(pair.as_str())?);
+            }
+            _ => (),
+        };
+    }
+    let floor_limit_indicator = FloorLimitIndicator {
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        debit_credit_indicator,
+        amount: required(amount, field, "amount")?,
+    };
+    Ok(floor_limit_indicator)
+}
+
+pub fn parse_13d_tag<'a>(
+    field: impl Into<FieldRef<'a>>,
+) -> Result<DateTime<FixedOffset>, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::DateTimeIndication])?;
+    let mut date = None;
+    let mut time = None;
+    let mut offset = None;
+    let pairs = parse_components(Rule::tag_13d_field, field)?;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::date => date = Some(date_from_mt940_date(pair.as_str())?),
+            Rule::time => {
+                let (hour, minute) = hour_and_minute(pair)?;
+                time = Some(NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(|| {
+                    DateParseError::TimeOutOfRange {
+                        hour: hour.to_string(),
+                        minute: minute.to_string(),
+                    }
+                })?);
+            }
+            Rule::utc_offset => {
+                let is_negative = pair.as_str().starts_with('-');
+                let (hour, minute) = hour_and_minute(pair)?;
+                let seconds = (hour * 3600 + minute * 60) as i32;
+                let seconds = if is_negative { -seconds } else { seconds };
+                offset = Some(FixedOffset::east_opt(seconds).ok_or_else(|| {
+                    DateParseError::TimeOutOfRange {
+                        hour: hour.to_string(),
+                        minute: minute.to_string(),
+                    }
+                })?);
+            }
+            _ => (),
+        };
+    }
+    let time = required(time, field, "time")?;
+    let date_time = required(date, field, "date")?
+        .and_time(time)
+        .and_local_timezone(required(offset, field, "utc offset")?)
+        .single()
+        .ok_or_else(|| DateParseError::TimeOutOfRange {
+            hour: time.hour().to_string(),
+            minute: time.minute().to_string(),
+        })?;
+    Ok(date_time)
+}
+
+/// Extract hour and minute from a pair containing a `hour` and `minute` rule.
+fn hour_and_minute(pair: Pair<Rule>) -> Result<(u32, u32), DateParseError> {
+    let mut hour = "0";
+    let mut minute = "0";
+    for p in pair.into_inner() {
+        match p.as_rule() {
+            Rule::hour => hour = p.as_str(),
+            Rule::minute => minute = p.as_str(),
+            _ => (),
+        }
+    }
+    match (hour.parse(), minute.parse()) {
+        (Ok(hour), Ok(minute)) => Ok((hour, minute)),
+        _ => Err(DateParseError::TimeOutOfRange {
+            hour: hour.to_string(),
+            minute: minute.to_string(),
+        }),
+    }
+}
+
+pub fn parse_90_tag<'a>(field: impl Into<FieldRef<'a>>) -> Result<EntriesSummary, ParseError> {
+    let field = field.into();
+    expect_tag(&field, &[Tag::DebitEntries, Tag::CreditEntries])?;
+    let mut number_of_entries = None;
+    let mut iso_currency_code = None;
+    let mut amount = None;
+    let pairs = parse_components(Rule::tag_90_field, field)?;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::number_of_entries => {
+                number_of_entries = Some(
+                    pair.as_str()
+                        .parse()
+                        .map_err(AmountParseError::IntParseError)?,
+                );
+            }
+            Rule::iso_currency_code => iso_currency_code = Some(pair.as_str().to_string()),
+            Rule::amount => {
+                amount = Some(decimal_from_mt940_amount(pair.as_str())?);
+            }
+            _ => (),
+        };
+    }
+    let entries_summary = EntriesSummary {
+        number_of_entries: required(number_of_entries, field, "number of entries")?,
+        iso_currency_code: required(iso_currency_code, field, "iso currency code")?,
+        amount: required(amount, field, "amount")?,
+    };
+    Ok(entries_summary)
+}
+
+/// Parse the value of `field` with `rule` and return its components.
+fn parse_components(rule: Rule, field: FieldRef<'_>) -> Result<Pairs<'_, Rule>, ParseError> {
+    let mut parsed_field = MT940Parser::parse(rule, field.value)?;
+    let pair = required(parsed_field.next(), field, "value")?;
+    Ok(pair.into_inner())
+}
+
+/// Fail unless `field` has one of the tags `expected`.
+fn expect_tag(field: &FieldRef<'_>, expected: &[Tag]) -> Result<(), ParseError> {
+    if expected.iter().any(|tag| tag == field.tag) {
+        return Ok(());
+    }
+    let expected = expected
+        .iter()
+        .map(Tag::as_str)
+        .collect::<Vec<_>>()
+        .join(" or ");
+    Err(RequiredTagNotFoundError::new(&expected).into())
+}
+
+/// Turn a missing component of `field` into an error.
+///
+/// The grammar makes sure that required components are present so this only fails if the grammar
+/// and the tag parsers disagree.
+fn required<T>(component: Option<T>, field: FieldRef<'_>, name: &str) -> Result<T, ParseError> {
+    component.ok_or_else(|| ParseError::MissingComponent {
+        tag: Tag::from(field.tag),
+        component: name.to_string(),
+        location: ErrorLocation::default(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use proptest::prelude::*;
@@ -305,6 +513,7 @@ mod tests {
     use rust_decimal::Decimal;
 
     use super::*;
+    use crate::{Field, StatementLine};
 
     proptest! {
         #[test]
@@ -351,6 +560,24 @@ mod tests {
         }
     }
 
+    #[rstest(
+        input,
+        expected,
+        case("NL12ABNA0123456789\nABNANL2A", ("NL12ABNA0123456789", "ABNANL2A")),
+        case("123456789\nDABADKKKXXX", ("123456789", "DABADKKKXXX"))
+    )]
+    fn tag_25p_input(input: &str, expected: (&str, &str)) {
+        let field = Field::new("25P", input);
+        let parsed = parse_25p_tag(&field).unwrap();
+        assert_eq!(parsed, expected);
+    }
+
+    #[rstest(input, case("123456789"), case("123456789\nDABA"))]
+    fn tag_25p_invalid(input: &str) {
+        let field = Field::new("25P", input);
+        assert!(parse_25p_tag(&field).is_err());
+    }
+
     proptest! {
         #[test]
         fn tag_28_input(statement_no in r"[[:digit:]]{1, 5}",
@@ -371,11 +598,11 @@ mod tests {
             let field = Field::from_str(&format!(":{}:{}", tag, input)).unwrap();
             let parsed = parse_28_tag(&field).unwrap();
             let expected = (
-                statement_no,
+                statement_no.as_str(),
                 if sequence_no.is_empty() {
                     None
                 } else {
-                    Some(sequence_no)
+                    Some(sequence_no.as_str())
                 }
             );
             prop_assert_eq!(parsed, expected);
@@ -403,7 +630,7 @@ mod tests {
             amount: Decimal::from_str(expected_decimal).unwrap(),
         };
         let field = Field::from_str(input).unwrap();
-        let parsed = parse_60_tag(&field).unwrap();
+        let parsed = parse_60_tag(&field, None).unwrap();
         assert_eq!(parsed, expected);
     }
 
@@ -426,7 +653,7 @@ mod tests {
                 amount=amount);
 
             let field = Field::from_str(&format!(":60{}:{}", intermediate, input)).unwrap();
-            let parsed = parse_60_tag(&field).unwrap();
+            let parsed = parse_60_tag(&field, None).unwrap();
             let expected = Balance {
                 is_intermediate: if intermediate == "M" { true } else { false },
                 debit_credit_indicator: DebitOrCredit::from_str(&debit_credit_indicator).unwrap(),
@@ -457,7 +684,7 @@ mod tests {
                 amount=amount);
 
             let field = Field::from_str(&format!(":62{}:{}", intermediate, input)).unwrap();
-            let parsed = parse_62_tag(&field).unwrap();
+            let parsed = parse_62_tag(&field, None).unwrap();
             let expected = Balance {
                 is_intermediate: if intermediate == "M" { true } else { false },
                 debit_credit_indicator: DebitOrCredit::from_str(&debit_credit_indicator).unwrap(),
@@ -487,7 +714,7 @@ mod tests {
                 amount=amount);
 
             let field = Field::from_str(&format!(":64:{}", input)).unwrap();
-            let parsed = parse_64_tag(&field).unwrap();
+            let parsed = parse_64_tag(&field, None).unwrap();
             let expected = AvailableBalance {
                 debit_credit_indicator: DebitOrCredit::from_str(&debit_credit_indicator).unwrap(),
                 date: date_from_mt940_date(&date).unwrap(),
@@ -516,7 +743,7 @@ mod tests {
                 amount=amount);
 
             let field = Field::from_str(&format!(":65:{}", input)).unwrap();
-            let parsed = parse_65_tag(&field).unwrap();
+            let parsed = parse_65_tag(&field, None).unwrap();
             let expected = AvailableBalance {
                 debit_credit_indicator: DebitOrCredit::from_str(&debit_credit_indicator).unwrap(),
                 date: date_from_mt940_date(&date).unwrap(),
@@ -527,13 +754,176 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tag_13d_input() {
+        let field = Field::from_str(":13D:1610301730+0100").unwrap();
+        let parsed = parse_13d_tag(&field).unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2016-10-30T17:30:00+01:00");
+    }
+
+    #[rstest(
+        input,
+        case(":13D:1610302930+0100"),
+        case(":13D:1610301730+2900"),
+        case(":13D:1613301730+0100")
+    )]
+    fn tag_13d_out_of_range(input: &str) {
+        let field = Field::from_str(input).unwrap();
+        assert!(matches!(
+            parse_13d_tag(&field),
+            Err(ParseError::DateParseError(..))
+        ));
+    }
+
+    #[rstest(
+        input,
+        expected_debit_credit_indicator,
+        case(":34F:EURD0,00", Some(DebitOrCredit::Debit)),
+        case(":34F:EURC0,00", Some(DebitOrCredit::Credit)),
+        case(":34F:EUR0,00", None)
+    )]
+    fn tag_34f_input(input: &str, expected_debit_credit_indicator: Option<DebitOrCredit>) {
+        let field = Field::from_str(input).unwrap();
+        let parsed = parse_34f_tag(&field).unwrap();
+        let expected = FloorLimitIndicator {
+            iso_currency_code: "EUR".into(),
+            debit_credit_indicator: expected_debit_credit_indicator,
+            amount: Decimal::from_str("0").unwrap(),
+        };
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn tag_90_input() {
+        let field = Field::from_str(":90D:12EUR2,30").unwrap();
+        let parsed = parse_90_tag(&field).unwrap();
+        let expected = EntriesSummary {
+            number_of_entries: 12,
+            iso_currency_code: "EUR".into(),
+            amount: Decimal::from_str("2.30").unwrap(),
+        };
+        assert_eq!(parsed, expected);
+    }
+
+    #[rstest(
+        input,
+        expected_indicator,
+        expected_funds_code,
+        case("D", ExtDebitOrCredit::Debit, None),
+        case("C", ExtDebitOrCredit::Credit, None),
+        case("RD", ExtDebitOrCredit::ReverseCredit, None),
+        case("RC", ExtDebitOrCredit::ReverseDebit, None),
+        case("DR", ExtDebitOrCredit::Debit, Some("R")),
+        case("CR", ExtDebitOrCredit::Credit, Some("R")),
+        case("DD", ExtDebitOrCredit::Debit, Some("D")),
+        case("CC", ExtDebitOrCredit::Credit, Some("C")),
+        case("RDR", ExtDebitOrCredit::ReverseCredit, Some("R")),
+        case("RCR", ExtDebitOrCredit::ReverseDebit, Some("R")),
+        case("RDD", ExtDebitOrCredit::ReverseCredit, Some("D")),
+        case("RCC", ExtDebitOrCredit::ReverseDebit, Some("C"))
+    )]
+    fn tag_61_reversal_and_funds_code(
+        input: &str,
+        expected_indicator: ExtDebitOrCredit,
+        expected_funds_code: Option<&str>,
+    ) {
+        let field = Field::new("61", &format!("0909250925{}583,92NMSCNONREF", input));
+        let parsed = parse_61_tag(&field, None).unwrap();
+        assert_eq!(parsed.ext_debit_credit_indicator, expected_indicator);
+        assert_eq!(parsed.funds_code, expected_funds_code);
+        assert_eq!(parsed.amount, Decimal::from_str("583.92").unwrap());
+    }
+
+    #[rstest(input, case("RR"), case("R"), case("RDRR"), case("DRR"))]
+    fn tag_61_invalid_reversal_and_funds_code(input: &str) {
+        let field = Field::new("61", &format!("0909250925{}583,92NMSCNONREF", input));
+        assert!(parse_61_tag(&field, None).is_err());
+    }
+
+    #[rstest(
+        input,
+        expected_bank_ref,
+        expected_supplementary_details,
+        case("NONREF//1234/5", Some("1234/5"), None),
+        case("NONREF//1234/5\nDetails", Some("1234/5"), Some("Details")),
+        case("NONREF//1234/OCMT/EUR583,92/", Some("1234"), Some("/OCMT/EUR583,92/")),
+        case(
+            "NONREF//1234567890123456/TRCD/01",
+            Some("1234567890123456"),
+            Some("/TRCD/01")
+        )
+    )]
+    fn tag_61_inline_supplementary_details(
+        input: &str,
+        expected_bank_ref: Option<&str>,
+        expected_supplementary_details: Option<&str>,
+    ) {
+        let field = Field::new("61", &format!("0909250925D583,92NMSC{}", input));
+        let parsed = parse_61_tag(&field, None).unwrap();
+        assert_eq!(parsed.bank_ref, expected_bank_ref);
+        assert_eq!(parsed.supplementary_details, expected_supplementary_details);
+    }
+
+    #[rstest(
+        input,
+        case("NONREF//12345678901234567/TRCD"),
+        case("NONREF//1234/TRCD/01/OCMT/EUR583,92/CHGS/EUR1,23"),
+        case("NONREF/OCMT/EUR583,92/CHGS/EUR1,")
+    )]
+    fn tag_61_invalid_inline_supplementary_details(input: &str) {
+        let field = Field::new("61", &format!("0909250925D583,92NMSC{}", input));
+        assert!(parse_61_tag(&field, None).is_err());
+    }
+
     #[test]
     fn tag_61_empty_entry_date() {
         let field = Field::from_str(":61:110701CN50,00NDISNONREF").unwrap();
-        let parsed = parse_61_tag(&field).unwrap();
+        let parsed = parse_61_tag(&field, None).unwrap();
         assert_eq!(parsed.entry_date, None);
     }
 
+    #[test]
+    fn tag_61_entry_date_out_of_range() {
+        let field = Field::from_str(":61:1107011301CN50,00NDISNONREF").unwrap();
+        assert_eq!(
+            parse_61_tag(&field, None),
+            Err(ParseError::DateParseError(
+                Box::new(DateParseError::OutOfRange {
+                    year: "2011".to_string(),
+                    month: "13".to_string(),
+                    day: "01".to_string(),
+                }),
+                ErrorLocation::default()
+            ))
+        );
+    }
+
+    #[test]
+    fn wrong_tag() {
+        let field = Field::new("21", "abc");
+        assert_eq!(
+            parse_25_tag(&field),
+            Err(RequiredTagNotFoundError::new("25").into())
+        );
+        assert_eq!(
+            parse_60_tag(&field, None),
+            Err(RequiredTagNotFoundError::new("60F or 60M").into())
+        );
+    }
+
+    #[test]
+    fn missing_component() {
+        let field = Field::new("20", "abc");
+        assert_eq!(
+            required(None::<String>, (&field).into(), "reference"),
+            Err(ParseError::MissingComponent {
+                tag: Tag::TransactionRef,
+                component: "reference".to_string(),
+                location: ErrorLocation::default(),
+            })
+        );
+    }
+
     proptest! {
         #[test]
         fn tag_61_input(date in (r"[[:digit:]]{2}[01][0-9][0-3][[:digit:]]").prop_filter("We need a valid date", |d| NaiveDate::parse_from_str(&d, "%y%m%d").is_ok()),
@@ -542,7 +932,7 @@ mod tests {
                         funds_code in r"[[:alpha:]]?",
                         amount_before_decimal in r"[[:digit:]]{1, 12}",
                         amount_after_decimal in r"[[:digit:]]{0, 2}",
-                        transaction_type_ident_code in r"[NF][[:alnum:]]{3}",
+                        transaction_type_ident_code in r"[NFS][[:alnum:]]{3}",
                         customer_ref in r"[0-9A-Za-z/\-\?:\(\)\.,‘\+\{\} ]{1, 16}",
                         bank_ref in r"[0-9A-Za-z/\-\?:\(\)\.,‘\+\{\} ]{0, 16}",
                         supplementary_details in r"[0-9A-Za-z/\-\?:\(\)\.,‘\+\{\} ]{0, 34}") {
@@ -584,13 +974,14 @@ mod tests {
                 customer_bank_ref=customer_bank_ref,
                 supplementary_details=supplementary_details);
             let field = Field::from_str(&format!(":61:{}", input)).unwrap();
-            let parsed = parse_61_tag(&field).unwrap();
+            let parsed = parse_61_tag(&field, None).unwrap();
             let expected = StatementLine {
                 value_date: date_from_mt940_date(&date).unwrap(),
                 entry_date: if has_short_date { Some(date_from_mt940_date(&date).unwrap()) } else { None },
                 ext_debit_credit_indicator: ExtDebitOrCredit::from_str(&ext_debit_credit_indicator).unwrap(),
                 funds_code: if funds_code.is_empty() { None } else { Some(funds_code) },
                 amount: decimal_from_mt940_amount(&amount).unwrap(),
+                entry_type: EntryType::from_str(&transaction_type_ident_code[..1]).unwrap(),
                 transaction_type_ident_code: TransactionTypeIdentificationCode::from_str(
                     &transaction_type_ident_code_no_prefix).unwrap_or_else(
                         |_| TransactionTypeIdentificationCode::NonStandard(
@@ -599,8 +990,20 @@ mod tests {
                 bank_ref: if bank_ref.is_empty() { None } else { Some(bank_ref) },
                 supplementary_details: if supplementary_details.is_empty() { None } else { Some(supplementary_details) },
                 information_to_account_owner: None,
+                information_lines: vec![],
+                structured_information: None,
+                sepa_purpose: None,
+                raw: None,
+                index: None,
             };
-            prop_assert_eq!(parsed, expected);
+            prop_assert_eq!(StatementLine::from(parsed), expected);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn tag_61_never_panics(input in r"[0-9]{6}[0-9]{0,4}R?[DC][A-Z]?[0-9,]{1,15}[NFS][0-9A-Z]{3}[0-9A-Za-z/ ]{1,20}") {
+            let _ = parse_61_tag(&Field::new("61", &input), None);
         }
     }
 
@@ -0,0 +1,67 @@
+//! Conversion of dates between `chrono` and the `time` crate.
+
+use chrono::{Datelike, NaiveDate};
+
+/// Convert a date of this crate, e.g. [`StatementLine::value_date`], to a [`time::Date`].
+///
+/// Returns `None` if the year isn't within the range [`time::Date`] supports, which can't happen
+/// for parsed dates.
+///
+/// [`StatementLine::value_date`]: crate::StatementLine::value_date
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, to_time_date};
+/// use time::{Date, Month};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n";
+///
+/// let input_parsed = parse_mt940(input).unwrap();
+/// assert_eq!(
+///     to_time_date(input_parsed[0].closing_balance.date),
+///     Some(Date::from_calendar_date(2009, Month::September, 30).unwrap())
+/// );
+/// ```
+pub fn to_time_date(date: NaiveDate) -> Option<time::Date> {
+    let ordinal = u16::try_from(date.ordinal()).ok()?;
+    time::Date::from_ordinal_date(date.year(), ordinal).ok()
+}
+
+/// Convert a [`time::Date`] to a date of this crate, e.g. to build a [`Balance`].
+///
+/// Returns `None` if the year isn't within the range [`NaiveDate`] supports, which can only
+/// happen if the `large-dates` feature of `time` is enabled.
+///
+/// [`Balance`]: crate::Balance
+pub fn from_time_date(date: time::Date) -> Option<NaiveDate> {
+    NaiveDate::from_yo_opt(date.year(), u32::from(date.ordinal()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn round_trip(days in 0..3_650_000i32) {
+            let date = NaiveDate::from_num_days_from_ce_opt(days).unwrap();
+            let time_date = to_time_date(date).unwrap();
+            prop_assert_eq!(time_date.to_julian_day() - days, 1_721_425);
+            prop_assert_eq!(from_time_date(time_date), Some(date));
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(to_time_date(NaiveDate::MAX), None);
+        assert_eq!(to_time_date(NaiveDate::MIN), None);
+    }
+}
@@ -1,12 +1,15 @@
 use serde_derive::{Deserialize, Serialize};
 use std::fmt;
-use strum_macros::{EnumIter, EnumString};
+use strum_macros::{AsRefStr, EnumIter, EnumString};
 
 /// Enum containing every SEPA-specified transaction type identification code.
 ///
 /// See here for source:
 /// <http://www.sepaforcorporates.com/swift-for-corporates/list-mt940-transaction-type-identification-codes/>
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, EnumString, EnumIter)]
+#[derive(
+    Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, AsRefStr, EnumString, EnumIter,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum TransactionTypeIdentificationCode {
     BNK,
     BOE,
@@ -68,6 +71,16 @@ pub enum TransactionTypeIdentificationCode {
     NonStandard(String),
 }
 
+impl TransactionTypeIdentificationCode {
+    /// The three character code as it appears in tag `:61:`, e.g. `"TRF"`.
+    pub fn code(&self) -> &str {
+        match self {
+            TransactionTypeIdentificationCode::NonStandard(code) => code,
+            known => known.as_ref(),
+        }
+    }
+}
+
 impl fmt::Display for TransactionTypeIdentificationCode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let description = match self {
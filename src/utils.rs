@@ -12,13 +12,12 @@ use crate::Rule;
 /// However, they might not always have digits behind the comma.
 pub fn decimal_from_mt940_amount(s: &str) -> Result<Decimal, AmountParseError> {
     // Split at decimal separator.
-    let split_decimal_str: Vec<&str> = s.split(',').collect();
-    if split_decimal_str.len() == 1 {
-        return Err(AmountParseError::NoComma(s.to_string()));
-    } else if split_decimal_str.len() > 2 {
+    let (int_part, frac_part) = s
+        .split_once(',')
+        .ok_or_else(|| AmountParseError::NoComma(s.to_string()))?;
+    if frac_part.contains(',') {
         return Err(AmountParseError::TooManyCommas(s.to_string()));
     }
-    let (int_part, frac_part) = (split_decimal_str[0], split_decimal_str[1]);
     let whole_number: i64 = format!("{}{}", int_part, frac_part)
         .parse()
         .map_err(AmountParseError::IntParseError)?;
@@ -30,35 +29,82 @@ pub fn decimal_from_mt940_amount(s: &str) -> Result<Decimal, AmountParseError> {
 /// MT940 has a weird date format in the form of YYMMDD. Since it has a shortened year, the
 /// assumption is made that all statement are in the year 20XX.
 pub fn date_from_mt940_date(s: &str) -> Result<NaiveDate, DateParseError> {
-    let parsed_date = MT940Parser::parse(Rule::date, s)?
-        .next()
-        .unwrap()
-        .into_inner();
+    date_from_mt940_date_with_pivot(s, None)
+}
+
+/// Like [`date_from_mt940_date`] but two-digit years from `year_pivot` upwards are in 19XX.
+pub fn date_from_mt940_date_with_pivot(
+    s: &str,
+    year_pivot: Option<u8>,
+) -> Result<NaiveDate, DateParseError> {
     let mut year = None;
     let mut month = None;
     let mut day = None;
+    let parsed_date = MT940Parser::parse(Rule::date, s)?.flat_map(|pair| pair.into_inner());
     for p in parsed_date {
         match p.as_rule() {
-            // Here I'm making an assumption that will only work for
-            // a limited but fairly long time: That all years that we
-            // see are at least the year 2000 and upwards. The
-            // problem is sadly that banks didn't make the field be the
-            // full year number but only a 2-digit number!
+            // Unless told otherwise, I'm making an assumption that will
+            // only work for a limited but fairly long time: That all
+            // years that we see are at least the year 2000 and upwards.
+            // The problem is sadly that banks didn't make the field be
+            // the full year number but only a 2-digit number!
             // How stupid.
-            Rule::year => year = Some(format!("20{}", p.as_str())),
+            Rule::year => {
+                let century = match year_pivot {
+                    Some(pivot) if p.as_str().parse::<u8>().is_ok_and(|y| y >= pivot) => "19",
+                    _ => "20",
+                };
+                year = Some(format!("{}{}", century, p.as_str()))
+            }
             Rule::month => month = Some(p.as_str()),
             Rule::day => day = Some(p.as_str()),
-            _ => unreachable!(),
+            _ => (),
         }
     }
-    NaiveDate::from_ymd_opt(
-        year.clone().unwrap().parse().unwrap(),
-        month.unwrap().parse().unwrap(),
-        day.unwrap().parse().unwrap(),
+    date_from_parts(
+        year.as_deref().unwrap_or_default(),
+        month.unwrap_or_default(),
+        day.unwrap_or_default(),
     )
+}
+
+/// Create a `NaiveDate` from the textual year, month and day.
+pub fn date_from_parts(year: &str, month: &str, day: &str) -> Result<NaiveDate, DateParseError> {
+    match (year.parse(), month.parse(), day.parse()) {
+        (Ok(y), Ok(m), Ok(d)) => NaiveDate::from_ymd_opt(y, m, d),
+        _ => None,
+    }
     .ok_or_else(|| DateParseError::OutOfRange {
-        year: year.unwrap(),
-        month: month.unwrap().to_string(),
-        day: day.unwrap().to_string(),
+        year: year.to_string(),
+        month: month.to_string(),
+        day: day.to_string(),
+    })
+}
+
+/// Append the information of a tag `:86:` to already existing information.
+///
+/// Information of subsequent `:86:` tags is simply concatenated.
+pub fn append_information(target: &mut Option<String>, info: String) {
+    match target {
+        Some(existing) => existing.push_str(&info),
+        None => *target = Some(info),
+    }
+}
+
+/// Like [`append_information`] but starts a new line for `line`.
+pub fn append_line(target: &mut Option<String>, line: String) {
+    match target {
+        Some(existing) => {
+            existing.push('\n');
+            existing.push_str(&line);
+        }
+        None => *target = Some(line),
+    }
+}
+
+/// The 64 bit FNV-1a hash which, unlike the hasher of the standard library, is stable.
+pub fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
     })
 }
@@ -0,0 +1,337 @@
+//! Validation of component lengths against the limits of the SWIFT spec.
+
+use rust_decimal::Decimal;
+
+use crate::builder::AMOUNT_MAX_LENGTH;
+use crate::errors::FieldLengthError;
+use crate::writer::{format_amount, TAG_86_LINE_LENGTH, TAG_86_MAX_LINES};
+use crate::{AvailableBalance, Balance, Field, Message, MessageRef, Tag};
+
+impl Message {
+    /// Check every component of this [`Message`] against the maximum length the SWIFT spec
+    /// allows, e.g. 16 characters for tag `:20:` or 6 lines of 65 characters for tag `:86:`.
+    ///
+    /// The parser is lenient in places, such as consecutive `:86:` tags, and a [`Message`] which
+    /// was modified in code isn't checked at all. Use this to make sure a statement is accepted by
+    /// others before sending it. Violations are returned in the order of their tags.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::parse_mt940;
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :62F:C090924EUR54484,04\r\n";
+    ///
+    /// let mut messages = parse_mt940(input).unwrap();
+    /// assert!(messages[0].validate_lengths().is_empty());
+    ///
+    /// messages[0].transaction_ref_no = "3996-11-11111111-2".to_string();
+    /// let violations = messages[0].validate_lengths();
+    /// assert_eq!(violations[0].tag(), "20");
+    /// assert_eq!(violations[0].length(), 18);
+    /// ```
+    pub fn validate_lengths(&self) -> Vec<FieldLengthError> {
+        Components::from(self).validate()
+    }
+}
+
+impl MessageRef<'_> {
+    /// Same as [`Message::validate_lengths`].
+    pub(crate) fn validate_lengths(&self) -> Vec<FieldLengthError> {
+        Components::from(self).validate()
+    }
+}
+
+/// The components of a [`Message`] or [`MessageRef`] which have a maximum length.
+struct Components<'a> {
+    transaction_ref_no: &'a str,
+    ref_to_related_msg: Option<&'a str>,
+    account_tag: &'static str,
+    account_id: &'a str,
+    statement_no: &'a str,
+    sequence_no: Option<&'a str>,
+    opening_balance: &'a Balance,
+    statement_lines: Vec<StatementLineComponents<'a>>,
+    closing_balance: &'a Balance,
+    closing_available_balance: Option<&'a AvailableBalance>,
+    forward_available_balances: &'a [AvailableBalance],
+    information_to_account_owner: Option<&'a str>,
+}
+
+/// The components of a statement line which have a maximum length.
+struct StatementLineComponents<'a> {
+    amount: &'a Decimal,
+    customer_ref: &'a str,
+    bank_ref: Option<&'a str>,
+    supplementary_details: Option<&'a str>,
+    information_to_account_owner: Option<&'a str>,
+}
+
+impl<'a> From<&'a Message> for Components<'a> {
+    fn from(message: &'a Message) -> Components<'a> {
+        Components {
+            transaction_ref_no: &message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg.as_deref(),
+            account_tag: account_tag(message.account_identifier_bic.is_some()),
+            account_id: &message.account_id,
+            statement_no: &message.statement_no,
+            sequence_no: message.sequence_no.as_deref(),
+            opening_balance: &message.opening_balance,
+            statement_lines: message
+                .statement_lines
+                .iter()
+                .map(|statement_line| StatementLineComponents {
+                    amount: &statement_line.amount,
+                    customer_ref: &statement_line.customer_ref,
+                    bank_ref: statement_line.bank_ref.as_deref(),
+                    supplementary_details: statement_line.supplementary_details.as_deref(),
+                    information_to_account_owner: statement_line
+                        .information_to_account_owner
+                        .as_deref(),
+                })
+                .collect(),
+            closing_balance: &message.closing_balance,
+            closing_available_balance: message.closing_available_balance.as_ref(),
+            forward_available_balances: &message.forward_available_balances,
+            information_to_account_owner: message.information_to_account_owner.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a MessageRef<'_>> for Components<'a> {
+    fn from(message: &'a MessageRef<'_>) -> Components<'a> {
+        Components {
+            transaction_ref_no: message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg,
+            account_tag: account_tag(message.account_identifier_bic.is_some()),
+            account_id: message.account_id,
+            statement_no: message.statement_no,
+            sequence_no: message.sequence_no,
+            opening_balance: &message.opening_balance,
+            statement_lines: message
+                .statement_lines
+                .iter()
+                .map(|statement_line| StatementLineComponents {
+                    amount: &statement_line.amount,
+                    customer_ref: statement_line.customer_ref,
+                    bank_ref: statement_line.bank_ref,
+                    supplementary_details: statement_line.supplementary_details,
+                    information_to_account_owner: statement_line
+                        .information_to_account_owner
+                        .as_deref(),
+                })
+                .collect(),
+            closing_balance: &message.closing_balance,
+            closing_available_balance: message.closing_available_balance.as_ref(),
+            forward_available_balances: &message.forward_available_balances,
+            information_to_account_owner: message.information_to_account_owner.as_deref(),
+        }
+    }
+}
+
+impl Components<'_> {
+    fn validate(&self) -> Vec<FieldLengthError> {
+        let mut violations = vec![];
+        let mut check = |tag: &str, component: &str, length: usize, max_length: usize| {
+            if length > max_length {
+                violations.push(FieldLengthError::new(tag, component, length, max_length));
+            }
+        };
+
+        check(
+            "20",
+            "transaction_ref_no",
+            chars(self.transaction_ref_no),
+            16,
+        );
+        if let Some(related) = self.ref_to_related_msg {
+            check("21", "ref_to_related_msg", chars(related), 16);
+        }
+        check(self.account_tag, "account_id", chars(self.account_id), 35);
+        check("28C", "statement_no", chars(self.statement_no), 5);
+        if let Some(sequence_no) = self.sequence_no {
+            check("28C", "sequence_no", chars(sequence_no), 5);
+        }
+        check_balance("60", self.opening_balance, &mut check);
+        for statement_line in &self.statement_lines {
+            check_statement_line(statement_line, &mut check);
+        }
+        check_balance("62", self.closing_balance, &mut check);
+        if let Some(balance) = self.closing_available_balance {
+            check_available_balance("64", balance, &mut check);
+        }
+        for balance in self.forward_available_balances {
+            check_available_balance("65", balance, &mut check);
+        }
+        if let Some(information) = self.information_to_account_owner {
+            check_information(information, &mut check);
+        }
+
+        violations
+    }
+}
+
+fn account_tag(has_bic: bool) -> &'static str {
+    match has_bic {
+        true => "25P",
+        false => "25",
+    }
+}
+
+/// Check the lengths of a single field which the grammar doesn't fully enforce.
+///
+/// The grammar limits tag `:86:` to 6 times 65 characters but not each line to 65 characters.
+pub(crate) fn validate_field_lengths(field: &Field) -> Vec<FieldLengthError> {
+    let mut violations = vec![];
+    if field.tag == Tag::InformationToAccountOwner {
+        check_information(&field.value, &mut |tag, component, length, max_length| {
+            if length > max_length {
+                violations.push(FieldLengthError::new(tag, component, length, max_length));
+            }
+        });
+    }
+    violations
+}
+
+fn chars(value: &str) -> usize {
+    value.chars().count()
+}
+
+fn amount_length(amount: &Decimal) -> usize {
+    format_amount(amount).len()
+}
+
+fn check_balance(tag: &str, balance: &Balance, check: &mut impl FnMut(&str, &str, usize, usize)) {
+    let suffix = if balance.is_intermediate { "M" } else { "F" };
+    check(
+        &format!("{}{}", tag, suffix),
+        "amount",
+        amount_length(&balance.amount),
+        AMOUNT_MAX_LENGTH,
+    );
+}
+
+fn check_available_balance(
+    tag: &str,
+    balance: &AvailableBalance,
+    check: &mut impl FnMut(&str, &str, usize, usize),
+) {
+    check(
+        tag,
+        "amount",
+        amount_length(&balance.amount),
+        AMOUNT_MAX_LENGTH,
+    );
+}
+
+fn check_statement_line(
+    statement_line: &StatementLineComponents,
+    check: &mut impl FnMut(&str, &str, usize, usize),
+) {
+    check(
+        "61",
+        "amount",
+        amount_length(statement_line.amount),
+        AMOUNT_MAX_LENGTH,
+    );
+    check("61", "customer_ref", chars(statement_line.customer_ref), 16);
+    if let Some(bank_ref) = statement_line.bank_ref {
+        check("61", "bank_ref", chars(bank_ref), 16);
+    }
+    if let Some(supplementary_details) = statement_line.supplementary_details {
+        check(
+            "61",
+            "supplementary_details",
+            chars(supplementary_details),
+            34,
+        );
+    }
+    if let Some(information) = statement_line.information_to_account_owner {
+        check_information(information, check);
+    }
+}
+
+/// Check the number of lines of tag `:86:` and the length of each of them.
+fn check_information(information: &str, check: &mut impl FnMut(&str, &str, usize, usize)) {
+    check("86", "lines", information.lines().count(), TAG_86_MAX_LINES);
+    for line in information.lines() {
+        check("86", "line", chars(line), TAG_86_LINE_LENGTH);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{parse_mt940, parse_mt940_with_config, ParseError, ParserConfig};
+
+    const INPUT: &str = "\
+        :20:ref\r\n\
+        :25:account\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR10,\r\n\
+        :61:0909250925D1,NTRFcustomer//bank\r\n\
+        :86:1\r\n\
+        2\r\n\
+        3\r\n\
+        :86:4\r\n\
+        5\r\n\
+        6\r\n\
+        :62F:C090925EUR9,\r\n";
+
+    #[test]
+    fn violations_in_order() {
+        let mut messages = parse_mt940(INPUT).unwrap();
+        assert_eq!(messages[0].validate_lengths(), vec![]);
+
+        let message = &mut messages[0];
+        message.account_id = "x".repeat(36);
+        message.statement_lines[0].customer_ref = "x".repeat(17);
+        message.statement_lines[0].amount = "1234567890123.45".parse().unwrap();
+        if let Some(ref mut information) = message.statement_lines[0].information_to_account_owner {
+            information.push_str(&format!("\n7\n{}", "x".repeat(66)));
+        }
+        let violations = message
+            .validate_lengths()
+            .iter()
+            .map(|err| {
+                (
+                    err.tag().to_string(),
+                    err.component().to_string(),
+                    err.length(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            vec![
+                ("25".to_string(), "account_id".to_string(), 36),
+                ("61".to_string(), "amount".to_string(), 16),
+                ("61".to_string(), "customer_ref".to_string(), 17),
+                ("86".to_string(), "lines".to_string(), 7),
+                ("86".to_string(), "line".to_string(), 66),
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_field_lengths() {
+        let input = INPUT.replace(":86:4", "4");
+        let config = ParserConfig::new().strict_field_lengths(true);
+        assert!(parse_mt940_with_config(&input, &config).is_ok());
+
+        // Continuing the last line in another tag makes it too long.
+        let input = input.replace("6\r\n", &format!("6\r\n:86:{}\r\n", "x".repeat(65)));
+        assert!(parse_mt940(&input).is_ok());
+        let err = parse_mt940_with_config(&input, &config).unwrap_err();
+        assert_eq!(
+            err.without_location().to_string(),
+            "Component 'line' of tag :86: exceeds its maximum length: 66 > 65"
+        );
+        assert!(matches!(err, ParseError::FieldLengthError(..)));
+    }
+}
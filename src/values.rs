@@ -0,0 +1,124 @@
+//! Parsing of the values of single tags without their `:NN:` prefix.
+//!
+//! This is for input which was split into tags already, e.g. when statements are stored in a
+//! database tag by tag.
+
+use crate::borrowed::FieldRef;
+use crate::tag_parsers::{parse_28_tag, parse_60_tag, parse_61_tag, parse_64_tag};
+use crate::{AvailableBalance, Balance, ParseError, StatementLine};
+
+fn field_ref<'a>(tag: &'a str, value: &'a str) -> FieldRef<'a> {
+    FieldRef {
+        tag,
+        value: value.trim(),
+        span: None,
+        raw: None,
+    }
+}
+
+/// Parse the value of an opening or closing balance, tags `:60F:` and `:62F:`.
+///
+/// The value doesn't tell whether the balance is intermediate, so [`Balance::is_intermediate`] is
+/// always `false`. Set it for values of tags `:60M:` and `:62M:`.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_balance, DebitOrCredit};
+///
+/// let balance = parse_balance("C090924EUR54484,04").unwrap();
+/// assert_eq!(balance.debit_credit_indicator, DebitOrCredit::Credit);
+/// assert_eq!(balance.iso_currency_code, "EUR");
+/// assert_eq!(balance.amount.to_string(), "54484.04");
+/// ```
+pub fn parse_balance(value: &str) -> Result<Balance, ParseError> {
+    parse_60_tag(field_ref("60F", value), None)
+}
+
+/// Parse the value of an available balance, tags `:64:` and `:65:`.
+pub fn parse_available_balance(value: &str) -> Result<AvailableBalance, ParseError> {
+    parse_64_tag(field_ref("64", value), None)
+}
+
+/// Parse the value of a statement line, tag `:61:`, including its supplementary details.
+///
+/// The information of the following tags `:86:` is not part of the value, so
+/// [`StatementLine::information_to_account_owner`] is always `None`.
+///
+/// # Example
+/// ```
+/// use mt940::parse_statement_line;
+///
+/// let statement_line = parse_statement_line("0909250925DR583,92NMSC1110030403010139//1234").unwrap();
+/// assert_eq!(statement_line.amount.to_string(), "583.92");
+/// assert_eq!(statement_line.customer_ref, "1110030403010139");
+/// assert_eq!(statement_line.bank_ref.as_deref(), Some("1234"));
+/// ```
+pub fn parse_statement_line(value: &str) -> Result<StatementLine, ParseError> {
+    parse_61_tag(field_ref("61", value), None).map(StatementLine::from)
+}
+
+/// Parse the value of tag `:28C:` into the statement number and the sequence number, if any.
+///
+/// # Example
+/// ```
+/// use mt940::parse_statement_number;
+///
+/// assert_eq!(
+///     parse_statement_number("00001/001").unwrap(),
+///     ("00001".to_string(), Some("001".to_string()))
+/// );
+/// ```
+pub fn parse_statement_number(value: &str) -> Result<(String, Option<String>), ParseError> {
+    let (statement_no, sequence_no) = parse_28_tag(field_ref("28C", value))?;
+    Ok((statement_no.to_string(), sequence_no.map(str::to_string)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{parse_mt940, DateParseError};
+
+    #[test]
+    fn same_as_in_message() {
+        let input = "\
+            :20:a\r\n\
+            :25:b\r\n\
+            :28C:1/2\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D4,NMSCref//bank\r\n\
+            details\r\n\
+            :62F:C090924EUR6,\r\n\
+            :64:C090924EUR5,\r\n";
+        let message = parse_mt940(input).unwrap().remove(0);
+        assert_eq!(
+            parse_balance("C090924EUR10,").unwrap(),
+            message.opening_balance
+        );
+        assert_eq!(
+            parse_available_balance("C090924EUR5,").unwrap(),
+            message.closing_available_balance.unwrap()
+        );
+        assert_eq!(
+            parse_statement_line("0909250925D4,NMSCref//bank\r\ndetails\r\n").unwrap(),
+            message.statement_lines.into_iter().next().unwrap()
+        );
+        assert_eq!(
+            parse_statement_number("1/2").unwrap(),
+            (message.statement_no, message.sequence_no)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse_balance("C091324EUR10,").unwrap_err(),
+            ParseError::DateParseError(err, _) if matches!(*err, DateParseError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            parse_statement_line(":61:0909250925D4,NMSCref").unwrap_err(),
+            ParseError::PestParseError(..)
+        ));
+    }
+}
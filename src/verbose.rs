@@ -0,0 +1,246 @@
+//! Parsing into the components of every tag along with where they are in the input.
+
+use pest::iterators::Pair;
+use pest::Parser;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{MT940Parser, ParseError, Rule, Span};
+
+/// A tag along with the components its value was parsed into.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VerboseField {
+    pub tag: String,
+
+    /// The whole field from its tag up to the end of its value.
+    pub span: Span,
+
+    /// The components of the value, empty for tags which are not part of MT940.
+    pub subfields: Vec<Subfield>,
+}
+
+/// A component of a tag such as the `amount` of tag `:61:`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Subfield {
+    /// The name of the grammar rule which matched, e.g. `customer_ref`.
+    pub name: String,
+
+    pub span: Span,
+
+    /// Components this component consists of, e.g. the `year`, `month` and `day` of a `date`.
+    pub subfields: Vec<Subfield>,
+}
+
+/// Parse a MT940 statement into its tags and their components without building [`Message`]s.
+///
+/// This is meant for tooling such as editors which need to know where each component is. Only
+/// the syntax of each tag is checked, not whether the tags make up valid messages. Errors point at
+/// where exactly in the value of a tag parsing failed.
+///
+/// [`Message`]: crate::Message
+///
+/// # Example
+/// ```
+/// use mt940::parse_mt940_verbose;
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n";
+///
+/// let fields = parse_mt940_verbose(input).unwrap();
+/// let amount = fields[1]
+///     .subfields
+///     .iter()
+///     .find(|subfield| subfield.name == "amount")
+///     .unwrap();
+/// assert_eq!(amount.span.snippet(input), Some("583,92"));
+/// assert_eq!((amount.span.line, amount.span.column), (2, 17));
+/// ```
+pub fn parse_mt940_verbose(statement: &str) -> Result<Vec<VerboseField>, ParseError> {
+    let parsed_fields = MT940Parser::parse(Rule::fields, statement)
+        .map_err(|err| ParseError::from_pest_error_with_span(Box::new(err)))?;
+
+    let mut fields = vec![];
+    for parsed_field in parsed_fields {
+        if parsed_field.as_rule() != Rule::statement_field {
+            continue;
+        }
+        let span = Span::from_pest(parsed_field.as_span());
+        let mut inner = parsed_field.into_inner();
+        let (Some(tag), Some(value)) = (inner.next(), inner.next()) else {
+            continue;
+        };
+        let tag = tag.into_inner().as_str().to_string();
+        let subfields = match value_rule(&tag) {
+            Some(rule) => parse_value(rule, value)?,
+            None => vec![],
+        };
+        fields.push(VerboseField {
+            tag,
+            span,
+            subfields,
+        });
+    }
+    Ok(fields)
+}
+
+/// The rule for the value of `tag`.
+fn value_rule(tag: &str) -> Option<Rule> {
+    let rule = match tag {
+        "20" => Rule::tag_20_field,
+        "21" => Rule::tag_21_field,
+        "25" => Rule::tag_25_field,
+        "25P" => Rule::tag_25p_field,
+        "28" | "28C" => Rule::tag_28c_field,
+        "60F" | "60M" => Rule::tag_60_field,
+        "61" => Rule::tag_61_field,
+        "62F" | "62M" => Rule::tag_62_field,
+        "64" => Rule::tag_64_field,
+        "65" => Rule::tag_65_field,
+        "86" => Rule::tag_86_field,
+        _ => return None,
+    };
+    Some(rule)
+}
+
+/// Parse `value` with `rule` and move the spans of its components from the value to the input.
+fn parse_value(rule: Rule, value: Pair<'_, Rule>) -> Result<Vec<Subfield>, ParseError> {
+    // Values are trimmed before parsing them just like the tag parsers do.
+    let text = value.as_str();
+    let trimmed = text.trim();
+    let leading = text.len() - text.trim_start().len();
+    let start = value.as_span().start_pos();
+    let base = Base {
+        offset: start.pos() + leading,
+        text: trimmed,
+        line_col: start.line_col(),
+        leading: text.get(..leading).unwrap_or_default(),
+    };
+
+    match MT940Parser::parse(rule, trimmed) {
+        Ok(pairs) => Ok(pairs
+            .flat_map(|pair| pair.into_inner())
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(|pair| base.subfield(pair))
+            .collect()),
+        Err(err) => {
+            let relative = Span::from_pest_error(&err);
+            Err(ParseError::from(err).with_span(base.span(relative.start, relative.end)))
+        }
+    }
+}
+
+/// Where the parsed value of a field is in the input.
+struct Base<'a> {
+    /// Byte offset of the trimmed value.
+    offset: usize,
+    /// The trimmed value.
+    text: &'a str,
+    /// Line and column of the untrimmed value.
+    line_col: (usize, usize),
+    /// What was trimmed from the start of the value.
+    leading: &'a str,
+}
+
+impl Base<'_> {
+    fn subfield(&self, pair: Pair<'_, Rule>) -> Subfield {
+        let span = self.span(pair.as_span().start(), pair.as_span().end());
+        Subfield {
+            name: format!("{:?}", pair.as_rule()),
+            span,
+            subfields: pair.into_inner().map(|pair| self.subfield(pair)).collect(),
+        }
+    }
+
+    /// The [`Span`] in the input of the bytes `start..end` of the trimmed value.
+    fn span(&self, start: usize, end: usize) -> Span {
+        let before = self
+            .leading
+            .chars()
+            .chain(self.text.get(..start).unwrap_or_default().chars());
+        let (mut line, mut column) = self.line_col;
+        for c in before {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Span {
+            start: self.offset + start,
+            end: self.offset + end,
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn names_and_snippets<'a>(subfields: &[Subfield], input: &'a str) -> Vec<(String, &'a str)> {
+        subfields
+            .iter()
+            .map(|subfield| {
+                (
+                    subfield.name.clone(),
+                    subfield.span.snippet(input).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn subfields_of_statement_line() {
+        let input = "\
+            :20:ref\r\n\
+            :61:0909250925RDR1,5NTRFcustomer//bank\r\n\
+            details\r\n\
+            :99:proprietary\r\n";
+        let fields = parse_mt940_verbose(input).unwrap();
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| field.tag.as_str())
+                .collect::<Vec<_>>(),
+            ["20", "61", "99"]
+        );
+        assert_eq!(
+            names_and_snippets(&fields[1].subfields, input),
+            vec![
+                ("date".to_string(), "090925"),
+                ("short_date".to_string(), "0925"),
+                ("ext_debit_credit_indicator".to_string(), "RD"),
+                ("funds_code".to_string(), "R"),
+                ("amount".to_string(), "1,5"),
+                ("transaction_type_ident_code".to_string(), "NTRF"),
+                ("customer_ref".to_string(), "customer"),
+                ("bank_ref".to_string(), "bank"),
+                ("supplementary_details".to_string(), "details"),
+            ]
+        );
+        assert_eq!(
+            names_and_snippets(&fields[1].subfields[0].subfields, input),
+            vec![
+                ("year".to_string(), "09"),
+                ("month".to_string(), "09"),
+                ("day".to_string(), "25"),
+            ]
+        );
+        let details = &fields[1].subfields[8].span;
+        assert_eq!((details.line, details.column), (3, 1));
+        assert!(fields[2].subfields.is_empty());
+    }
+
+    #[test]
+    fn error_points_into_value() {
+        let input = ":20:ref\r\n:60F:C090924EURX,\r\n";
+        let err = parse_mt940_verbose(input).unwrap_err();
+        let span = err.span().unwrap();
+        assert_eq!((span.line, span.column), (2, 16));
+        assert_eq!(span.start, 24);
+    }
+}
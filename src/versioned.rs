@@ -0,0 +1,256 @@
+//! Serialized messages along with the version of their format.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    AvailableBalance, Balance, EntryType, ExtDebitOrCredit, Message, StatementLine,
+    TransactionTypeIdentificationCode,
+};
+
+/// The version of the serialized format of [`Message`]s written by this crate.
+///
+/// It is increased whenever a change to [`Message`] or its parts would keep previously serialized
+/// messages from being read again. Reading such older messages is then taken care of by
+/// [`ParsedStatements`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Messages along with the [`SCHEMA_VERSION`] they were serialized with.
+///
+/// Use this instead of a plain list of [`Message`]s to store messages long-term. Deserializing
+/// also accepts a plain list as written before versions were introduced, including messages as
+/// serialized by version 1.0 of this crate. Messages of a newer, unknown version are rejected
+/// rather than read incompletely.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, ParsedStatements, SCHEMA_VERSION};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :62F:C090930EUR54484,04\r\n";
+///
+/// let parsed = ParsedStatements::new(parse_mt940(input).unwrap());
+/// let json = serde_json::to_string(&parsed).unwrap();
+/// assert!(json.starts_with(r#"{"schema_version":1,"messages":[{"#));
+///
+/// let read: ParsedStatements = serde_json::from_str(&json).unwrap();
+/// assert_eq!(read, parsed);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "AnyVersion")]
+pub struct ParsedStatements {
+    pub schema_version: u32,
+    pub messages: Vec<Message>,
+}
+
+impl ParsedStatements {
+    /// Wrap `messages` with the current [`SCHEMA_VERSION`].
+    pub fn new(messages: Vec<Message>) -> ParsedStatements {
+        ParsedStatements {
+            schema_version: SCHEMA_VERSION,
+            messages,
+        }
+    }
+}
+
+/// Every serialized format of messages which can be read.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyVersion {
+    Versioned {
+        schema_version: u32,
+        messages: Vec<Message>,
+    },
+    /// A plain list of messages as written by version 1.0 of this crate.
+    V0(Vec<MessageV0>),
+    /// A plain list of messages as written before [`SCHEMA_VERSION`] 1.
+    Unversioned(Vec<Message>),
+}
+
+/// A [`Message`] as serialized by version 1.0 of this crate.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MessageV0 {
+    transaction_ref_no: String,
+    ref_to_related_msg: Option<String>,
+    account_id: String,
+    statement_no: String,
+    sequence_no: Option<String>,
+    opening_balance: Balance,
+    statement_lines: Vec<StatementLineV0>,
+    closing_balance: Balance,
+    closing_available_balance: Option<AvailableBalance>,
+    forward_available_balance: Option<AvailableBalance>,
+    information_to_account_owner: Option<String>,
+}
+
+/// A [`StatementLine`] as serialized by version 1.0 of this crate.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatementLineV0 {
+    value_date: NaiveDate,
+    entry_date: Option<NaiveDate>,
+    ext_debit_credit_indicator: ExtDebitOrCredit,
+    funds_code: Option<String>,
+    amount: Decimal,
+    transaction_type_ident_code: TransactionTypeIdentificationCode,
+    customer_ref: String,
+    bank_ref: Option<String>,
+    supplementary_details: Option<String>,
+    information_to_account_owner: Option<String>,
+}
+
+impl From<MessageV0> for Message {
+    fn from(message: MessageV0) -> Message {
+        Message {
+            transaction_ref_no: message.transaction_ref_no,
+            ref_to_related_msg: message.ref_to_related_msg,
+            account_id: message.account_id,
+            account_identifier_bic: None,
+            statement_no: message.statement_no,
+            sequence_no: message.sequence_no,
+            opening_balance: message.opening_balance,
+            statement_lines: message
+                .statement_lines
+                .into_iter()
+                .map(StatementLine::from)
+                .collect(),
+            closing_balance: message.closing_balance,
+            closing_available_balance: message.closing_available_balance,
+            forward_available_balances: message.forward_available_balance.into_iter().collect(),
+            information_to_account_owner: message.information_to_account_owner,
+            information_lines: vec![],
+            non_swift_fields: vec![],
+            custom_fields: Default::default(),
+            raw: None,
+            source: None,
+        }
+    }
+}
+
+impl From<StatementLineV0> for StatementLine {
+    fn from(statement_line: StatementLineV0) -> StatementLine {
+        StatementLine {
+            value_date: statement_line.value_date,
+            entry_date: statement_line.entry_date,
+            ext_debit_credit_indicator: statement_line.ext_debit_credit_indicator,
+            funds_code: statement_line.funds_code,
+            amount: statement_line.amount,
+            // Version 1.0 didn't keep the entry type and nearly all of them are non-SWIFT.
+            entry_type: EntryType::default(),
+            transaction_type_ident_code: statement_line.transaction_type_ident_code,
+            customer_ref: statement_line.customer_ref,
+            bank_ref: statement_line.bank_ref,
+            supplementary_details: statement_line.supplementary_details,
+            information_to_account_owner: statement_line.information_to_account_owner,
+            information_lines: vec![],
+            structured_information: None,
+            sepa_purpose: None,
+            raw: None,
+            index: None,
+        }
+    }
+}
+
+impl TryFrom<AnyVersion> for ParsedStatements {
+    type Error = String;
+
+    fn try_from(any: AnyVersion) -> Result<ParsedStatements, String> {
+        match any {
+            AnyVersion::Versioned {
+                schema_version,
+                messages,
+            } if schema_version <= SCHEMA_VERSION => Ok(ParsedStatements::new(messages)),
+            AnyVersion::Versioned { schema_version, .. } => Err(format!(
+                "Schema version {} is newer than the supported version {}",
+                schema_version, SCHEMA_VERSION
+            )),
+            AnyVersion::V0(messages) => Ok(ParsedStatements::new(
+                messages.into_iter().map(Message::from).collect(),
+            )),
+            AnyVersion::Unversioned(messages) => Ok(ParsedStatements::new(messages)),
+        }
+    }
+}
+
+impl From<Vec<Message>> for ParsedStatements {
+    fn from(messages: Vec<Message>) -> ParsedStatements {
+        ParsedStatements::new(messages)
+    }
+}
+
+impl From<ParsedStatements> for Vec<Message> {
+    fn from(parsed: ParsedStatements) -> Vec<Message> {
+        parsed.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parse_mt940;
+
+    const MESSAGE: &str = "\
+        :20:a\r\n\
+        :25:b\r\n\
+        :28C:1\r\n\
+        :60F:C090924EUR1,\r\n\
+        :61:0909250925D1,NMSCref\r\n\
+        :86:info\r\n\
+        :62F:C090924EUR0,\r\n";
+
+    #[test]
+    fn reads_every_version() {
+        let messages = parse_mt940(MESSAGE).unwrap();
+        let expected = ParsedStatements::new(messages.clone());
+
+        let unversioned = serde_json::to_string(&messages).unwrap();
+        let read: ParsedStatements = serde_json::from_str(&unversioned).unwrap();
+        assert_eq!(read, expected);
+
+        let versioned = serde_json::to_string(&expected).unwrap();
+        let read: ParsedStatements = serde_json::from_str(&versioned).unwrap();
+        assert_eq!(read, expected);
+
+        let v0 = unversioned
+            .replace(r#""entry_type":"NonSwift","#, "")
+            .replace(
+                r#""forward_available_balances":[]"#,
+                r#""forward_available_balance":null"#,
+            );
+        assert_ne!(v0, unversioned);
+        let read: ParsedStatements = serde_json::from_str(&v0).unwrap();
+        assert_eq!(read, expected);
+
+        let newer = versioned.replace(r#""schema_version":1"#, r#""schema_version":2"#);
+        let err = serde_json::from_str::<ParsedStatements>(&newer).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Schema version 2 is newer than the supported version 1"
+        );
+    }
+
+    #[test]
+    fn reads_v0_forward_available_balance() {
+        let messages = parse_mt940(&format!("{}:65:C090925EUR2,\r\n", MESSAGE)).unwrap();
+        let balance = serde_json::to_string(&messages[0].forward_available_balances[0]).unwrap();
+        let v0 = serde_json::to_string(&messages)
+            .unwrap()
+            .replace(r#""entry_type":"NonSwift","#, "")
+            .replace(
+                &format!(r#""forward_available_balances":[{}]"#, balance),
+                &format!(r#""forward_available_balance":{}"#, balance),
+            );
+        assert!(v0.contains("forward_available_balance\""));
+
+        let read: ParsedStatements = serde_json::from_str(&v0).unwrap();
+        assert_eq!(read.messages, messages);
+    }
+}
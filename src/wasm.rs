@@ -0,0 +1,67 @@
+//! Bindings for JavaScript via `wasm-bindgen`.
+//!
+//! Statements are passed in as strings and parsed messages are returned as the same JSON that
+//! `sta2json` prints. Errors are thrown as JavaScript `Error`s carrying the message of the
+//! [`ParseError`](crate::ParseError).
+//!
+//! Build the package with [wasm-pack](https://rustwasm.github.io/wasm-pack/):
+//!
+//! ```sh
+//! wasm-pack build --target web -- --features wasm
+//! ```
+//!
+//! ```js
+//! import init, { parseMt940, sanitize } from "./pkg/mt940.js";
+//!
+//! await init();
+//! const messages = JSON.parse(parseMt940(sanitize(statement)));
+//! ```
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+/// Parse a MT940 statement into a JSON array of messages.
+///
+/// This is [`parse_mt940`](crate::parse_mt940) for JavaScript.
+#[wasm_bindgen(js_name = parseMt940)]
+pub fn parse_mt940(statement: &str) -> Result<String, JsError> {
+    let messages = crate::parse_mt940(statement).map_err(to_js_error)?;
+    serde_json::to_string(&messages).map_err(to_js_error)
+}
+
+/// Try to make a statement conform to MT940 before parsing it.
+///
+/// This is [`sanitize`](crate::sanitizers::sanitize) for JavaScript.
+#[wasm_bindgen]
+pub fn sanitize(statement: &str) -> String {
+    crate::sanitizers::sanitize(statement)
+}
+
+/// Turn an error into a JavaScript `Error` with the same message.
+///
+/// Errors are converted by message only as `JsError` can't carry structured data.
+fn to_js_error(err: impl fmt::Display) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::Message;
+
+    #[test]
+    fn parse_mt940_returns_json() {
+        let input = "\
+            :20:3996-11-11111111\r\n\
+            :25:DABADKKK/111111-11111111\r\n\
+            :28C:00001/001\r\n\
+            :60F:C090924EUR54484,04\r\n\
+            :62F:C090930EUR54484,04\r\n";
+        let json = parse_mt940(&sanitize(input)).unwrap();
+        let messages: Vec<Message> = serde_json::from_str(&json).unwrap();
+        assert_eq!(messages, crate::parse_mt940(input).unwrap());
+    }
+}
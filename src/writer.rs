@@ -0,0 +1,409 @@
+//! Serialization of parsed [`Message`]s back into MT940 text.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    AvailableBalance, Balance, BalanceKind, DebitOrCredit, EntryType, ExtDebitOrCredit, Field,
+    Message, StatementLine,
+};
+
+/// Maximum length of a single line of tag `:86:`.
+pub(crate) const TAG_86_LINE_LENGTH: usize = 65;
+
+/// Maximum number of lines of a single tag `:86:`.
+pub(crate) const TAG_86_MAX_LINES: usize = 6;
+
+/// How lines of MT940 text are terminated.
+///
+/// Input is accepted with either. This only affects text written by this crate, e.g. by
+/// [`serialize_mt940_with_line_ending`] or a [`SanitizerPipeline`].
+///
+/// [`SanitizerPipeline`]: crate::sanitizers::SanitizerPipeline
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LineEnding {
+    /// `\r\n` as required by SWIFT.
+    #[default]
+    CrLf,
+    /// `\n` as common on Unix.
+    Lf,
+}
+
+impl LineEnding {
+    /// The characters terminating a line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Lf => "\n",
+        }
+    }
+}
+
+/// Serialize a list of [`Message`]s into a single MT940 statement.
+///
+/// Messages are written one after another without any separators in between. Lines are
+/// terminated with `\r\n`.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, serialize_mt940};
+///
+/// let input = "\
+///     :20:3996-11-11111111\r\n\
+///     :25:DABADKKK/111111-11111111\r\n\
+///     :28C:00001/001\r\n\
+///     :60F:C090924EUR54484,04\r\n\
+///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+///     :86:11100304030101391234\r\n\
+///     Beneficiary name\r\n\
+///     :62F:C090930EUR53126,94\r\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// assert_eq!(serialize_mt940(&messages), input);
+/// ```
+pub fn serialize_mt940(messages: &[Message]) -> String {
+    serialize_mt940_with_line_ending(messages, LineEnding::CrLf)
+}
+
+/// Like [`serialize_mt940`] but terminating lines with `line_ending`.
+///
+/// # Example
+/// ```
+/// use mt940::{parse_mt940, serialize_mt940_with_line_ending, LineEnding};
+///
+/// let input = "\
+///     :20:3996-11-11111111\n\
+///     :25:DABADKKK/111111-11111111\n\
+///     :28C:00001/001\n\
+///     :60F:C090924EUR54484,04\n\
+///     :62F:C090930EUR54484,04\n";
+///
+/// let messages = parse_mt940(input).unwrap();
+/// assert_eq!(serialize_mt940_with_line_ending(&messages, LineEnding::Lf), input);
+/// ```
+pub fn serialize_mt940_with_line_ending(messages: &[Message], line_ending: LineEnding) -> String {
+    messages
+        .iter()
+        .map(|message| message.to_mt940_string_with_line_ending(line_ending))
+        .collect()
+}
+
+impl Message {
+    /// Serialize this [`Message`] into MT940 text.
+    ///
+    /// Lines of tag `:86:` longer than 65 characters are continued in another tag `:86:`.
+    /// Information with more than 6 lines is written as is rather than split at a line break,
+    /// which would be lost when parsing the output again. Use [`Message::validate_lengths`] to
+    /// find such information beforehand.
+    pub fn to_mt940_string(&self) -> String {
+        self.to_mt940_string_with_line_ending(LineEnding::CrLf)
+    }
+
+    /// Like [`Message::to_mt940_string`] but terminating lines with `line_ending`.
+    pub fn to_mt940_string_with_line_ending(&self, line_ending: LineEnding) -> String {
+        let mut out = String::new();
+        for field in self.to_fields() {
+            push_tag(&mut out, field.tag.as_str(), &field.value, line_ending);
+        }
+        out
+    }
+
+    /// The [`Field`]s [`Message::to_mt940_string`] writes, in order.
+    ///
+    /// This is the inverse of [`Message::from_fields`] and allows transforming single fields
+    /// without dealing with the text representation. Fields of [`Message::non_swift_fields`] and
+    /// [`Message::custom_fields`] are not included.
+    ///
+    /// # Example
+    /// ```
+    /// use mt940::{parse_mt940, Message};
+    ///
+    /// let input = "\
+    ///     :20:3996-11-11111111\r\n\
+    ///     :25:DABADKKK/111111-11111111\r\n\
+    ///     :28C:00001/001\r\n\
+    ///     :60F:C090924EUR54484,04\r\n\
+    ///     :61:0909250925DR583,92NMSC1110030403010139//1234\r\n\
+    ///     :62F:C090930EUR53900,12\r\n";
+    ///
+    /// let messages = parse_mt940(input).unwrap();
+    /// let mut fields = messages[0].to_fields();
+    /// assert_eq!(fields[1].tag, "25");
+    /// fields[1].value = "NEWACCOUNT".to_string();
+    ///
+    /// let message = Message::from_fields(fields).unwrap();
+    /// assert_eq!(message.account_id, "NEWACCOUNT");
+    /// ```
+    pub fn to_fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::new("20", &self.transaction_ref_no)];
+        if let Some(ref related) = self.ref_to_related_msg {
+            fields.push(Field::new("21", related));
+        }
+        fields.push(match self.account_identifier_bic {
+            Some(ref bic) => Field::new("25P", &format!("{}\n{}", self.account_id, bic)),
+            None => Field::new("25", &self.account_id),
+        });
+        let statement_no = match self.sequence_no {
+            Some(ref sequence_no) => format!("{}/{}", self.statement_no, sequence_no),
+            None => self.statement_no.clone(),
+        };
+        fields.push(Field::new("28C", &statement_no));
+        fields.push(Field::new(
+            BalanceKind::Opening.tag(self.opening_balance.status()),
+            &format_balance(&self.opening_balance),
+        ));
+        for statement_line in &self.statement_lines {
+            fields.extend(statement_line_fields(statement_line));
+        }
+        fields.push(Field::new(
+            BalanceKind::Closing.tag(self.closing_balance.status()),
+            &format_balance(&self.closing_balance),
+        ));
+        if let Some(ref balance) = self.closing_available_balance {
+            fields.push(Field::new("64", &format_available_balance(balance)));
+        }
+        for balance in &self.forward_available_balances {
+            fields.push(Field::new("65", &format_available_balance(balance)));
+        }
+        if let Some(ref info) = self.information_to_account_owner {
+            fields.extend(tag_86_fields(info));
+        }
+        fields
+    }
+}
+
+/// The `:61:` field of `statement_line` followed by its `:86:` fields, if any.
+pub(crate) fn statement_line_fields(statement_line: &StatementLine) -> Vec<Field> {
+    let mut fields = vec![Field::new("61", &format_statement_line(statement_line))];
+    if let Some(ref info) = statement_line.information_to_account_owner {
+        fields.extend(tag_86_fields(info));
+    }
+    fields
+}
+
+/// Write a tag and its value. Newlines within the value are written as `line_ending`.
+fn push_tag(out: &mut String, tag: &str, value: &str, line_ending: LineEnding) {
+    out.push(':');
+    out.push_str(tag);
+    out.push(':');
+    out.push_str(&value.replace('\n', line_ending.as_str()));
+    out.push_str(line_ending.as_str());
+}
+
+/// One or more `:86:` fields respecting the allowed line length.
+///
+/// When parsing, consecutive `:86:` tags are joined without a separator. Therefore, a line that is
+/// too long is continued in a new `:86:` tag rather than wrapped onto a new line so that parsing
+/// the output yields the original information again. For the same reason, a new `:86:` tag is
+/// never started at a line break: information with more than 6 lines is written as is, which
+/// [`Message::validate_lengths`] reports.
+fn tag_86_fields(info: &str) -> Vec<Field> {
+    let mut blocks: Vec<Vec<String>> = vec![vec![]];
+    for line in info.split('\n') {
+        let mut chunks = split_tag_86_line(line).into_iter();
+        if let Some(block) = blocks.last_mut() {
+            block.push(chunks.next().unwrap_or_default());
+        }
+        for chunk in chunks {
+            blocks.push(vec![chunk]);
+        }
+    }
+    blocks
+        .into_iter()
+        .map(|block| Field::new("86", &block.join("\n")))
+        .collect()
+}
+
+/// Split a line into chunks of at most 65 characters.
+///
+/// Field values are trimmed when parsing so we avoid splitting next to whitespace where possible.
+pub(crate) fn split_tag_86_line(line: &str) -> Vec<String> {
+    let mut chars: &[char] = &line.chars().collect::<Vec<char>>();
+    let mut chunks = vec![];
+    while chars.len() > TAG_86_LINE_LENGTH {
+        let split_at = chars
+            .windows(2)
+            .take(TAG_86_LINE_LENGTH)
+            .rposition(|pair| {
+                matches!(pair, [before, after] if !before.is_whitespace() && !after.is_whitespace())
+            })
+            .map_or(TAG_86_LINE_LENGTH, |i| i + 1);
+        let (chunk, rest) = chars.split_at(split_at);
+        chunks.push(chunk.iter().collect());
+        chars = rest;
+    }
+    chunks.push(chars.iter().collect());
+    chunks
+}
+
+fn format_date(date: &NaiveDate) -> String {
+    date.format("%y%m%d").to_string()
+}
+
+/// Format an amount with a comma as the decimal separator as required by MT940.
+///
+/// MT940 amounts always contain a decimal separator even if there are no decimal places.
+pub(crate) fn format_amount(amount: &Decimal) -> String {
+    let formatted = amount.abs().to_string().replace('.', ",");
+    if formatted.contains(',') {
+        formatted
+    } else {
+        format!("{},", formatted)
+    }
+}
+
+pub(crate) fn format_debit_credit(debit_credit_indicator: &DebitOrCredit) -> &'static str {
+    match debit_credit_indicator {
+        DebitOrCredit::Debit => "D",
+        DebitOrCredit::Credit => "C",
+    }
+}
+
+/// Inverse of the `FromStr` implementation of [`ExtDebitOrCredit`].
+pub(crate) fn format_ext_debit_credit(
+    ext_debit_credit_indicator: &ExtDebitOrCredit,
+) -> &'static str {
+    match ext_debit_credit_indicator {
+        ExtDebitOrCredit::Debit => "D",
+        ExtDebitOrCredit::Credit => "C",
+        ExtDebitOrCredit::ReverseCredit => "RD",
+        ExtDebitOrCredit::ReverseDebit => "RC",
+    }
+}
+
+fn format_entry_type(entry_type: &EntryType) -> char {
+    match entry_type {
+        EntryType::Swift => 'S',
+        EntryType::NonSwift => 'N',
+        EntryType::FirstAdvice => 'F',
+    }
+}
+
+fn format_balance(balance: &Balance) -> String {
+    format!(
+        "{}{}{}{}",
+        format_debit_credit(&balance.debit_credit_indicator),
+        format_date(&balance.date),
+        balance.iso_currency_code,
+        format_amount(&balance.amount)
+    )
+}
+
+fn format_available_balance(balance: &AvailableBalance) -> String {
+    format!(
+        "{}{}{}{}",
+        format_debit_credit(&balance.debit_credit_indicator),
+        format_date(&balance.date),
+        balance.iso_currency_code,
+        format_amount(&balance.amount)
+    )
+}
+
+fn format_statement_line(statement_line: &StatementLine) -> String {
+    let mut value = format_date(&statement_line.value_date);
+    if let Some(entry_date) = statement_line.entry_date {
+        value.push_str(&entry_date.format("%m%d").to_string());
+    }
+    value.push_str(format_ext_debit_credit(
+        &statement_line.ext_debit_credit_indicator,
+    ));
+    if let Some(ref funds_code) = statement_line.funds_code {
+        value.push_str(funds_code);
+    }
+    value.push_str(&format_amount(&statement_line.amount));
+    value.push(format_entry_type(&statement_line.entry_type));
+    value.push_str(statement_line.transaction_type_ident_code.code());
+    value.push_str(&statement_line.customer_ref);
+    if let Some(ref bank_ref) = statement_line.bank_ref {
+        value.push_str("//");
+        value.push_str(bank_ref);
+    }
+    if let Some(ref supplementary_details) = statement_line.supplementary_details {
+        value.push('\n');
+        value.push_str(supplementary_details);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[rstest(
+        input,
+        expected,
+        case("380115.12", "380115,12"),
+        case("380115.10", "380115,10"),
+        case("380115", "380115,"),
+        case("0.12", "0,12"),
+        case("0", "0,")
+    )]
+    fn format_amount_comma(input: &str, expected: &str) {
+        assert_eq!(format_amount(&Decimal::from_str(input).unwrap()), expected);
+    }
+
+    #[test]
+    fn tag_86_long_line_is_continued() {
+        let info = format!("{}\n2\n3", "x".repeat(70));
+        assert_eq!(
+            tag_86_fields(&info),
+            vec![
+                Field::new("86", &"x".repeat(65)),
+                Field::new("86", "xxxxx\n2\n3"),
+            ]
+        );
+    }
+
+    #[rstest(
+        info,
+        case("1\n2\n3\n4\n5\n6\n7"),
+        case(&format!("{}\n2\n3\n4\n5\n6\n7", "x".repeat(70))),
+        case(&format!("1\n2\n3\n4\n5\n{}\n7", "x".repeat(140)))
+    )]
+    fn tag_86_round_trip(info: &str) {
+        let input = "\
+            :20:ref\r\n\
+            :25:account\r\n\
+            :28C:1\r\n\
+            :60F:C090924EUR10,\r\n\
+            :61:0909250925D1,NTRFcustomer//bank\r\n\
+            :62F:C090925EUR9,\r\n";
+        let mut message = crate::parse_mt940(input).unwrap().remove(0);
+        message.statement_lines[0].information_to_account_owner = Some(info.to_string());
+        message.information_to_account_owner = Some(info.to_string());
+
+        let parsed = crate::parse_mt940(&message.to_mt940_string()).unwrap();
+        assert_eq!(parsed, vec![message]);
+    }
+
+    #[test]
+    fn fields_round_trip() {
+        let input = "\
+            :20:ref\r\n\
+            :21:related\r\n\
+            :25P:account\r\n\
+            DABADKKK\r\n\
+            :28C:1/2\r\n\
+            :60M:C090924EUR10,\r\n\
+            :61:0909250925D1,NTRFcustomer//bank\r\n\
+            :86:info\r\n\
+            :62M:C090925EUR9,\r\n\
+            :64:C090925EUR9,\r\n\
+            :65:C090926EUR9,\r\n\
+            :86:message info\r\n";
+        let messages = crate::parse_mt940(input).unwrap();
+        let fields = messages[0].to_fields();
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| field.tag.as_str())
+                .collect::<Vec<_>>(),
+            ["20", "21", "25P", "28C", "60M", "61", "86", "62M", "64", "65", "86"]
+        );
+        assert_eq!(Message::from_fields(fields).unwrap(), messages[0]);
+    }
+}
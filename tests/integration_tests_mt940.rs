@@ -5,9 +5,13 @@ use std::fs;
 use std::io;
 use std::path::PathBuf;
 
-use mt940::sanitizers::sanitize;
+use mt940::sanitizers::{sanitize, SanitizerPipeline, WrapLongLines};
 use mt940::{
-    parse_mt940, DateParseError, Message, ParseError, RequiredTagNotFoundError, UnexpectedTagError,
+    parse_mt940, parse_mt940_borrowed, parse_mt940_borrowed_with_config, parse_mt940_file,
+    parse_mt940_from_reader, parse_mt940_iter, parse_mt940_with_config, serialize_mt940,
+    serialize_mt940_with_line_ending, DateParseError, Dialect, Encoding, ErrorLocation, LineEnding,
+    Message, ParseError, ParsedStatements, ParserConfig, RequiredTagNotFoundError,
+    StructuredInformation, Tag, UnexpectedTagError,
 };
 
 /// Parse a bunch of MT940 statements that should just work even without sanitation.
@@ -30,6 +34,140 @@ fn parse_mt940_statement_success(statement_path: &str) {
     assert_eq!(expected_messages, parsed_messaged);
 }
 
+/// Lazily parsing statements must yield the same messages as parsing them at once.
+#[rstest(
+    statement_path,
+    case("danskebank/MT940_DK_Example.sta"),
+    case("betterplace/sepa_mt9401.sta"),
+    case("jejik/abnamro.sta"),
+    case("jejik/rabobank-iban.sta"),
+    case("sparkasse/buxtehude.sta")
+)]
+fn parse_mt940_iter_statement_success(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = sanitize(&fs::read_to_string(&full_path).unwrap());
+    let parsed_messages = parse_mt940_iter(&input_data)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(parse_mt940(&input_data).unwrap(), parsed_messages);
+}
+
+/// The same goes for statements which parse without sanitation, preamble and all.
+#[rstest(
+    statement_path,
+    case("danskebank/MT940_DK_Example.sta"),
+    case("betterplace/sepa_mt9401.sta"),
+    case("jejik/rabobank-iban.sta")
+)]
+fn parse_mt940_iter_unsanitized_statement_success(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let parsed_messages = parse_mt940_iter(&input_data)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(parse_mt940(&input_data).unwrap(), parsed_messages);
+}
+
+/// Parsing statements into borrowed messages must yield the same messages as parsing them owned.
+#[rstest(
+    statement_path,
+    case("danskebank/MT940_DK_Example.sta"),
+    case("betterplace/sepa_mt9401.sta"),
+    case("jejik/abnamro.sta"),
+    case("jejik/rabobank-iban.sta"),
+    case("sparkasse/buxtehude.sta")
+)]
+fn parse_mt940_borrowed_statement_success(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = sanitize(&fs::read_to_string(&full_path).unwrap());
+    let parsed_messages = parse_mt940_borrowed(&input_data)
+        .unwrap()
+        .into_iter()
+        .map(Message::from)
+        .collect::<Vec<_>>();
+
+    assert_eq!(parse_mt940(&input_data).unwrap(), parsed_messages);
+}
+
+/// The borrowed parser must honor the config the same way as the owned parser.
+#[rstest(
+    statement_path,
+    case("betterplace/sepa_mt9401.sta"),
+    case("jejik/abnamro.sta"),
+    case("sparkasse/buxtehude.sta")
+)]
+fn parse_mt940_borrowed_with_config_statement_success(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = sanitize(&fs::read_to_string(&full_path).unwrap());
+    let config = ParserConfig::new()
+        .keep_raw(true)
+        .keep_information_lines(true)
+        .sepa_purpose(true)
+        .track_source(true)
+        .source_label(statement_path);
+    let parsed_messages = parse_mt940_borrowed_with_config(&input_data, &config)
+        .unwrap()
+        .into_iter()
+        .map(Message::from)
+        .collect::<Vec<_>>();
+
+    assert!(parsed_messages[0].raw.is_some());
+    assert_eq!(
+        parse_mt940_with_config(&input_data, &config).unwrap(),
+        parsed_messages
+    );
+}
+
+/// Parse statements of banks with known deviations using their dialect instead of sanitation.
+#[rstest(
+    statement_path,
+    dialect,
+    case("jejik/ing.sta", Dialect::Ing),
+    case("jejik/abnamro.sta", Dialect::AbnAmro),
+    case("abnamro/mt940.sta", Dialect::AbnAmro),
+    case("jejik/rabobank-iban.sta", Dialect::Rabobank),
+    case("mBank/mt940.sta", Dialect::MBank),
+    case("mBank/with_newline_in_tnr.sta", Dialect::MBank),
+    case("sparkasse/buxtehude.sta", Dialect::Sparkasse)
+)]
+fn parse_mt940_statement_success_with_dialect(statement_path: &str, dialect: Dialect) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let parsed_messages =
+        parse_mt940_with_config(&input_data, &ParserConfig::from(dialect)).unwrap();
+
+    let expected_data = fs::read_to_string(full_path.with_extension("json")).unwrap();
+    let expected_messages: Vec<Message> = serde_json::from_str(&expected_data).unwrap();
+
+    assert_eq!(expected_messages.len(), parsed_messages.len());
+    for (expected, parsed) in expected_messages.iter().zip(&parsed_messages) {
+        assert_eq!(expected.transaction_ref_no, parsed.transaction_ref_no);
+        assert_eq!(expected.closing_balance, parsed.closing_balance);
+        assert_eq!(expected.statement_lines.len(), parsed.statement_lines.len());
+    }
+}
+
+/// The Dutch dialects break the keywords in `:86:` down.
+#[test]
+fn parse_mt940_dutch_information_with_dialect() {
+    let input_data = fs::read_to_string("tests/data/mt940/full/jejik/rabobank-iban.sta").unwrap();
+    let parsed_messages =
+        parse_mt940_with_config(&input_data, &ParserConfig::from(Dialect::Rabobank)).unwrap();
+
+    let statement_line = &parsed_messages[0].statement_lines[0];
+    let Some(StructuredInformation::Dutch(information)) = &statement_line.structured_information
+    else {
+        panic!("{:?}", statement_line.structured_information);
+    };
+    assert_eq!(information.name.as_deref(), Some("CONTRA ACCOUNT HOLDER"));
+    assert_eq!(
+        information.end_to_end_ref.as_deref(),
+        Some("01-01-2013 12:00 0030000987654321")
+    );
+}
+
 /// Parse a bunch of MT940 statements that only work after sanitation.
 #[rstest(
     statement_path,
@@ -60,6 +198,85 @@ fn parse_mt940_statement_success_with_sanitation(statement_path: &str) {
     assert_eq!(expected_messages, parsed_messages);
 }
 
+/// Parsed statements must survive being written back to MT940 and parsed again.
+#[rstest(
+    statement_path,
+    case("danskebank/MT940_DK_Example.sta"),
+    case("danskebank/MT940_FI_Example.sta"),
+    case("danskebank/MT940_NO_Example.sta"),
+    case("danskebank/MT940_SE_Example.sta"),
+    case("cmxl/mt940_1.sta"),
+    case("betterplace/sepa_mt9401.sta"),
+    case("betterplace/sepa_snippet.sta"),
+    case("cmxl/mt940_2.sta"),
+    case("jejik/abnamro.sta"),
+    case("jejik/ing.sta"),
+    case("jejik/knab.sta"),
+    case("jejik/postfinance.sta"),
+    case("jejik/rabobank-iban.sta"),
+    case("jejik/sns.sta"),
+    case("mBank/mt940.sta"),
+    case("sparkasse/buxtehude.sta"),
+    case("abnamro/mt940.sta")
+)]
+fn serialize_mt940_round_trip(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let parsed_messages = parse_mt940(&sanitize(&input_data)).unwrap();
+
+    let serialized = serialize_mt940(&parsed_messages);
+    let reparsed_messages = parse_mt940(&serialized).unwrap();
+
+    assert_eq!(parsed_messages, reparsed_messages);
+}
+
+/// JSON written by version 1.0 of this crate must still be readable.
+#[rstest(
+    json_path,
+    case("cmxl/mt940_1.json"),
+    case("mBank/mt940.json"),
+    case("sparkasse/buxtehude.json")
+)]
+fn deserialize_v0_json(json_path: &str) {
+    let v0_path = PathBuf::from(format!("tests/data/mt940/json-v0/{}", json_path));
+    let v0_data = fs::read_to_string(&v0_path).unwrap();
+    let read: ParsedStatements = serde_json::from_str(&v0_data).unwrap();
+
+    let current_path = PathBuf::from(format!("tests/data/mt940/full/{}", json_path));
+    let current_data = fs::read_to_string(&current_path).unwrap();
+    let expected: Vec<Message> = serde_json::from_str(&current_data).unwrap();
+
+    assert_eq!(read.messages, expected);
+}
+
+/// Statements with `\n` line endings must parse the same as with `\r\n`.
+#[rstest(
+    statement_path,
+    case("danskebank/MT940_DK_Example.sta"),
+    case("cmxl/mt940_1.sta"),
+    case("betterplace/sepa_mt9401.sta"),
+    case("jejik/ing.sta"),
+    case("mBank/with_newline_in_tnr.sta"),
+    case("sparkasse/buxtehude.sta")
+)]
+fn parse_mt940_statement_success_with_lf_line_endings(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt940/full/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let sanitized = SanitizerPipeline::default()
+        .line_ending(LineEnding::Lf)
+        .sanitize(&input_data);
+    assert!(!sanitized.contains('\r'));
+    let parsed_messages = parse_mt940(&sanitized).unwrap();
+
+    let expected_data = fs::read_to_string(full_path.with_extension("json")).unwrap();
+    let expected_messages: Vec<Message> = serde_json::from_str(&expected_data).unwrap();
+    assert_eq!(expected_messages, parsed_messages);
+
+    let serialized = serialize_mt940_with_line_ending(&parsed_messages, LineEnding::Lf);
+    assert!(!serialized.contains('\r'));
+    assert_eq!(parse_mt940(&serialized).unwrap(), parsed_messages);
+}
+
 /// Parse a bunch of invalid statements that should fail even with sanitation.
 #[rstest(
     statement_path,
@@ -74,12 +291,30 @@ fn parse_mt940_statement_fail(statement_path: &str) {
     assert!(parsed_messages.is_err());
 }
 
+/// Regression corpus of adversarial inputs. Parsing them may fail but must never panic.
+#[rstest(
+    statement_path,
+    case("amount_only_commas.sta"),
+    case("empty.sta"),
+    case("extreme_values.sta"),
+    case("invalid_entry_date.sta"),
+    case("only_tag_markers.sta"),
+    case("tag_before_tag_20.sta"),
+    case("truncated_tag_61.sta")
+)]
+fn parse_fuzz_corpus_no_panic(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/fuzz/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let _ = parse_mt940(&input_data);
+    let _ = parse_mt940(&sanitize(&input_data));
+}
+
 #[test]
 fn fail_no_tag_20() {
     let input_data = "http://example.com";
     let parsed = parse_mt940(&input_data);
     let expected = RequiredTagNotFoundError::new("20");
-    if let Err(ParseError::RequiredTagNotFoundError(e)) = parsed {
+    if let Err(ParseError::RequiredTagNotFoundError(e, _)) = parsed {
         assert_eq!(e, expected);
         return;
     }
@@ -90,11 +325,14 @@ fn fail_no_tag_20() {
 fn fail_februrary_30() {
     let input_data = fs::read_to_string("tests/data/mt940/special-cases/february_30.sta").unwrap();
     let parsed = parse_mt940(&input_data);
-    let expected = ParseError::DateParseError(Box::new(DateParseError::OutOfRange {
-        year: "2016".to_string(),
-        month: "02".to_string(),
-        day: "30".to_string(),
-    }));
+    let expected = ParseError::DateParseError(
+        Box::new(DateParseError::OutOfRange {
+            year: "2016".to_string(),
+            month: "02".to_string(),
+            day: "30".to_string(),
+        }),
+        ErrorLocation::default(),
+    );
     assert_eq!(parsed, Err(expected));
 }
 
@@ -103,7 +341,7 @@ fn fail_incomplete_tag_61() {
     let input_data =
         fs::read_to_string("tests/data/mt940/special-cases/incomplete_tag_61.sta").unwrap();
     if let Err(e) = parse_mt940(&input_data) {
-        if let ParseError::PestParseError(e) = e {
+        if let ParseError::PestParseError(e, _) = e {
             if let ErrorVariant::ParsingError {
                 positives: _,
                 negatives: _,
@@ -122,7 +360,7 @@ fn fail_invalid_statement() {
     let input_data =
         fs::read_to_string("tests/data/mt940/special-cases/invalid_statement.sta").unwrap();
     if let Err(e) = parse_mt940(&input_data) {
-        if let ParseError::RequiredTagNotFoundError(e) = e {
+        if let ParseError::RequiredTagNotFoundError(e, _) = e {
             assert_eq!(e, RequiredTagNotFoundError::new("20"));
             return;
         }
@@ -135,7 +373,7 @@ fn fail_overly_long_details() {
     let input_data =
         fs::read_to_string("tests/data/mt940/special-cases/overly_long_details.sta").unwrap();
     if let Err(e) = parse_mt940(&input_data) {
-        if let ParseError::PestParseError(e) = e {
+        if let ParseError::PestParseError(e, _) = e {
             let e = format!("{}", e);
             assert!(e.contains("?33g Erhebung?34992?60000000012345 BIC: BYLADEMM "));
             return;
@@ -144,6 +382,21 @@ fn fail_overly_long_details() {
     assert!(false);
 }
 
+#[test]
+fn parse_overly_long_details_after_wrapping() {
+    let input_data =
+        fs::read_to_string("tests/data/mt940/special-cases/overly_long_details.sta").unwrap();
+    let pipeline = SanitizerPipeline::default()
+        .without("strip_excess_tag86_lines")
+        .push(WrapLongLines);
+    let parsed = parse_mt940(&pipeline.sanitize(&input_data)).unwrap();
+    let info = parsed[0].statement_lines[0]
+        .information_to_account_owner
+        .as_deref()
+        .unwrap();
+    assert!(info.ends_with("?61ABWA: Finanzamt Muenchen ?62ABWA+Finanzamt Sentinel"));
+}
+
 #[test]
 fn fail_invalid_utf8() {
     let input_data =
@@ -151,15 +404,57 @@ fn fail_invalid_utf8() {
     assert_eq!(input_data.kind(), io::ErrorKind::InvalidData);
 }
 
+#[test]
+fn fail_invalid_utf8_from_reader() {
+    let file = fs::File::open("tests/data/mt940/special-cases/invalid_utf8.sta").unwrap();
+    let parsed = parse_mt940_from_reader(file).collect::<Result<Vec<_>, _>>();
+    if let Err(ParseError::IoError { kind, .. }) = parsed {
+        assert_eq!(kind, io::ErrorKind::InvalidData);
+        return;
+    }
+    panic!("Expected IoError, got {:?}", parsed);
+}
+
+#[test]
+fn decode_invalid_utf8_as_latin1() {
+    let input_data = fs::read("tests/data/mt940/special-cases/invalid_utf8.sta").unwrap();
+    let decoded = Encoding::Latin1.decode(&input_data).unwrap();
+    assert!(decoded.contains("Deviza \u{A0}tutal\u{A0}s"));
+    assert!(decoded.starts_with(":20:STARTUMS\n:25:UBRTHUHB/"));
+}
+
+#[test]
+fn parse_mt940_file_success() {
+    let path = "tests/data/mt940/full/danskebank/MT940_DK_Example.sta";
+    let parsed = parse_mt940_file(path)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        parse_mt940(&fs::read_to_string(path).unwrap()).unwrap(),
+        parsed
+    );
+}
+
+#[test]
+fn fail_parse_mt940_file_missing() {
+    let err = parse_mt940_file("tests/data/mt940/does_not_exist.sta").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
 #[test]
 fn fail_unexpected_tag() {
     let input_data =
         fs::read_to_string("tests/data/mt940/special-cases/unexpected_tag.sta").unwrap();
     if let Err(e) = parse_mt940(&input_data) {
-        if let ParseError::UnexpectedTagError(e) = e {
+        if let ParseError::UnexpectedTagError(e, _) = e {
             assert_eq!(
                 e,
-                UnexpectedTagError::new("28C", "20", vec!["21".to_string(), "25".to_string()])
+                UnexpectedTagError::new(
+                    Tag::StatementNo,
+                    Some(Tag::TransactionRef),
+                    vec![Tag::RelatedRef, Tag::AccountId, Tag::AccountIdWithBic]
+                )
             );
             return;
         }
@@ -171,7 +466,20 @@ fn fail_unexpected_tag() {
 fn fail_unknown_tag() {
     let input_data = fs::read_to_string("tests/data/mt940/special-cases/unknown_tag.sta").unwrap();
     if let Err(e) = parse_mt940(&input_data) {
-        assert_eq!(e, ParseError::UnknownTagError("12".to_string()));
+        assert_eq!(
+            e,
+            ParseError::UnknownTagError("12".to_string(), ErrorLocation::default())
+        );
+        let span = e.span().unwrap();
+        assert!(span.snippet(&input_data).unwrap().starts_with(":12:"));
+
+        // The tag is really part of a time within tag 86.
+        let config = ParserConfig::new().escape_unknown_tags(true);
+        let parsed = parse_mt940_with_config(&input_data, &config).unwrap();
+        let info = parsed[0].statement_lines[0]
+            .information_to_account_owner
+            .as_deref();
+        assert!(info.unwrap().ends_with("2017-01-01T13\n:12:11"));
         return;
     }
     assert!(false);
@@ -0,0 +1,49 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::fs;
+use std::path::PathBuf;
+
+use mt940::{parse_mt942, ErrorLocation, InterimMessage, ParseError, RequiredTagNotFoundError};
+
+/// Parse a bunch of MT942 statements that should just work even without sanitation.
+#[rstest(statement_path, case("generic/mt942.sta"))]
+fn parse_mt942_statement_success(statement_path: &str) {
+    let full_path = PathBuf::from(format!("tests/data/mt942/full/{}", statement_path));
+    let input_data = fs::read_to_string(&full_path).unwrap();
+    let parsed_messages = parse_mt942(&input_data).unwrap();
+
+    let expected_data = fs::read_to_string(full_path.with_extension("json")).unwrap();
+    let expected_messages: Vec<InterimMessage> = serde_json::from_str(&expected_data).unwrap();
+
+    assert_eq!(expected_messages, parsed_messages);
+}
+
+/// A MT940 statement is not a valid MT942 statement as it contains balances.
+#[test]
+fn fail_mt940_as_mt942() {
+    let input_data =
+        fs::read_to_string("tests/data/mt940/full/danskebank/MT940_FI_Example.sta").unwrap();
+    let err = parse_mt942(&input_data).unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::UnknownTagError("60F".to_string(), ErrorLocation::default())
+    );
+    assert_eq!(err.span().map(|span| span.line), Some(9));
+}
+
+#[test]
+fn fail_missing_tag_13d() {
+    let input_data = "\
+        :20:CGNGHKLI0290980\r\n\
+        :25:GJB0291077111\r\n\
+        :28C:03917/00001\r\n\
+        :34F:EURD0,00\r\n";
+    let parsed = parse_mt942(input_data);
+    assert_eq!(
+        parsed,
+        Err(ParseError::RequiredTagNotFoundError(
+            RequiredTagNotFoundError::new("13D"),
+            ErrorLocation::default()
+        ))
+    );
+}